@@ -10,14 +10,6 @@ use std::{
 };
 
 fn main() {
-    if std::env::var("DOCS_RS").is_ok() {
-        // don't build c++ library on docs.rs due to network restrictions
-        return;
-    }
-
-    #[cfg(all(feature = "quiet", feature = "logging"))]
-    compile_error!("cannot combine cadical features quiet and logging");
-
     // Select commit based on features. If conflict, always choose newest release
     let (tag, patch) = if cfg!(feature = "v1-9-5") {
         ("refs/tags/rel-1.9.5", "patches/v192.patch")
@@ -66,6 +58,21 @@ fn main() {
         ("refs/tags/rel-1.9.5", "patches/v192.patch")
     };
 
+    // The external propagator, proof tracing and cube APIs are only available
+    // from v1.9.2 on
+    println!("cargo:rustc-check-cfg=cfg(cadical_ge_1_9_2)");
+    if version_at_least(tag, (1, 9, 2)) {
+        println!("cargo:rustc-cfg=cadical_ge_1_9_2");
+    }
+
+    if std::env::var("DOCS_RS").is_ok() {
+        // don't build c++ library on docs.rs due to network restrictions
+        return;
+    }
+
+    #[cfg(all(feature = "quiet", feature = "logging"))]
+    compile_error!("cannot combine cadical features quiet and logging");
+
     // Build C++ library
     build(
         "https://github.com/arminbiere/cadical.git",
@@ -87,6 +94,16 @@ fn main() {
     println!("cargo:rustc-link-search={}/lib", out_dir);
 }
 
+/// Checks whether the version of a release tag is at least the given version
+fn version_at_least(tag: &str, min: (u32, u32, u32)) -> bool {
+    let mut nums = tag
+        .trim_start_matches("refs/tags/rel-")
+        .split('.')
+        .map(|num| num.parse::<u32>().expect("invalid version in release tag"));
+    let mut next = || nums.next().expect("invalid version in release tag");
+    (next(), next(), next()) >= min
+}
+
 fn build(repo: &str, branch: &str, reference: &str, patch: &str) {
     let out_dir = env::var("OUT_DIR").unwrap();
     let mut cadical_dir_str = out_dir.clone();
@@ -0,0 +1,401 @@
+//! # Cube-and-Conquer
+//!
+//! CaDiCaL's lookahead can be used to split an instance into cubes, i.e.,
+//! conjunctions of literals, such that the instance is satisfiable if and only
+//! if it is satisfiable under one of the cubes. The cubes can then be solved
+//! sequentially in one solver with [`CaDiCaL::solve_cubes`] or in parallel
+//! across fresh solver instances with [`solve_cubes_parallel`].
+//!
+//! ## Example Useage
+//!
+//! ```
+//! use rustsat::{clause, instances::Cnf, lit};
+//! use rustsat::solvers::{Solve, SolverResult};
+//! use rustsat_cadical::{cube, CaDiCaL, CubeResult, CubesWithStatus};
+//!
+//! let mut cnf = Cnf::new();
+//! cnf.add_clause(clause![lit![0], lit![1], lit![2]]);
+//! cnf.add_clause(clause![!lit![0], lit![1]]);
+//! cnf.add_clause(clause![!lit![1], lit![2]]);
+//! cnf.add_clause(clause![!lit![2], !lit![0]]);
+//!
+//! let mut solver = CaDiCaL::default();
+//! solver.add_cnf_ref(&cnf).unwrap();
+//! let CubesWithStatus { status, cubes } = solver.generate_cubes(2).unwrap();
+//! // the instance might already be solved while generating the cubes
+//! assert_ne!(status, Some(SolverResult::Unsat));
+//!
+//! let res = cube::solve_cubes_parallel(&cnf, &cubes, 2).unwrap();
+//! assert!(matches!(res, CubeResult::Sat { .. }));
+//! ```
+
+use core::ffi::{c_int, c_void};
+use std::{
+    slice,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    thread,
+};
+
+use rustsat::{
+    instances::Cnf,
+    solvers::{ControlSignal, Solve, SolveIncremental, SolverResult, Terminate},
+    types::{Assignment, Lit},
+};
+
+use super::{ffi, ffi::abort_on_panic, handle_oom, CaDiCaL, InternalSolverState, InvalidApiReturn};
+
+/// Cubes generated by [`CaDiCaL::generate_cubes`] together with the status of
+/// the instance
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CubesWithStatus {
+    /// [`SolverResult::Sat`] or [`SolverResult::Unsat`] if the instance was
+    /// solved while generating the cubes, [`None`] if it is undecided
+    pub status: Option<SolverResult>,
+    /// The generated cubes
+    pub cubes: Vec<Vec<Lit>>,
+}
+
+/// Result of solving a set of cubes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CubeResult {
+    /// The instance is satisfiable under a cube
+    Sat {
+        /// The index of the satisfiable cube
+        cube: usize,
+        /// The solution found under the cube
+        solution: Assignment,
+    },
+    /// The instance is unsatisfiable under all cubes
+    Unsat,
+    /// Solving was interrupted before all cubes were solved
+    Interrupted,
+}
+
+impl CaDiCaL<'_, '_> {
+    /// Runs CaDiCaL's lookahead and returns the literal that it would branch
+    /// on. Returns `None` if the instance is already solved or no decision is
+    /// left.
+    ///
+    /// # Errors
+    ///
+    /// If CaDiCaL runs out of memory, returns [`rustsat::OutOfMemory`].
+    pub fn lookahead(&mut self) -> anyhow::Result<Option<Lit>> {
+        // If already solved, there is nothing to branch on
+        if matches!(
+            self.state,
+            InternalSolverState::Sat | InternalSolverState::Unsat(_)
+        ) {
+            return Ok(None);
+        }
+        let mut lit: c_int = 0;
+        handle_oom!(unsafe { ffi::ccadical_lookahead_mem(self.handle, &mut lit) });
+        self.state = InternalSolverState::Input;
+        Ok(Lit::from_ipasir(lit).ok())
+    }
+
+    /// Splits the instance into cubes via lookahead, branching up to `depth`
+    /// times. The instance is satisfiable if and only if it is satisfiable
+    /// under one of the returned cubes. If the instance is solved while
+    /// generating the cubes, this is reflected in the returned status. If the
+    /// solver is already in a solved state, no lookahead is performed and the
+    /// status is returned with trivial cubes.
+    ///
+    /// # Errors
+    ///
+    /// - If `depth` does not fit into a C `int`
+    /// - If CaDiCaL runs out of memory, returns [`rustsat::OutOfMemory`]
+    /// - [`InvalidApiReturn`] if CaDiCaL returns an unexpected status
+    pub fn generate_cubes(&mut self, depth: u32) -> anyhow::Result<CubesWithStatus> {
+        // If already solved, return trivial cubes
+        if let InternalSolverState::Sat = self.state {
+            return Ok(CubesWithStatus {
+                status: Some(SolverResult::Sat),
+                cubes: vec![vec![]],
+            });
+        }
+        if let InternalSolverState::Unsat(_) = self.state {
+            return Ok(CubesWithStatus {
+                status: Some(SolverResult::Unsat),
+                cubes: vec![],
+            });
+        }
+        let depth: c_int = depth.try_into()?;
+        let mut cubes: Vec<Vec<Lit>> = vec![];
+        let ret = handle_oom!(unsafe {
+            ffi::ccadical_generate_cubes_mem(
+                self.handle,
+                depth,
+                0,
+                &mut cubes as *mut Vec<Vec<Lit>> as *mut c_void,
+                ccadical_cube_cb,
+            )
+        });
+        match ret {
+            0 => {
+                self.state = InternalSolverState::Input;
+                Ok(CubesWithStatus {
+                    status: None,
+                    cubes,
+                })
+            }
+            10 => {
+                self.state = InternalSolverState::Input;
+                // keep the instance satisfiable under one of the cubes
+                if cubes.is_empty() {
+                    cubes.push(vec![]);
+                }
+                Ok(CubesWithStatus {
+                    status: Some(SolverResult::Sat),
+                    cubes,
+                })
+            }
+            20 => {
+                self.state = InternalSolverState::Unsat(vec![]);
+                Ok(CubesWithStatus {
+                    status: Some(SolverResult::Unsat),
+                    cubes: vec![],
+                })
+            }
+            value => Err(InvalidApiReturn {
+                api_call: "ccadical_generate_cubes_mem",
+                value,
+            }
+            .into()),
+        }
+    }
+
+    /// Solves the given cubes one after the other as assumptions in this
+    /// solver. Stops at the first satisfiable cube, in which case the solver
+    /// stays in the satisfied state for that cube.
+    ///
+    /// # Errors
+    ///
+    /// - If CaDiCaL runs out of memory, returns [`rustsat::OutOfMemory`]
+    /// - [`InvalidApiReturn`] if CaDiCaL returns an unexpected status
+    pub fn solve_cubes(&mut self, cubes: &[Vec<Lit>]) -> anyhow::Result<CubeResult> {
+        for (idx, cube) in cubes.iter().enumerate() {
+            match self.solve_assumps(cube)? {
+                SolverResult::Sat => {
+                    return Ok(CubeResult::Sat {
+                        cube: idx,
+                        solution: self.full_solution()?,
+                    })
+                }
+                SolverResult::Unsat => (),
+                SolverResult::Interrupted => return Ok(CubeResult::Interrupted),
+            }
+        }
+        Ok(CubeResult::Unsat)
+    }
+}
+
+/// Solves the given cubes in parallel on `n_threads` threads, each with a
+/// fresh CaDiCaL instance that the CNF is loaded into. As soon as one cube is
+/// found satisfiable, all other solvers are terminated.
+///
+/// # Errors
+///
+/// If one of the worker threads fails to load the CNF or to solve a cube, the
+/// other workers are stopped and the first error is returned. A worker fails
+/// if CaDiCaL runs out of memory, returning [`rustsat::OutOfMemory`], or
+/// returns an unexpected status, returning [`InvalidApiReturn`].
+pub fn solve_cubes_parallel(
+    cnf: &Cnf,
+    cubes: &[Vec<Lit>],
+    n_threads: usize,
+) -> anyhow::Result<CubeResult> {
+    if cubes.is_empty() {
+        return Ok(CubeResult::Unsat);
+    }
+    let n_threads = n_threads.clamp(1, cubes.len());
+    let next = AtomicUsize::new(0);
+    let done = AtomicBool::new(false);
+    let conquer = || -> anyhow::Result<Option<CubeResult>> {
+        let mut solver = CaDiCaL::default();
+        solver.attach_terminator(|| {
+            if done.load(Ordering::Relaxed) {
+                ControlSignal::Terminate
+            } else {
+                ControlSignal::Continue
+            }
+        });
+        solver.add_cnf_ref(cnf)?;
+        while !done.load(Ordering::Relaxed) {
+            let idx = next.fetch_add(1, Ordering::Relaxed);
+            if idx >= cubes.len() {
+                break;
+            }
+            match solver.solve_assumps(&cubes[idx])? {
+                SolverResult::Sat => {
+                    done.store(true, Ordering::Relaxed);
+                    return Ok(Some(CubeResult::Sat {
+                        cube: idx,
+                        solution: solver.full_solution()?,
+                    }));
+                }
+                SolverResult::Unsat => (),
+                SolverResult::Interrupted => break,
+            }
+        }
+        Ok(None)
+    };
+    let results: Vec<_> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..n_threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let res = conquer();
+                    if res.is_err() {
+                        // stop the other threads, not all cubes can be solved
+                        done.store(true, Ordering::Relaxed);
+                    }
+                    res
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("cube solver thread panicked"))
+            .collect()
+    });
+    let mut error = None;
+    for res in results {
+        match res {
+            Ok(Some(sat)) => return Ok(sat),
+            Ok(None) => (),
+            Err(err) => {
+                error.get_or_insert(err);
+            }
+        }
+    }
+    match error {
+        Some(err) => Err(err),
+        None => Ok(CubeResult::Unsat),
+    }
+}
+
+extern "C" fn ccadical_cube_cb(data: *mut c_void, lits: *const c_int, len: usize) {
+    abort_on_panic(|| {
+        let cubes = unsafe { &mut *(data as *mut Vec<Vec<Lit>>) };
+        if len == 0 {
+            cubes.push(vec![]);
+            return;
+        }
+        let lits = unsafe { slice::from_raw_parts(lits, len) };
+        cubes.push(
+            lits.iter()
+                .map(|il| Lit::from_ipasir(*il).expect("Invalid literal in cube from CaDiCaL"))
+                .collect(),
+        );
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use rustsat::{
+        clause,
+        instances::Cnf,
+        lit,
+        solvers::{Solve, SolveIncremental, SolverResult},
+        types::TernaryVal,
+    };
+
+    use super::{solve_cubes_parallel, CubeResult, CubesWithStatus};
+    use crate::CaDiCaL;
+
+    /// Pigeon hole principle with `n + 1` pigeons and `n` holes
+    fn pigeon_hole(n: u32) -> Cnf {
+        let pos = |pigeon: u32, hole: u32| lit![pigeon * n + hole];
+        let mut cnf = Cnf::new();
+        for pigeon in 0..=n {
+            cnf.add_clause((0..n).map(|hole| pos(pigeon, hole)).collect());
+        }
+        for hole in 0..n {
+            for p1 in 0..=n {
+                for p2 in p1 + 1..=n {
+                    cnf.add_binary(!pos(p1, hole), !pos(p2, hole));
+                }
+            }
+        }
+        cnf
+    }
+
+    fn sat_cnf() -> Cnf {
+        let mut cnf = Cnf::new();
+        cnf.add_clause(clause![lit![0], lit![1], lit![2], lit![3]]);
+        cnf.add_clause(clause![!lit![0], lit![1]]);
+        cnf.add_clause(clause![!lit![1], lit![2]]);
+        cnf.add_clause(clause![!lit![2], !lit![0]]);
+        cnf.add_clause(clause![!lit![3], lit![4]]);
+        cnf.add_clause(clause![!lit![4], !lit![5]]);
+        cnf
+    }
+
+    #[test]
+    fn lookahead() {
+        let mut solver = CaDiCaL::default();
+        solver.add_cnf(sat_cnf()).unwrap();
+        if let Some(lit) = solver.lookahead().unwrap() {
+            assert!(lit.var() <= rustsat::var![5]);
+        }
+        assert_eq!(solver.solve().unwrap(), SolverResult::Sat);
+    }
+
+    #[test]
+    fn cubes_cover_unsat() {
+        let cnf = pigeon_hole(4);
+        let mut solver = CaDiCaL::default();
+        solver.add_cnf_ref(&cnf).unwrap();
+        let CubesWithStatus { status, cubes } = solver.generate_cubes(3).unwrap();
+        assert_ne!(status, Some(SolverResult::Sat));
+        for cube in &cubes {
+            assert!(cube.len() <= 3);
+        }
+        assert_eq!(solver.solve_cubes(&cubes).unwrap(), CubeResult::Unsat);
+        assert_eq!(
+            solve_cubes_parallel(&cnf, &cubes, 4).unwrap(),
+            CubeResult::Unsat
+        );
+    }
+
+    #[test]
+    fn cubes_sat() {
+        let cnf = sat_cnf();
+        let mut solver = CaDiCaL::default();
+        solver.add_cnf_ref(&cnf).unwrap();
+        let CubesWithStatus { status, cubes } = solver.generate_cubes(2).unwrap();
+        assert_ne!(status, Some(SolverResult::Unsat));
+        assert!(!cubes.is_empty());
+        let check = |res: CubeResult| match res {
+            CubeResult::Sat { cube, solution } => {
+                for lit in &cubes[cube] {
+                    assert_eq!(solution.lit_value(*lit), TernaryVal::True);
+                }
+                for cl in cnf.iter() {
+                    assert_eq!(cl.evaluate(&solution), TernaryVal::True);
+                }
+            }
+            _ => panic!("expected satisfiable cube"),
+        };
+        check(solver.solve_cubes(&cubes).unwrap());
+        check(solve_cubes_parallel(&cnf, &cubes, 2).unwrap());
+    }
+
+    #[test]
+    fn no_cubes() {
+        let mut solver = CaDiCaL::default();
+        solver.add_unit(lit![0]).unwrap();
+        solver.add_unit(!lit![0]).unwrap();
+        assert_eq!(solver.solve_assumps(&[]).unwrap(), SolverResult::Unsat);
+        assert_eq!(
+            solver.generate_cubes(2).unwrap(),
+            CubesWithStatus {
+                status: Some(SolverResult::Unsat),
+                cubes: vec![],
+            }
+        );
+        assert_eq!(
+            solve_cubes_parallel(&Cnf::new(), &[], 2).unwrap(),
+            CubeResult::Unsat
+        );
+    }
+}
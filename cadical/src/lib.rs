@@ -19,6 +19,23 @@
 //!
 //! Without any features selected, the newest version will be used.
 //! If conflicting CaDiCaL versions are requested, the newest requested version will be selected.
+//!
+//! ## External Propagators
+//!
+//! From CaDiCaL 1.9.2, external propagators implementing [`ExternalPropagator`]
+//! can be connected to the solver via the IPASIR-UP interface. See the
+//! [`propagator`] module for details.
+//!
+//! ## Proof Tracing
+//!
+//! From CaDiCaL 1.9.2, proofs of unsatisfiability can be traced to a file or
+//! writer in DRAT, LRAT or FRAT format. See the [`proof`] module for details.
+//!
+//! ## Cube-and-Conquer
+//!
+//! From CaDiCaL 1.9.2, CaDiCaL's lookahead can be used to split an instance
+//! into cubes, which can then be solved sequentially or in parallel. See the
+//! [`cube`] module for details.
 
 #![warn(missing_docs)]
 
@@ -35,6 +52,19 @@ use rustsat::solvers::{
 use rustsat::types::{Clause, Lit, TernaryVal, Var};
 use thiserror::Error;
 
+#[cfg(cadical_ge_1_9_2)]
+pub mod propagator;
+#[cfg(cadical_ge_1_9_2)]
+pub use propagator::ExternalPropagator;
+#[cfg(cadical_ge_1_9_2)]
+pub mod proof;
+#[cfg(cadical_ge_1_9_2)]
+pub use proof::ProofFormat;
+#[cfg(cadical_ge_1_9_2)]
+pub mod cube;
+#[cfg(cadical_ge_1_9_2)]
+pub use cube::{CubeResult, CubesWithStatus};
+
 const OUT_OF_MEM: c_int = 50;
 
 macro_rules! handle_oom {
@@ -100,6 +130,10 @@ pub struct CaDiCaL<'term, 'learn> {
     terminate_cb: OptTermCallbackStore<'term>,
     learner_cb: OptLearnCallbackStore<'learn>,
     stats: SolverStats,
+    #[cfg(cadical_ge_1_9_2)]
+    propagator: Option<Box<propagator::PropagatorData>>,
+    #[cfg(cadical_ge_1_9_2)]
+    proof: Option<proof::ProofTrace>,
 }
 
 unsafe impl Send for CaDiCaL<'_, '_> {}
@@ -116,6 +150,10 @@ impl Default for CaDiCaL<'_, '_> {
             terminate_cb: Default::default(),
             learner_cb: Default::default(),
             stats: Default::default(),
+            #[cfg(cadical_ge_1_9_2)]
+            propagator: None,
+            #[cfg(cadical_ge_1_9_2)]
+            proof: None,
         };
         let quiet = CString::new("quiet").unwrap();
         unsafe { ffi::ccadical_set_option_ret(solver.handle, quiet.as_ptr(), 1) };
@@ -803,6 +841,10 @@ impl SolveStats for CaDiCaL<'_, '_> {
 
 impl Drop for CaDiCaL<'_, '_> {
     fn drop(&mut self) {
+        #[cfg(cadical_ge_1_9_2)]
+        self.disconnect_propagator();
+        #[cfg(cadical_ge_1_9_2)]
+        let _ = self.close_proof();
         unsafe { ffi::ccadical_release(self.handle) }
     }
 }
@@ -1004,31 +1046,106 @@ mod ffi {
         pub fn ccadical_flippable(solver: *mut CaDiCaLHandle, lit: c_int) -> bool;
     }
 
+    /// Callbacks for the IPASIR-UP external propagator interface
+    #[cfg(cadical_ge_1_9_2)]
+    #[repr(C)]
+    pub struct CCaDiCaLPropagator {
+        pub data: *mut c_void,
+        pub is_lazy: bool,
+        pub notify_assignment: extern "C" fn(data: *mut c_void, lit: c_int, is_fixed: bool),
+        pub notify_new_decision_level: extern "C" fn(data: *mut c_void),
+        pub notify_backtrack: extern "C" fn(data: *mut c_void, new_level: usize),
+        pub cb_check_found_model:
+            extern "C" fn(data: *mut c_void, model: *const c_int, len: usize) -> bool,
+        pub cb_decide: extern "C" fn(data: *mut c_void) -> c_int,
+        pub cb_propagate: extern "C" fn(data: *mut c_void) -> c_int,
+        pub cb_add_reason_clause_lit:
+            extern "C" fn(data: *mut c_void, propagated_lit: c_int) -> c_int,
+        pub cb_has_external_clause: extern "C" fn(data: *mut c_void) -> bool,
+        pub cb_add_external_clause_lit: extern "C" fn(data: *mut c_void) -> c_int,
+    }
+
+    #[cfg(cadical_ge_1_9_2)]
+    #[link(name = "cadical", kind = "static")]
+    extern "C" {
+        pub fn ccadical_connect_propagator(
+            solver: *mut CaDiCaLHandle,
+            propagator: CCaDiCaLPropagator,
+        ) -> *mut c_void;
+        pub fn ccadical_disconnect_propagator(solver: *mut CaDiCaLHandle, propagator: *mut c_void);
+        pub fn ccadical_add_observed_var(solver: *mut CaDiCaLHandle, var: c_int) -> c_int;
+        pub fn ccadical_remove_observed_var(solver: *mut CaDiCaLHandle, var: c_int);
+        pub fn ccadical_reset_observed_vars(solver: *mut CaDiCaLHandle);
+        pub fn ccadical_is_decision(solver: *mut CaDiCaLHandle, lit: c_int) -> bool;
+        pub fn ccadical_trace_proof(
+            solver: *mut CaDiCaLHandle,
+            file: *mut CFile,
+            path: *const c_char,
+        );
+        pub fn ccadical_close_proof(solver: *mut CaDiCaLHandle);
+        pub fn ccadical_conclude(solver: *mut CaDiCaLHandle);
+        pub fn ccadical_lookahead_mem(solver: *mut CaDiCaLHandle, lit: *mut c_int) -> c_int;
+        pub fn ccadical_generate_cubes_mem(
+            solver: *mut CaDiCaLHandle,
+            depth: c_int,
+            min_depth: c_int,
+            data: *mut c_void,
+            cube: extern "C" fn(data: *mut c_void, lits: *const c_int, len: usize),
+        ) -> c_int;
+    }
+
+    /// Opaque C file type for proof tracing
+    #[cfg(cadical_ge_1_9_2)]
+    #[repr(C)]
+    pub struct CFile {
+        _private: [u8; 0],
+    }
+
+    // C standard library functions for proof files
+    #[cfg(cadical_ge_1_9_2)]
+    extern "C" {
+        pub fn fopen(path: *const c_char, mode: *const c_char) -> *mut CFile;
+        pub fn fclose(file: *mut CFile) -> c_int;
+    }
+
+    /// Runs the body of a callback from CaDiCaL and aborts the process if it
+    /// panics, since unwinding into C++ is undefined behaviour
+    pub fn abort_on_panic<T, F: FnOnce() -> T>(f: F) -> T {
+        match std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)) {
+            Ok(val) => val,
+            Err(_) => std::process::abort(),
+        }
+    }
+
     // Raw callbacks forwarding to user callbacks
     pub extern "C" fn ccadical_terminate_cb(ptr: *const c_void) -> c_int {
-        let cb = unsafe { &mut *(ptr as *mut TermCallbackPtr<'_>) };
-        match cb() {
-            ControlSignal::Continue => 0,
-            ControlSignal::Terminate => 1,
-        }
+        abort_on_panic(|| {
+            let cb = unsafe { &mut *(ptr as *mut TermCallbackPtr<'_>) };
+            match cb() {
+                ControlSignal::Continue => 0,
+                ControlSignal::Terminate => 1,
+            }
+        })
     }
 
     pub extern "C" fn ccadical_learn_cb(ptr: *const c_void, clause: *const c_int) {
-        let cb = unsafe { &mut *(ptr as *mut LearnCallbackPtr<'_>) };
+        abort_on_panic(|| {
+            let cb = unsafe { &mut *(ptr as *mut LearnCallbackPtr<'_>) };
 
-        let mut cnt = 0;
-        for n in 0.. {
-            if unsafe { *clause.offset(n) } != 0 {
-                cnt += 1;
+            let mut cnt = 0;
+            for n in 0.. {
+                if unsafe { *clause.offset(n) } != 0 {
+                    cnt += 1;
+                }
             }
-        }
-        let int_slice = unsafe { slice::from_raw_parts(clause, cnt) };
-        let clause = int_slice
-            .iter()
-            .map(|il| {
-                Lit::from_ipasir(*il).expect("Invalid literal in learned clause from CaDiCaL")
-            })
-            .collect();
-        cb(clause)
+            let int_slice = unsafe { slice::from_raw_parts(clause, cnt) };
+            let clause = int_slice
+                .iter()
+                .map(|il| {
+                    Lit::from_ipasir(*il).expect("Invalid literal in learned clause from CaDiCaL")
+                })
+                .collect();
+            cb(clause)
+        })
     }
 }
@@ -0,0 +1,332 @@
+//! # Proof Tracing
+//!
+//! CaDiCaL can trace proofs of unsatisfiability in DRAT, LRAT and FRAT
+//! format, available from CaDiCaL 1.9.2. Proof tracing needs to be enabled
+//! before any clauses are added to the solver. The proof is flushed and
+//! closed when [`CaDiCaL::close_proof`] is called or the solver is dropped.
+//!
+//! ## Example Useage
+//!
+//! ```
+//! use rustsat::{lit, solvers::{Solve, SolverResult}};
+//! use rustsat_cadical::{CaDiCaL, ProofFormat};
+//!
+//! let path = std::env::temp_dir().join("rustsat-cadical-doc-proof.drat");
+//! let mut solver = CaDiCaL::default();
+//! solver.trace_proof(&path, ProofFormat::Drat { binary: false }).unwrap();
+//! solver.add_binary(lit![0], lit![1]).unwrap();
+//! solver.add_binary(!lit![0], lit![1]).unwrap();
+//! solver.add_binary(lit![0], !lit![1]).unwrap();
+//! solver.add_binary(!lit![0], !lit![1]).unwrap();
+//! assert_eq!(solver.solve().unwrap(), SolverResult::Unsat);
+//! solver.close_proof().unwrap();
+//! # std::fs::remove_file(&path).unwrap();
+//! ```
+
+use core::ffi::c_int;
+use std::{
+    ffi::CString,
+    fmt,
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use rustsat::solvers::{SolverState, StateError};
+
+use super::{ffi, CaDiCaL, InternalSolverState, InvalidApiReturn};
+
+/// Counter for unique names of temporary proof files within the process
+static PROOF_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Proof formats that CaDiCaL can trace
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofFormat {
+    /// DRAT proofs in text or binary format
+    Drat {
+        /// Whether to write the binary format
+        binary: bool,
+    },
+    /// LRAT proofs in text or binary format
+    Lrat {
+        /// Whether to write the binary format
+        binary: bool,
+    },
+    /// FRAT proofs in text or binary format
+    Frat {
+        /// Whether to write the binary format
+        binary: bool,
+    },
+}
+
+impl fmt::Display for ProofFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (name, binary) = match self {
+            ProofFormat::Drat { binary } => ("drat", binary),
+            ProofFormat::Lrat { binary } => ("lrat", binary),
+            ProofFormat::Frat { binary } => ("frat", binary),
+        };
+        if *binary {
+            write!(f, "binary {}", name)
+        } else {
+            write!(f, "{}", name)
+        }
+    }
+}
+
+/// A proof that is currently traced
+pub(crate) struct ProofTrace {
+    /// The C file the proof is written to
+    file: *mut ffi::CFile,
+    /// If the proof is traced via a temporary file, the writer to copy it to
+    /// when closing
+    forward: Option<(PathBuf, Box<dyn Write + Send>)>,
+}
+
+impl CaDiCaL<'_, '_> {
+    /// Starts tracing a proof to a file. Needs to be called before any
+    /// clauses are added to the solver.
+    ///
+    /// # Errors
+    ///
+    /// - [`StateError`] if the solver is not in configuring state
+    /// - If a proof is already being traced
+    /// - If the file cannot be created
+    /// - [`InvalidApiReturn`] if CaDiCaL rejects the proof format
+    pub fn trace_proof<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        format: ProofFormat,
+    ) -> anyhow::Result<()> {
+        self.start_proof(path.as_ref(), format, None)
+    }
+
+    /// Starts tracing a proof to a writer. The proof is buffered in a
+    /// temporary file and copied to the writer when the proof is closed.
+    /// Needs to be called before any clauses are added to the solver. The
+    /// writer needs to be [`Send`], since the solver can be sent to other
+    /// threads.
+    ///
+    /// # Errors
+    ///
+    /// - [`StateError`] if the solver is not in configuring state
+    /// - If a proof is already being traced
+    /// - If the temporary file cannot be created
+    /// - [`InvalidApiReturn`] if CaDiCaL rejects the proof format
+    pub fn trace_proof_to<W: Write + Send + 'static>(
+        &mut self,
+        writer: W,
+        format: ProofFormat,
+    ) -> anyhow::Result<()> {
+        let path = std::env::temp_dir().join(format!(
+            "rustsat-cadical-proof-{}-{}",
+            std::process::id(),
+            PROOF_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        self.start_proof(&path.clone(), format, Some((path, Box::new(writer))))
+    }
+
+    fn start_proof(
+        &mut self,
+        path: &Path,
+        format: ProofFormat,
+        forward: Option<(PathBuf, Box<dyn Write + Send>)>,
+    ) -> anyhow::Result<()> {
+        if self.state != InternalSolverState::Configuring {
+            return Err(StateError {
+                required_state: SolverState::Configuring,
+                actual_state: self.state.to_external(),
+            }
+            .into());
+        }
+        if self.proof.is_some() {
+            anyhow::bail!("a proof is already being traced");
+        }
+        let (lrat, frat, binary) = match format {
+            ProofFormat::Drat { binary } => (0, 0, binary),
+            ProofFormat::Lrat { binary } => (1, 0, binary),
+            ProofFormat::Frat { binary } => (0, 1, binary),
+        };
+        for (name, value) in [
+            ("lrat", lrat),
+            ("frat", frat),
+            ("binary", c_int::from(binary)),
+        ] {
+            let c_name = CString::new(name).unwrap();
+            if !unsafe { ffi::ccadical_set_option_ret(self.handle, c_name.as_ptr(), value) } {
+                return Err(InvalidApiReturn {
+                    api_call: "ccadical_set_option_ret",
+                    value: 0,
+                }
+                .into());
+            }
+        }
+        let c_path = CString::new(
+            path.to_str()
+                .ok_or_else(|| anyhow::anyhow!("proof path is not valid UTF-8"))?,
+        )?;
+        let mode = CString::new("wb").unwrap();
+        let file = unsafe { ffi::fopen(c_path.as_ptr(), mode.as_ptr()) };
+        if file.is_null() {
+            return Err(io::Error::last_os_error().into());
+        }
+        unsafe { ffi::ccadical_trace_proof(self.handle, file, c_path.as_ptr()) };
+        self.proof = Some(ProofTrace { file, forward });
+        Ok(())
+    }
+
+    /// Checks whether a proof is currently traced
+    pub fn is_tracing_proof(&self) -> bool {
+        self.proof.is_some()
+    }
+
+    /// Finishes the proof, flushes it and closes the file. If the solver is
+    /// in a satisfied or unsatisfied state, the proof is concluded first.
+    /// Does nothing if no proof is traced.
+    ///
+    /// # Errors
+    ///
+    /// If the proof cannot be copied to the writer it is traced to.
+    pub fn close_proof(&mut self) -> anyhow::Result<()> {
+        let Some(proof) = self.proof.take() else {
+            return Ok(());
+        };
+        if matches!(
+            self.state,
+            InternalSolverState::Sat | InternalSolverState::Unsat(_)
+        ) {
+            unsafe { ffi::ccadical_conclude(self.handle) };
+        }
+        unsafe { ffi::ccadical_close_proof(self.handle) };
+        if unsafe { ffi::fclose(proof.file) } != 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+        if let Some((path, mut writer)) = proof.forward {
+            let copied = File::open(&path).and_then(|mut file| io::copy(&mut file, &mut writer));
+            let _ = fs::remove_file(&path);
+            copied?;
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        io::Write,
+        sync::{Arc, Mutex},
+    };
+
+    use rustsat::{
+        lit,
+        solvers::{Solve, SolverResult, SolverState, StateError},
+    };
+
+    use super::ProofFormat;
+    use crate::CaDiCaL;
+
+    /// A writer into a shared buffer
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn unsat_proof(format: ProofFormat) -> Vec<u8> {
+        let buf = SharedBuf::default();
+        {
+            let mut solver = CaDiCaL::default();
+            solver.trace_proof_to(buf.clone(), format).unwrap();
+            assert!(solver.is_tracing_proof());
+            solver.add_binary(lit![0], lit![1]).unwrap();
+            solver.add_binary(!lit![0], lit![1]).unwrap();
+            solver.add_binary(lit![0], !lit![1]).unwrap();
+            solver.add_binary(!lit![0], !lit![1]).unwrap();
+            assert_eq!(solver.solve().unwrap(), SolverResult::Unsat);
+            // proof is closed on drop
+        }
+        // the solver dropped its clone of the buffer
+        Arc::try_unwrap(buf.0).unwrap().into_inner().unwrap()
+    }
+
+    #[test]
+    fn drat() {
+        let proof = unsat_proof(ProofFormat::Drat { binary: false });
+        let proof = String::from_utf8(proof).unwrap();
+        // the proof derives the empty clause
+        assert!(proof.lines().any(|line| line.trim() == "0"));
+        assert!(!unsat_proof(ProofFormat::Drat { binary: true }).is_empty());
+    }
+
+    #[test]
+    fn lrat() {
+        let proof = unsat_proof(ProofFormat::Lrat { binary: false });
+        let proof = String::from_utf8(proof).unwrap();
+        // the proof derives the empty clause with a clause id
+        assert!(proof.lines().any(|line| {
+            let tokens: Vec<_> = line.split_whitespace().collect();
+            tokens.len() > 2 && tokens[0].parse::<u64>().is_ok() && tokens[1] == "0"
+        }));
+    }
+
+    #[test]
+    fn frat() {
+        let proof = unsat_proof(ProofFormat::Frat { binary: false });
+        let proof = String::from_utf8(proof).unwrap();
+        // the proof adds the empty clause
+        assert!(proof.lines().any(|line| {
+            let tokens: Vec<_> = line.split_whitespace().collect();
+            tokens.len() > 2 && tokens[0] == "a" && tokens[2] == "0"
+        }));
+    }
+
+    #[test]
+    fn after_input() {
+        let mut solver = CaDiCaL::default();
+        solver.add_unit(lit![0]).unwrap();
+        assert_eq!(
+            solver
+                .trace_proof_to(SharedBuf::default(), ProofFormat::Drat { binary: false })
+                .unwrap_err()
+                .downcast::<StateError>()
+                .unwrap(),
+            StateError {
+                required_state: SolverState::Configuring,
+                actual_state: SolverState::Input
+            }
+        );
+    }
+
+    #[test]
+    fn second_trace() {
+        let buf = SharedBuf::default();
+        let mut solver = CaDiCaL::default();
+        solver
+            .trace_proof_to(buf.clone(), ProofFormat::Drat { binary: false })
+            .unwrap();
+        assert!(solver
+            .trace_proof_to(SharedBuf::default(), ProofFormat::Drat { binary: true })
+            .is_err());
+        // the first proof is still traced
+        assert!(solver.is_tracing_proof());
+        solver.add_binary(lit![0], lit![1]).unwrap();
+        solver.add_binary(!lit![0], lit![1]).unwrap();
+        solver.add_binary(lit![0], !lit![1]).unwrap();
+        solver.add_binary(!lit![0], !lit![1]).unwrap();
+        assert_eq!(solver.solve().unwrap(), SolverResult::Unsat);
+        solver.close_proof().unwrap();
+        drop(solver);
+        let proof =
+            String::from_utf8(Arc::try_unwrap(buf.0).unwrap().into_inner().unwrap()).unwrap();
+        assert!(proof.lines().any(|line| line.trim() == "0"));
+    }
+}
@@ -0,0 +1,501 @@
+//! # External Propagators (IPASIR-UP)
+//!
+//! Interface for connecting user propagators to CaDiCaL via the
+//! [IPASIR-UP](https://doi.org/10.4230/LIPIcs.SAT.2023.8) interface,
+//! available from CaDiCaL 1.9.2. An external propagator is notified about
+//! assignments to _observed_ variables and can propagate literals, add
+//! clauses and reject models found by the solver. This allows for
+//! implementing lazy theory reasoning in Rust.
+//!
+//! ## Example
+//!
+//! A propagator lazily enforcing that variables 0 and 1 are not both true.
+//!
+//! ```
+//! use rustsat::{
+//!     clause, lit,
+//!     solvers::{Solve, SolverResult},
+//!     types::{Clause, Lit, TernaryVal},
+//!     var,
+//! };
+//! use rustsat_cadical::{CaDiCaL, ExternalPropagator};
+//!
+//! #[derive(Default)]
+//! struct NotBoth {
+//!     pending: Option<Clause>,
+//! }
+//!
+//! impl ExternalPropagator for NotBoth {
+//!     fn notify_assignment(&mut self, _lit: Lit, _is_fixed: bool) {}
+//!     fn notify_new_decision_level(&mut self) {}
+//!     fn notify_backtrack(&mut self, _new_level: usize) {}
+//!     fn check_found_model(&mut self, model: &[Lit]) -> bool {
+//!         if model.contains(&lit![0]) && model.contains(&lit![1]) {
+//!             self.pending = Some(clause![!lit![0], !lit![1]]);
+//!             return false;
+//!         }
+//!         true
+//!     }
+//!     fn add_reason_clause(&mut self, _propagated_lit: Lit) -> Clause {
+//!         unreachable!("never propagates")
+//!     }
+//!     fn add_external_clause(&mut self) -> Option<Clause> {
+//!         self.pending.take()
+//!     }
+//! }
+//!
+//! let mut solver = CaDiCaL::default();
+//! solver.connect_propagator(NotBoth::default()).unwrap();
+//! solver.add_observed_var(var![0]).unwrap();
+//! solver.add_observed_var(var![1]).unwrap();
+//! solver.add_unit(lit![0]).unwrap();
+//! solver.add_binary(lit![1], lit![2]).unwrap();
+//! assert_eq!(solver.solve().unwrap(), SolverResult::Sat);
+//! assert_eq!(solver.lit_val(lit![1]).unwrap(), TernaryVal::False);
+//! ```
+
+use core::ffi::{c_int, c_void};
+
+use rustsat::types::{Clause, Lit, Var};
+use thiserror::Error;
+
+use super::{ffi, ffi::abort_on_panic, handle_oom, CaDiCaL};
+
+/// Trait for external propagators connected to CaDiCaL. All literals passed
+/// to and from the propagator must be over observed variables.
+///
+/// Propagated literals and external clauses are forwarded to CaDiCaL one
+/// literal at a time, the propagator only needs to return complete clauses.
+pub trait ExternalPropagator {
+    /// Whether the propagator only checks complete assignments. Lazy
+    /// propagators are only called via [`ExternalPropagator::check_found_model`]
+    /// and [`ExternalPropagator::add_external_clause`].
+    fn is_lazy(&self) -> bool {
+        false
+    }
+
+    /// Notifies the propagator about the assignment of an observed literal.
+    /// `is_fixed` is true if the literal is assigned at the root level.
+    fn notify_assignment(&mut self, lit: Lit, is_fixed: bool);
+
+    /// Notifies the propagator about a new decision level
+    fn notify_new_decision_level(&mut self);
+
+    /// Notifies the propagator that the solver backtracked to a given
+    /// decision level
+    fn notify_backtrack(&mut self, new_level: usize);
+
+    /// Checks a complete model found by the solver. If the model is rejected
+    /// by returning `false`, the propagator must provide a clause falsified
+    /// by the model via [`ExternalPropagator::add_external_clause`].
+    fn check_found_model(&mut self, model: &[Lit]) -> bool;
+
+    /// Lets the propagator make the next decision, [`None`] leaves the
+    /// decision to the solver
+    fn decide(&mut self) -> Option<Lit> {
+        None
+    }
+
+    /// Asks the propagator for a literal to propagate under the current
+    /// assignment, [`None`] if there is none
+    fn propagate(&mut self) -> Option<Lit> {
+        None
+    }
+
+    /// Gets the reason clause for a literal that was propagated by
+    /// [`ExternalPropagator::propagate`]. The clause must contain the
+    /// propagated literal and all other literals must be false.
+    fn add_reason_clause(&mut self, propagated_lit: Lit) -> Clause;
+
+    /// Gets a clause that the propagator wants to add to the solver, [`None`]
+    /// if there is none
+    fn add_external_clause(&mut self) -> Option<Clause>;
+}
+
+/// Error returned if an operation requires a connected external propagator
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("no external propagator is connected to the solver")]
+pub struct NoPropagator;
+
+/// An external propagator connected to CaDiCaL, together with the clause
+/// that is currently forwarded to the solver
+pub(crate) struct PropagatorData {
+    prop: Box<dyn ExternalPropagator + Send>,
+    /// The C++ propagator object forwarding to this data
+    handle: *mut c_void,
+    /// The clause currently being passed to the solver, zero-terminated
+    clause: Vec<c_int>,
+    /// The next literal of the clause to pass to the solver
+    pos: usize,
+}
+
+impl PropagatorData {
+    fn load_clause(&mut self, clause: Clause) {
+        self.clause.clear();
+        self.clause.extend(clause.iter().map(|lit| lit.to_ipasir()));
+        self.clause.push(0);
+        self.pos = 0;
+    }
+
+    /// Returns the next literal of the current clause and resets the clause
+    /// after the terminating zero
+    fn next_clause_lit(&mut self) -> c_int {
+        let lit = self.clause.get(self.pos).copied().unwrap_or(0);
+        self.pos += 1;
+        if lit == 0 {
+            self.clause.clear();
+            self.pos = 0;
+        }
+        lit
+    }
+}
+
+impl CaDiCaL<'_, '_> {
+    /// Connects an external propagator to the solver, replacing a
+    /// previously connected one. Observed variables need to be added with
+    /// [`CaDiCaL::add_observed_var`] afterwards.
+    ///
+    /// To access the state of the propagator after solving, either
+    /// disconnect it with [`CaDiCaL::disconnect_propagator`] or share the
+    /// state, e.g., via [`std::sync::Arc`]. The propagator needs to be
+    /// [`Send`] since the solver can be sent to other threads.
+    ///
+    /// If a method of the propagator panics, the process is aborted, since
+    /// the panic cannot unwind through CaDiCaL.
+    ///
+    /// # Errors
+    ///
+    /// If CaDiCaL runs out of memory, returns [`rustsat::OutOfMemory`].
+    pub fn connect_propagator<P>(&mut self, prop: P) -> anyhow::Result<()>
+    where
+        P: ExternalPropagator + Send + 'static,
+    {
+        self.disconnect_propagator();
+        let mut data = Box::new(PropagatorData {
+            prop: Box::new(prop),
+            handle: std::ptr::null_mut(),
+            clause: vec![],
+            pos: 0,
+        });
+        let callbacks = ffi::CCaDiCaLPropagator {
+            data: data.as_mut() as *mut PropagatorData as *mut c_void,
+            is_lazy: data.prop.is_lazy(),
+            notify_assignment,
+            notify_new_decision_level,
+            notify_backtrack,
+            cb_check_found_model: check_found_model,
+            cb_decide: decide,
+            cb_propagate: propagate,
+            cb_add_reason_clause_lit: add_reason_clause_lit,
+            cb_has_external_clause: has_external_clause,
+            cb_add_external_clause_lit: add_external_clause_lit,
+        };
+        let handle = unsafe { ffi::ccadical_connect_propagator(self.handle, callbacks) };
+        if handle.is_null() {
+            return anyhow::Context::context(
+                Err(rustsat::OutOfMemory::ExternalApi),
+                "cadical out of memory",
+            );
+        }
+        data.handle = handle;
+        self.propagator = Some(data);
+        Ok(())
+    }
+
+    /// Disconnects the external propagator, if one is connected, and returns
+    /// it
+    pub fn disconnect_propagator(&mut self) -> Option<Box<dyn ExternalPropagator + Send>> {
+        let data = self.propagator.take()?;
+        unsafe { ffi::ccadical_disconnect_propagator(self.handle, data.handle) };
+        Some(data.prop)
+    }
+
+    /// Checks whether an external propagator is connected
+    pub fn has_propagator(&self) -> bool {
+        self.propagator.is_some()
+    }
+
+    /// Marks a variable as observed by the external propagator. The variable
+    /// is frozen while it is observed.
+    ///
+    /// # Errors
+    ///
+    /// - [`NoPropagator`] if no propagator is connected
+    /// - If CaDiCaL runs out of memory, returns [`rustsat::OutOfMemory`]
+    pub fn add_observed_var(&mut self, var: Var) -> anyhow::Result<()> {
+        anyhow::ensure!(self.propagator.is_some(), NoPropagator);
+        handle_oom!(unsafe { ffi::ccadical_add_observed_var(self.handle, var.to_ipasir()) });
+        Ok(())
+    }
+
+    /// Removes a variable from the observed variables
+    ///
+    /// # Errors
+    ///
+    /// [`NoPropagator`] if no propagator is connected.
+    pub fn remove_observed_var(&mut self, var: Var) -> anyhow::Result<()> {
+        anyhow::ensure!(self.propagator.is_some(), NoPropagator);
+        unsafe { ffi::ccadical_remove_observed_var(self.handle, var.to_ipasir()) };
+        Ok(())
+    }
+
+    /// Removes all variables from the observed variables
+    ///
+    /// # Errors
+    ///
+    /// [`NoPropagator`] if no propagator is connected.
+    pub fn reset_observed_vars(&mut self) -> anyhow::Result<()> {
+        anyhow::ensure!(self.propagator.is_some(), NoPropagator);
+        unsafe { ffi::ccadical_reset_observed_vars(self.handle) };
+        Ok(())
+    }
+
+    /// Checks whether an observed literal is currently assigned as a
+    /// decision
+    ///
+    /// # Errors
+    ///
+    /// [`NoPropagator`] if no propagator is connected.
+    pub fn is_decision(&self, lit: Lit) -> anyhow::Result<bool> {
+        anyhow::ensure!(self.propagator.is_some(), NoPropagator);
+        Ok(unsafe { ffi::ccadical_is_decision(self.handle, lit.to_ipasir()) })
+    }
+}
+
+// Raw callbacks forwarding to the external propagator
+
+fn prop_data<'a>(ptr: *mut c_void) -> &'a mut PropagatorData {
+    unsafe { &mut *(ptr as *mut PropagatorData) }
+}
+
+fn from_ipasir(lit: c_int) -> Lit {
+    Lit::from_ipasir(lit).expect("invalid literal from CaDiCaL propagator interface")
+}
+
+pub(crate) extern "C" fn notify_assignment(ptr: *mut c_void, lit: c_int, is_fixed: bool) {
+    abort_on_panic(|| {
+        prop_data(ptr)
+            .prop
+            .notify_assignment(from_ipasir(lit), is_fixed)
+    })
+}
+
+pub(crate) extern "C" fn notify_new_decision_level(ptr: *mut c_void) {
+    abort_on_panic(|| prop_data(ptr).prop.notify_new_decision_level())
+}
+
+pub(crate) extern "C" fn notify_backtrack(ptr: *mut c_void, new_level: usize) {
+    abort_on_panic(|| prop_data(ptr).prop.notify_backtrack(new_level))
+}
+
+pub(crate) extern "C" fn check_found_model(
+    ptr: *mut c_void,
+    model: *const c_int,
+    len: usize,
+) -> bool {
+    abort_on_panic(|| {
+        let model: Vec<Lit> = unsafe { std::slice::from_raw_parts(model, len) }
+            .iter()
+            .map(|&lit| from_ipasir(lit))
+            .collect();
+        prop_data(ptr).prop.check_found_model(&model)
+    })
+}
+
+pub(crate) extern "C" fn decide(ptr: *mut c_void) -> c_int {
+    abort_on_panic(|| {
+        prop_data(ptr)
+            .prop
+            .decide()
+            .map_or(0, |lit| lit.to_ipasir())
+    })
+}
+
+pub(crate) extern "C" fn propagate(ptr: *mut c_void) -> c_int {
+    abort_on_panic(|| {
+        prop_data(ptr)
+            .prop
+            .propagate()
+            .map_or(0, |lit| lit.to_ipasir())
+    })
+}
+
+pub(crate) extern "C" fn add_reason_clause_lit(ptr: *mut c_void, propagated_lit: c_int) -> c_int {
+    abort_on_panic(|| {
+        let data = prop_data(ptr);
+        if data.clause.is_empty() {
+            let reason = data.prop.add_reason_clause(from_ipasir(propagated_lit));
+            data.load_clause(reason);
+        }
+        data.next_clause_lit()
+    })
+}
+
+pub(crate) extern "C" fn has_external_clause(ptr: *mut c_void) -> bool {
+    abort_on_panic(|| {
+        let data = prop_data(ptr);
+        if !data.clause.is_empty() {
+            return true;
+        }
+        match data.prop.add_external_clause() {
+            Some(clause) => {
+                data.load_clause(clause);
+                true
+            }
+            None => false,
+        }
+    })
+}
+
+pub(crate) extern "C" fn add_external_clause_lit(ptr: *mut c_void) -> c_int {
+    abort_on_panic(|| prop_data(ptr).next_clause_lit())
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use rustsat::{
+        clause, lit,
+        solvers::{Solve, SolverResult},
+        types::{Clause, Lit, TernaryVal},
+        var,
+    };
+
+    use super::{ExternalPropagator, NoPropagator};
+    use crate::CaDiCaL;
+
+    /// Propagator enforcing that at most one of the observed literals is
+    /// true, by propagation or by rejecting models
+    struct AtMostOne {
+        lits: Vec<Lit>,
+        lazy: bool,
+        /// Assigned literals with the decision level they were assigned at
+        trail: Vec<(Lit, usize)>,
+        level: usize,
+        pending: Option<Clause>,
+        n_checks: Arc<Mutex<usize>>,
+    }
+
+    impl AtMostOne {
+        fn new(lits: Vec<Lit>, lazy: bool, n_checks: Arc<Mutex<usize>>) -> Self {
+            AtMostOne {
+                lits,
+                lazy,
+                trail: vec![],
+                level: 0,
+                pending: None,
+                n_checks,
+            }
+        }
+
+        fn true_lit(&self) -> Option<Lit> {
+            self.trail
+                .iter()
+                .map(|&(lit, _)| lit)
+                .find(|lit| self.lits.contains(lit))
+        }
+    }
+
+    impl ExternalPropagator for AtMostOne {
+        fn is_lazy(&self) -> bool {
+            self.lazy
+        }
+
+        fn notify_assignment(&mut self, lit: Lit, is_fixed: bool) {
+            self.trail
+                .push((lit, if is_fixed { 0 } else { self.level }));
+        }
+
+        fn notify_new_decision_level(&mut self) {
+            self.level += 1;
+        }
+
+        fn notify_backtrack(&mut self, new_level: usize) {
+            self.level = new_level;
+            self.trail.retain(|&(_, level)| level <= new_level);
+        }
+
+        fn check_found_model(&mut self, model: &[Lit]) -> bool {
+            *self.n_checks.lock().unwrap() += 1;
+            let trues: Vec<Lit> = self
+                .lits
+                .iter()
+                .copied()
+                .filter(|lit| model.contains(lit))
+                .collect();
+            if trues.len() > 1 {
+                self.pending = Some(clause![!trues[0], !trues[1]]);
+                return false;
+            }
+            true
+        }
+
+        fn propagate(&mut self) -> Option<Lit> {
+            let tl = self.true_lit()?;
+            self.lits
+                .iter()
+                .find(|&&lit| lit != tl && !self.trail.iter().any(|&(l, _)| l.var() == lit.var()))
+                .map(|&lit| !lit)
+        }
+
+        fn add_reason_clause(&mut self, propagated_lit: Lit) -> Clause {
+            let tl = self.true_lit().expect("propagation without reason");
+            clause![propagated_lit, !tl]
+        }
+
+        fn add_external_clause(&mut self) -> Option<Clause> {
+            self.pending.take()
+        }
+    }
+
+    fn at_most_one(lazy: bool) {
+        let n_checks = Arc::new(Mutex::new(0));
+        let mut solver = CaDiCaL::default();
+        solver
+            .connect_propagator(AtMostOne::new(
+                vec![lit![0], lit![1], lit![2]],
+                lazy,
+                n_checks.clone(),
+            ))
+            .unwrap();
+        for idx in 0..3 {
+            solver.add_observed_var(var![idx]).unwrap();
+        }
+        solver.add_unit(lit![0]).unwrap();
+        solver.add_ternary(lit![1], lit![2], lit![3]).unwrap();
+        assert_eq!(solver.solve().unwrap(), SolverResult::Sat);
+        assert_eq!(solver.lit_val(lit![1]).unwrap(), TernaryVal::False);
+        assert_eq!(solver.lit_val(lit![2]).unwrap(), TernaryVal::False);
+        assert_eq!(solver.lit_val(lit![3]).unwrap(), TernaryVal::True);
+        assert!(*n_checks.lock().unwrap() > 0);
+        solver.add_unit(lit![1]).unwrap();
+        assert_eq!(solver.solve().unwrap(), SolverResult::Unsat);
+        assert!(solver.disconnect_propagator().is_some());
+        assert!(!solver.has_propagator());
+    }
+
+    #[test]
+    fn eager() {
+        at_most_one(false)
+    }
+
+    #[test]
+    fn lazy() {
+        at_most_one(true)
+    }
+
+    #[test]
+    fn no_propagator() {
+        let mut solver = CaDiCaL::default();
+        assert_eq!(
+            solver
+                .add_observed_var(var![0])
+                .unwrap_err()
+                .downcast::<NoPropagator>()
+                .unwrap(),
+            NoPropagator
+        );
+        assert!(solver.disconnect_propagator().is_none());
+    }
+}
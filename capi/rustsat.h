@@ -47,7 +47,8 @@ typedef enum MaybeError {
  * Implementation of the binary adder tree totalizer encoding \[1\].
  * The implementation is incremental as extended in \[2\].
  * The implementation is based on a node database.
- * For now, this implementation only supports upper bounding.
+ * Upper and lower bounds share the same output literals, i.e., encoding both
+ * bounds does not introduce additional variables.
  *
  * # References
  *
@@ -65,6 +66,11 @@ typedef struct DbTotalizer DbTotalizer;
  * encoding resets the entire encoding and with the next encode and entirely
  * new encoding will be returned.
  *
+ * Lower bounds are encoded as upper bounds on the negated input literals
+ * (`sum of lits >= lb` iff `sum of negated lits <= weight_sum - lb`) in a
+ * second DPW that is built when lower bounding is first requested. Both
+ * directions use the same precision.
+ *
  * ## References
  *
  * - \[1\] Tobias Paxian and Sven Reimer and Bernd Becker: _Dynamic Polynomial
@@ -97,6 +103,17 @@ extern "C" {
  */
 enum MaybeError dpw_add(struct DynamicPolyWatchdog *dpw, int lit, size_t weight);
 
+/**
+ * Gets the next greater lower bound value that can be encoded without
+ * setting tares. This is used for coarse convergence.
+ *
+ * # Safety
+ *
+ * `dpw` must be a return value of [`dpw_new`] that [`dpw_drop`] has
+ * not yet been called on.
+ */
+size_t dpw_coarse_lb(struct DynamicPolyWatchdog *dpw, size_t lb);
+
 /**
  * Gets the next smaller upper bound value that can be encoded without
  * setting tares. This is used for coarse convergence.
@@ -118,6 +135,36 @@ size_t dpw_coarse_ub(struct DynamicPolyWatchdog *dpw, size_t ub);
  */
 void dpw_drop(struct DynamicPolyWatchdog *dpw);
 
+/**
+ * Lazily builds the _change in_ pseudo-boolean encoding to enable
+ * lower bounds from within the range. A change might only be a change
+ * in bounds, the [`DynamicPolyWatchdog`] does not support adding
+ * literals at the moment.
+ *
+ * The min and max bounds are inclusive. After a call to
+ * [`dpw_encode_lb`] with `min_bound=2` and `max_bound=4`, bounds
+ * satisfying `2 <= bound <= 4` can be enforced.
+ *
+ * Clauses are returned via the `collector`. The `collector` function should expect
+ * clauses to be passed similarly to `ipasir_add`, as a 0-terminated sequence of literals
+ * where the literals are passed as the first argument and the `collector_data` as a
+ * second.
+ *
+ * `n_vars_used` must be the number of variables already used and will be incremented by
+ * the number of variables used up in the encoding.
+ *
+ * # Safety
+ *
+ * `dpw` must be a return value of [`dpw_new`] that [`dpw_drop`] has
+ * not yet been called on.
+ */
+void dpw_encode_lb(struct DynamicPolyWatchdog *dpw,
+                   size_t min_bound,
+                   size_t max_bound,
+                   int *n_vars_used,
+                   CClauseCollector collector,
+                   void *collector_data);
+
 /**
  * Lazily builds the _change in_ pseudo-boolean encoding to enable
  * upper bounds from within the range. A change might only be a change
@@ -148,6 +195,27 @@ void dpw_encode_ub(struct DynamicPolyWatchdog *dpw,
                    CClauseCollector collector,
                    void *collector_data);
 
+/**
+ * Returns assumptions/units for enforcing a lower bound (`sum of lits
+ * >= lb`). Make sure that [`dpw_encode_lb`] has been called adequately
+ * and nothing has been called afterwards, otherwise
+ * [`MaybeError::NotEncoded`] will be returned. If `lb` is larger than
+ * the sum of all input weights, [`MaybeError::Unsat`] is returned.
+ *
+ * Assumptions are returned via the collector callback. There is _no_
+ * terminating zero, all assumptions are passed when [`dpw_enforce_lb`]
+ * returns.
+ *
+ * # Safety
+ *
+ * `dpw` must be a return value of [`dpw_new`] that [`dpw_drop`] has
+ * not yet been called on.
+ */
+enum MaybeError dpw_enforce_lb(struct DynamicPolyWatchdog *dpw,
+                               size_t lb,
+                               CAssumpCollector collector,
+                               void *collector_data);
+
 /**
  * Returns assumptions/units for enforcing an upper bound (`sum of lits
  * <= ub`). Make sure that [`dpw_encode_ub`] has been called adequately
@@ -341,7 +341,10 @@ pub mod encodings {
         use std::ffi::{c_int, c_void};
 
         use rustsat::{
-            encodings::pb::{BoundUpper, BoundUpperIncremental, DynamicPolyWatchdog},
+            encodings::pb::{
+                BoundLower, BoundLowerIncremental, BoundUpper, BoundUpperIncremental,
+                DynamicPolyWatchdog,
+            },
             types::Lit,
         };
 
@@ -476,6 +479,96 @@ pub mod encodings {
             ret
         }
 
+        /// Lazily builds the _change in_ pseudo-boolean encoding to enable
+        /// lower bounds from within the range. A change might only be a change
+        /// in bounds, the [`DynamicPolyWatchdog`] does not support adding
+        /// literals at the moment.
+        ///
+        /// The min and max bounds are inclusive. After a call to
+        /// [`dpw_encode_lb`] with `min_bound=2` and `max_bound=4`, bounds
+        /// satisfying `2 <= bound <= 4` can be enforced.
+        ///
+        /// Clauses are returned via the `collector`. The `collector` function should expect
+        /// clauses to be passed similarly to `ipasir_add`, as a 0-terminated sequence of literals
+        /// where the literals are passed as the first argument and the `collector_data` as a
+        /// second.
+        ///
+        /// `n_vars_used` must be the number of variables already used and will be incremented by
+        /// the number of variables used up in the encoding.
+        ///
+        /// # Safety
+        ///
+        /// `dpw` must be a return value of [`dpw_new`] that [`dpw_drop`] has
+        /// not yet been called on.
+        #[no_mangle]
+        pub unsafe extern "C" fn dpw_encode_lb(
+            dpw: *mut DynamicPolyWatchdog,
+            min_bound: usize,
+            max_bound: usize,
+            n_vars_used: &mut c_int,
+            collector: CClauseCollector,
+            collector_data: *mut c_void,
+        ) {
+            assert!(min_bound <= max_bound);
+            let mut collector = ClauseCollector::new(collector, collector_data);
+            let mut var_manager = VarManager::new(n_vars_used);
+            let mut boxed = unsafe { Box::from_raw(dpw) };
+            boxed
+                .encode_lb_change(min_bound..=max_bound, &mut collector, &mut var_manager)
+                .expect("clause collector returned out of memory");
+            Box::into_raw(boxed);
+        }
+
+        /// Returns assumptions/units for enforcing a lower bound (`sum of lits
+        /// >= lb`). Make sure that [`dpw_encode_lb`] has been called adequately
+        /// and nothing has been called afterwards, otherwise
+        /// [`MaybeError::NotEncoded`] will be returned. If `lb` is larger than
+        /// the sum of all input weights, [`MaybeError::Unsat`] is returned.
+        ///
+        /// Assumptions are returned via the collector callback. There is _no_
+        /// terminating zero, all assumptions are passed when [`dpw_enforce_lb`]
+        /// returns.
+        ///
+        /// # Safety
+        ///
+        /// `dpw` must be a return value of [`dpw_new`] that [`dpw_drop`] has
+        /// not yet been called on.
+        #[no_mangle]
+        pub unsafe extern "C" fn dpw_enforce_lb(
+            dpw: *mut DynamicPolyWatchdog,
+            lb: usize,
+            collector: CAssumpCollector,
+            collector_data: *mut c_void,
+        ) -> MaybeError {
+            let boxed = unsafe { Box::from_raw(dpw) };
+            let ret = match boxed.enforce_lb(lb) {
+                Ok(assumps) => {
+                    assumps.into_iter().for_each(|l| {
+                        collector(l.to_ipasir(), collector_data);
+                    });
+                    MaybeError::Ok
+                }
+                Err(err) => err.into(),
+            };
+            Box::into_raw(boxed);
+            ret
+        }
+
+        /// Gets the next greater lower bound value that can be encoded without
+        /// setting tares. This is used for coarse convergence.
+        ///
+        /// # Safety
+        ///
+        /// `dpw` must be a return value of [`dpw_new`] that [`dpw_drop`] has
+        /// not yet been called on.
+        #[no_mangle]
+        pub unsafe extern "C" fn dpw_coarse_lb(dpw: *mut DynamicPolyWatchdog, lb: usize) -> usize {
+            let boxed = unsafe { Box::from_raw(dpw) };
+            let ret = boxed.coarse_lb(lb);
+            Box::into_raw(boxed);
+            ret
+        }
+
         /// Set the precision at which to build the encoding at. With `divisor = 8` the encoding will
         /// effectively be built such that the weight of every input literal is divided by `divisor`
         /// (interger division, rounding down). Divisor values must be powers of 2. After building
@@ -680,6 +773,89 @@ pub mod encodings {
                 })
                 .success();
             }
+
+            #[test]
+            fn basic_lb() {
+                (assert_c! {
+                    #include <assert.h>
+                    #include "rustsat.h"
+
+                    void assump_counter(int lit, void *data) {
+                        assert(lit);
+                        int *cnt = (int *) data;
+                        (*cnt)++;
+                    }
+
+                    void clause_counter(int lit, void *data) {
+                        if (!lit) {
+                            int *cnt = (int *)data;
+                            (*cnt)++;
+                        }
+                    }
+
+                    int main() {
+                        DynamicPolyWatchdog *dpw = dpw_new();
+                        assert(dpw_add(dpw, 1, 1) == Ok);
+                        assert(dpw_add(dpw, 2, 1) == Ok);
+                        assert(dpw_add(dpw, 3, 2) == Ok);
+                        assert(dpw_add(dpw, 4, 2) == Ok);
+                        int n_used = 4;
+                        int n_clauses = 0;
+                        dpw_encode_lb(dpw, 0, 6, &n_used, &clause_counter, &n_clauses);
+                        assert(n_used == 13);
+                        assert(n_clauses == 13);
+                        int n_assumps = 0;
+                        assert(dpw_enforce_lb(dpw, 7, &assump_counter, &n_assumps) == Unsat);
+                        assert(dpw_enforce_lb(dpw, 4, &assump_counter, &n_assumps) == Ok);
+                        assert(n_assumps > 0);
+                        assert(dpw_add(dpw, 5, 1) == InvalidState);
+                        dpw_drop(dpw);
+                        return 0;
+                    }
+                })
+                .success();
+            }
+
+            #[test]
+            fn coarse_convergence_lb() {
+                (assert_c! {
+                    #include <assert.h>
+                    #include "rustsat.h"
+
+                    void assump_counter(int lit, void *data) {
+                        assert(lit);
+                        int *cnt = (int *) data;
+                        (*cnt)++;
+                    }
+
+                    void clause_counter(int lit, void *data) {
+                        if (!lit) {
+                            int *cnt = (int *)data;
+                            (*cnt)++;
+                        }
+                    }
+
+                    int main() {
+                        DynamicPolyWatchdog *dpw = dpw_new();
+                        assert(dpw_add(dpw, 1, 5) == Ok);
+                        assert(dpw_add(dpw, 2, 3) == Ok);
+                        assert(dpw_add(dpw, 3, 8) == Ok);
+                        assert(dpw_add(dpw, 4, 7) == Ok);
+                        int n_used = 4;
+                        int n_clauses = 0;
+                        dpw_encode_lb(dpw, 1, 23, &n_used, &clause_counter, &n_clauses);
+                        for (size_t lb = 1; lb < 17; lb++) {
+                            size_t coarse_lb = dpw_coarse_lb(dpw, lb);
+                            assert(coarse_lb >= lb);
+                            if (lb % 8 == 0) assert(coarse_lb == lb);
+                            int n_assumps = 0;
+                            assert(dpw_enforce_lb(dpw, coarse_lb, &assump_counter, &n_assumps) == Ok);
+                            assert(n_assumps == 1);
+                        }
+                    }
+                })
+                .success();
+            }
         }
     }
 }
@@ -48,7 +48,12 @@ fn build(repo: &str, branch: &str, reference: &str) {
     let mut kissat_dir_str = out_dir.clone();
     kissat_dir_str.push_str("/kissat");
     let kissat_dir = Path::new(&kissat_dir_str);
+    // Glue code compiled together with Kissat, rebuild if it changed
+    let glue_path = Path::new("csrc").join("proof.c");
+    let glue_copy = Path::new(&out_dir).join("proof.c");
+    let glue_changed = fs::read(&glue_path).ok() != fs::read(&glue_copy).ok();
     if update_repo(kissat_dir, repo, branch, reference)
+        || glue_changed
         || !Path::new(&out_dir).join("libkissat.a").exists()
     {
         // Repo changed, rebuild
@@ -108,7 +113,9 @@ fn build(repo: &str, branch: &str, reference: &str) {
             .include(kissat_dir.join("src"))
             .warnings(false)
             .files(src_files)
+            .file(&glue_path)
             .compile("kissat");
+        fs::copy(&glue_path, &glue_copy).expect("Failed to copy kissat glue code");
     };
 }
 
@@ -0,0 +1,45 @@
+// Glue code for tracing proofs through the Kissat library API
+
+#include "file.h"
+#include "internal.h"
+#include "proof.h"
+
+#include <stdbool.h>
+#include <stdlib.h>
+
+// Opens the proof file at `path` and starts tracing a DRAT proof to it.
+// Returns a handle to the opened file or a null pointer if the file could not
+// be opened or Kissat was built without proof support.
+void *kissat_rustsat_trace_proof (kissat *solver, const char *path,
+                                  bool binary) {
+#ifdef NPROOFS
+  (void) solver;
+  (void) path;
+  (void) binary;
+  return 0;
+#else
+  file *proof_file = malloc (sizeof *proof_file);
+  if (!proof_file)
+    return 0;
+  if (!kissat_open_to_write_file (proof_file, path)) {
+    free (proof_file);
+    return 0;
+  }
+  kissat_init_proof (solver, proof_file, binary);
+  return proof_file;
+#endif
+}
+
+// Flushes and releases the proof and closes the file opened by
+// `kissat_rustsat_trace_proof`.
+void kissat_rustsat_close_proof (kissat *solver, void *proof_file) {
+#ifdef NPROOFS
+  (void) solver;
+  (void) proof_file;
+#else
+  if (solver->proof)
+    kissat_release_proof (solver);
+  kissat_close_file (proof_file);
+  free (proof_file);
+#endif
+}
@@ -22,6 +22,11 @@
 //!
 //! Without any features selected, the newest version will be used.
 //! If conflicting Kissat versions are requested, the newest requested version will be selected.
+//!
+//! ## Proof Tracing
+//!
+//! DRAT proofs of unsatisfiability can be traced to a file or writer. See the
+//! [`proof`] module for details.
 
 #![warn(missing_docs)]
 
@@ -39,6 +44,9 @@ use rustsat::{
 };
 use thiserror::Error;
 
+pub mod proof;
+pub use proof::ProofFormat;
+
 /// Fatal error returned if the Kissat API returns an invalid value
 #[derive(Error, Clone, Copy, PartialEq, Eq, Debug)]
 #[error("kissat c-api returned an invalid value: {api_call} -> {value}")]
@@ -77,6 +85,7 @@ pub struct Kissat<'term> {
     state: InternalSolverState,
     terminate_cb: OptTermCallbackStore<'term>,
     stats: SolverStats,
+    proof: Option<proof::ProofTrace>,
 }
 
 unsafe impl Send for Kissat<'_> {}
@@ -88,6 +97,7 @@ impl Default for Kissat<'_> {
             state: Default::default(),
             terminate_cb: Default::default(),
             stats: Default::default(),
+            proof: None,
         };
         let quiet = CString::new("quiet").unwrap();
         unsafe { ffi::kissat_set_option(solver.handle, quiet.as_ptr(), 1) };
@@ -385,6 +395,7 @@ impl SolveStats for Kissat<'_> {
 
 impl Drop for Kissat<'_> {
     fn drop(&mut self) {
+        let _ = self.close_proof();
         unsafe { ffi::kissat_release(self.handle) }
     }
 }
@@ -532,6 +543,13 @@ mod ffi {
         pub fn kissat_print_statistics(solver: *mut KissatHandle);
         // This is from `error.h`
         pub fn kissat_call_function_instead_of_abort(abort: Option<extern "C" fn()>);
+        // Proof tracing glue code
+        pub fn kissat_rustsat_trace_proof(
+            solver: *mut KissatHandle,
+            path: *const c_char,
+            binary: bool,
+        ) -> *mut c_void;
+        pub fn kissat_rustsat_close_proof(solver: *mut KissatHandle, proof_file: *mut c_void);
     }
 
     // Raw callbacks forwarding to user callbacks
@@ -0,0 +1,269 @@
+//! # Proof Tracing
+//!
+//! Kissat can trace DRAT proofs of unsatisfiability in text or binary format.
+//! LRAT proofs are not supported by the Kissat versions available in this
+//! crate. Proof tracing needs to be enabled before any clauses are added to
+//! the solver. The proof is flushed and closed when [`Kissat::close_proof`] is
+//! called or the solver is dropped.
+//!
+//! ## Example Useage
+//!
+//! ```
+//! use rustsat::{lit, solvers::{Solve, SolverResult}};
+//! use rustsat_kissat::{Kissat, ProofFormat};
+//!
+//! let path = std::env::temp_dir().join("rustsat-kissat-doc-proof.drat");
+//! let mut solver = Kissat::default();
+//! solver.trace_proof(&path, ProofFormat::Drat { binary: false }).unwrap();
+//! solver.add_binary(lit![0], lit![1]).unwrap();
+//! solver.add_binary(!lit![0], lit![1]).unwrap();
+//! solver.add_binary(lit![0], !lit![1]).unwrap();
+//! solver.add_binary(!lit![0], !lit![1]).unwrap();
+//! assert_eq!(solver.solve().unwrap(), SolverResult::Unsat);
+//! solver.close_proof().unwrap();
+//! # std::fs::remove_file(&path).unwrap();
+//! ```
+
+use core::ffi::c_void;
+use std::{
+    ffi::CString,
+    fmt,
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use rustsat::solvers::{SolverState, StateError};
+
+use super::{ffi, InternalSolverState, Kissat};
+
+/// Counter for unique names of temporary proof files within the process
+static PROOF_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Proof formats that Kissat can trace
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofFormat {
+    /// DRAT proofs in text or binary format
+    Drat {
+        /// Whether to write the binary format
+        binary: bool,
+    },
+}
+
+impl fmt::Display for ProofFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProofFormat::Drat { binary: true } => write!(f, "binary drat"),
+            ProofFormat::Drat { binary: false } => write!(f, "drat"),
+        }
+    }
+}
+
+/// A proof that is currently traced
+pub(crate) struct ProofTrace {
+    /// The Kissat file the proof is written to
+    file: *mut c_void,
+    /// If the proof is traced via a temporary file, the writer to copy it to
+    /// when closing
+    forward: Option<(PathBuf, Box<dyn Write + Send>)>,
+}
+
+impl Kissat<'_> {
+    /// Starts tracing a proof to a file. Needs to be called before any
+    /// clauses are added to the solver.
+    ///
+    /// # Errors
+    ///
+    /// - [`StateError`] if the solver is not in configuring state
+    /// - If a proof is already being traced
+    /// - If the file cannot be created or Kissat was built without proof
+    ///   support
+    pub fn trace_proof<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        format: ProofFormat,
+    ) -> anyhow::Result<()> {
+        self.start_proof(path.as_ref(), format, None)
+    }
+
+    /// Starts tracing a proof to a writer. The proof is buffered in a
+    /// temporary file and copied to the writer when the proof is closed.
+    /// Needs to be called before any clauses are added to the solver. The
+    /// writer needs to be [`Send`], since the solver can be sent to other
+    /// threads.
+    ///
+    /// # Errors
+    ///
+    /// - [`StateError`] if the solver is not in configuring state
+    /// - If a proof is already being traced
+    /// - If the temporary file cannot be created or Kissat was built without
+    ///   proof support
+    pub fn trace_proof_to<W: Write + Send + 'static>(
+        &mut self,
+        writer: W,
+        format: ProofFormat,
+    ) -> anyhow::Result<()> {
+        let path = std::env::temp_dir().join(format!(
+            "rustsat-kissat-proof-{}-{}",
+            std::process::id(),
+            PROOF_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        self.start_proof(&path.clone(), format, Some((path, Box::new(writer))))
+    }
+
+    fn start_proof(
+        &mut self,
+        path: &Path,
+        format: ProofFormat,
+        forward: Option<(PathBuf, Box<dyn Write + Send>)>,
+    ) -> anyhow::Result<()> {
+        if self.state != InternalSolverState::Configuring {
+            return Err(StateError {
+                required_state: SolverState::Configuring,
+                actual_state: self.state.to_external(),
+            }
+            .into());
+        }
+        if self.proof.is_some() {
+            anyhow::bail!("a proof is already being traced");
+        }
+        let ProofFormat::Drat { binary } = format;
+        let c_path = CString::new(
+            path.to_str()
+                .ok_or_else(|| anyhow::anyhow!("proof path is not valid UTF-8"))?,
+        )?;
+        let file = unsafe { ffi::kissat_rustsat_trace_proof(self.handle, c_path.as_ptr(), binary) };
+        if file.is_null() {
+            anyhow::bail!("kissat could not open proof file {}", path.display());
+        }
+        self.proof = Some(ProofTrace { file, forward });
+        Ok(())
+    }
+
+    /// Checks whether a proof is currently traced
+    pub fn is_tracing_proof(&self) -> bool {
+        self.proof.is_some()
+    }
+
+    /// Flushes the proof and closes the file. Does nothing if no proof is
+    /// traced.
+    ///
+    /// # Errors
+    ///
+    /// If the proof cannot be copied to the writer it is traced to.
+    pub fn close_proof(&mut self) -> anyhow::Result<()> {
+        let Some(proof) = self.proof.take() else {
+            return Ok(());
+        };
+        unsafe { ffi::kissat_rustsat_close_proof(self.handle, proof.file) };
+        if let Some((path, mut writer)) = proof.forward {
+            let copied = File::open(&path).and_then(|mut file| io::copy(&mut file, &mut writer));
+            let _ = fs::remove_file(&path);
+            copied?;
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        io::Write,
+        sync::{Arc, Mutex},
+    };
+
+    use rustsat::{
+        lit,
+        solvers::{Solve, SolverResult, SolverState, StateError},
+    };
+
+    use super::ProofFormat;
+    use crate::Kissat;
+
+    /// A writer into a shared buffer
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn unsat_proof(format: ProofFormat) -> Vec<u8> {
+        let buf = SharedBuf::default();
+        {
+            let mut solver = Kissat::default();
+            solver.trace_proof_to(buf.clone(), format).unwrap();
+            assert!(solver.is_tracing_proof());
+            solver.add_binary(lit![0], lit![1]).unwrap();
+            solver.add_binary(!lit![0], lit![1]).unwrap();
+            solver.add_binary(lit![0], !lit![1]).unwrap();
+            solver.add_binary(!lit![0], !lit![1]).unwrap();
+            assert_eq!(solver.solve().unwrap(), SolverResult::Unsat);
+            // proof is closed on drop
+        }
+        // the solver dropped its clone of the buffer
+        Arc::try_unwrap(buf.0).unwrap().into_inner().unwrap()
+    }
+
+    #[test]
+    fn drat() {
+        let proof = unsat_proof(ProofFormat::Drat { binary: false });
+        let proof = String::from_utf8(proof).unwrap();
+        // the proof derives the empty clause
+        assert!(proof.lines().any(|line| line.trim() == "0"));
+    }
+
+    #[test]
+    fn binary_drat() {
+        assert!(!unsat_proof(ProofFormat::Drat { binary: true }).is_empty());
+    }
+
+    #[test]
+    fn after_input() {
+        let mut solver = Kissat::default();
+        solver.add_unit(lit![0]).unwrap();
+        assert_eq!(
+            solver
+                .trace_proof_to(SharedBuf::default(), ProofFormat::Drat { binary: false })
+                .unwrap_err()
+                .downcast::<StateError>()
+                .unwrap(),
+            StateError {
+                required_state: SolverState::Configuring,
+                actual_state: SolverState::Input
+            }
+        );
+    }
+
+    #[test]
+    fn second_trace() {
+        let buf = SharedBuf::default();
+        let mut solver = Kissat::default();
+        solver
+            .trace_proof_to(buf.clone(), ProofFormat::Drat { binary: false })
+            .unwrap();
+        assert!(solver
+            .trace_proof_to(SharedBuf::default(), ProofFormat::Drat { binary: true })
+            .is_err());
+        // the first proof is still traced
+        assert!(solver.is_tracing_proof());
+        solver.add_binary(lit![0], lit![1]).unwrap();
+        solver.add_binary(!lit![0], lit![1]).unwrap();
+        solver.add_binary(lit![0], !lit![1]).unwrap();
+        solver.add_binary(!lit![0], !lit![1]).unwrap();
+        assert_eq!(solver.solve().unwrap(), SolverResult::Unsat);
+        solver.close_proof().unwrap();
+        drop(solver);
+        let proof =
+            String::from_utf8(Arc::try_unwrap(buf.0).unwrap().into_inner().unwrap()).unwrap();
+        assert!(proof.lines().any(|line| line.trim() == "0"));
+    }
+}
@@ -1,6 +1,10 @@
 //! # Encodings for Common Constraint Types to CNF
 //!
-//! CNF encodings for cardinality and pseudo-boolean constraints.
+//! CNF encodings for cardinality, pseudo-boolean and XOR constraints, as
+//! well as integer variables and global constraints over finite-domain
+//! variables.
+
+use std::ops::{AddAssign, RangeBounds};
 
 use thiserror::Error;
 
@@ -9,7 +13,10 @@ use crate::types::{Clause, Lit};
 pub mod am1;
 pub mod atomics;
 pub mod card;
+pub mod global;
+pub mod int;
 pub mod pb;
+pub mod xor;
 
 /// Trait for collecting clauses. Mainly used when generating encodings and implemented by
 /// [`crate::instances::Cnf`], and solvers.
@@ -41,6 +48,16 @@ pub enum Error {
     Unsat,
 }
 
+/// Types of reification of a constraint `C` with an activation literal `a`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Reification {
+    /// Half reification, i.e., `a -> C`
+    #[default]
+    Half,
+    /// Full reification, i.e., `a <-> C`
+    Full,
+}
+
 /// Trait for encodings that track statistics.
 pub trait EncodeStats {
     /// Gets the number of clauses in the encoding
@@ -50,6 +67,42 @@ pub trait EncodeStats {
     fn n_vars(&self) -> u32;
 }
 
+/// Size of an encoding, as predicted by [`PredictStats`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EncodingStats {
+    /// The number of clauses
+    pub n_clauses: usize,
+    /// The number of auxiliary variables
+    pub n_vars: u32,
+}
+
+impl AddAssign for EncodingStats {
+    fn add_assign(&mut self, rhs: Self) {
+        self.n_clauses += rhs.n_clauses;
+        self.n_vars += rhs.n_vars;
+    }
+}
+
+/// Trait for encodings that can predict their size from their structure,
+/// without generating any clauses. This can be used to select an encoding
+/// before committing to one.
+///
+/// Predictions are for encoding the current input literals, assuming that
+/// nothing has been encoded yet, i.e., they match the [`EncodeStats`] of a
+/// freshly constructed encoding after a single call to `encode_ub` or
+/// `encode_lb` with the same range.
+pub trait PredictStats {
+    /// Predicts the size of the encoding for upper bounds in `range`
+    fn predict_ub<R>(&self, range: R) -> EncodingStats
+    where
+        R: RangeBounds<usize>;
+
+    /// Predicts the size of the encoding for lower bounds in `range`
+    fn predict_lb<R>(&self, range: R) -> EncodingStats
+    where
+        R: RangeBounds<usize>;
+}
+
 #[path = "encodings/nodedb.rs"]
 mod nodedbimpl;
 
@@ -20,12 +20,30 @@
 //! enc.encode(&mut encoding, &mut var_manager).unwrap();
 //! ```
 
-use super::CollectClauses;
-use crate::instances::ManageVars;
+use super::{CollectClauses, EncodeStats, IterInputs};
+use crate::{instances::ManageVars, types::Lit};
 
 mod pairwise;
 pub use pairwise::Pairwise;
 
+mod ladder;
+pub use ladder::Ladder;
+
+mod commander;
+pub use commander::Commander;
+
+mod bimander;
+pub use bimander::Bimander;
+
+mod product;
+pub use product::Product;
+
+mod bitwise;
+pub use bitwise::Bitwise;
+
+mod exactlyone;
+pub use exactlyone::ExactlyOne;
+
 /// Trait for all at-most-1 encodings
 pub trait Encode {
     /// Gets the number of literals in the encoding
@@ -47,3 +65,200 @@ pub type Def = Pairwise;
 pub fn new_default_am1() -> impl Encode {
     Def::default()
 }
+
+/// The at-most-1 encodings that can be selected at runtime via [`Selected`]
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub enum Variant {
+    /// The [`Pairwise`] encoding
+    #[default]
+    Pairwise,
+    /// The [`Ladder`] encoding
+    Ladder,
+    /// The [`Commander`] encoding
+    Commander,
+    /// The [`Bimander`] encoding
+    Bimander,
+    /// The [`Product`] encoding
+    Product,
+    /// The [`Bitwise`] encoding
+    Bitwise,
+}
+
+impl Variant {
+    /// Selects a suitable at-most-1 encoding for a given number of literals.
+    /// Small constraints are encoded [`Variant::Pairwise`], medium ones with a
+    /// [`Variant::Ladder`] and large ones with a [`Variant::Product`]
+    /// encoding, which requires the fewest clauses of the encodings that
+    /// maintain arc consistency.
+    pub fn auto(n_lits: usize) -> Self {
+        if n_lits <= 6 {
+            Variant::Pairwise
+        } else if n_lits <= 20 {
+            Variant::Ladder
+        } else {
+            Variant::Product
+        }
+    }
+}
+
+/// An at-most-1 encoding selected at runtime
+///
+/// # Example
+///
+/// ```
+/// # use rustsat::{
+/// #     encodings::am1::{Encode, Selected, Variant},
+/// #     instances::{BasicVarManager, Cnf, ManageVars},
+/// #     lit, var,
+/// # };
+/// #
+/// let mut var_manager = BasicVarManager::default();
+/// var_manager.increase_next_free(var![10]);
+///
+/// let mut enc = Selected::new(Variant::Product, (0..10).map(|idx| lit![idx]).collect());
+/// let mut encoding = Cnf::new();
+/// enc.encode(&mut encoding, &mut var_manager).unwrap();
+/// ```
+pub enum Selected {
+    /// A [`Pairwise`] encoding
+    Pairwise(Pairwise),
+    /// A [`Ladder`] encoding
+    Ladder(Ladder),
+    /// A [`Commander`] encoding
+    Commander(Commander),
+    /// A [`Bimander`] encoding
+    Bimander(Bimander),
+    /// A [`Product`] encoding
+    Product(Product),
+    /// A [`Bitwise`] encoding
+    Bitwise(Bitwise),
+}
+
+impl Selected {
+    /// Creates a new encoding of the given variant over some literals
+    pub fn new(variant: Variant, lits: Vec<Lit>) -> Self {
+        match variant {
+            Variant::Pairwise => Selected::Pairwise(Pairwise::from(lits)),
+            Variant::Ladder => Selected::Ladder(Ladder::from(lits)),
+            Variant::Commander => Selected::Commander(Commander::from(lits)),
+            Variant::Bimander => Selected::Bimander(Bimander::from(lits)),
+            Variant::Product => Selected::Product(Product::from(lits)),
+            Variant::Bitwise => Selected::Bitwise(Bitwise::from(lits)),
+        }
+    }
+
+    /// Gets the variant of the encoding
+    pub fn variant(&self) -> Variant {
+        match self {
+            Selected::Pairwise(_) => Variant::Pairwise,
+            Selected::Ladder(_) => Variant::Ladder,
+            Selected::Commander(_) => Variant::Commander,
+            Selected::Bimander(_) => Variant::Bimander,
+            Selected::Product(_) => Variant::Product,
+            Selected::Bitwise(_) => Variant::Bitwise,
+        }
+    }
+}
+
+impl Default for Selected {
+    fn default() -> Self {
+        Selected::new(Variant::default(), vec![])
+    }
+}
+
+/// Creates an encoding with the variant selected by [`Variant::auto`]
+impl From<Vec<Lit>> for Selected {
+    fn from(lits: Vec<Lit>) -> Self {
+        Selected::new(Variant::auto(lits.len()), lits)
+    }
+}
+
+/// Creates an encoding with the variant selected by [`Variant::auto`]
+impl FromIterator<Lit> for Selected {
+    fn from_iter<T: IntoIterator<Item = Lit>>(iter: T) -> Self {
+        Self::from(Vec::from_iter(iter))
+    }
+}
+
+impl Encode for Selected {
+    fn n_lits(&self) -> usize {
+        match self {
+            Selected::Pairwise(enc) => enc.n_lits(),
+            Selected::Ladder(enc) => enc.n_lits(),
+            Selected::Commander(enc) => enc.n_lits(),
+            Selected::Bimander(enc) => enc.n_lits(),
+            Selected::Product(enc) => enc.n_lits(),
+            Selected::Bitwise(enc) => enc.n_lits(),
+        }
+    }
+
+    fn encode<Col>(
+        &mut self,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        match self {
+            Selected::Pairwise(enc) => enc.encode(collector, var_manager),
+            Selected::Ladder(enc) => enc.encode(collector, var_manager),
+            Selected::Commander(enc) => enc.encode(collector, var_manager),
+            Selected::Bimander(enc) => enc.encode(collector, var_manager),
+            Selected::Product(enc) => enc.encode(collector, var_manager),
+            Selected::Bitwise(enc) => enc.encode(collector, var_manager),
+        }
+    }
+}
+
+impl IterInputs for Selected {
+    type Iter<'a> = std::iter::Copied<std::slice::Iter<'a, Lit>>;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        match self {
+            Selected::Pairwise(enc) => enc.iter(),
+            Selected::Ladder(enc) => enc.iter(),
+            Selected::Commander(enc) => enc.iter(),
+            Selected::Bimander(enc) => enc.iter(),
+            Selected::Product(enc) => enc.iter(),
+            Selected::Bitwise(enc) => enc.iter(),
+        }
+    }
+}
+
+impl EncodeStats for Selected {
+    fn n_clauses(&self) -> usize {
+        match self {
+            Selected::Pairwise(enc) => enc.n_clauses(),
+            Selected::Ladder(enc) => enc.n_clauses(),
+            Selected::Commander(enc) => enc.n_clauses(),
+            Selected::Bimander(enc) => enc.n_clauses(),
+            Selected::Product(enc) => enc.n_clauses(),
+            Selected::Bitwise(enc) => enc.n_clauses(),
+        }
+    }
+
+    fn n_vars(&self) -> u32 {
+        match self {
+            Selected::Pairwise(enc) => enc.n_vars(),
+            Selected::Ladder(enc) => enc.n_vars(),
+            Selected::Commander(enc) => enc.n_vars(),
+            Selected::Bimander(enc) => enc.n_vars(),
+            Selected::Product(enc) => enc.n_vars(),
+            Selected::Bitwise(enc) => enc.n_vars(),
+        }
+    }
+}
+
+impl Extend<Lit> for Selected {
+    fn extend<T: IntoIterator<Item = Lit>>(&mut self, iter: T) {
+        match self {
+            Selected::Pairwise(enc) => enc.extend(iter),
+            Selected::Ladder(enc) => enc.extend(iter),
+            Selected::Commander(enc) => enc.extend(iter),
+            Selected::Bimander(enc) => enc.extend(iter),
+            Selected::Product(enc) => enc.extend(iter),
+            Selected::Bitwise(enc) => enc.extend(iter),
+        }
+    }
+}
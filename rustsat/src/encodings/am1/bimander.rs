@@ -0,0 +1,122 @@
+//! # Bimander At-Most-1 Encoding
+//!
+//! Combines the pairwise encoding within small groups of input literals with
+//! the bitwise encoding over the groups.
+//!
+//! ## References
+//!
+//! - Van-Hau Nguyen and Son Thay Mai: _A New Method to Encode the At-Most-One Constraint into SAT_, SoICT 2015.
+
+use super::{bitwise, pairwise, Encode};
+use crate::{
+    encodings::{CollectClauses, EncodeStats, IterInputs},
+    instances::ManageVars,
+    types::Lit,
+};
+
+/// Implementation of the bimander at-most-1 encoding.
+///
+/// # References
+///
+/// - Van-Hau Nguyen and Son Thay Mai: _A New Method to Encode the At-Most-One Constraint into SAT_, SoICT 2015.
+pub struct Bimander {
+    /// Input literals
+    in_lits: Vec<Lit>,
+    /// The size of the groups
+    group_size: usize,
+    /// The number of variables in the encoding
+    n_vars: u32,
+    /// The number of clauses in the encoding
+    n_clauses: usize,
+}
+
+impl Bimander {
+    /// Sets the size of the groups that are encoded pairwise. The default is 2.
+    ///
+    /// # Panics
+    ///
+    /// If `group_size == 0`.
+    pub fn set_group_size(&mut self, group_size: usize) {
+        assert!(group_size >= 1, "group size must be at least 1");
+        self.group_size = group_size;
+    }
+}
+
+impl Default for Bimander {
+    fn default() -> Self {
+        Self {
+            in_lits: Default::default(),
+            group_size: 2,
+            n_vars: Default::default(),
+            n_clauses: Default::default(),
+        }
+    }
+}
+
+impl Encode for Bimander {
+    fn n_lits(&self) -> usize {
+        self.in_lits.len()
+    }
+
+    fn encode<Col>(
+        &mut self,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        let prev_clauses = collector.n_clauses();
+        let prev_vars = var_manager.n_used();
+        let n_groups = (self.in_lits.len() + self.group_size - 1) / self.group_size;
+        let bits: Vec<Lit> = (0..bitwise::n_bits(n_groups))
+            .map(|_| var_manager.new_var().pos_lit())
+            .collect();
+        for (idx, group) in self.in_lits.chunks(self.group_size).enumerate() {
+            pairwise::encode_lits(group, collector)?;
+            bitwise::encode_index(group, idx, &bits, collector)?;
+        }
+        self.n_clauses = collector.n_clauses() - prev_clauses;
+        self.n_vars = var_manager.n_used() - prev_vars;
+        Ok(())
+    }
+}
+
+impl IterInputs for Bimander {
+    type Iter<'a> = std::iter::Copied<std::slice::Iter<'a, Lit>>;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.in_lits.iter().copied()
+    }
+}
+
+impl EncodeStats for Bimander {
+    fn n_clauses(&self) -> usize {
+        self.n_clauses
+    }
+
+    fn n_vars(&self) -> u32 {
+        self.n_vars
+    }
+}
+
+impl From<Vec<Lit>> for Bimander {
+    fn from(lits: Vec<Lit>) -> Self {
+        Self {
+            in_lits: lits,
+            ..Default::default()
+        }
+    }
+}
+
+impl FromIterator<Lit> for Bimander {
+    fn from_iter<T: IntoIterator<Item = Lit>>(iter: T) -> Self {
+        Self::from(Vec::from_iter(iter))
+    }
+}
+
+impl Extend<Lit> for Bimander {
+    fn extend<T: IntoIterator<Item = Lit>>(&mut self, iter: T) {
+        self.in_lits.extend(iter)
+    }
+}
@@ -0,0 +1,126 @@
+//! # Bitwise At-Most-1 Encoding
+//!
+//! Also known as the binary at-most-1 encoding. Each input literal implies the
+//! binary representation of its index on `ceil(log2 n)` auxiliary variables.
+//!
+//! ## References
+//!
+//! - Alan M. Frisch and Paul A. Giannaros: _SAT Encodings of the At-Most-k Constraint_, ModRef 2010.
+//! - Steven D. Prestwich: _CNF Encodings_, in Handbook of Satisfiability 2021.
+
+use super::Encode;
+use crate::{
+    encodings::{atomics, CollectClauses, EncodeStats, IterInputs},
+    instances::ManageVars,
+    types::Lit,
+};
+
+/// Implementation of the bitwise at-most-1 encoding.
+///
+/// # References
+///
+/// - Alan M. Frisch and Paul A. Giannaros: _SAT Encodings of the At-Most-k Constraint_, ModRef 2010.
+/// - Steven D. Prestwich: _CNF Encodings_, in Handbook of Satisfiability 2021.
+#[derive(Default)]
+pub struct Bitwise {
+    /// Input literals
+    in_lits: Vec<Lit>,
+    /// The number of variables in the encoding
+    n_vars: u32,
+    /// The number of clauses in the encoding
+    n_clauses: usize,
+}
+
+/// Gets the number of bits needed to distinguish `n` values
+pub(super) fn n_bits(n: usize) -> usize {
+    if n <= 1 {
+        return 0;
+    }
+    (usize::BITS - (n - 1).leading_zeros()) as usize
+}
+
+/// Encodes that each literal in a group implies the binary representation of
+/// `idx` on the given bits
+pub(super) fn encode_index<Col>(
+    group: &[Lit],
+    idx: usize,
+    bits: &[Lit],
+    collector: &mut Col,
+) -> Result<(), crate::OutOfMemory>
+where
+    Col: CollectClauses,
+{
+    for &lit in group {
+        for (bit_idx, &bit) in bits.iter().enumerate() {
+            let bit = if idx & (1 << bit_idx) != 0 { bit } else { !bit };
+            collector.add_clause(atomics::lit_impl_lit(lit, bit))?;
+        }
+    }
+    Ok(())
+}
+
+impl Encode for Bitwise {
+    fn n_lits(&self) -> usize {
+        self.in_lits.len()
+    }
+
+    fn encode<Col>(
+        &mut self,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        let prev_clauses = collector.n_clauses();
+        let prev_vars = var_manager.n_used();
+        let bits: Vec<Lit> = (0..n_bits(self.in_lits.len()))
+            .map(|_| var_manager.new_var().pos_lit())
+            .collect();
+        for (idx, &lit) in self.in_lits.iter().enumerate() {
+            encode_index(&[lit], idx, &bits, collector)?;
+        }
+        self.n_clauses = collector.n_clauses() - prev_clauses;
+        self.n_vars = var_manager.n_used() - prev_vars;
+        Ok(())
+    }
+}
+
+impl IterInputs for Bitwise {
+    type Iter<'a> = std::iter::Copied<std::slice::Iter<'a, Lit>>;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.in_lits.iter().copied()
+    }
+}
+
+impl EncodeStats for Bitwise {
+    fn n_clauses(&self) -> usize {
+        self.n_clauses
+    }
+
+    fn n_vars(&self) -> u32 {
+        self.n_vars
+    }
+}
+
+impl From<Vec<Lit>> for Bitwise {
+    fn from(lits: Vec<Lit>) -> Self {
+        Self {
+            in_lits: lits,
+            ..Default::default()
+        }
+    }
+}
+
+impl FromIterator<Lit> for Bitwise {
+    fn from_iter<T: IntoIterator<Item = Lit>>(iter: T) -> Self {
+        Self::from(Vec::from_iter(iter))
+    }
+}
+
+impl Extend<Lit> for Bitwise {
+    fn extend<T: IntoIterator<Item = Lit>>(&mut self, iter: T) {
+        self.in_lits.extend(iter)
+    }
+}
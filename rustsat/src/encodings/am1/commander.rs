@@ -0,0 +1,140 @@
+//! # Commander At-Most-1 Encoding
+//!
+//! The input literals are split into groups, each of which gets a commander
+//! variable. Within the groups, the constraint is encoded pairwise and the
+//! commander variables are constrained recursively.
+//!
+//! ## References
+//!
+//! - Will Klieber and Gihwon Kwon: _Efficient CNF Encoding for Selecting 1 from N Objects_, CFV 2007.
+
+use super::{pairwise, Encode};
+use crate::{
+    encodings::{atomics, CollectClauses, EncodeStats, IterInputs},
+    instances::ManageVars,
+    types::Lit,
+};
+
+/// Implementation of the commander at-most-1 encoding.
+///
+/// # References
+///
+/// - Will Klieber and Gihwon Kwon: _Efficient CNF Encoding for Selecting 1 from N Objects_, CFV 2007.
+pub struct Commander {
+    /// Input literals
+    in_lits: Vec<Lit>,
+    /// The size of the groups
+    group_size: usize,
+    /// The number of variables in the encoding
+    n_vars: u32,
+    /// The number of clauses in the encoding
+    n_clauses: usize,
+}
+
+impl Commander {
+    /// Sets the size of the groups that get a commander variable. The default
+    /// is 3.
+    ///
+    /// # Panics
+    ///
+    /// If `group_size < 2`.
+    pub fn set_group_size(&mut self, group_size: usize) {
+        assert!(group_size >= 2, "group size must be at least 2");
+        self.group_size = group_size;
+    }
+
+    /// Recursively encodes the constraint over a slice of literals
+    fn encode_rec<Col>(
+        lits: &[Lit],
+        group_size: usize,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        if lits.len() <= group_size {
+            return pairwise::encode_lits(lits, collector);
+        }
+        let mut commanders = Vec::with_capacity((lits.len() + group_size - 1) / group_size);
+        for group in lits.chunks(group_size) {
+            pairwise::encode_lits(group, collector)?;
+            let cmdr = var_manager.new_var().pos_lit();
+            collector.extend_clauses(atomics::clause_impl_lit(group, cmdr))?;
+            commanders.push(cmdr);
+        }
+        Commander::encode_rec(&commanders, group_size, collector, var_manager)
+    }
+}
+
+impl Default for Commander {
+    fn default() -> Self {
+        Self {
+            in_lits: Default::default(),
+            group_size: 3,
+            n_vars: Default::default(),
+            n_clauses: Default::default(),
+        }
+    }
+}
+
+impl Encode for Commander {
+    fn n_lits(&self) -> usize {
+        self.in_lits.len()
+    }
+
+    fn encode<Col>(
+        &mut self,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        let prev_clauses = collector.n_clauses();
+        let prev_vars = var_manager.n_used();
+        Commander::encode_rec(&self.in_lits, self.group_size, collector, var_manager)?;
+        self.n_clauses = collector.n_clauses() - prev_clauses;
+        self.n_vars = var_manager.n_used() - prev_vars;
+        Ok(())
+    }
+}
+
+impl IterInputs for Commander {
+    type Iter<'a> = std::iter::Copied<std::slice::Iter<'a, Lit>>;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.in_lits.iter().copied()
+    }
+}
+
+impl EncodeStats for Commander {
+    fn n_clauses(&self) -> usize {
+        self.n_clauses
+    }
+
+    fn n_vars(&self) -> u32 {
+        self.n_vars
+    }
+}
+
+impl From<Vec<Lit>> for Commander {
+    fn from(lits: Vec<Lit>) -> Self {
+        Self {
+            in_lits: lits,
+            ..Default::default()
+        }
+    }
+}
+
+impl FromIterator<Lit> for Commander {
+    fn from_iter<T: IntoIterator<Item = Lit>>(iter: T) -> Self {
+        Self::from(Vec::from_iter(iter))
+    }
+}
+
+impl Extend<Lit> for Commander {
+    fn extend<T: IntoIterator<Item = Lit>>(&mut self, iter: T) {
+        self.in_lits.extend(iter)
+    }
+}
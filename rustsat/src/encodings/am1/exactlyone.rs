@@ -0,0 +1,119 @@
+//! # Exactly-1 Constraints
+//!
+//! Encodes exactly-1 constraints with an at-most-1 encoding and an additional
+//! at-least-1 clause.
+
+use super::{Encode, Selected};
+use crate::{
+    encodings::{CollectClauses, EncodeStats, IterInputs},
+    instances::ManageVars,
+    types::{Clause, Lit},
+};
+
+/// An exactly-1 constraint encoded with the at-most-1 encoding `AM1` and an
+/// at-least-1 clause. By default, the at-most-1 encoding is selected
+/// automatically with [`super::Variant::auto`].
+///
+/// # Example
+///
+/// ```
+/// # use rustsat::{
+/// #     encodings::am1::{Encode, ExactlyOne, Ladder},
+/// #     instances::{BasicVarManager, Cnf, ManageVars},
+/// #     lit, var,
+/// # };
+/// #
+/// let mut var_manager = BasicVarManager::default();
+/// var_manager.increase_next_free(var![3]);
+///
+/// let mut enc = ExactlyOne::<Ladder>::from(vec![lit![0], lit![1], lit![2]]);
+/// let mut encoding = Cnf::new();
+/// enc.encode(&mut encoding, &mut var_manager).unwrap();
+/// ```
+#[derive(Default)]
+pub struct ExactlyOne<AM1 = Selected> {
+    /// The at-most-1 encoding
+    am1: AM1,
+    /// The number of clauses in the encoding
+    n_clauses: usize,
+}
+
+impl<AM1> Encode for ExactlyOne<AM1>
+where
+    AM1: Encode + EncodeStats + IterInputs,
+{
+    fn n_lits(&self) -> usize {
+        self.am1.n_lits()
+    }
+
+    fn encode<Col>(
+        &mut self,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        self.am1.encode(collector, var_manager)?;
+        collector.add_clause(Clause::from_iter(self.am1.iter()))?;
+        self.n_clauses = self.am1.n_clauses() + 1;
+        Ok(())
+    }
+}
+
+impl<AM1> IterInputs for ExactlyOne<AM1>
+where
+    AM1: IterInputs,
+{
+    type Iter<'a>
+        = AM1::Iter<'a>
+    where
+        AM1: 'a;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.am1.iter()
+    }
+}
+
+impl<AM1> EncodeStats for ExactlyOne<AM1>
+where
+    AM1: EncodeStats,
+{
+    fn n_clauses(&self) -> usize {
+        self.n_clauses
+    }
+
+    fn n_vars(&self) -> u32 {
+        self.am1.n_vars()
+    }
+}
+
+impl<AM1> From<Vec<Lit>> for ExactlyOne<AM1>
+where
+    AM1: From<Vec<Lit>>,
+{
+    fn from(lits: Vec<Lit>) -> Self {
+        Self {
+            am1: AM1::from(lits),
+            n_clauses: 0,
+        }
+    }
+}
+
+impl<AM1> FromIterator<Lit> for ExactlyOne<AM1>
+where
+    AM1: From<Vec<Lit>>,
+{
+    fn from_iter<T: IntoIterator<Item = Lit>>(iter: T) -> Self {
+        Self::from(Vec::from_iter(iter))
+    }
+}
+
+impl<AM1> Extend<Lit> for ExactlyOne<AM1>
+where
+    AM1: Extend<Lit>,
+{
+    fn extend<T: IntoIterator<Item = Lit>>(&mut self, iter: T) {
+        self.am1.extend(iter)
+    }
+}
@@ -0,0 +1,109 @@
+//! # Ladder At-Most-1 Encoding
+//!
+//! Also known as the sequential counter at-most-1 encoding. Requires `n - 1`
+//! auxiliary variables and `3n - 4` clauses.
+//!
+//! ## References
+//!
+//! - Carsten Sinz: _Towards an Optimal CNF Encoding of Boolean Cardinality Constraints_, CP 2005.
+//! - Ian P. Gent and Peter Nightingale: _A New Encoding of AllDifferent into SAT_, ModRef 2004.
+
+use super::Encode;
+use crate::{
+    encodings::{atomics, CollectClauses, EncodeStats, IterInputs},
+    instances::ManageVars,
+    types::Lit,
+};
+
+/// Implementation of the ladder at-most-1 encoding.
+///
+/// # References
+///
+/// - Carsten Sinz: _Towards an Optimal CNF Encoding of Boolean Cardinality Constraints_, CP 2005.
+/// - Ian P. Gent and Peter Nightingale: _A New Encoding of AllDifferent into SAT_, ModRef 2004.
+#[derive(Default)]
+pub struct Ladder {
+    /// Input literals
+    in_lits: Vec<Lit>,
+    /// The number of variables in the encoding
+    n_vars: u32,
+    /// The number of clauses in the encoding
+    n_clauses: usize,
+}
+
+impl Encode for Ladder {
+    fn n_lits(&self) -> usize {
+        self.in_lits.len()
+    }
+
+    fn encode<Col>(
+        &mut self,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        let prev_clauses = collector.n_clauses();
+        let prev_vars = var_manager.n_used();
+        if self.in_lits.len() > 1 {
+            // `steps[i]` is true if any of the first `i + 1` literals is true
+            let steps: Vec<Lit> = (0..self.in_lits.len() - 1)
+                .map(|_| var_manager.new_var().pos_lit())
+                .collect();
+            for (idx, &lit) in self.in_lits.iter().enumerate() {
+                if idx < steps.len() {
+                    collector.add_clause(atomics::lit_impl_lit(lit, steps[idx]))?;
+                }
+                if idx > 0 {
+                    collector.add_clause(atomics::lit_impl_lit(lit, !steps[idx - 1]))?;
+                    if idx < steps.len() {
+                        collector.add_clause(atomics::lit_impl_lit(steps[idx - 1], steps[idx]))?;
+                    }
+                }
+            }
+        }
+        self.n_clauses = collector.n_clauses() - prev_clauses;
+        self.n_vars = var_manager.n_used() - prev_vars;
+        Ok(())
+    }
+}
+
+impl IterInputs for Ladder {
+    type Iter<'a> = std::iter::Copied<std::slice::Iter<'a, Lit>>;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.in_lits.iter().copied()
+    }
+}
+
+impl EncodeStats for Ladder {
+    fn n_clauses(&self) -> usize {
+        self.n_clauses
+    }
+
+    fn n_vars(&self) -> u32 {
+        self.n_vars
+    }
+}
+
+impl From<Vec<Lit>> for Ladder {
+    fn from(lits: Vec<Lit>) -> Self {
+        Self {
+            in_lits: lits,
+            ..Default::default()
+        }
+    }
+}
+
+impl FromIterator<Lit> for Ladder {
+    fn from_iter<T: IntoIterator<Item = Lit>>(iter: T) -> Self {
+        Self::from(Vec::from_iter(iter))
+    }
+}
+
+impl Extend<Lit> for Ladder {
+    fn extend<T: IntoIterator<Item = Lit>>(&mut self, iter: T) {
+        self.in_lits.extend(iter)
+    }
+}
@@ -39,16 +39,24 @@ impl Encode for Pairwise {
         Col: CollectClauses,
     {
         let prev_clauses = collector.n_clauses();
-        let lits = &self.in_lits;
-        let clause_iter = (0..self.in_lits.len()).flat_map(|first| {
-            (first + 1..self.in_lits.len()).map(move |second| clause![!lits[first], !lits[second]])
-        });
-        collector.extend_clauses(clause_iter)?;
+        encode_lits(&self.in_lits, collector)?;
         self.n_clauses = collector.n_clauses() - prev_clauses;
         Ok(())
     }
 }
 
+/// Encodes a pairwise at-most-1 constraint over a slice of literals. This is
+/// used as the base case of other encodings.
+pub(super) fn encode_lits<Col>(lits: &[Lit], collector: &mut Col) -> Result<(), crate::OutOfMemory>
+where
+    Col: CollectClauses,
+{
+    let clause_iter = (0..lits.len()).flat_map(|first| {
+        (first + 1..lits.len()).map(move |second| clause![!lits[first], !lits[second]])
+    });
+    collector.extend_clauses(clause_iter)
+}
+
 impl IterInputs for Pairwise {
     type Iter<'a> = std::iter::Copied<std::slice::Iter<'a, Lit>>;
 
@@ -0,0 +1,126 @@
+//! # Product At-Most-1 Encoding
+//!
+//! The input literals are arranged in a grid of size roughly `sqrt(n) x
+//! sqrt(n)`. Each literal implies its row and column variable and the at-most-1
+//! constraints over the row and column variables are encoded recursively.
+//! Requires `2n + 4 sqrt(n) + O(n^(1/4))` clauses.
+//!
+//! ## References
+//!
+//! - Jingchao Chen: _A New SAT Encoding of the At-Most-One Constraint_, ModRef 2010.
+
+use super::{pairwise, Encode};
+use crate::{
+    encodings::{atomics, CollectClauses, EncodeStats, IterInputs},
+    instances::ManageVars,
+    types::Lit,
+};
+
+/// Up to this number of literals, the constraint is encoded pairwise
+const PAIRWISE_THRESHOLD: usize = 4;
+
+/// Implementation of the product at-most-1 encoding.
+///
+/// # References
+///
+/// - Jingchao Chen: _A New SAT Encoding of the At-Most-One Constraint_, ModRef 2010.
+#[derive(Default)]
+pub struct Product {
+    /// Input literals
+    in_lits: Vec<Lit>,
+    /// The number of variables in the encoding
+    n_vars: u32,
+    /// The number of clauses in the encoding
+    n_clauses: usize,
+}
+
+impl Product {
+    /// Recursively encodes the constraint over a slice of literals
+    fn encode_rec<Col>(
+        lits: &[Lit],
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        if lits.len() <= PAIRWISE_THRESHOLD {
+            return pairwise::encode_lits(lits, collector);
+        }
+        let n_rows = (lits.len() as f64).sqrt().ceil() as usize;
+        let n_cols = (lits.len() + n_rows - 1) / n_rows;
+        let rows: Vec<Lit> = (0..n_rows)
+            .map(|_| var_manager.new_var().pos_lit())
+            .collect();
+        let cols: Vec<Lit> = (0..n_cols)
+            .map(|_| var_manager.new_var().pos_lit())
+            .collect();
+        for (idx, &lit) in lits.iter().enumerate() {
+            collector.add_clause(atomics::lit_impl_lit(lit, rows[idx / n_cols]))?;
+            collector.add_clause(atomics::lit_impl_lit(lit, cols[idx % n_cols]))?;
+        }
+        Product::encode_rec(&rows, collector, var_manager)?;
+        Product::encode_rec(&cols, collector, var_manager)
+    }
+}
+
+impl Encode for Product {
+    fn n_lits(&self) -> usize {
+        self.in_lits.len()
+    }
+
+    fn encode<Col>(
+        &mut self,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        let prev_clauses = collector.n_clauses();
+        let prev_vars = var_manager.n_used();
+        Product::encode_rec(&self.in_lits, collector, var_manager)?;
+        self.n_clauses = collector.n_clauses() - prev_clauses;
+        self.n_vars = var_manager.n_used() - prev_vars;
+        Ok(())
+    }
+}
+
+impl IterInputs for Product {
+    type Iter<'a> = std::iter::Copied<std::slice::Iter<'a, Lit>>;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.in_lits.iter().copied()
+    }
+}
+
+impl EncodeStats for Product {
+    fn n_clauses(&self) -> usize {
+        self.n_clauses
+    }
+
+    fn n_vars(&self) -> u32 {
+        self.n_vars
+    }
+}
+
+impl From<Vec<Lit>> for Product {
+    fn from(lits: Vec<Lit>) -> Self {
+        Self {
+            in_lits: lits,
+            ..Default::default()
+        }
+    }
+}
+
+impl FromIterator<Lit> for Product {
+    fn from_iter<T: IntoIterator<Item = Lit>>(iter: T) -> Self {
+        Self::from(Vec::from_iter(iter))
+    }
+}
+
+impl Extend<Lit> for Product {
+    fn extend<T: IntoIterator<Item = Lit>>(&mut self, iter: T) {
+        self.in_lits.extend(iter)
+    }
+}
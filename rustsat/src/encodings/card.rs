@@ -31,7 +31,7 @@ use std::{
     ops::{Bound, Range, RangeBounds},
 };
 
-use super::{CollectClauses, Error};
+use super::{atomics, CollectClauses, Error, Reification};
 use crate::{
     clause,
     instances::ManageVars,
@@ -49,6 +49,15 @@ pub mod simulators;
 pub mod dbtotalizer;
 pub use dbtotalizer::DbTotalizer;
 
+pub mod seqcounter;
+pub use seqcounter::SeqCounter;
+
+pub mod cardnetwork;
+pub use cardnetwork::CardinalityNetwork;
+
+pub mod modtotalizer;
+pub use modtotalizer::{KModuloTotalizer, ModuloTotalizer};
+
 /// Trait for all cardinality encodings of form `sum of lits <> rhs`
 pub trait Encode {
     /// Gets the number of input literals in the encoding
@@ -76,6 +85,30 @@ pub trait BoundUpper: Encode {
     /// adequately and nothing has been called afterwards, otherwise
     /// [`Error::NotEncoded`] will be returned.
     fn enforce_ub(&self, ub: usize) -> Result<Vec<Lit>, Error>;
+    /// Encodes the half-reified upper bound `act -> (sum of lits <= ub)` to CNF.
+    /// Full reification can be achieved by additionally encoding `!act ->
+    /// (sum of lits >= ub + 1)` with [`BoundLower::encode_lb_reified`].
+    fn encode_ub_reified<Col>(
+        &mut self,
+        ub: usize,
+        act: Lit,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        if ub >= self.n_lits() {
+            return Ok(());
+        }
+        self.encode_ub(ub..ub + 1, collector, var_manager)?;
+        let assumps = self.enforce_ub(ub).expect("upper bound was just encoded");
+        collector.extend_clauses(
+            assumps
+                .into_iter()
+                .map(|assump| atomics::lit_impl_lit(act, assump)),
+        )
+    }
     /// Encodes an upper bound cardinality constraint to CNF
     ///
     /// # Errors
@@ -126,6 +159,33 @@ pub trait BoundLower: Encode {
     /// the number of literals in the encoding, [`Error::Unsat`] is
     /// returned.
     fn enforce_lb(&self, lb: usize) -> Result<Vec<Lit>, Error>;
+    /// Encodes the half-reified lower bound `act -> (sum of lits >= lb)` to CNF.
+    /// Full reification can be achieved by additionally encoding `!act ->
+    /// (sum of lits <= lb - 1)` with [`BoundUpper::encode_ub_reified`].
+    fn encode_lb_reified<Col>(
+        &mut self,
+        lb: usize,
+        act: Lit,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        if lb == 0 {
+            return Ok(());
+        }
+        if lb > self.n_lits() {
+            return collector.add_clause(clause![!act]);
+        }
+        self.encode_lb(lb..lb + 1, collector, var_manager)?;
+        let assumps = self.enforce_lb(lb).expect("lower bound was just encoded");
+        collector.extend_clauses(
+            assumps
+                .into_iter()
+                .map(|assump| atomics::lit_impl_lit(act, assump)),
+        )
+    }
     /// Encodes a lower bound cardinality constraint to CNF
     ///
     /// # Errors
@@ -185,6 +245,20 @@ pub trait BoundBoth: BoundUpper + BoundLower {
         assumps.extend(self.enforce_lb(b)?);
         Ok(assumps)
     }
+    /// Encodes the half-reified equality `act -> (sum of lits = b)` to CNF
+    fn encode_eq_reified<Col>(
+        &mut self,
+        b: usize,
+        act: Lit,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        self.encode_ub_reified(b, act, collector, var_manager)?;
+        self.encode_lb_reified(b, act, collector, var_manager)
+    }
     /// Encodes an equality cardinality constraint to CNF
     ///
     /// # Errors
@@ -347,13 +421,23 @@ pub fn new_default_inc_both() -> impl BoundBoth {
     DefIncBothBounding::default()
 }
 
-/// A default encoder for any cardinality constraint. This uses a
-/// [`DefBothBounding`] to encode non-trivial constraints.
+/// A default encoder for any cardinality constraint. Constraints of the form
+/// `sum of lits <= 1` and `sum of lits = 1` are encoded with an automatically
+/// selected at-most-1 encoding (see [`super::am1::Variant::auto`]), all other
+/// non-trivial constraints with a [`DefBothBounding`].
 pub fn default_encode_cardinality_constraint<Col: CollectClauses>(
     constr: CardConstraint,
     collector: &mut Col,
     var_manager: &mut dyn ManageVars,
 ) -> Result<(), crate::OutOfMemory> {
+    use super::am1::{self, Encode as _};
+    if constr.is_exactly_one() {
+        return am1::ExactlyOne::<am1::Selected>::from(constr.into_lits())
+            .encode(collector, var_manager);
+    }
+    if constr.is_at_most_one() {
+        return am1::Selected::from(constr.into_lits()).encode(collector, var_manager);
+    }
     encode_cardinality_constraint::<DefBothBounding, Col>(constr, collector, var_manager)
 }
 
@@ -386,6 +470,91 @@ pub fn encode_cardinality_constraint<CE: BoundBoth + FromIterator<Lit>, Col: Col
     }
 }
 
+/// A default encoder for reified cardinality constraints. Encodes `act ->
+/// constr` for [`Reification::Half`] and `act <-> constr` for
+/// [`Reification::Full`] with a [`DefBothBounding`].
+pub fn default_encode_reified_cardinality_constraint<Col: CollectClauses>(
+    constr: CardConstraint,
+    act: Lit,
+    reif: Reification,
+    collector: &mut Col,
+    var_manager: &mut dyn ManageVars,
+) -> Result<(), crate::OutOfMemory> {
+    encode_reified_cardinality_constraint::<DefBothBounding, Col>(
+        constr,
+        act,
+        reif,
+        collector,
+        var_manager,
+    )
+}
+
+/// An encoder for reified cardinality constraints with an encoding of choice.
+/// Encodes `act -> constr` for [`Reification::Half`] and `act <-> constr` for
+/// [`Reification::Full`].
+pub fn encode_reified_cardinality_constraint<
+    CE: BoundBoth + FromIterator<Lit>,
+    Col: CollectClauses,
+>(
+    constr: CardConstraint,
+    act: Lit,
+    reif: Reification,
+    collector: &mut Col,
+    var_manager: &mut dyn ManageVars,
+) -> Result<(), crate::OutOfMemory> {
+    if reif == Reification::Half {
+        if constr.is_tautology() {
+            return Ok(());
+        }
+        if constr.is_clause() {
+            let mut clause = constr.into_clause().unwrap();
+            clause.add(!act);
+            return collector.add_clause(clause);
+        }
+    }
+    match constr {
+        CardConstraint::UB(constr) => {
+            let (lits, ub) = constr.decompose();
+            let mut enc = CE::from_iter(lits);
+            enc.encode_ub_reified(ub, act, collector, var_manager)?;
+            if reif == Reification::Full {
+                enc.encode_lb_reified(ub + 1, !act, collector, var_manager)?;
+            }
+        }
+        CardConstraint::LB(constr) => {
+            let (lits, lb) = constr.decompose();
+            let mut enc = CE::from_iter(lits);
+            enc.encode_lb_reified(lb, act, collector, var_manager)?;
+            if reif == Reification::Full {
+                if lb == 0 {
+                    collector.add_clause(clause![act])?;
+                } else {
+                    enc.encode_ub_reified(lb - 1, !act, collector, var_manager)?;
+                }
+            }
+        }
+        CardConstraint::EQ(constr) => {
+            let (lits, b) = constr.decompose();
+            let mut enc = CE::from_iter(lits);
+            enc.encode_eq_reified(b, act, collector, var_manager)?;
+            if reif == Reification::Full {
+                // `!act -> (sum of lits <= b - 1 or sum of lits >= b + 1)`
+                let mut clause = clause![act];
+                if b > 0 {
+                    let below = var_manager.new_var().pos_lit();
+                    enc.encode_ub_reified(b - 1, below, collector, var_manager)?;
+                    clause.add(below);
+                }
+                let above = var_manager.new_var().pos_lit();
+                enc.encode_lb_reified(b + 1, above, collector, var_manager)?;
+                clause.add(above);
+                collector.add_clause(clause)?;
+            }
+        }
+    }
+    Ok(())
+}
+
 fn prepare_ub_range<Enc: Encode, R: RangeBounds<usize>>(enc: &Enc, range: R) -> Range<usize> {
     (match range.start_bound() {
         Bound::Included(b) => *b,
@@ -0,0 +1,600 @@
+//! # Cardinality Network Encoding
+//!
+//! Implementation of the simplified cardinality networks \[1\] built from
+//! odd-even merging networks. For `n` input literals and a bound `k`, the
+//! encoding requires `O(n log^2 k)` clauses and variables, which makes it scale
+//! better than the [`super::Totalizer`] for many inputs and moderate bounds.
+//!
+//! The network is built for a block size `k` that is a power of two. When a
+//! bound larger than the current block size is requested, the network is
+//! rebuilt for the next fitting block size. Clauses of earlier networks stay in
+//! the collector but do not constrain the input literals.
+//!
+//! ## References
+//!
+//! - \[1\] Roberto Asín, Robert Nieuwenhuis, Albert Oliveras and Enric
+//!   Rodríguez-Carbonell: _Cardinality Networks: a theoretical and empirical
+//!   study_, Constraints 2011.
+
+use super::{
+    BoundLower, BoundLowerIncremental, BoundUpper, BoundUpperIncremental, Encode,
+    EncodeIncremental, Error,
+};
+use crate::{
+    encodings::{atomics, CollectClauses, EncodeStats, IterInputs},
+    instances::ManageVars,
+    types::Lit,
+};
+use std::{cmp, ops::RangeBounds};
+
+/// Implementation of the cardinality network encoding \[1\].
+///
+/// **Note**: the network is only built for bounds up to its block size, the
+/// smallest power of two covering the largest requested bound. Requesting a
+/// bound beyond the block size, or adding input literals after encoding,
+/// rebuilds the entire network. The clauses and variables of the previous
+/// network are left behind in the collector and variable manager and are
+/// counted in [`EncodeStats`]. For incremental use with growing bounds, prefer
+/// encoding the largest expected bound first.
+///
+/// # References
+///
+/// - \[1\] Roberto Asín, Robert Nieuwenhuis, Albert Oliveras and Enric
+///   Rodríguez-Carbonell: _Cardinality Networks: a theoretical and empirical
+///   study_, Constraints 2011.
+#[derive(Default)]
+pub struct CardinalityNetwork {
+    /// Input literals to the network
+    in_lits: Vec<Lit>,
+    /// The currently built network
+    net: Option<Network>,
+    /// Whether the upper bounding clauses of the network are encoded
+    ub_encoded: bool,
+    /// Whether the lower bounding clauses of the network are encoded
+    lb_encoded: bool,
+    /// The number of variables in the encoding
+    n_vars: u32,
+    /// The number of clauses in the encoding
+    n_clauses: usize,
+}
+
+impl CardinalityNetwork {
+    /// Makes sure that the built network covers all input literals and has
+    /// outputs up to `max_out`. If the network is rebuilt, the directions
+    /// encoded previously are also encoded for the new network.
+    fn ensure_network<Col>(
+        &mut self,
+        max_out: usize,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        let max_out = cmp::min(max_out, self.in_lits.len());
+        if let Some(net) = &self.net {
+            if net.n_lits == self.in_lits.len() && net.outputs.len() >= max_out {
+                return Ok(());
+            }
+        }
+        let block = cmp::max(
+            max_out.next_power_of_two(),
+            self.net.as_ref().map_or(1, |net| net.block),
+        );
+        let n_vars_before = var_manager.n_used();
+        self.net = Some(Network::new(&self.in_lits, block, var_manager));
+        self.n_vars += var_manager.n_used() - n_vars_before;
+        if self.ub_encoded {
+            self.ub_encoded = false;
+            self.encode_ub_clauses(collector)?;
+        }
+        if self.lb_encoded {
+            self.lb_encoded = false;
+            self.encode_lb_clauses(collector)?;
+        }
+        Ok(())
+    }
+
+    /// Adds the upper bounding clauses of the network to the collector, if not
+    /// already done
+    fn encode_ub_clauses<Col>(&mut self, collector: &mut Col) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        if self.ub_encoded {
+            return Ok(());
+        }
+        let net = self.net.as_ref().expect("network must be built");
+        let n_clauses_before = collector.n_clauses();
+        for gate in &net.gates {
+            match *gate {
+                Gate::Or { a, b, out } => {
+                    collector.add_clause(atomics::lit_impl_lit(a, out))?;
+                    collector.add_clause(atomics::lit_impl_lit(b, out))?;
+                }
+                Gate::And { a, b, out } => {
+                    collector.add_clause(atomics::cube_impl_lit(&[a, b], out))?
+                }
+            }
+        }
+        self.n_clauses += collector.n_clauses() - n_clauses_before;
+        self.ub_encoded = true;
+        Ok(())
+    }
+
+    /// Adds the lower bounding clauses of the network to the collector, if not
+    /// already done
+    fn encode_lb_clauses<Col>(&mut self, collector: &mut Col) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        if self.lb_encoded {
+            return Ok(());
+        }
+        let net = self.net.as_ref().expect("network must be built");
+        let n_clauses_before = collector.n_clauses();
+        for gate in &net.gates {
+            match *gate {
+                Gate::Or { a, b, out } => {
+                    collector.add_clause(atomics::lit_impl_clause(out, &[a, b]))?
+                }
+                Gate::And { a, b, out } => {
+                    collector.add_clause(atomics::lit_impl_lit(out, a))?;
+                    collector.add_clause(atomics::lit_impl_lit(out, b))?;
+                }
+            }
+        }
+        self.n_clauses += collector.n_clauses() - n_clauses_before;
+        self.lb_encoded = true;
+        Ok(())
+    }
+
+    /// Gets the network if it is built for the current input literals
+    fn current_net(&self) -> Option<&Network> {
+        self.net
+            .as_ref()
+            .filter(|net| net.n_lits == self.in_lits.len())
+    }
+}
+
+impl Encode for CardinalityNetwork {
+    fn n_lits(&self) -> usize {
+        self.in_lits.len()
+    }
+}
+
+impl IterInputs for CardinalityNetwork {
+    type Iter<'a> = super::totalizer::TotIter<'a>;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.in_lits.iter().copied()
+    }
+}
+
+impl EncodeIncremental for CardinalityNetwork {
+    fn reserve(&mut self, var_manager: &mut dyn ManageVars) {
+        let max_out = self.in_lits.len();
+        if self
+            .current_net()
+            .map_or(false, |net| net.outputs.len() >= max_out)
+        {
+            return;
+        }
+        // The clauses for the new network are added on the next call to
+        // `encode_ub_change` or `encode_lb_change`
+        self.ub_encoded = false;
+        self.lb_encoded = false;
+        let n_vars_before = var_manager.n_used();
+        self.net = Some(Network::new(
+            &self.in_lits,
+            max_out.next_power_of_two(),
+            var_manager,
+        ));
+        self.n_vars += var_manager.n_used() - n_vars_before;
+    }
+}
+
+impl BoundUpper for CardinalityNetwork {
+    fn encode_ub<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        self.ub_encoded = false;
+        self.encode_ub_change(range, collector, var_manager)
+    }
+
+    fn enforce_ub(&self, ub: usize) -> Result<Vec<Lit>, Error> {
+        if ub >= self.in_lits.len() {
+            return Ok(vec![]);
+        };
+        if !self.ub_encoded {
+            return Err(Error::NotEncoded);
+        }
+        match self.current_net() {
+            Some(net) if ub < net.outputs.len() => {
+                Ok(net.outputs[ub].map(|olit| vec![!olit]).unwrap_or_default())
+            }
+            _ => Err(Error::NotEncoded),
+        }
+    }
+}
+
+impl BoundLower for CardinalityNetwork {
+    fn encode_lb<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        self.lb_encoded = false;
+        self.encode_lb_change(range, collector, var_manager)
+    }
+
+    fn enforce_lb(&self, lb: usize) -> Result<Vec<Lit>, Error> {
+        if lb > self.in_lits.len() {
+            return Err(Error::Unsat);
+        } else if lb == 0 {
+            return Ok(vec![]);
+        };
+        if !self.lb_encoded {
+            return Err(Error::NotEncoded);
+        }
+        match self.current_net() {
+            Some(net) if lb <= net.outputs.len() => net.outputs[lb - 1]
+                .map(|olit| vec![olit])
+                .ok_or(Error::Unsat),
+            _ => Err(Error::NotEncoded),
+        }
+    }
+}
+
+impl BoundUpperIncremental for CardinalityNetwork {
+    fn encode_ub_change<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        let range = super::prepare_ub_range(self, range);
+        if range.is_empty() {
+            return Ok(());
+        };
+        // Enforcing `ub` requires output `ub + 1`
+        self.ensure_network(range.end, collector, var_manager)?;
+        self.encode_ub_clauses(collector)
+    }
+}
+
+impl BoundLowerIncremental for CardinalityNetwork {
+    fn encode_lb_change<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        let range = super::prepare_lb_range(self, range);
+        if range.is_empty() {
+            return Ok(());
+        };
+        self.ensure_network(range.end - 1, collector, var_manager)?;
+        self.encode_lb_clauses(collector)
+    }
+}
+
+impl EncodeStats for CardinalityNetwork {
+    fn n_clauses(&self) -> usize {
+        self.n_clauses
+    }
+
+    fn n_vars(&self) -> u32 {
+        self.n_vars
+    }
+}
+
+impl From<Vec<Lit>> for CardinalityNetwork {
+    fn from(lits: Vec<Lit>) -> Self {
+        Self {
+            in_lits: lits,
+            ..Default::default()
+        }
+    }
+}
+
+impl FromIterator<Lit> for CardinalityNetwork {
+    fn from_iter<T: IntoIterator<Item = Lit>>(iter: T) -> Self {
+        Self::from(Vec::from_iter(iter))
+    }
+}
+
+impl Extend<Lit> for CardinalityNetwork {
+    fn extend<T: IntoIterator<Item = Lit>>(&mut self, iter: T) {
+        self.in_lits.extend(iter)
+    }
+}
+
+/// A gate in the network. Each 2-comparator consists of an [`Gate::Or`] for
+/// the larger and an [`Gate::And`] for the smaller output.
+#[derive(Clone, Copy)]
+enum Gate {
+    Or { a: Lit, b: Lit, out: Lit },
+    And { a: Lit, b: Lit, out: Lit },
+}
+
+/// A wire in the network. `None` is a wire that is constantly false, which is
+/// used for padding to powers of two and simplified away during building.
+type Wire = Option<Lit>;
+
+/// A cardinality network over a fixed number of input literals
+struct Network {
+    /// The number of input literals the network is built for
+    n_lits: usize,
+    /// The block size, i.e., number of sorted outputs, of the network
+    block: usize,
+    /// The sorted outputs of the network
+    outputs: Vec<Wire>,
+    /// The gates of the network
+    gates: Vec<Gate>,
+}
+
+impl Network {
+    /// Builds a network sorting the `block` largest values of the input
+    fn new(lits: &[Lit], block: usize, var_manager: &mut dyn ManageVars) -> Self {
+        debug_assert!(block.is_power_of_two());
+        let mut net = Network {
+            n_lits: lits.len(),
+            block,
+            outputs: vec![],
+            gates: vec![],
+        };
+        let mut blocks = lits.chunks(block).map(|chunk| {
+            let mut wires: Vec<Wire> = chunk.iter().copied().map(Some).collect();
+            wires.resize(block, None);
+            wires
+        });
+        net.outputs = match blocks.next() {
+            Some(first) => {
+                let mut outputs = net.hsort(&first, var_manager);
+                for blk in blocks {
+                    let sorted = net.hsort(&blk, var_manager);
+                    outputs = net.smerge(&outputs, &sorted, block, var_manager);
+                }
+                outputs
+            }
+            None => vec![],
+        };
+        net.outputs.truncate(lits.len());
+        net
+    }
+
+    /// Creates the larger output of a 2-comparator
+    fn max(&mut self, a: Wire, b: Wire, var_manager: &mut dyn ManageVars) -> Wire {
+        match (a, b) {
+            (None, w) | (w, None) => w,
+            (Some(a), Some(b)) => {
+                let out = var_manager.new_var().pos_lit();
+                self.gates.push(Gate::Or { a, b, out });
+                Some(out)
+            }
+        }
+    }
+
+    /// Creates the smaller output of a 2-comparator
+    fn min(&mut self, a: Wire, b: Wire, var_manager: &mut dyn ManageVars) -> Wire {
+        match (a, b) {
+            (None, _) | (_, None) => None,
+            (Some(a), Some(b)) => {
+                let out = var_manager.new_var().pos_lit();
+                self.gates.push(Gate::And { a, b, out });
+                Some(out)
+            }
+        }
+    }
+
+    /// Sorts a sequence of wires with a length that is a power of two
+    fn hsort(&mut self, wires: &[Wire], var_manager: &mut dyn ManageVars) -> Vec<Wire> {
+        debug_assert!(wires.len().is_power_of_two());
+        if wires.len() == 1 {
+            return wires.to_vec();
+        }
+        let half = wires.len() / 2;
+        let upper = self.hsort(&wires[..half], var_manager);
+        let lower = self.hsort(&wires[half..], var_manager);
+        self.hmerge(&upper, &lower, var_manager)
+    }
+
+    /// Merges two sorted sequences of equal length that is a power of two
+    fn hmerge(&mut self, a: &[Wire], b: &[Wire], var_manager: &mut dyn ManageVars) -> Vec<Wire> {
+        debug_assert_eq!(a.len(), b.len());
+        let n = a.len();
+        if n == 1 {
+            return vec![
+                self.max(a[0], b[0], var_manager),
+                self.min(a[0], b[0], var_manager),
+            ];
+        }
+        let (a_odd, a_even) = split_odd_even(a);
+        let (b_odd, b_even) = split_odd_even(b);
+        let d = self.hmerge(&a_odd, &b_odd, var_manager);
+        let e = self.hmerge(&a_even, &b_even, var_manager);
+        let mut out = Vec::with_capacity(2 * n);
+        out.push(d[0]);
+        for idx in 0..n - 1 {
+            out.push(self.max(d[idx + 1], e[idx], var_manager));
+            out.push(self.min(d[idx + 1], e[idx], var_manager));
+        }
+        out.push(e[n - 1]);
+        out
+    }
+
+    /// Simplified merge of two sorted sequences of equal length `n` that is a
+    /// power of two. Only the largest `n_out <= n + 1` outputs are produced.
+    fn smerge(
+        &mut self,
+        a: &[Wire],
+        b: &[Wire],
+        n_out: usize,
+        var_manager: &mut dyn ManageVars,
+    ) -> Vec<Wire> {
+        debug_assert_eq!(a.len(), b.len());
+        let n = a.len();
+        debug_assert!(n_out <= n + 1);
+        if n_out == 0 {
+            return vec![];
+        }
+        if n == 1 {
+            let mut out = vec![self.max(a[0], b[0], var_manager)];
+            if n_out > 1 {
+                out.push(self.min(a[0], b[0], var_manager));
+            }
+            return out;
+        }
+        let (a_odd, a_even) = split_odd_even(a);
+        let (b_odd, b_even) = split_odd_even(b);
+        let d = self.smerge(&a_odd, &b_odd, n_out / 2 + 1, var_manager);
+        let e = self.smerge(&a_even, &b_even, n_out / 2, var_manager);
+        let mut out = Vec::with_capacity(n_out);
+        out.push(d[0]);
+        for idx in 0..n / 2 {
+            if 2 * idx + 1 >= n_out {
+                break;
+            }
+            out.push(self.max(d[idx + 1], e[idx], var_manager));
+            if 2 * idx + 2 >= n_out {
+                break;
+            }
+            out.push(self.min(d[idx + 1], e[idx], var_manager));
+        }
+        out
+    }
+}
+
+/// Splits a sequence of wires into the wires at odd and even positions
+/// (1-indexed, as in the paper)
+fn split_odd_even(wires: &[Wire]) -> (Vec<Wire>, Vec<Wire>) {
+    (
+        wires.iter().step_by(2).copied().collect(),
+        wires.iter().skip(1).step_by(2).copied().collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CardinalityNetwork;
+    use crate::{
+        encodings::{
+            card::{
+                BoundBoth, BoundLower, BoundLowerIncremental, BoundUpper, BoundUpperIncremental,
+            },
+            EncodeStats, Error,
+        },
+        instances::{BasicVarManager, Cnf, ManageVars},
+        lit, var,
+    };
+
+    #[test]
+    fn cnet_functions() {
+        let mut cnet = CardinalityNetwork::default();
+        cnet.extend(vec![lit![0], lit![1], lit![2], lit![3]]);
+        assert_eq!(cnet.enforce_ub(2), Err(Error::NotEncoded));
+        assert_eq!(cnet.enforce_lb(2), Err(Error::NotEncoded));
+        assert_eq!(cnet.enforce_lb(5), Err(Error::Unsat));
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        let mut cnf = Cnf::new();
+        cnet.encode_ub(0..5, &mut cnf, &mut var_manager).unwrap();
+        cnet.encode_lb(0..5, &mut cnf, &mut var_manager).unwrap();
+        // Batcher's odd-even merge sort on four inputs has five comparators
+        assert_eq!(cnet.n_vars(), 10);
+        assert_eq!(cnf.len(), cnet.n_clauses());
+        assert_eq!(cnet.enforce_ub(2).unwrap().len(), 1);
+        assert_eq!(cnet.enforce_lb(2).unwrap().len(), 1);
+        assert_eq!(cnet.enforce_eq(2).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn cnet_small_bound() {
+        let mut cnet = CardinalityNetwork::default();
+        cnet.extend((0..8).map(|idx| lit![idx]));
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![8]);
+        let mut cnf = Cnf::new();
+        cnet.encode_ub(0..1, &mut cnf, &mut var_manager).unwrap();
+        // blocks of size one are merged into a chain of disjunctions
+        assert_eq!(cnet.n_vars(), 7);
+        assert_eq!(cnet.enforce_ub(0).unwrap().len(), 1);
+        assert_eq!(cnet.enforce_ub(1), Err(Error::NotEncoded));
+        cnet.encode_ub_change(0..3, &mut cnf, &mut var_manager)
+            .unwrap();
+        assert_eq!(cnet.enforce_ub(1).unwrap().len(), 1);
+        assert_eq!(cnet.enforce_ub(2).unwrap().len(), 1);
+        assert_eq!(cnf.len(), cnet.n_clauses());
+    }
+
+    #[test]
+    fn cnet_incremental_building_ub() {
+        let mut cnet1 = CardinalityNetwork::default();
+        cnet1.extend(vec![lit![0], lit![1], lit![2], lit![3]]);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        let mut cnf1 = Cnf::new();
+        cnet1.encode_ub(0..4, &mut cnf1, &mut var_manager).unwrap();
+        let mut cnet2 = CardinalityNetwork::default();
+        cnet2.extend(vec![lit![0], lit![1], lit![2], lit![3]]);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        let mut cnf2 = Cnf::new();
+        cnet2.encode_ub(0..2, &mut cnf2, &mut var_manager).unwrap();
+        assert_eq!(cnet2.enforce_ub(3), Err(Error::NotEncoded));
+        cnet2
+            .encode_ub_change(0..4, &mut cnf2, &mut var_manager)
+            .unwrap();
+        assert_eq!(cnet2.enforce_ub(3).unwrap().len(), 1);
+        assert_eq!(cnf1.len(), cnet1.n_clauses());
+        assert_eq!(cnf2.len(), cnet2.n_clauses());
+    }
+
+    #[test]
+    fn cnet_incremental_building_lb() {
+        let mut cnet = CardinalityNetwork::default();
+        cnet.extend(vec![lit![0], lit![1], lit![2], lit![3]]);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        let mut cnf = Cnf::new();
+        cnet.encode_lb(0..3, &mut cnf, &mut var_manager).unwrap();
+        assert_eq!(cnet.enforce_lb(2).unwrap().len(), 1);
+        assert_eq!(cnet.enforce_lb(3), Err(Error::NotEncoded));
+        cnet.encode_lb_change(0..5, &mut cnf, &mut var_manager)
+            .unwrap();
+        assert_eq!(cnet.enforce_lb(4).unwrap().len(), 1);
+        assert_eq!(cnf.len(), cnet.n_clauses());
+    }
+
+    #[test]
+    fn cnet_both() {
+        let mut cnet = CardinalityNetwork::from(vec![lit![0], lit![1], lit![2], lit![3], lit![4]]);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![5]);
+        let mut cnf = Cnf::new();
+        cnet.encode_both(2..3, &mut cnf, &mut var_manager).unwrap();
+        assert_eq!(cnet.enforce_eq(2).unwrap().len(), 2);
+        assert_eq!(cnf.len(), cnet.n_clauses());
+    }
+}
@@ -13,18 +13,21 @@ use crate::{
     encodings::{
         atomics,
         nodedb::{NodeById, NodeCon, NodeId, NodeLike},
-        CollectClauses, EncodeStats, Error,
+        CollectClauses, EncodeStats, EncodingStats, Error, PredictStats,
     },
     instances::ManageVars,
-    types::{Lit, RsHashMap},
+    types::{Lit, RsHashMap, RsHashSet},
 };
 
-use super::{BoundUpper, BoundUpperIncremental, Encode, EncodeIncremental};
+use super::{
+    BoundLower, BoundLowerIncremental, BoundUpper, BoundUpperIncremental, Encode, EncodeIncremental,
+};
 
 /// Implementation of the binary adder tree totalizer encoding \[1\].
 /// The implementation is incremental as extended in \[2\].
 /// The implementation is based on a node database.
-/// For now, this implementation only supports upper bounding.
+/// Upper and lower bounds share the same output literals, i.e., encoding both
+/// bounds does not introduce additional variables.
 ///
 /// # References
 ///
@@ -78,6 +81,30 @@ impl DbTotalizer {
             Some(root) => self.db[*root].depth(),
         }
     }
+
+    /// Predicts the size of an encoding with `predict` on the tree that the
+    /// next encoding will use. If the tree needs to be extended, this is done
+    /// on a copy of the database.
+    fn predict<F>(&self, predict: F) -> EncodingStats
+    where
+        F: FnOnce(&TotDb, NodeId) -> EncodingStats,
+    {
+        if self.lit_buffer.is_empty() {
+            return self
+                .root
+                .map_or_else(EncodingStats::default, |root| predict(&self.db, root));
+        }
+        let mut db = self.db.clone();
+        let new_tree = db.lit_tree(&self.lit_buffer);
+        let root = match self.root {
+            Some(old_root) => {
+                db.merge(&[NodeCon::full(old_root), NodeCon::full(new_tree)])
+                    .id
+            }
+            None => new_tree,
+        };
+        predict(&db, root)
+    }
 }
 
 impl Encode for DbTotalizer {
@@ -109,7 +136,7 @@ impl BoundUpper for DbTotalizer {
         Col: CollectClauses,
         R: RangeBounds<usize>,
     {
-        self.db.reset_encoded();
+        self.db.reset_encoded_pos();
         self.encode_ub_change(range, collector, var_manager)
     }
 
@@ -127,7 +154,7 @@ impl BoundUpper for DbTotalizer {
                     return Ok(vec![!*lit]);
                 }
                 INode::Unit(node) => {
-                    if let LitData::Lit { lit, enc_pos } = node.lits[ub] {
+                    if let LitData::Lit { lit, enc_pos, .. } = node.lits[ub] {
                         if enc_pos {
                             return Ok(vec![!lit]);
                         }
@@ -169,6 +196,81 @@ impl BoundUpperIncremental for DbTotalizer {
     }
 }
 
+impl BoundLower for DbTotalizer {
+    fn encode_lb<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        self.db.reset_encoded_neg();
+        self.encode_lb_change(range, collector, var_manager)
+    }
+
+    fn enforce_lb(&self, lb: usize) -> Result<Vec<Lit>, Error> {
+        if lb > self.n_lits() {
+            return Err(Error::Unsat);
+        }
+        if lb == 0 {
+            return Ok(vec![]);
+        }
+        if !self.lit_buffer.is_empty() {
+            return Err(Error::NotEncoded);
+        }
+        if let Some(id) = self.root {
+            match &self.db[id].0 {
+                INode::Leaf(lit) => {
+                    debug_assert_eq!(lb, 1);
+                    return Ok(vec![*lit]);
+                }
+                INode::Unit(node) => {
+                    if let LitData::Lit { lit, enc_neg, .. } = node.lits[lb - 1] {
+                        if enc_neg {
+                            return Ok(vec![lit]);
+                        }
+                    }
+                }
+                INode::General(_) | INode::Dummy => panic!(),
+            }
+        }
+        Err(Error::NotEncoded)
+    }
+}
+
+impl BoundLowerIncremental for DbTotalizer {
+    fn encode_lb_change<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        let range = super::prepare_lb_range(self, range);
+        if range.is_empty() {
+            return Ok(());
+        }
+        self.extend_tree();
+        if let Some(id) = self.root {
+            let n_vars_before = var_manager.n_used();
+            let n_clauses_before = collector.n_clauses();
+            for val in range {
+                self.db
+                    .define_neg_tot(id, val - 1, collector, var_manager)?;
+            }
+            self.n_clauses += collector.n_clauses() - n_clauses_before;
+            self.n_vars += var_manager.n_used() - n_vars_before;
+        };
+        Ok(())
+    }
+}
+
 impl EncodeStats for DbTotalizer {
     fn n_clauses(&self) -> usize {
         self.n_clauses
@@ -179,6 +281,44 @@ impl EncodeStats for DbTotalizer {
     }
 }
 
+impl PredictStats for DbTotalizer {
+    fn predict_ub<R>(&self, range: R) -> EncodingStats
+    where
+        R: RangeBounds<usize>,
+    {
+        let range = super::prepare_ub_range(self, range);
+        if range.is_empty() {
+            return EncodingStats::default();
+        }
+        self.predict(|db, root| {
+            let mut defined = RsHashSet::default();
+            let mut stats = EncodingStats::default();
+            for idx in range {
+                stats += db.predict_pos_tot(root, idx, &mut defined);
+            }
+            stats
+        })
+    }
+
+    fn predict_lb<R>(&self, range: R) -> EncodingStats
+    where
+        R: RangeBounds<usize>,
+    {
+        let range = super::prepare_lb_range(self, range);
+        if range.is_empty() {
+            return EncodingStats::default();
+        }
+        self.predict(|db, root| {
+            let mut defined = RsHashSet::default();
+            let mut stats = EncodingStats::default();
+            for val in range {
+                stats += db.predict_neg_tot(root, val - 1, &mut defined);
+            }
+            stats
+        })
+    }
+}
+
 impl From<Vec<Lit>> for DbTotalizer {
     fn from(lits: Vec<Lit>) -> Self {
         Self {
@@ -396,6 +536,21 @@ impl Node {
         }
     }
 
+    /// Checks if a given output value is negatively encoded
+    pub fn encoded_neg(&self, val: usize) -> bool {
+        match &self.0 {
+            INode::Leaf(..) => {
+                if val != 1 {
+                    return false;
+                }
+                true
+            }
+            INode::Unit(node) => node.encoded_neg(val),
+            INode::General(node) => node.encoded_neg(val),
+            INode::Dummy => true,
+        }
+    }
+
     /// Returns the internal node and panics if the node is not a unit
     pub(crate) fn unit(&self) -> &UnitNode {
         match &self.0 {
@@ -490,6 +645,12 @@ impl UnitNode {
     pub fn encoded_pos(&self, val: usize) -> bool {
         self.lits[val - 1].encoded_pos()
     }
+
+    /// Checks if a given value is negatively encoded
+    #[inline]
+    pub fn encoded_neg(&self, val: usize) -> bool {
+        self.lits[val - 1].encoded_neg()
+    }
 }
 
 impl Index<usize> for UnitNode {
@@ -548,6 +709,15 @@ impl GeneralNode {
             .map(|dat| dat.encoded_pos())
             .unwrap_or(false)
     }
+
+    /// Checks if a given value is negatively encoded
+    #[inline]
+    pub fn encoded_neg(&self, val: usize) -> bool {
+        self.lits
+            .get(&val)
+            .map(|dat| dat.encoded_neg())
+            .unwrap_or(false)
+    }
 }
 
 /// Data associated with an output literal in a [`Node`]
@@ -558,8 +728,7 @@ pub(crate) enum LitData {
     Lit {
         lit: Lit,
         enc_pos: bool,
-        // needed when getting around to implement lower bounding
-        // enc_neg: bool,
+        enc_neg: bool,
     },
 }
 
@@ -568,6 +737,7 @@ impl LitData {
         LitData::Lit {
             lit,
             enc_pos: false,
+            enc_neg: false,
         }
     }
 
@@ -586,6 +756,14 @@ impl LitData {
             LitData::Lit { enc_pos, .. } => *enc_pos,
         }
     }
+
+    #[inline]
+    fn encoded_neg(&self) -> bool {
+        match self {
+            LitData::None => false,
+            LitData::Lit { enc_neg, .. } => *enc_neg,
+        }
+    }
 }
 
 /// A totalizer database
@@ -964,92 +1142,514 @@ impl TotDb {
         Ok(olit)
     }
 
-    /// Recursively reserves all variables in the subtree rooted at the given node
-    pub fn reserve_vars(&mut self, id: NodeId, var_manager: &mut dyn ManageVars) {
-        if self[id].is_leaf() {
-            return;
-        }
-
-        // Recurse
-        self.reserve_vars(self[id].left().unwrap().id, var_manager);
-        self.reserve_vars(self[id].right().unwrap().id, var_manager);
+    /// Generates the encoding to define the negative output literal with value
+    /// `val`, if it is not already defined. Recurses down the tree. The
+    /// returned literal is the output literal and the encoding is added to the
+    /// `collector`.
+    pub fn define_neg<Col>(
+        &mut self,
+        id: NodeId,
+        val: usize,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<Option<Lit>, crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        debug_assert!(val <= self[id].max_val());
+        debug_assert!(val > 0);
+        match &self[id].0 {
+            INode::Leaf(lit) => {
+                debug_assert_eq!(val, 1);
+                if val != 1 {
+                    return Ok(None);
+                }
+                Ok(Some(*lit))
+            }
+            INode::Unit(node) => {
+                if val > node.lits.len() || val == 0 {
+                    return Ok(None);
+                }
 
-        match &mut self[id].0 {
-            INode::Unit(UnitNode { lits, .. }) => {
-                for olit in lits {
-                    if let LitData::None = olit {
-                        *olit = LitData::new_lit(var_manager.new_var().pos_lit())
+                // Check if already encoded
+                if let LitData::Lit { lit, enc_neg, .. } = node.lits[val - 1] {
+                    if enc_neg {
+                        return Ok(Some(lit));
                     }
                 }
+
+                Ok(Some(self.define_neg_tot(
+                    id,
+                    val - 1,
+                    collector,
+                    var_manager,
+                )?))
             }
-            INode::General(GeneralNode { lits, .. }) => {
-                for (_, olit) in lits.iter_mut() {
-                    if let LitData::None = olit {
-                        *olit = LitData::new_lit(var_manager.new_var().pos_lit())
+            INode::General(node) => {
+                // Check if already encoded
+                if let Some(lit_data) = node.lits.get(&val) {
+                    if let LitData::Lit {
+                        lit, enc_neg: true, ..
+                    } = lit_data
+                    {
+                        return Ok(Some(*lit));
                     }
+                } else {
+                    return Ok(None);
                 }
-            }
-            INode::Leaf(_) | INode::Dummy => panic!(),
-        }
-    }
 
-    /// Resets the status of what has already been encoded
-    pub fn reset_encoded(&mut self) {
-        for node in &mut self.nodes {
-            match &mut node.0 {
-                INode::Unit(UnitNode { lits, .. }) => {
-                    for lit in lits {
-                        if let LitData::Lit { enc_pos, .. } = lit {
-                            *enc_pos = false
-                        }
+                debug_assert!(val <= node.max_val);
+                debug_assert!(node.lits.contains_key(&val));
+
+                let lcon = node.left;
+                let rcon = node.right;
+
+                // Reserve variable for this node, if needed
+                let olit = if let Some(&olit) = node.lit(val) {
+                    olit
+                } else {
+                    let olit = var_manager.new_var().pos_lit();
+                    *self[id].mut_general().lits.get_mut(&val).unwrap() = LitData::new_lit(olit);
+                    olit
+                };
+
+                // All values of the left connection smaller than `val`, including zero
+                let mut lvals: Vec<_> = std::iter::once(0)
+                    .chain(
+                        self[lcon.id]
+                            .vals(lcon.offset() + 1..)
+                            .map(|lval| lcon.map(lval)),
+                    )
+                    .take_while(|&lval| lval < val)
+                    .collect();
+                lvals.dedup();
+
+                // If the left connection has at most value `lval`, the right
+                // connection needs to be at least `val - lval`
+                for lval in lvals {
+                    let mut clause = vec![];
+                    if let Some(llit) =
+                        self.define_neg_con(lcon, lval + 1, collector, var_manager)?
+                    {
+                        clause.push(llit);
                     }
-                }
-                INode::General(GeneralNode { lits, .. }) => {
-                    for lit in lits.values_mut() {
-                        if let LitData::Lit { enc_pos, .. } = lit {
-                            *enc_pos = false
-                        }
+                    if let Some(rlit) =
+                        self.define_neg_con(rcon, val - lval, collector, var_manager)?
+                    {
+                        clause.push(rlit);
                     }
+                    collector.add_clause(atomics::lit_impl_clause(olit, &clause))?;
                 }
-                INode::Leaf(_) | INode::Dummy => (),
+
+                // Mark negative literal as encoded
+                match &mut self[id].mut_general().lits.get_mut(&val).unwrap() {
+                    LitData::None => panic!(),
+                    LitData::Lit { enc_neg, .. } => *enc_neg = true,
+                };
+
+                Ok(Some(olit))
             }
+            INode::Dummy => Ok(None),
         }
     }
 
-    /// Resets the reserved variables in the database. This also resets the
-    /// status of what has already been encoded.
-    #[cfg(feature = "internals")]
-    pub fn reset_vars(&mut self) {
-        for node in &mut self.nodes {
-            match &mut node.0 {
-                INode::Leaf(_) | INode::Dummy => (),
-                INode::Unit(UnitNode { lits, .. }) => {
-                    for lit in lits {
-                        *lit = LitData::None;
-                    }
-                }
-                INode::General(GeneralNode { lits, .. }) => {
-                    for lit in lits.values_mut() {
-                        *lit = LitData::None;
-                    }
-                }
+    /// Defines the negative output literal of the child node of a connection
+    /// that is required for the value of the connection to be at least `val`.
+    /// Returns [`None`] if the connection can never reach `val`.
+    fn define_neg_con<Col>(
+        &mut self,
+        con: NodeCon,
+        val: usize,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<Option<Lit>, crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        debug_assert!(val > 0);
+        if let Some(limit) = con.len_limit {
+            if (val - 1) / con.multiplier() >= limit.into() {
+                return Ok(None);
             }
         }
+        // The smallest value of the child that maps to at least `val`
+        match self[con.id].vals(con.rev_map_round_up(val)..).next() {
+            Some(child_val) => self.define_neg(con.id, child_val, collector, var_manager),
+            None => Ok(None),
+        }
     }
-}
-
-/// Totalizer encoding types that do not own but reference their [`TotDb`]
-#[cfg(feature = "internals")]
-pub mod referenced {
-    use std::cell::RefCell;
 
-    use crate::{
-        encodings::{
-            card::{BoundUpper, BoundUpperIncremental, Encode, EncodeIncremental},
-            nodedb::{NodeId, NodeLike},
-            CollectClauses, Error,
-        },
-        instances::ManageVars,
+    /// Defines a negative output, assuming that the structure is a non-weighted totalizer
+    ///
+    /// The `idx` parameter is the output index, i.e., not the value represented by the output, but
+    /// `value - 1`.
+    pub fn define_neg_tot<Col>(
+        &mut self,
+        id: NodeId,
+        idx: usize,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<Lit, crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        let node = &self[id];
+        debug_assert!(idx < node.max_val());
+        if node.is_leaf() {
+            debug_assert_eq!(idx, 0);
+            return Ok(node[1]);
+        }
+        let lcon = node.left().unwrap();
+        let rcon = node.right().unwrap();
+        debug_assert!(matches!(
+            self[rcon.id].0,
+            INode::Leaf(_) | INode::Unit(_) | INode::Dummy
+        ));
+        debug_assert!(matches!(
+            self[lcon.id].0,
+            INode::Leaf(_) | INode::Unit(_) | INode::Dummy
+        ));
+        debug_assert_eq!(lcon.multiplier(), 1);
+        debug_assert_eq!(rcon.multiplier(), 1);
+        let node = node.unit();
+
+        // Check if already encoded
+        if let LitData::Lit { lit, enc_neg, .. } = node.lits[idx] {
+            if enc_neg {
+                return Ok(lit);
+            }
+        }
+
+        let con_idx = |idx: usize, con: NodeCon| con.rev_map(idx + 1) - 1;
+
+        // treat dummy nodes by passing through other connection
+        if matches!(self[lcon.id].0, INode::Dummy) || matches!(self[rcon.id].0, INode::Dummy) {
+            let realcon = if matches!(self[lcon.id].0, INode::Dummy) {
+                &rcon
+            } else {
+                &lcon
+            };
+            debug_assert!(matches!(
+                self[realcon.id].0,
+                INode::Leaf(_) | INode::Unit(_)
+            ));
+            let ilit =
+                self.define_neg_tot(realcon.id, con_idx(idx, *realcon), collector, var_manager)?;
+            // Reserve variable for this node, if needed
+            let olit = if let Some(&olit) = self[id].lit(idx + 1) {
+                olit
+            } else {
+                let olit = var_manager.new_var().pos_lit();
+                self[id].mut_unit().lits[idx] = LitData::new_lit(olit);
+                olit
+            };
+            collector.add_clause(atomics::lit_impl_lit(olit, ilit))?;
+            match &mut self[id].mut_unit().lits[idx] {
+                LitData::None => unreachable!(),
+                LitData::Lit { enc_neg, .. } => *enc_neg = true,
+            };
+            return Ok(olit);
+        }
+
+        let l_len = self.con_len(lcon);
+        let r_len = self.con_len(rcon);
+
+        // The range of left indices that influence the current literal
+        // (ignoring offset and divisor). An index equal to the length of the
+        // connection represents the left child having all its inputs true.
+        let l_min_idx = idx.saturating_sub(r_len);
+        let l_max_idx = cmp::min(l_len, idx);
+
+        // Encode children (recurse)
+        for lidx in l_min_idx..cmp::min(l_max_idx + 1, l_len) {
+            self.define_neg_tot(lcon.id, con_idx(lidx, lcon), collector, var_manager)?;
+        }
+        for lidx in l_min_idx..=l_max_idx {
+            let ridx = idx - lidx;
+            if ridx < r_len {
+                self.define_neg_tot(rcon.id, con_idx(ridx, rcon), collector, var_manager)?;
+            }
+        }
+
+        // Reserve variable for this node, if needed
+        let olit = if let Some(&olit) = self[id].lit(idx + 1) {
+            olit
+        } else {
+            let olit = var_manager.new_var().pos_lit();
+            self[id].mut_unit().lits[idx] = LitData::new_lit(olit);
+            olit
+        };
+
+        // Get reference to literals of children
+        let tmp_olit_l;
+        let llits = match &self[lcon.id].0 {
+            INode::Leaf(lit) => {
+                tmp_olit_l = LitData::new_lit(*lit);
+                std::slice::from_ref(&tmp_olit_l)
+            }
+            INode::Unit(UnitNode { lits, .. }) => lits,
+            _ => panic!(),
+        };
+        let tmp_olit_r;
+        let rlits = match &self[rcon.id].0 {
+            INode::Leaf(lit) => {
+                tmp_olit_r = LitData::new_lit(*lit);
+                std::slice::from_ref(&tmp_olit_r)
+            }
+            INode::Unit(UnitNode { lits, .. }) => lits,
+            _ => panic!(),
+        };
+
+        // Encode this node
+        let clause_for_lidx = |lidx: usize| {
+            let ridx = idx - lidx;
+            if lidx == l_len {
+                return atomics::lit_impl_lit(olit, *rlits[con_idx(ridx, rcon)].lit().unwrap());
+            }
+            let llit = *llits[con_idx(lidx, lcon)].lit().unwrap();
+            if ridx == r_len {
+                return atomics::lit_impl_lit(olit, llit);
+            }
+            let rlit = *rlits[con_idx(ridx, rcon)].lit().unwrap();
+            atomics::lit_impl_clause(olit, &[llit, rlit])
+        };
+        let clause_iter = (l_min_idx..=l_max_idx).map(clause_for_lidx);
+        collector.extend_clauses(clause_iter)?;
+
+        // Mark negative literal as encoded
+        match &mut self[id].mut_unit().lits[idx] {
+            LitData::None => panic!(),
+            LitData::Lit { enc_neg, .. } => *enc_neg = true,
+        };
+
+        Ok(olit)
+    }
+
+    /// Predicts the size of the encoding that [`TotDb::define_pos_tot`]
+    /// generates, assuming that no output literals are encoded or reserved yet.
+    /// Outputs that are already accounted for, e.g., in shared substructures,
+    /// are tracked in `defined`.
+    pub fn predict_pos_tot(
+        &self,
+        id: NodeId,
+        idx: usize,
+        defined: &mut RsHashSet<(NodeId, usize)>,
+    ) -> EncodingStats {
+        let node = &self[id];
+        debug_assert!(idx < node.max_val());
+        if node.is_leaf() || !defined.insert((id, idx)) {
+            return EncodingStats::default();
+        }
+        let lcon = node.left().unwrap();
+        let rcon = node.right().unwrap();
+
+        let con_idx = |idx: usize, con: NodeCon| con.rev_map(idx + 1) - 1;
+
+        // treat dummy nodes by passing through other connection
+        if matches!(self[lcon.id].0, INode::Dummy) || matches!(self[rcon.id].0, INode::Dummy) {
+            let realcon = if matches!(self[lcon.id].0, INode::Dummy) {
+                rcon
+            } else {
+                lcon
+            };
+            let mut stats = self.predict_pos_tot(realcon.id, con_idx(idx, realcon), defined);
+            stats += EncodingStats {
+                n_clauses: 1,
+                n_vars: 1,
+            };
+            return stats;
+        }
+
+        // Same index ranges as in `define_pos_tot`
+        let l_max_idx = cmp::min(self.con_len(lcon) - 1, idx);
+        let r_max_idx = cmp::min(self.con_len(rcon) - 1, idx);
+        let l_min_idx = if idx == r_max_idx {
+            0
+        } else {
+            idx - r_max_idx - 1
+        };
+        let r_min_idx = if idx == l_max_idx {
+            0
+        } else {
+            idx - l_max_idx - 1
+        };
+
+        let mut stats = EncodingStats::default();
+        for lidx in l_min_idx..=l_max_idx {
+            stats += self.predict_pos_tot(lcon.id, con_idx(lidx, lcon), defined);
+        }
+        for ridx in r_min_idx..=r_max_idx {
+            stats += self.predict_pos_tot(rcon.id, con_idx(ridx, rcon), defined);
+        }
+        stats += EncodingStats {
+            n_clauses: usize::from(l_max_idx == idx)
+                + usize::from(r_max_idx == idx)
+                + cmp::min(l_max_idx + 1, idx).saturating_sub(l_min_idx),
+            n_vars: 1,
+        };
+        stats
+    }
+
+    /// Predicts the size of the encoding that [`TotDb::define_neg_tot`]
+    /// generates, assuming that no output literals are encoded or reserved yet.
+    /// Outputs that are already accounted for, e.g., in shared substructures,
+    /// are tracked in `defined`.
+    pub fn predict_neg_tot(
+        &self,
+        id: NodeId,
+        idx: usize,
+        defined: &mut RsHashSet<(NodeId, usize)>,
+    ) -> EncodingStats {
+        let node = &self[id];
+        debug_assert!(idx < node.max_val());
+        if node.is_leaf() || !defined.insert((id, idx)) {
+            return EncodingStats::default();
+        }
+        let lcon = node.left().unwrap();
+        let rcon = node.right().unwrap();
+
+        let con_idx = |idx: usize, con: NodeCon| con.rev_map(idx + 1) - 1;
+
+        // treat dummy nodes by passing through other connection
+        if matches!(self[lcon.id].0, INode::Dummy) || matches!(self[rcon.id].0, INode::Dummy) {
+            let realcon = if matches!(self[lcon.id].0, INode::Dummy) {
+                rcon
+            } else {
+                lcon
+            };
+            let mut stats = self.predict_neg_tot(realcon.id, con_idx(idx, realcon), defined);
+            stats += EncodingStats {
+                n_clauses: 1,
+                n_vars: 1,
+            };
+            return stats;
+        }
+
+        // Same index ranges as in `define_neg_tot`
+        let l_len = self.con_len(lcon);
+        let r_len = self.con_len(rcon);
+        let l_min_idx = idx.saturating_sub(r_len);
+        let l_max_idx = cmp::min(l_len, idx);
+
+        let mut stats = EncodingStats::default();
+        for lidx in l_min_idx..cmp::min(l_max_idx + 1, l_len) {
+            stats += self.predict_neg_tot(lcon.id, con_idx(lidx, lcon), defined);
+        }
+        for lidx in l_min_idx..=l_max_idx {
+            let ridx = idx - lidx;
+            if ridx < r_len {
+                stats += self.predict_neg_tot(rcon.id, con_idx(ridx, rcon), defined);
+            }
+        }
+        stats += EncodingStats {
+            n_clauses: l_max_idx + 1 - l_min_idx,
+            n_vars: 1,
+        };
+        stats
+    }
+
+    /// Recursively reserves all variables in the subtree rooted at the given node
+    pub fn reserve_vars(&mut self, id: NodeId, var_manager: &mut dyn ManageVars) {
+        if self[id].is_leaf() {
+            return;
+        }
+
+        // Recurse
+        self.reserve_vars(self[id].left().unwrap().id, var_manager);
+        self.reserve_vars(self[id].right().unwrap().id, var_manager);
+
+        match &mut self[id].0 {
+            INode::Unit(UnitNode { lits, .. }) => {
+                for olit in lits {
+                    if let LitData::None = olit {
+                        *olit = LitData::new_lit(var_manager.new_var().pos_lit())
+                    }
+                }
+            }
+            INode::General(GeneralNode { lits, .. }) => {
+                for (_, olit) in lits.iter_mut() {
+                    if let LitData::None = olit {
+                        *olit = LitData::new_lit(var_manager.new_var().pos_lit())
+                    }
+                }
+            }
+            INode::Leaf(_) | INode::Dummy => panic!(),
+        }
+    }
+
+    /// Resets the status of what has already been encoded
+    pub fn reset_encoded(&mut self) {
+        self.reset_encoded_flags(true, true)
+    }
+
+    /// Resets the status of which positive output literals have already been
+    /// encoded. This is used when rebuilding the upper bounding encoding.
+    pub fn reset_encoded_pos(&mut self) {
+        self.reset_encoded_flags(true, false)
+    }
+
+    /// Resets the status of which negative output literals have already been
+    /// encoded. This is used when rebuilding the lower bounding encoding.
+    pub fn reset_encoded_neg(&mut self) {
+        self.reset_encoded_flags(false, true)
+    }
+
+    fn reset_encoded_flags(&mut self, pos: bool, neg: bool) {
+        let reset = |lit: &mut LitData| {
+            if let LitData::Lit {
+                enc_pos, enc_neg, ..
+            } = lit
+            {
+                *enc_pos &= !pos;
+                *enc_neg &= !neg;
+            }
+        };
+        for node in &mut self.nodes {
+            match &mut node.0 {
+                INode::Unit(UnitNode { lits, .. }) => lits.iter_mut().for_each(reset),
+                INode::General(GeneralNode { lits, .. }) => lits.values_mut().for_each(reset),
+                INode::Leaf(_) | INode::Dummy => (),
+            }
+        }
+    }
+
+    /// Resets the reserved variables in the database. This also resets the
+    /// status of what has already been encoded.
+    #[cfg(feature = "internals")]
+    pub fn reset_vars(&mut self) {
+        for node in &mut self.nodes {
+            match &mut node.0 {
+                INode::Leaf(_) | INode::Dummy => (),
+                INode::Unit(UnitNode { lits, .. }) => {
+                    for lit in lits {
+                        *lit = LitData::None;
+                    }
+                }
+                INode::General(GeneralNode { lits, .. }) => {
+                    for lit in lits.values_mut() {
+                        *lit = LitData::None;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Totalizer encoding types that do not own but reference their [`TotDb`]
+#[cfg(feature = "internals")]
+pub mod referenced {
+    use std::cell::RefCell;
+
+    use crate::{
+        encodings::{
+            card::{
+                BoundLower, BoundLowerIncremental, BoundUpper, BoundUpperIncremental, Encode,
+                EncodeIncremental,
+            },
+            nodedb::{NodeId, NodeLike},
+            CollectClauses, Error,
+        },
+        instances::ManageVars,
         types::Lit,
     };
 
@@ -1144,7 +1744,7 @@ pub mod referenced {
             Col: CollectClauses,
             R: std::ops::RangeBounds<usize>,
         {
-            self.db.reset_encoded();
+            self.db.reset_encoded_pos();
             self.encode_ub_change(range, collector, var_manager)
         }
 
@@ -1158,7 +1758,7 @@ pub mod referenced {
                     return Ok(vec![!*lit]);
                 }
                 INode::Unit(node) => {
-                    if let LitData::Lit { lit, enc_pos } = node.lits[ub] {
+                    if let LitData::Lit { lit, enc_pos, .. } = node.lits[ub] {
                         if enc_pos {
                             return Ok(vec![!lit]);
                         }
@@ -1181,7 +1781,7 @@ pub mod referenced {
             Col: CollectClauses,
             R: std::ops::RangeBounds<usize>,
         {
-            self.db.borrow_mut().reset_encoded();
+            self.db.borrow_mut().reset_encoded_pos();
             self.encode_ub_change(range, collector, var_manager)
         }
 
@@ -1195,7 +1795,7 @@ pub mod referenced {
                     return Ok(vec![!*lit]);
                 }
                 INode::Unit(node) => {
-                    if let LitData::Lit { lit, enc_pos } = node.lits[ub] {
+                    if let LitData::Lit { lit, enc_pos, .. } = node.lits[ub] {
                         if enc_pos {
                             return Ok(vec![!lit]);
                         }
@@ -1253,6 +1853,133 @@ pub mod referenced {
             Ok(())
         }
     }
+
+    impl BoundLower for Tot<'_> {
+        fn encode_lb<Col, R>(
+            &mut self,
+            range: R,
+            collector: &mut Col,
+            var_manager: &mut dyn ManageVars,
+        ) -> Result<(), crate::OutOfMemory>
+        where
+            Col: CollectClauses,
+            R: std::ops::RangeBounds<usize>,
+        {
+            self.db.reset_encoded_neg();
+            self.encode_lb_change(range, collector, var_manager)
+        }
+
+        fn enforce_lb(&self, lb: usize) -> Result<Vec<Lit>, Error> {
+            if lb > self.n_lits() {
+                return Err(Error::Unsat);
+            }
+            if lb == 0 {
+                return Ok(vec![]);
+            }
+            match &self.db[self.root].0 {
+                INode::Leaf(lit) => {
+                    debug_assert_eq!(lb, 1);
+                    return Ok(vec![*lit]);
+                }
+                INode::Unit(node) => {
+                    if let LitData::Lit { lit, enc_neg, .. } = node.lits[lb - 1] {
+                        if enc_neg {
+                            return Ok(vec![lit]);
+                        }
+                    }
+                }
+                INode::General(_) | INode::Dummy => panic!(),
+            }
+            Err(Error::NotEncoded)
+        }
+    }
+
+    impl BoundLower for TotCell<'_> {
+        fn encode_lb<Col, R>(
+            &mut self,
+            range: R,
+            collector: &mut Col,
+            var_manager: &mut dyn ManageVars,
+        ) -> Result<(), crate::OutOfMemory>
+        where
+            Col: CollectClauses,
+            R: std::ops::RangeBounds<usize>,
+        {
+            self.db.borrow_mut().reset_encoded_neg();
+            self.encode_lb_change(range, collector, var_manager)
+        }
+
+        fn enforce_lb(&self, lb: usize) -> Result<Vec<Lit>, Error> {
+            if lb > self.n_lits() {
+                return Err(Error::Unsat);
+            }
+            if lb == 0 {
+                return Ok(vec![]);
+            }
+            match &self.db.borrow()[self.root].0 {
+                INode::Leaf(lit) => {
+                    debug_assert_eq!(lb, 1);
+                    return Ok(vec![*lit]);
+                }
+                INode::Unit(node) => {
+                    if let LitData::Lit { lit, enc_neg, .. } = node.lits[lb - 1] {
+                        if enc_neg {
+                            return Ok(vec![lit]);
+                        }
+                    }
+                }
+                INode::General(_) | INode::Dummy => panic!(),
+            }
+            Err(Error::NotEncoded)
+        }
+    }
+
+    impl BoundLowerIncremental for Tot<'_> {
+        fn encode_lb_change<Col, R>(
+            &mut self,
+            range: R,
+            collector: &mut Col,
+            var_manager: &mut dyn ManageVars,
+        ) -> Result<(), crate::OutOfMemory>
+        where
+            Col: CollectClauses,
+            R: std::ops::RangeBounds<usize>,
+        {
+            let range = super::super::prepare_lb_range(self, range);
+            if range.is_empty() {
+                return Ok(());
+            }
+            for val in range {
+                self.db
+                    .define_neg_tot(self.root, val - 1, collector, var_manager)?;
+            }
+            Ok(())
+        }
+    }
+
+    impl BoundLowerIncremental for TotCell<'_> {
+        fn encode_lb_change<Col, R>(
+            &mut self,
+            range: R,
+            collector: &mut Col,
+            var_manager: &mut dyn ManageVars,
+        ) -> Result<(), crate::OutOfMemory>
+        where
+            Col: CollectClauses,
+            R: std::ops::RangeBounds<usize>,
+        {
+            let range = super::super::prepare_lb_range(self, range);
+            if range.is_empty() {
+                return Ok(());
+            }
+            for val in range {
+                self.db
+                    .borrow_mut()
+                    .define_neg_tot(self.root, val - 1, collector, var_manager)?;
+            }
+            Ok(())
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1260,9 +1987,11 @@ mod tests {
     use super::{DbTotalizer, TotDb};
     use crate::{
         encodings::{
-            card::{BoundUpper, BoundUpperIncremental},
+            card::{
+                BoundBoth, BoundLower, BoundLowerIncremental, BoundUpper, BoundUpperIncremental,
+            },
             nodedb::{NodeById, NodeCon, NodeLike},
-            EncodeStats, Error,
+            EncodeStats, EncodingStats, Error, PredictStats,
         },
         instances::{BasicVarManager, Cnf, ManageVars},
         lit, var,
@@ -1300,6 +2029,92 @@ mod tests {
         debug_assert_eq!(cnf.len(), 3);
     }
 
+    #[test]
+    fn tot_db_neg() {
+        let mut db = TotDb::default();
+        let root = db.lit_tree(&[lit![0], lit![1], lit![2], lit![3]]);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+
+        let mut cnf = Cnf::new();
+        db.define_neg_tot(root, 0, &mut cnf, &mut var_manager)
+            .unwrap();
+        debug_assert_eq!(cnf.len(), 3);
+
+        db.reset_encoded();
+        let mut cnf = Cnf::new();
+        db.define_neg_tot(root, 1, &mut cnf, &mut var_manager)
+            .unwrap();
+        debug_assert_eq!(cnf.len(), 8);
+
+        db.reset_encoded();
+        let mut cnf = Cnf::new();
+        db.define_neg_tot(root, 2, &mut cnf, &mut var_manager)
+            .unwrap();
+        debug_assert_eq!(cnf.len(), 9);
+
+        db.reset_encoded();
+        let mut cnf = Cnf::new();
+        db.define_neg_tot(root, 3, &mut cnf, &mut var_manager)
+            .unwrap();
+        debug_assert_eq!(cnf.len(), 6);
+    }
+
+    #[test]
+    fn predict_stats() {
+        for n_lits in 1..12 {
+            let lits: Vec<_> = (0..n_lits).map(|idx| lit![idx]).collect();
+            for start in 0..=n_lits as usize + 1 {
+                for end in start..=n_lits as usize + 2 {
+                    let mut tot = DbTotalizer::from(lits.clone());
+                    let pred = tot.predict_ub(start..end);
+                    let mut var_manager = BasicVarManager::default();
+                    var_manager.increase_next_free(var![n_lits]);
+                    let mut cnf = Cnf::new();
+                    tot.encode_ub(start..end, &mut cnf, &mut var_manager)
+                        .unwrap();
+                    assert_eq!(
+                        pred,
+                        EncodingStats {
+                            n_clauses: cnf.len(),
+                            n_vars: tot.n_vars()
+                        }
+                    );
+
+                    let mut tot = DbTotalizer::from(lits.clone());
+                    let pred = tot.predict_lb(start..end);
+                    let mut var_manager = BasicVarManager::default();
+                    var_manager.increase_next_free(var![n_lits]);
+                    let mut cnf = Cnf::new();
+                    tot.encode_lb(start..end, &mut cnf, &mut var_manager)
+                        .unwrap();
+                    assert_eq!(
+                        pred,
+                        EncodingStats {
+                            n_clauses: cnf.len(),
+                            n_vars: tot.n_vars()
+                        }
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn predict_stats_extended() {
+        let mut tot = DbTotalizer::default();
+        tot.extend(vec![lit![0], lit![1], lit![2], lit![3], lit![4]]);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![8]);
+        let mut cnf = Cnf::new();
+        tot.encode_ub(0..1, &mut cnf, &mut var_manager).unwrap();
+        tot.extend(vec![lit![5], lit![6], lit![7]]);
+        let pred = tot.predict_ub(0..6);
+        let mut cnf = Cnf::new();
+        tot.encode_ub(0..6, &mut cnf, &mut var_manager).unwrap();
+        assert_eq!(pred.n_clauses, cnf.len());
+    }
+
     #[test]
     fn weighted_tot_db() {
         let mut db = TotDb::default();
@@ -1414,6 +2229,38 @@ mod tests {
         assert_eq!(cnf.len(), tot.n_clauses());
     }
 
+    #[test]
+    fn functions_lb() {
+        let mut tot = DbTotalizer::default();
+        tot.extend(vec![lit![0], lit![1], lit![2], lit![3]]);
+        assert_eq!(tot.enforce_lb(2), Err(Error::NotEncoded));
+        assert_eq!(tot.enforce_lb(5), Err(Error::Unsat));
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        let mut cnf = Cnf::new();
+        tot.encode_lb(0..5, &mut cnf, &mut var_manager).unwrap();
+        assert_eq!(cnf.len(), 14);
+        assert_eq!(tot.n_clauses(), 14);
+        assert_eq!(tot.n_vars(), 8);
+        assert_eq!(tot.enforce_lb(2).unwrap().len(), 1);
+        assert_eq!(tot.enforce_ub(2), Err(Error::NotEncoded));
+    }
+
+    #[test]
+    fn functions_both() {
+        let mut tot = DbTotalizer::default();
+        tot.extend(vec![lit![0], lit![1], lit![2], lit![3]]);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        let mut cnf = Cnf::new();
+        tot.encode_both(0..5, &mut cnf, &mut var_manager).unwrap();
+        assert_eq!(cnf.len(), 28);
+        assert_eq!(tot.n_clauses(), 28);
+        // upper and lower bounds share the output literals
+        assert_eq!(tot.n_vars(), 8);
+        assert_eq!(tot.enforce_eq(2).unwrap().len(), 2);
+    }
+
     #[test]
     fn incremental_building_ub() {
         let mut tot1 = DbTotalizer::default();
@@ -1435,6 +2282,27 @@ mod tests {
         assert_eq!(cnf2.len(), tot2.n_clauses());
     }
 
+    #[test]
+    fn incremental_building_lb() {
+        let mut tot1 = DbTotalizer::default();
+        tot1.extend(vec![lit![0], lit![1], lit![2], lit![3]]);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        let mut cnf1 = Cnf::new();
+        tot1.encode_lb(0..5, &mut cnf1, &mut var_manager).unwrap();
+        let mut tot2 = DbTotalizer::default();
+        tot2.extend(vec![lit![0], lit![1], lit![2], lit![3]]);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        let mut cnf2 = Cnf::new();
+        tot2.encode_lb(0..3, &mut cnf2, &mut var_manager).unwrap();
+        tot2.encode_lb_change(0..5, &mut cnf2, &mut var_manager)
+            .unwrap();
+        assert_eq!(cnf1.len(), cnf2.len());
+        assert_eq!(cnf1.len(), tot1.n_clauses());
+        assert_eq!(cnf2.len(), tot2.n_clauses());
+    }
+
     #[test]
     fn drain() {
         let mut db = TotDb::default();
@@ -0,0 +1,721 @@
+//! # Modulo Totalizer Encoding
+//!
+//! Implementation of the modulo totalizer encoding \[1\] and its k-ary
+//! generalization. Instead of counting in unary, each node of the totalizer
+//! tree represents its count in base `p` (the modulo) with `K` digits. All but
+//! the most significant digit are unary counters up to `p - 1`, the most
+//! significant digit is a unary counter that is only built up to the largest
+//! bound that is encoded. For `K = 2`, this is the modulo totalizer of \[1\],
+//! which requires `O(n sqrt(k))` clauses instead of the `O(n k)` clauses of
+//! the [`super::Totalizer`].
+//!
+//! The encoding only supports upper bounding. Lower bounds can be enforced via
+//! [`super::simulators::Inverted`].
+//!
+//! ## References
+//!
+//! - \[1\] Toru Ogawa, YangYang Liu, Ryuzo Hasegawa, Miyuki Koshimura and Hiroshi Fujita: _Modulo Based CNF Encoding of Cardinality Constraints and Its Application to MaxSAT Solvers_, ICTAI 2013.
+//! - \[2\] Antonio Morgado, Alexey Ignatiev and Joao Marques-Silva: _MSCG: Robust Core-Guided MaxSAT Solving_, JSAT 2015.
+
+use super::{BoundUpper, BoundUpperIncremental, Encode, EncodeIncremental, Error};
+use crate::{
+    encodings::{atomics, CollectClauses, EncodeStats, IterInputs},
+    instances::ManageVars,
+    types::Lit,
+};
+use std::{cmp, ops::RangeBounds};
+
+/// The modulo totalizer encoding \[1\], representing counts with a quotient and
+/// a remainder.
+///
+/// # References
+///
+/// - \[1\] Toru Ogawa, YangYang Liu, Ryuzo Hasegawa, Miyuki Koshimura and Hiroshi Fujita: _Modulo Based CNF Encoding of Cardinality Constraints and Its Application to MaxSAT Solvers_, ICTAI 2013.
+pub type ModuloTotalizer = KModuloTotalizer<2>;
+
+/// The k-modulo totalizer encoding, generalizing the modulo totalizer \[1\] to
+/// counts represented with `K` digits in base `p`. For `K = 1`, the encoding is
+/// equivalent to the [`super::Totalizer`].
+///
+/// If no modulo is set via [`KModuloTotalizer::set_modulo`], the smallest `p`
+/// such that `p^K` is at least the largest bound in the first encoded range is
+/// used.
+///
+/// `K` must be at least `1`, otherwise the encoding fails to compile.
+///
+/// ```compile_fail
+/// use rustsat::encodings::card::KModuloTotalizer;
+///
+/// let _ = KModuloTotalizer::<0>::default();
+/// ```
+///
+/// # References
+///
+/// - \[1\] Toru Ogawa, YangYang Liu, Ryuzo Hasegawa, Miyuki Koshimura and Hiroshi Fujita: _Modulo Based CNF Encoding of Cardinality Constraints and Its Application to MaxSAT Solvers_, ICTAI 2013.
+/// - \[2\] Antonio Morgado, Alexey Ignatiev and Joao Marques-Silva: _MSCG: Robust Core-Guided MaxSAT Solving_, JSAT 2015.
+pub struct KModuloTotalizer<const K: usize> {
+    /// Input literals to the totalizer
+    in_lits: Vec<Lit>,
+    /// Index of the next literal in [`KModuloTotalizer::in_lits`] that is not in the tree yet
+    not_enc_idx: usize,
+    /// The modulo, i.e., the base of the digits, `0` if not set yet
+    modulo: usize,
+    /// The nodes of the tree, children always come before their parents
+    nodes: Vec<Node>,
+    /// The index of the root node, if the tree is constructed
+    root: Option<usize>,
+    /// The literals for enforcing upper bounds at the current root
+    ub_outs: Vec<Option<UbOutput>>,
+    /// The number of variables in the totalizer
+    n_vars: u32,
+    /// The number of clauses in the totalizer
+    n_clauses: usize,
+}
+
+impl<const K: usize> KModuloTotalizer<K> {
+    /// Compile-time check that the number of digits is valid, needs to be
+    /// referenced in all constructors
+    const CHECK_K: () = assert!(K >= 1, "the number of digits K must be at least 1");
+
+    /// Sets the modulo of the encoding
+    ///
+    /// # Panics
+    ///
+    /// If `modulo < 2` or if the encoding has already been built.
+    pub fn set_modulo(&mut self, modulo: usize) {
+        assert!(modulo >= 2, "modulo must be at least 2");
+        assert!(
+            self.nodes.is_empty(),
+            "cannot change the modulo of a built encoding"
+        );
+        self.modulo = modulo;
+    }
+
+    /// Gets the modulo of the encoding, `None` if not determined yet
+    pub fn modulo(&self) -> Option<usize> {
+        if self.modulo == 0 {
+            None
+        } else {
+            Some(self.modulo)
+        }
+    }
+
+    /// Sets the modulo, if not set yet, to the smallest value such that `p^K >=
+    /// max_val`
+    fn init_modulo(&mut self, max_val: usize) {
+        if self.modulo != 0 {
+            return;
+        }
+        let mut modulo: usize = 2;
+        while modulo
+            .checked_pow(K as u32)
+            .map_or(false, |cap| cap < max_val)
+        {
+            modulo += 1;
+        }
+        self.modulo = modulo;
+    }
+
+    /// Gets the weight of the most significant digit
+    fn top_weight(&self) -> usize {
+        self.modulo.pow(K as u32 - 1)
+    }
+
+    /// Recursively builds the tree over the given input literals and returns
+    /// the index of the subtree root
+    fn build_tree(&mut self, lits: &[Lit]) -> usize {
+        debug_assert_ne!(lits.len(), 0);
+
+        if lits.len() == 1 {
+            self.nodes.push(Node::new_leaf::<K>(lits[0]));
+            return self.nodes.len() - 1;
+        }
+
+        let split = lits.len() / 2;
+        let left = self.build_tree(&lits[..split]);
+        let right = self.build_tree(&lits[split..]);
+        self.add_internal(left, right)
+    }
+
+    /// Adds an internal node over two existing nodes and returns its index
+    fn add_internal(&mut self, left: usize, right: usize) -> usize {
+        let (lnode, rnode) = (&self.nodes[left], &self.nodes[right]);
+        let mut widths = Vec::with_capacity(K - 1);
+        let mut has_carry = Vec::with_capacity(K - 1);
+        let mut carry = false;
+        for digit in 0..K - 1 {
+            let sum = lnode.widths[digit] + rnode.widths[digit] + carry as usize;
+            widths.push(cmp::min(sum, self.modulo - 1));
+            carry = sum >= self.modulo;
+            has_carry.push(carry);
+        }
+        let top_max = lnode.top_max + rnode.top_max + carry as usize;
+        self.nodes.push(Node {
+            digits: vec![vec![]; K],
+            carries: vec![],
+            widths,
+            has_carry,
+            children: Some((left, right)),
+            lower_encoded: false,
+            top_encoded: 0,
+            top_max,
+        });
+        self.nodes.len() - 1
+    }
+
+    /// Extends the tree at the root node with added literals
+    fn extend_tree(&mut self) {
+        if self.not_enc_idx != self.in_lits.len() {
+            let lits = self.in_lits[self.not_enc_idx..].to_vec();
+            let subtree = self.build_tree(&lits);
+            self.root = match self.root {
+                None => Some(subtree),
+                Some(old_root) => Some(self.add_internal(old_root, subtree)),
+            };
+            self.not_enc_idx = self.in_lits.len();
+            self.ub_outs.clear();
+        }
+    }
+
+    /// Allocates the variables of an internal node with the most significant
+    /// digit built up to `limit`
+    fn alloc_node(&mut self, idx: usize, limit: usize, var_manager: &mut dyn ManageVars) {
+        let node = &mut self.nodes[idx];
+        if node.children.is_none() {
+            return;
+        }
+        if node.carries.len() < K - 1 {
+            // Allocate lower digits
+            for digit in 0..K - 1 {
+                node.digits[digit] = (0..node.widths[digit])
+                    .map(|_| var_manager.new_var().pos_lit())
+                    .collect();
+                node.carries.push(if node.has_carry[digit] {
+                    Some(var_manager.new_var().pos_lit())
+                } else {
+                    None
+                });
+            }
+        }
+        let width = cmp::min(node.top_max, limit);
+        while node.digits[K - 1].len() < width {
+            node.digits[K - 1].push(var_manager.new_var().pos_lit());
+        }
+    }
+
+    /// Encodes all nodes of the tree with the most significant digit built up
+    /// to `limit`
+    fn encode_nodes<Col>(
+        &mut self,
+        limit: usize,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        for idx in 0..self.nodes.len() {
+            let (left, right) = match self.nodes[idx].children {
+                Some(children) => children,
+                None => continue,
+            };
+            self.alloc_node(idx, limit, var_manager);
+            let (done, rest) = self.nodes.split_at_mut(idx);
+            let node = &mut rest[0];
+            node.encode(&done[left], &done[right], self.modulo, limit, collector)?;
+        }
+        Ok(())
+    }
+
+    /// Gets the premises under which the count at the root exceeds `ub`. The
+    /// tree must be encoded with the most significant digit up to `ub / p^(K-1)
+    /// + 1` or be fully reserved.
+    fn ub_premises(&self, ub: usize) -> Vec<Vec<Lit>> {
+        let root = &self.nodes[self.root.expect("tree must be built")];
+        let get = |digit: usize, val: usize| -> Option<Option<Lit>> {
+            if val == 0 {
+                Some(None)
+            } else {
+                root.digits[digit].get(val - 1).copied().map(Some)
+            }
+        };
+        let top_val = ub / self.top_weight();
+        let mut premises = vec![];
+        if let Some(Some(lit)) = get(K - 1, top_val + 1) {
+            premises.push(vec![lit]);
+        }
+        for digit in (0..K - 1).rev() {
+            let mut premise = vec![];
+            let mut feasible = true;
+            for higher in (digit + 1..K).rev() {
+                let val = if higher == K - 1 {
+                    top_val
+                } else {
+                    (ub / self.modulo.pow(higher as u32)) % self.modulo
+                };
+                match get(higher, val) {
+                    Some(Some(lit)) => premise.push(lit),
+                    Some(None) => (),
+                    None => feasible = false,
+                }
+            }
+            let val = (ub / self.modulo.pow(digit as u32)) % self.modulo;
+            match get(digit, val + 1) {
+                Some(Some(lit)) => premise.push(lit),
+                _ => feasible = false,
+            }
+            if feasible {
+                premises.push(premise);
+            }
+        }
+        debug_assert!(!premises.is_empty());
+        premises
+    }
+
+    /// Encodes the literal for enforcing the upper bound `ub` at the root
+    fn encode_ub_output<Col>(
+        &mut self,
+        ub: usize,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        if self.ub_outs[ub].map_or(false, |out| out.encoded) {
+            return Ok(());
+        }
+        let premises = self.ub_premises(ub);
+        if premises.len() == 1 && premises[0].len() == 1 {
+            self.ub_outs[ub] = Some(UbOutput {
+                lit: premises[0][0],
+                encoded: true,
+            });
+            return Ok(());
+        }
+        let olit = match self.ub_outs[ub] {
+            Some(out) => out.lit,
+            None => var_manager.new_var().pos_lit(),
+        };
+        for premise in premises {
+            collector.add_clause(atomics::cube_impl_lit(&premise, olit))?;
+        }
+        self.ub_outs[ub] = Some(UbOutput {
+            lit: olit,
+            encoded: true,
+        });
+        Ok(())
+    }
+}
+
+impl<const K: usize> Encode for KModuloTotalizer<K> {
+    fn n_lits(&self) -> usize {
+        self.in_lits.len()
+    }
+}
+
+impl<const K: usize> IterInputs for KModuloTotalizer<K> {
+    type Iter<'a> = super::totalizer::TotIter<'a>;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.in_lits.iter().copied()
+    }
+}
+
+impl<const K: usize> EncodeIncremental for KModuloTotalizer<K> {
+    fn reserve(&mut self, var_manager: &mut dyn ManageVars) {
+        if self.in_lits.is_empty() {
+            return;
+        }
+        self.init_modulo(self.in_lits.len());
+        self.extend_tree();
+        let n_vars_before = var_manager.n_used();
+        for idx in 0..self.nodes.len() {
+            self.alloc_node(idx, usize::MAX, var_manager);
+        }
+        self.ub_outs.resize(self.in_lits.len(), None);
+        for ub in 0..self.in_lits.len() {
+            if self.ub_outs[ub].is_some() {
+                continue;
+            }
+            let premises = self.ub_premises(ub);
+            let lit = if premises.len() == 1 && premises[0].len() == 1 {
+                premises[0][0]
+            } else {
+                var_manager.new_var().pos_lit()
+            };
+            self.ub_outs[ub] = Some(UbOutput {
+                lit,
+                encoded: false,
+            });
+        }
+        self.n_vars += var_manager.n_used() - n_vars_before;
+    }
+}
+
+impl<const K: usize> BoundUpper for KModuloTotalizer<K> {
+    fn encode_ub<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        // Reset all encoding markers so that the clauses are encoded again
+        for node in &mut self.nodes {
+            if node.children.is_some() {
+                node.lower_encoded = false;
+                node.top_encoded = 0;
+            }
+        }
+        for out in self.ub_outs.iter_mut().flatten() {
+            out.encoded = false;
+        }
+        self.encode_ub_change(range, collector, var_manager)
+    }
+
+    fn enforce_ub(&self, ub: usize) -> Result<Vec<Lit>, Error> {
+        if ub >= self.in_lits.len() {
+            return Ok(vec![]);
+        };
+        if self.not_enc_idx != self.in_lits.len() {
+            return Err(Error::NotEncoded);
+        };
+        match self.ub_outs.get(ub) {
+            Some(Some(out)) if out.encoded => Ok(vec![!out.lit]),
+            _ => Err(Error::NotEncoded),
+        }
+    }
+}
+
+impl<const K: usize> BoundUpperIncremental for KModuloTotalizer<K> {
+    fn encode_ub_change<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        let range = super::prepare_ub_range(self, range);
+        if range.is_empty() {
+            return Ok(());
+        };
+        self.init_modulo(range.end);
+        self.extend_tree();
+        let n_vars_before = var_manager.n_used();
+        let n_clauses_before = collector.n_clauses();
+        let limit = (range.end - 1) / self.top_weight() + 1;
+        self.encode_nodes(limit, collector, var_manager)?;
+        self.ub_outs.resize(self.in_lits.len(), None);
+        for ub in range {
+            self.encode_ub_output(ub, collector, var_manager)?;
+        }
+        self.n_clauses += collector.n_clauses() - n_clauses_before;
+        self.n_vars += var_manager.n_used() - n_vars_before;
+        Ok(())
+    }
+}
+
+impl<const K: usize> EncodeStats for KModuloTotalizer<K> {
+    fn n_clauses(&self) -> usize {
+        self.n_clauses
+    }
+
+    fn n_vars(&self) -> u32 {
+        self.n_vars
+    }
+}
+
+impl<const K: usize> Default for KModuloTotalizer<K> {
+    fn default() -> Self {
+        let () = Self::CHECK_K;
+        Self {
+            in_lits: vec![],
+            not_enc_idx: 0,
+            modulo: 0,
+            nodes: vec![],
+            root: None,
+            ub_outs: vec![],
+            n_vars: 0,
+            n_clauses: 0,
+        }
+    }
+}
+
+impl<const K: usize> From<Vec<Lit>> for KModuloTotalizer<K> {
+    fn from(lits: Vec<Lit>) -> Self {
+        Self {
+            in_lits: lits,
+            ..Default::default()
+        }
+    }
+}
+
+impl<const K: usize> FromIterator<Lit> for KModuloTotalizer<K> {
+    fn from_iter<T: IntoIterator<Item = Lit>>(iter: T) -> Self {
+        Self::from(Vec::from_iter(iter))
+    }
+}
+
+impl<const K: usize> Extend<Lit> for KModuloTotalizer<K> {
+    fn extend<T: IntoIterator<Item = Lit>>(&mut self, iter: T) {
+        self.in_lits.extend(iter)
+    }
+}
+
+/// The literal enforcing an upper bound at the root
+#[derive(Clone, Copy)]
+struct UbOutput {
+    lit: Lit,
+    /// Whether the clauses defining the literal are encoded
+    encoded: bool,
+}
+
+/// A node in the modulo totalizer tree
+struct Node {
+    /// The unary counters of the digits, starting from the least significant
+    /// one. `digits[d][v - 1]` is implied if digit `d` has at least value `v`.
+    digits: Vec<Vec<Lit>>,
+    /// The carries out of all but the most significant digit
+    carries: Vec<Option<Lit>>,
+    /// The maximum values of all but the most significant digit
+    widths: Vec<usize>,
+    /// Whether there can be a carry out of all but the most significant digit
+    has_carry: Vec<bool>,
+    /// The children of internal nodes
+    children: Option<(usize, usize)>,
+    /// Whether the clauses for the lower digits are encoded
+    lower_encoded: bool,
+    /// Up to which value the most significant digit is encoded
+    top_encoded: usize,
+    /// The maximum value of the most significant digit
+    top_max: usize,
+}
+
+impl Node {
+    fn new_leaf<const K: usize>(lit: Lit) -> Node {
+        let mut digits = vec![vec![]; K];
+        digits[0].push(lit);
+        let mut widths = vec![0; K - 1];
+        if K > 1 {
+            widths[0] = 1;
+        }
+        Node {
+            digits,
+            carries: vec![None; K - 1],
+            widths,
+            has_carry: vec![false; K - 1],
+            children: None,
+            lower_encoded: true,
+            top_encoded: (K == 1) as usize,
+            top_max: (K == 1) as usize,
+        }
+    }
+
+    /// Gets the premise for a digit of this node having value `val`
+    fn digit_premise(&self, digit: usize, val: usize) -> Option<Lit> {
+        if val == 0 {
+            None
+        } else {
+            Some(self.digits[digit][val - 1])
+        }
+    }
+
+    /// Encodes the clauses of an internal node that are not encoded yet
+    fn encode<Col>(
+        &mut self,
+        left: &Node,
+        right: &Node,
+        modulo: usize,
+        limit: usize,
+        collector: &mut Col,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        let n_digits = self.digits.len();
+        if !self.lower_encoded {
+            for digit in 0..n_digits - 1 {
+                let carry_in = if digit > 0 {
+                    self.carries[digit - 1]
+                } else {
+                    None
+                };
+                let carry_out = self.carries[digit];
+                for lval in 0..=left.digits[digit].len() {
+                    for rval in 0..=right.digits[digit].len() {
+                        for cval in 0..=carry_in.is_some() as usize {
+                            let sum = lval + rval + cval;
+                            if sum == 0 {
+                                continue;
+                            }
+                            let premise: Vec<Lit> = [
+                                left.digit_premise(digit, lval),
+                                right.digit_premise(digit, rval),
+                                if cval > 0 { carry_in } else { None },
+                            ]
+                            .into_iter()
+                            .flatten()
+                            .collect();
+                            if sum < modulo {
+                                let olit = self.digits[digit][sum - 1];
+                                match carry_out {
+                                    Some(carry) => collector.add_clause(
+                                        atomics::cube_impl_clause(&premise, &[carry, olit]),
+                                    )?,
+                                    None => collector
+                                        .add_clause(atomics::cube_impl_lit(&premise, olit))?,
+                                }
+                            } else {
+                                let carry = carry_out.expect("carry must exist");
+                                collector.add_clause(atomics::cube_impl_lit(&premise, carry))?;
+                                if sum > modulo {
+                                    let olit = self.digits[digit][sum - modulo - 1];
+                                    collector.add_clause(atomics::cube_impl_lit(&premise, olit))?;
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            self.lower_encoded = true;
+        }
+        let top = n_digits - 1;
+        let width = cmp::min(self.top_max, limit);
+        if width > self.top_encoded {
+            let carry_in = if top > 0 { self.carries[top - 1] } else { None };
+            for lval in 0..=left.top_encoded {
+                for rval in 0..=right.top_encoded {
+                    for cval in 0..=carry_in.is_some() as usize {
+                        let sum = cmp::min(lval + rval + cval, width);
+                        if sum <= self.top_encoded {
+                            continue;
+                        }
+                        let premise: Vec<Lit> = [
+                            left.digit_premise(top, lval),
+                            right.digit_premise(top, rval),
+                            if cval > 0 { carry_in } else { None },
+                        ]
+                        .into_iter()
+                        .flatten()
+                        .collect();
+                        collector.add_clause(atomics::cube_impl_lit(
+                            &premise,
+                            self.digits[top][sum - 1],
+                        ))?;
+                    }
+                }
+            }
+            self.top_encoded = width;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{KModuloTotalizer, ModuloTotalizer};
+    use crate::{
+        encodings::{
+            card::{BoundUpper, BoundUpperIncremental, EncodeIncremental},
+            EncodeStats, Error,
+        },
+        instances::{BasicVarManager, Cnf, ManageVars},
+        lit, var,
+    };
+
+    #[test]
+    fn mtot_functions() {
+        let mut mtot = ModuloTotalizer::default();
+        mtot.extend(vec![lit![0], lit![1], lit![2], lit![3]]);
+        assert_eq!(mtot.enforce_ub(2), Err(Error::NotEncoded));
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        let mut cnf = Cnf::new();
+        mtot.encode_ub(0..4, &mut cnf, &mut var_manager).unwrap();
+        assert_eq!(mtot.modulo(), Some(2));
+        assert_eq!(cnf.len(), mtot.n_clauses());
+        assert_eq!(mtot.enforce_ub(2).unwrap().len(), 1);
+        assert_eq!(mtot.enforce_ub(4).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn mtot_set_modulo() {
+        let mut mtot = ModuloTotalizer::from(vec![lit![0], lit![1], lit![2], lit![3], lit![4]]);
+        mtot.set_modulo(3);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![5]);
+        let mut cnf = Cnf::new();
+        mtot.encode_ub(2..3, &mut cnf, &mut var_manager).unwrap();
+        assert_eq!(mtot.modulo(), Some(3));
+        assert_eq!(mtot.enforce_ub(2).unwrap().len(), 1);
+        assert_eq!(mtot.enforce_ub(3), Err(Error::NotEncoded));
+    }
+
+    #[test]
+    #[should_panic]
+    fn mtot_set_modulo_built() {
+        let mut mtot = ModuloTotalizer::from(vec![lit![0], lit![1], lit![2]]);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![3]);
+        let mut cnf = Cnf::new();
+        mtot.encode_ub(0..2, &mut cnf, &mut var_manager).unwrap();
+        mtot.set_modulo(3);
+    }
+
+    #[test]
+    fn mtot_incremental_building() {
+        let mut mtot1 = ModuloTotalizer::default();
+        mtot1.extend(vec![lit![0], lit![1], lit![2], lit![3]]);
+        mtot1.set_modulo(2);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        let mut cnf1 = Cnf::new();
+        mtot1.encode_ub(0..4, &mut cnf1, &mut var_manager).unwrap();
+        let mut mtot2 = ModuloTotalizer::default();
+        mtot2.extend(vec![lit![0], lit![1], lit![2], lit![3]]);
+        mtot2.set_modulo(2);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        let mut cnf2 = Cnf::new();
+        mtot2.encode_ub(0..2, &mut cnf2, &mut var_manager).unwrap();
+        mtot2
+            .encode_ub_change(0..4, &mut cnf2, &mut var_manager)
+            .unwrap();
+        // clauses for the clipped most significant digit stay in the encoding
+        assert!(cnf1.len() <= cnf2.len());
+        assert_eq!(cnf1.len(), mtot1.n_clauses());
+        assert_eq!(cnf2.len(), mtot2.n_clauses());
+        assert_eq!(mtot1.n_vars(), mtot2.n_vars());
+    }
+
+    #[test]
+    fn mtot_reserve() {
+        let mut mtot = ModuloTotalizer::from(vec![lit![0], lit![1], lit![2], lit![3], lit![4]]);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![5]);
+        mtot.reserve(&mut var_manager);
+        let n_vars = var_manager.n_used();
+        let mut cnf = Cnf::new();
+        mtot.encode_ub(0..5, &mut cnf, &mut var_manager).unwrap();
+        assert_eq!(var_manager.n_used(), n_vars);
+        assert_eq!(mtot.enforce_ub(3).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn kmtot_unary_is_totalizer() {
+        let mut kmtot = KModuloTotalizer::<1>::from(vec![lit![0], lit![1], lit![2], lit![3]]);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        let mut cnf = Cnf::new();
+        kmtot.encode_ub(0..4, &mut cnf, &mut var_manager).unwrap();
+        // same number of variables as the totalizer
+        assert_eq!(kmtot.n_vars(), 8);
+    }
+}
@@ -0,0 +1,418 @@
+//! # Sequential Counter Encoding
+//!
+//! Implementation of the sequential counter encoding \[1\]. The encoding
+//! requires `O(n*k)` clauses and variables, where `n` is the number of input
+//! literals and `k` is the largest bound to enforce. This makes it a good
+//! choice for small bounds on long input lists. The implementation is
+//! incremental both in the bound and in the input literals.
+//!
+//! ## References
+//!
+//! - \[1\] Carsten Sinz: _Towards an Optimal CNF Encoding of Boolean Cardinality Constraints_, CP 2005.
+
+use super::{
+    BoundLower, BoundLowerIncremental, BoundUpper, BoundUpperIncremental, Encode,
+    EncodeIncremental, Error,
+};
+use crate::{
+    encodings::{atomics, CollectClauses, EncodeStats, IterInputs},
+    instances::ManageVars,
+    types::Lit,
+};
+use std::{cmp, ops::RangeBounds};
+
+/// Implementation of the sequential counter encoding \[1\].
+///
+/// The register `regs[i][j]` is true if at least `j + 1` of the first `i + 1`
+/// input literals are true. Upper bounding adds the clauses propagating the
+/// counter upwards, lower bounding adds the clauses propagating it downwards.
+/// Both share the same registers.
+///
+/// # References
+///
+/// - \[1\] Carsten Sinz: _Towards an Optimal CNF Encoding of Boolean Cardinality Constraints_, CP 2005.
+#[derive(Default)]
+pub struct SeqCounter {
+    /// Input literals to the counter
+    in_lits: Vec<Lit>,
+    /// The counter registers, one row per input literal
+    regs: Vec<Vec<Lit>>,
+    /// The number of rows encoded for upper bounding
+    ub_rows: usize,
+    /// The number of counter columns encoded for upper bounding
+    ub_cols: usize,
+    /// The number of rows encoded for lower bounding
+    lb_rows: usize,
+    /// The number of counter columns encoded for lower bounding
+    lb_cols: usize,
+    /// The number of variables in the counter
+    n_vars: u32,
+    /// The number of clauses in the counter
+    n_clauses: usize,
+}
+
+impl SeqCounter {
+    /// Makes sure that registers exist for all input literals up to counter
+    /// value `cols`
+    fn extend_regs(&mut self, cols: usize, var_manager: &mut dyn ManageVars) {
+        for (idx, &lit) in self.in_lits.iter().enumerate() {
+            if idx >= self.regs.len() {
+                if idx == 0 {
+                    // The first register is the first input literal itself
+                    self.regs.push(vec![lit]);
+                    continue;
+                }
+                self.regs.push(vec![]);
+            }
+            let width = cmp::min(idx + 1, cols);
+            let row = &mut self.regs[idx];
+            while row.len() < width {
+                row.push(var_manager.new_var().pos_lit());
+                self.n_vars += 1;
+            }
+        }
+    }
+
+    /// Encodes the upward propagating clauses for all registers up to counter
+    /// value `cols` that are not encoded yet
+    fn encode_ub_cols<Col>(
+        &mut self,
+        cols: usize,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        let cols = cmp::max(cols, self.ub_cols);
+        if cols == 0 || (self.ub_rows == self.in_lits.len() && cols == self.ub_cols) {
+            return Ok(());
+        }
+        self.extend_regs(cols, var_manager);
+        let n_clauses_before = collector.n_clauses();
+        for idx in 1..self.in_lits.len() {
+            let first_col = if idx < self.ub_rows { self.ub_cols } else { 0 };
+            let lit = self.in_lits[idx];
+            let (prev, row) = (&self.regs[idx - 1], &self.regs[idx]);
+            for col in first_col..row.len() {
+                if col < prev.len() {
+                    // The counter does not decrease
+                    collector.add_clause(atomics::lit_impl_lit(prev[col], row[col]))?;
+                }
+                if col == 0 {
+                    collector.add_clause(atomics::lit_impl_lit(lit, row[col]))?;
+                } else {
+                    collector
+                        .add_clause(atomics::cube_impl_lit(&[lit, prev[col - 1]], row[col]))?;
+                }
+            }
+        }
+        self.n_clauses += collector.n_clauses() - n_clauses_before;
+        self.ub_rows = self.in_lits.len();
+        self.ub_cols = cols;
+        Ok(())
+    }
+
+    /// Encodes the downward propagating clauses for all registers up to
+    /// counter value `cols` that are not encoded yet
+    fn encode_lb_cols<Col>(
+        &mut self,
+        cols: usize,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        let cols = cmp::max(cols, self.lb_cols);
+        if cols == 0 || (self.lb_rows == self.in_lits.len() && cols == self.lb_cols) {
+            return Ok(());
+        }
+        self.extend_regs(cols, var_manager);
+        let n_clauses_before = collector.n_clauses();
+        for idx in 1..self.in_lits.len() {
+            let first_col = if idx < self.lb_rows { self.lb_cols } else { 0 };
+            let lit = self.in_lits[idx];
+            let (prev, row) = (&self.regs[idx - 1], &self.regs[idx]);
+            for col in first_col..row.len() {
+                if col < prev.len() {
+                    // The count was either already reached or the input
+                    // literal increased it
+                    collector.add_clause(atomics::lit_impl_clause(row[col], &[prev[col], lit]))?;
+                    if col > 0 {
+                        collector.add_clause(atomics::lit_impl_clause(
+                            row[col],
+                            &[prev[col], prev[col - 1]],
+                        ))?;
+                    }
+                } else {
+                    // The count can only be reached if all literals are true
+                    collector.add_clause(atomics::lit_impl_lit(row[col], lit))?;
+                    if col > 0 {
+                        collector.add_clause(atomics::lit_impl_lit(row[col], prev[col - 1]))?;
+                    }
+                }
+            }
+        }
+        self.n_clauses += collector.n_clauses() - n_clauses_before;
+        self.lb_rows = self.in_lits.len();
+        self.lb_cols = cols;
+        Ok(())
+    }
+
+    /// Gets the output literal for counter value `val`
+    fn output(&self, val: usize) -> Lit {
+        debug_assert!(val > 0 && val <= self.in_lits.len());
+        self.regs[self.in_lits.len() - 1][val - 1]
+    }
+}
+
+impl Encode for SeqCounter {
+    fn n_lits(&self) -> usize {
+        self.in_lits.len()
+    }
+}
+
+impl IterInputs for SeqCounter {
+    type Iter<'a> = super::totalizer::TotIter<'a>;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.in_lits.iter().copied()
+    }
+}
+
+impl EncodeIncremental for SeqCounter {
+    fn reserve(&mut self, var_manager: &mut dyn ManageVars) {
+        self.extend_regs(self.in_lits.len(), var_manager);
+    }
+}
+
+impl BoundUpper for SeqCounter {
+    fn encode_ub<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        self.ub_rows = 0;
+        self.ub_cols = 0;
+        self.encode_ub_change(range, collector, var_manager)
+    }
+
+    fn enforce_ub(&self, ub: usize) -> Result<Vec<Lit>, Error> {
+        if ub >= self.in_lits.len() {
+            return Ok(vec![]);
+        };
+        if self.ub_rows != self.in_lits.len() || ub >= self.ub_cols {
+            return Err(Error::NotEncoded);
+        };
+        Ok(vec![!self.output(ub + 1)])
+    }
+}
+
+impl BoundLower for SeqCounter {
+    fn encode_lb<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        self.lb_rows = 0;
+        self.lb_cols = 0;
+        self.encode_lb_change(range, collector, var_manager)
+    }
+
+    fn enforce_lb(&self, lb: usize) -> Result<Vec<Lit>, Error> {
+        if lb > self.in_lits.len() {
+            return Err(Error::Unsat);
+        } else if lb == 0 {
+            return Ok(vec![]);
+        };
+        if self.lb_rows != self.in_lits.len() || lb > self.lb_cols {
+            return Err(Error::NotEncoded);
+        };
+        Ok(vec![self.output(lb)])
+    }
+}
+
+impl BoundUpperIncremental for SeqCounter {
+    fn encode_ub_change<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        let range = super::prepare_ub_range(self, range);
+        if range.is_empty() {
+            return Ok(());
+        };
+        // Enforcing `ub` requires the counter to count up to `ub + 1`
+        self.encode_ub_cols(range.end, collector, var_manager)
+    }
+}
+
+impl BoundLowerIncremental for SeqCounter {
+    fn encode_lb_change<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        let range = super::prepare_lb_range(self, range);
+        if range.is_empty() {
+            return Ok(());
+        };
+        self.encode_lb_cols(range.end - 1, collector, var_manager)
+    }
+}
+
+impl EncodeStats for SeqCounter {
+    fn n_clauses(&self) -> usize {
+        self.n_clauses
+    }
+
+    fn n_vars(&self) -> u32 {
+        self.n_vars
+    }
+}
+
+impl From<Vec<Lit>> for SeqCounter {
+    fn from(lits: Vec<Lit>) -> Self {
+        Self {
+            in_lits: lits,
+            ..Default::default()
+        }
+    }
+}
+
+impl FromIterator<Lit> for SeqCounter {
+    fn from_iter<T: IntoIterator<Item = Lit>>(iter: T) -> Self {
+        Self::from(Vec::from_iter(iter))
+    }
+}
+
+impl Extend<Lit> for SeqCounter {
+    fn extend<T: IntoIterator<Item = Lit>>(&mut self, iter: T) {
+        self.in_lits.extend(iter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SeqCounter;
+    use crate::{
+        encodings::{
+            card::{
+                BoundBoth, BoundLower, BoundLowerIncremental, BoundUpper, BoundUpperIncremental,
+            },
+            EncodeStats, Error,
+        },
+        instances::{BasicVarManager, Cnf, ManageVars},
+        lit, var,
+    };
+
+    #[test]
+    fn seq_functions() {
+        let mut seq = SeqCounter::default();
+        seq.extend(vec![lit![0], lit![1], lit![2], lit![3]]);
+        assert_eq!(seq.enforce_ub(2), Err(Error::NotEncoded));
+        assert_eq!(seq.enforce_lb(2), Err(Error::NotEncoded));
+        assert_eq!(seq.enforce_lb(5), Err(Error::Unsat));
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        let mut cnf = Cnf::new();
+        seq.encode_ub(0..5, &mut cnf, &mut var_manager).unwrap();
+        seq.encode_lb(0..5, &mut cnf, &mut var_manager).unwrap();
+        assert_eq!(seq.n_vars(), 9);
+        assert_eq!(cnf.len(), seq.n_clauses());
+        assert_eq!(seq.enforce_ub(2).unwrap().len(), 1);
+        assert_eq!(seq.enforce_lb(2).unwrap().len(), 1);
+        assert_eq!(seq.enforce_eq(2).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn seq_small_bound() {
+        let mut seq = SeqCounter::default();
+        seq.extend((0..10).map(|idx| lit![idx]));
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![10]);
+        let mut cnf = Cnf::new();
+        seq.encode_ub(1..2, &mut cnf, &mut var_manager).unwrap();
+        // two counter columns for all but the first row, which is the input
+        assert_eq!(seq.n_vars(), 2 * 9);
+        assert_eq!(seq.enforce_ub(1).unwrap().len(), 1);
+        assert_eq!(seq.enforce_ub(2), Err(Error::NotEncoded));
+    }
+
+    #[test]
+    fn seq_incremental_building_ub() {
+        let mut seq1 = SeqCounter::default();
+        seq1.extend(vec![lit![0], lit![1], lit![2], lit![3]]);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        let mut cnf1 = Cnf::new();
+        seq1.encode_ub(0..4, &mut cnf1, &mut var_manager).unwrap();
+        let mut seq2 = SeqCounter::default();
+        seq2.extend(vec![lit![0], lit![1]]);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        let mut cnf2 = Cnf::new();
+        seq2.encode_ub(0..2, &mut cnf2, &mut var_manager).unwrap();
+        seq2.extend(vec![lit![2], lit![3]]);
+        assert_eq!(seq2.enforce_ub(2), Err(Error::NotEncoded));
+        seq2.encode_ub_change(0..4, &mut cnf2, &mut var_manager)
+            .unwrap();
+        assert_eq!(cnf1.len(), cnf2.len());
+        assert_eq!(cnf1.len(), seq1.n_clauses());
+        assert_eq!(cnf2.len(), seq2.n_clauses());
+        assert_eq!(seq1.n_vars(), seq2.n_vars());
+    }
+
+    #[test]
+    fn seq_incremental_building_lb() {
+        let mut seq1 = SeqCounter::default();
+        seq1.extend(vec![lit![0], lit![1], lit![2], lit![3]]);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        let mut cnf1 = Cnf::new();
+        seq1.encode_lb(0..5, &mut cnf1, &mut var_manager).unwrap();
+        let mut seq2 = SeqCounter::default();
+        seq2.extend(vec![lit![0], lit![1], lit![2], lit![3]]);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        let mut cnf2 = Cnf::new();
+        seq2.encode_lb(0..3, &mut cnf2, &mut var_manager).unwrap();
+        seq2.encode_lb_change(0..5, &mut cnf2, &mut var_manager)
+            .unwrap();
+        assert_eq!(cnf1.len(), cnf2.len());
+        assert_eq!(cnf1.len(), seq1.n_clauses());
+        assert_eq!(cnf2.len(), seq2.n_clauses());
+    }
+
+    #[test]
+    fn seq_both() {
+        let mut seq = SeqCounter::from(vec![lit![0], lit![1], lit![2], lit![3]]);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        let mut cnf = Cnf::new();
+        seq.encode_both(2..3, &mut cnf, &mut var_manager).unwrap();
+        assert_eq!(seq.enforce_eq(2).unwrap().len(), 2);
+        assert_eq!(cnf.len(), seq.n_clauses());
+    }
+}
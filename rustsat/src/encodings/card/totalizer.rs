@@ -17,7 +17,7 @@ use super::{
     EncodeIncremental, Error,
 };
 use crate::{
-    encodings::{atomics, CollectClauses, EncodeStats, IterInputs},
+    encodings::{atomics, CollectClauses, EncodeStats, EncodingStats, IterInputs, PredictStats},
     instances::ManageVars,
     types::Lit,
 };
@@ -82,6 +82,17 @@ impl Totalizer {
         }
     }
 
+    /// Gets the shape of the tree that the next encoding will use
+    fn shape(&self) -> Option<Shape<'_>> {
+        let n_new = self.in_lits.len() - self.not_enc_idx;
+        match &self.root {
+            None if n_new == 0 => None,
+            None => Some(Shape::Balanced(n_new)),
+            Some(root) if n_new == 0 => Some(Shape::Built(root)),
+            Some(root) => Some(Shape::Extended(root, n_new)),
+        }
+    }
+
     /// Gets the maximum depth of the tree
     pub fn depth(&self) -> usize {
         match &self.root {
@@ -300,6 +311,32 @@ impl EncodeStats for Totalizer {
     }
 }
 
+impl PredictStats for Totalizer {
+    fn predict_ub<R>(&self, range: R) -> EncodingStats
+    where
+        R: RangeBounds<usize>,
+    {
+        let range = super::prepare_ub_range(self, range);
+        if range.is_empty() {
+            return EncodingStats::default();
+        }
+        self.shape()
+            .map_or_else(EncodingStats::default, |shape| shape.predict_ub(range).0)
+    }
+
+    fn predict_lb<R>(&self, range: R) -> EncodingStats
+    where
+        R: RangeBounds<usize>,
+    {
+        let range = super::prepare_lb_range(self, range);
+        if range.is_empty() {
+            return EncodingStats::default();
+        }
+        self.shape()
+            .map_or_else(EncodingStats::default, |shape| shape.predict_lb(range).0)
+    }
+}
+
 impl From<Vec<Lit>> for Totalizer {
     fn from(lits: Vec<Lit>) -> Self {
         Self {
@@ -864,6 +901,111 @@ impl Node {
     }
 }
 
+/// The shape of a (partially) unbuilt subtree, used for predicting the size of
+/// the encoding without building the tree
+#[derive(Clone, Copy)]
+enum Shape<'a> {
+    /// A subtree that is already built
+    Built(&'a Node),
+    /// A subtree that [`Totalizer::build_tree`] will build over a number of literals
+    Balanced(usize),
+    /// A built subtree that [`Totalizer::extend_tree`] will extend with a
+    /// number of literals
+    Extended(&'a Node, usize),
+}
+
+impl<'a> Shape<'a> {
+    fn max_val(self) -> usize {
+        match self {
+            Shape::Built(node) => node.max_val(),
+            Shape::Balanced(n_lits) => n_lits,
+            Shape::Extended(node, n_lits) => node.max_val() + n_lits,
+        }
+    }
+
+    /// Gets the children of the subtree root, or [`None`] if it is a leaf
+    fn children(self) -> Option<(Shape<'a>, Shape<'a>)> {
+        match self {
+            Shape::Built(Node::Leaf { .. }) | Shape::Balanced(1) => None,
+            Shape::Built(Node::Internal { left, right, .. }) => {
+                Some((Shape::Built(left), Shape::Built(right)))
+            }
+            Shape::Balanced(n_lits) => Some((
+                Shape::Balanced(n_lits / 2),
+                Shape::Balanced(n_lits - n_lits / 2),
+            )),
+            Shape::Extended(node, n_lits) => Some((Shape::Built(node), Shape::Balanced(n_lits))),
+        }
+    }
+
+    /// Predicts the size of [`Node::rec_encode_ub`] for a fresh subtree.
+    /// Additionally returns the number of output literals of the subtree root.
+    fn predict_ub(self, range: Range<usize>) -> (EncodingStats, usize) {
+        let Some((left, right)) = self.children() else {
+            return (EncodingStats::default(), 1);
+        };
+        let max_val = self.max_val();
+        let range = range.start..cmp::min(max_val + 1, range.end);
+        if range.is_empty() {
+            return (EncodingStats::default(), 0);
+        }
+        let (mut stats, left_len) =
+            left.predict_ub(Node::compute_required_range(range.clone(), right.max_val()));
+        let (right_stats, right_len) =
+            right.predict_ub(Node::compute_required_range(range.clone(), left.max_val()));
+        stats += right_stats;
+        // Clauses for all value combinations with sum in `(start, end]`
+        let n_out = cmp::min(range.end, max_val);
+        stats += EncodingStats {
+            n_clauses: n_sum_pairs(left_len, right_len, range.start + 1..range.end + 1),
+            n_vars: n_out.saturating_sub(range.start) as u32,
+        };
+        (stats, n_out)
+    }
+
+    /// Predicts the size of [`Node::rec_encode_lb`] for a fresh subtree.
+    /// Additionally returns the number of output literals of the subtree root.
+    fn predict_lb(self, range: Range<usize>) -> (EncodingStats, usize) {
+        let Some((left, right)) = self.children() else {
+            return (EncodingStats::default(), 1);
+        };
+        let max_val = self.max_val();
+        let range = range.start..cmp::min(max_val + 1, range.end);
+        if range.is_empty() {
+            return (EncodingStats::default(), 0);
+        }
+        let (mut stats, left_len) =
+            left.predict_lb(Node::compute_required_range(range.clone(), right.max_val()));
+        let (right_stats, right_len) =
+            right.predict_lb(Node::compute_required_range(range.clone(), left.max_val()));
+        stats += right_stats;
+        // Clauses for all value combinations with sum in `[start - 1, end - 1)`,
+        // except for both children being at their maximum
+        let sums = range.start.saturating_sub(1)..range.end.saturating_sub(1);
+        let mut n_clauses = n_sum_pairs(left_len, right_len, sums.clone());
+        if sums.contains(&(left_len + right_len)) {
+            n_clauses -= 1;
+        }
+        let n_out = cmp::min(range.end.saturating_sub(1), max_val);
+        stats += EncodingStats {
+            n_clauses,
+            n_vars: n_out.saturating_sub(range.start.saturating_sub(1)) as u32,
+        };
+        (stats, n_out)
+    }
+}
+
+/// Counts the pairs of values `a <= a_max` and `b <= b_max` with `a + b` in `sums`
+fn n_sum_pairs(a_max: usize, b_max: usize, sums: Range<usize>) -> usize {
+    (0..=a_max)
+        .map(|a| {
+            let b_min = sums.start.saturating_sub(a);
+            let b_end = cmp::min(b_max + 1, sums.end.saturating_sub(a));
+            b_end.saturating_sub(b_min)
+        })
+        .sum()
+}
+
 #[cfg(test)]
 mod tests {
     use super::{Node, Totalizer};
@@ -872,7 +1014,7 @@ mod tests {
             card::{
                 BoundBoth, BoundLower, BoundLowerIncremental, BoundUpper, BoundUpperIncremental,
             },
-            EncodeStats, Error,
+            EncodeStats, EncodingStats, Error, PredictStats,
         },
         instances::{BasicVarManager, Cnf, ManageVars},
         lit, var,
@@ -1145,4 +1287,59 @@ mod tests {
         assert_eq!(cnf1.len(), tot1.n_clauses());
         assert_eq!(cnf2.len(), tot2.n_clauses());
     }
+
+    #[test]
+    fn tot_predict_stats() {
+        for n_lits in 1..12 {
+            let lits: Vec<_> = (0..n_lits).map(|idx| lit![idx]).collect();
+            for start in 0..=n_lits as usize + 1 {
+                for end in start..=n_lits as usize + 2 {
+                    let mut tot = Totalizer::from(lits.clone());
+                    let pred = tot.predict_ub(start..end);
+                    let mut var_manager = BasicVarManager::default();
+                    var_manager.increase_next_free(var![n_lits]);
+                    let mut cnf = Cnf::new();
+                    tot.encode_ub(start..end, &mut cnf, &mut var_manager)
+                        .unwrap();
+                    assert_eq!(
+                        pred,
+                        EncodingStats {
+                            n_clauses: cnf.len(),
+                            n_vars: tot.n_vars()
+                        }
+                    );
+
+                    let mut tot = Totalizer::from(lits.clone());
+                    let pred = tot.predict_lb(start..end);
+                    let mut var_manager = BasicVarManager::default();
+                    var_manager.increase_next_free(var![n_lits]);
+                    let mut cnf = Cnf::new();
+                    tot.encode_lb(start..end, &mut cnf, &mut var_manager)
+                        .unwrap();
+                    assert_eq!(
+                        pred,
+                        EncodingStats {
+                            n_clauses: cnf.len(),
+                            n_vars: tot.n_vars()
+                        }
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tot_predict_stats_extended() {
+        let mut tot = Totalizer::default();
+        tot.extend(vec![lit![0], lit![1], lit![2], lit![3], lit![4]]);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![8]);
+        let mut cnf = Cnf::new();
+        tot.encode_ub(0..1, &mut cnf, &mut var_manager).unwrap();
+        tot.extend(vec![lit![5], lit![6], lit![7]]);
+        let pred = tot.predict_ub(0..6);
+        let mut cnf = Cnf::new();
+        tot.encode_ub(0..6, &mut cnf, &mut var_manager).unwrap();
+        assert_eq!(pred.n_clauses, cnf.len());
+    }
 }
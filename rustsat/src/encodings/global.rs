@@ -0,0 +1,539 @@
+//! # CNF Encodings for Global Constraints
+//!
+//! Encodings of common global constraints from constraint programming over
+//! finite-domain variables in the direct encoding. A variable with domain
+//! `0..n` is represented by a _one-hot group_ of `n` literals, where literal
+//! `v` is true if and only if the variable takes value `v`. The encodings
+//! assume that exactly one literal of each group is true, groups with this
+//! constraint can be created with [`one_hot`].
+//!
+//! ## Example Useage
+//!
+//! ```
+//! # use rustsat::{
+//! #     encodings::global,
+//! #     instances::{BasicVarManager, Cnf, ManageVars},
+//! # };
+//! #
+//! let mut var_manager = BasicVarManager::default();
+//! let mut encoding = Cnf::new();
+//!
+//! let x = global::one_hot(3, &mut encoding, &mut var_manager).unwrap();
+//! let y = global::one_hot(3, &mut encoding, &mut var_manager).unwrap();
+//! let z = global::one_hot(3, &mut encoding, &mut var_manager).unwrap();
+//! global::alldifferent(&[&x, &y, &z], &mut encoding, &mut var_manager).unwrap();
+//! // (x, y) must be one of (0, 1) or (2, 0)
+//! global::table(&[&x, &y], &[[0, 1], [2, 0]], &mut encoding, &mut var_manager).unwrap();
+//! ```
+
+use crate::{
+    clause,
+    encodings::{
+        am1::{self, Encode},
+        atomics, CollectClauses,
+    },
+    instances::ManageVars,
+    types::{Clause, Lit},
+};
+
+/// Creates a new one-hot group for a variable with domain `0..n_vals` and
+/// encodes that exactly one of its literals is true
+///
+/// # Errors
+///
+/// If the clause collector runs out of memory, returns [`crate::OutOfMemory`].
+pub fn one_hot<Col>(
+    n_vals: usize,
+    collector: &mut Col,
+    var_manager: &mut dyn ManageVars,
+) -> Result<Vec<Lit>, crate::OutOfMemory>
+where
+    Col: CollectClauses,
+{
+    let lits: Vec<Lit> = (0..n_vals)
+        .map(|_| var_manager.new_var().pos_lit())
+        .collect();
+    am1::ExactlyOne::<am1::Selected>::from(lits.clone()).encode(collector, var_manager)?;
+    Ok(lits)
+}
+
+/// Encodes that all variables take pairwise different values, with an
+/// at-most-1 constraint per value
+///
+/// # Errors
+///
+/// If the clause collector runs out of memory, returns [`crate::OutOfMemory`].
+pub fn alldifferent<Col>(
+    groups: &[&[Lit]],
+    collector: &mut Col,
+    var_manager: &mut dyn ManageVars,
+) -> Result<(), crate::OutOfMemory>
+where
+    Col: CollectClauses,
+{
+    let max_val = groups.iter().map(|g| g.len()).max().unwrap_or(0);
+    for val in 0..max_val {
+        let lits: Vec<Lit> = groups.iter().filter_map(|g| g.get(val).copied()).collect();
+        if lits.len() > 1 {
+            am1::Selected::from(lits).encode(collector, var_manager)?;
+        }
+    }
+    Ok(())
+}
+
+/// Encodes `value = array[index]` for a constant array. Indices that are out
+/// of bounds of the array and array entries that are out of the domain of
+/// `value` are excluded.
+///
+/// # Errors
+///
+/// If the clause collector runs out of memory, returns [`crate::OutOfMemory`].
+pub fn element<Col>(
+    index: &[Lit],
+    array: &[usize],
+    value: &[Lit],
+    collector: &mut Col,
+) -> Result<(), crate::OutOfMemory>
+where
+    Col: CollectClauses,
+{
+    // index = i -> value = array[i]
+    collector.extend_clauses(index.iter().enumerate().map(|(idx, &ilit)| {
+        match array.get(idx).and_then(|&val| value.get(val)) {
+            Some(&vlit) => atomics::lit_impl_lit(ilit, vlit),
+            None => clause![!ilit],
+        }
+    }))?;
+    // value = v -> index in { i | array[i] = v }
+    collector.extend_clauses(value.iter().enumerate().map(|(val, &vlit)| {
+        let support: Vec<Lit> = index
+            .iter()
+            .zip(array)
+            .filter_map(|(&ilit, &aval)| (aval == val).then_some(ilit))
+            .collect();
+        atomics::lit_impl_clause(vlit, &support)
+    }))
+}
+
+/// Encodes `value = array[index]` for an array of variables. Indices that
+/// are out of bounds of the array are excluded.
+///
+/// # Errors
+///
+/// If the clause collector runs out of memory, returns [`crate::OutOfMemory`].
+pub fn element_var<Col>(
+    index: &[Lit],
+    array: &[&[Lit]],
+    value: &[Lit],
+    collector: &mut Col,
+) -> Result<(), crate::OutOfMemory>
+where
+    Col: CollectClauses,
+{
+    for (idx, &ilit) in index.iter().enumerate() {
+        let Some(elem) = array.get(idx) else {
+            collector.add_clause(clause![!ilit])?;
+            continue;
+        };
+        for val in 0..std::cmp::max(elem.len(), value.len()) {
+            match (elem.get(val), value.get(val)) {
+                // index = i -> (array[i] = v <-> value = v)
+                (Some(&elit), Some(&vlit)) => collector.extend_clauses([
+                    atomics::cube_impl_lit(&[ilit, elit], vlit),
+                    atomics::cube_impl_lit(&[ilit, vlit], elit),
+                ])?,
+                (Some(&lit), None) | (None, Some(&lit)) => {
+                    collector.add_clause(clause![!ilit, !lit])?
+                }
+                (None, None) => unreachable!(),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Encodes that the values of the variables are one of the allowed tuples.
+/// Each tuple gets a selector variable, the encoding maintains generalized arc
+/// consistency under unit propagation.
+///
+/// # Panics
+///
+/// If a tuple does not have one value per variable.
+///
+/// # Errors
+///
+/// If the clause collector runs out of memory, returns [`crate::OutOfMemory`].
+pub fn table<Col, T>(
+    vars: &[&[Lit]],
+    tuples: &[T],
+    collector: &mut Col,
+    var_manager: &mut dyn ManageVars,
+) -> Result<(), crate::OutOfMemory>
+where
+    Col: CollectClauses,
+    T: AsRef<[usize]>,
+{
+    let tuples: Vec<&[usize]> = tuples
+        .iter()
+        .map(|tup| {
+            let tup = tup.as_ref();
+            assert_eq!(tup.len(), vars.len(), "tuple length does not match");
+            tup
+        })
+        // tuples with values outside the domains can never be selected
+        .filter(|tup| tup.iter().zip(vars).all(|(&val, var)| val < var.len()))
+        .collect();
+    let selectors: Vec<Lit> = tuples
+        .iter()
+        .map(|_| var_manager.new_var().pos_lit())
+        .collect();
+    collector.add_clause(selectors.iter().copied().collect())?;
+    for (tup, &sel) in tuples.iter().zip(&selectors) {
+        collector.extend_clauses(
+            tup.iter()
+                .zip(vars)
+                .map(|(&val, var)| atomics::lit_impl_lit(sel, var[val])),
+        )?;
+    }
+    // every value needs a supporting tuple
+    for (pos, var) in vars.iter().enumerate() {
+        collector.extend_clauses(var.iter().enumerate().map(|(val, &vlit)| {
+            let support: Vec<Lit> = tuples
+                .iter()
+                .zip(&selectors)
+                .filter_map(|(tup, &sel)| (tup[pos] == val).then_some(sel))
+                .collect();
+            atomics::lit_impl_clause(vlit, &support)
+        }))?;
+    }
+    Ok(())
+}
+
+/// Encodes that the values of the variables are none of the forbidden
+/// tuples, with one clause per tuple
+///
+/// # Panics
+///
+/// If a tuple does not have one value per variable.
+///
+/// # Errors
+///
+/// If the clause collector runs out of memory, returns [`crate::OutOfMemory`].
+pub fn negative_table<Col, T>(
+    vars: &[&[Lit]],
+    tuples: &[T],
+    collector: &mut Col,
+) -> Result<(), crate::OutOfMemory>
+where
+    Col: CollectClauses,
+    T: AsRef<[usize]>,
+{
+    collector.extend_clauses(tuples.iter().filter_map(|tup| {
+        let tup = tup.as_ref();
+        assert_eq!(tup.len(), vars.len(), "tuple length does not match");
+        tup.iter()
+            .zip(vars)
+            .map(|(&val, var)| var.get(val).map(|&lit| !lit))
+            .collect::<Option<Clause>>()
+    }))
+}
+
+/// A deterministic finite automaton over the symbols `0..n_symbols`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dfa {
+    /// The start state
+    start: usize,
+    /// Whether the states are accepting
+    accepting: Vec<bool>,
+    /// The transition function, indexed by state and symbol
+    transitions: Vec<Vec<Option<usize>>>,
+}
+
+impl Dfa {
+    /// Creates a new automaton without transitions and accepting states
+    ///
+    /// # Panics
+    ///
+    /// If the start state is not one of the states.
+    pub fn new(n_states: usize, n_symbols: usize, start: usize) -> Self {
+        assert!(start < n_states, "start state does not exist");
+        Dfa {
+            start,
+            accepting: vec![false; n_states],
+            transitions: vec![vec![None; n_symbols]; n_states],
+        }
+    }
+
+    /// Gets the number of states
+    pub fn n_states(&self) -> usize {
+        self.accepting.len()
+    }
+
+    /// Gets the number of symbols
+    pub fn n_symbols(&self) -> usize {
+        self.transitions.first().map_or(0, |t| t.len())
+    }
+
+    /// Adds the transition from state `from` to state `to` on `symbol`,
+    /// replacing a previous transition
+    ///
+    /// # Panics
+    ///
+    /// If a state or the symbol does not exist.
+    pub fn add_transition(&mut self, from: usize, symbol: usize, to: usize) {
+        assert!(to < self.n_states(), "state does not exist");
+        self.transitions[from][symbol] = Some(to);
+    }
+
+    /// Marks a state as accepting
+    pub fn set_accepting(&mut self, state: usize) {
+        self.accepting[state] = true;
+    }
+
+    /// Gets the state reached from `state` on `symbol`, if any
+    pub fn next(&self, state: usize, symbol: usize) -> Option<usize> {
+        *self.transitions[state].get(symbol)?
+    }
+
+    /// Checks whether the automaton accepts a word
+    pub fn accepts(&self, word: &[usize]) -> bool {
+        word.iter()
+            .try_fold(self.start, |state, &sym| self.next(state, sym))
+            .map_or(false, |state| self.accepting[state])
+    }
+}
+
+/// Encodes that the sequence of values of the variables is accepted by an
+/// automaton. The automaton is unrolled over the sequence, with one-hot state
+/// literals for each position. Only states that are reachable from the start
+/// and from which an accepting state can still be reached get literals.
+///
+/// # Errors
+///
+/// If the clause collector runs out of memory, returns [`crate::OutOfMemory`].
+pub fn regular<Col>(
+    vars: &[&[Lit]],
+    dfa: &Dfa,
+    collector: &mut Col,
+    var_manager: &mut dyn ManageVars,
+) -> Result<(), crate::OutOfMemory>
+where
+    Col: CollectClauses,
+{
+    // backward pass: states from which an accepting state is reachable with
+    // the remaining variables
+    let mut live = vec![dfa.accepting.clone()];
+    for var in vars.iter().rev() {
+        let next_live = live.last().unwrap();
+        let cur_live: Vec<bool> = (0..dfa.n_states())
+            .map(|state| {
+                (0..var.len()).any(|sym| dfa.next(state, sym).map_or(false, |n| next_live[n]))
+            })
+            .collect();
+        live.push(cur_live);
+    }
+    live.reverse();
+    if !live[0][dfa.start] {
+        return collector.add_clause(Clause::new());
+    }
+    // forward pass: layers of reachable live states with their literals, a
+    // layer with a single state does not need a literal since it is forced
+    let mut layer: Vec<(usize, Option<Lit>)> = vec![(dfa.start, None)];
+    for (pos, var) in vars.iter().enumerate() {
+        let mut next_states: Vec<usize> = layer
+            .iter()
+            .flat_map(|&(state, _)| (0..var.len()).filter_map(move |sym| dfa.next(state, sym)))
+            .filter(|&next| live[pos + 1][next])
+            .collect();
+        next_states.sort_unstable();
+        next_states.dedup();
+        let next_layer: Vec<(usize, Option<Lit>)> = if next_states.len() == 1 {
+            vec![(next_states[0], None)]
+        } else {
+            next_states
+                .into_iter()
+                .map(|state| (state, Some(var_manager.new_var().pos_lit())))
+                .collect()
+        };
+        for &(state, slit) in &layer {
+            for (sym, &vlit) in var.iter().enumerate() {
+                let next = dfa
+                    .next(state, sym)
+                    .and_then(|next| next_layer.iter().find(|&&(s, _)| s == next));
+                let mut cl = Clause::new();
+                if let Some(slit) = slit {
+                    cl.add(!slit);
+                }
+                cl.add(!vlit);
+                match next {
+                    Some((_, None)) => continue,
+                    Some((_, Some(nlit))) => cl.add(*nlit),
+                    // transitions that are undefined or lead to dead states
+                    None => (),
+                }
+                collector.add_clause(cl)?;
+            }
+        }
+        if next_layer.len() > 1 {
+            am1::Selected::from_iter(next_layer.iter().map(|&(_, lit)| lit.unwrap()))
+                .encode(collector, var_manager)?;
+        }
+        layer = next_layer;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        instances::{BasicVarManager, Cnf, ManageVars},
+        types::{Assignment, Lit, TernaryVal, Var},
+    };
+
+    /// Checks that a combination of values of the groups can be extended to
+    /// satisfy the encoding iff it satisfies `pred`. The groups need to use
+    /// the first variables.
+    fn check<P>(groups: &[Vec<Lit>], cnf: &Cnf, n_vars: u32, pred: P)
+    where
+        P: Fn(&[usize]) -> bool,
+    {
+        let n_group_vars: u32 = groups.iter().map(|g| g.len() as u32).sum();
+        let n_aux = n_vars - n_group_vars;
+        assert!(n_aux <= 16);
+        let n_combs: usize = groups.iter().map(|g| g.len()).product();
+        for comb in 0..n_combs {
+            let mut vals = vec![];
+            let mut rest = comb;
+            for g in groups {
+                vals.push(rest % g.len());
+                rest /= g.len();
+            }
+            let mut assign = Assignment::default();
+            for (g, &val) in groups.iter().zip(&vals) {
+                for (idx, &lit) in g.iter().enumerate() {
+                    assign.assign_lit(if idx == val { lit } else { !lit });
+                }
+            }
+            let extendable = (0..1_u32 << n_aux).any(|aux| {
+                for idx in 0..n_aux {
+                    assign.assign_var(
+                        Var::new(n_group_vars + idx),
+                        TernaryVal::from((aux >> idx) & 1 == 1),
+                    );
+                }
+                cnf.iter().all(|cl| cl.is_sat(&assign))
+            });
+            assert_eq!(extendable, pred(&vals), "values {vals:?}");
+        }
+    }
+
+    fn groups(sizes: &[usize], var_manager: &mut BasicVarManager) -> Vec<Vec<Lit>> {
+        sizes
+            .iter()
+            .map(|&n| (0..n).map(|_| var_manager.new_var().pos_lit()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn one_hot() {
+        let mut var_manager = BasicVarManager::default();
+        let mut cnf = Cnf::new();
+        let lits = super::one_hot(4, &mut cnf, &mut var_manager).unwrap();
+        for vals in 0..16_u32 {
+            let assign: Assignment = lits
+                .iter()
+                .enumerate()
+                .map(|(idx, &lit)| if vals >> idx & 1 == 1 { lit } else { !lit })
+                .collect();
+            assert_eq!(
+                cnf.iter().all(|cl| cl.is_sat(&assign)),
+                vals.count_ones() == 1
+            );
+        }
+    }
+
+    #[test]
+    fn alldifferent() {
+        let mut var_manager = BasicVarManager::default();
+        let gs = groups(&[3, 3, 2], &mut var_manager);
+        let mut cnf = Cnf::new();
+        let refs: Vec<&[Lit]> = gs.iter().map(|g| &g[..]).collect();
+        super::alldifferent(&refs, &mut cnf, &mut var_manager).unwrap();
+        check(&gs, &cnf, var_manager.n_used(), |v| {
+            v[0] != v[1] && v[0] != v[2] && v[1] != v[2]
+        });
+    }
+
+    #[test]
+    fn element() {
+        let mut var_manager = BasicVarManager::default();
+        let gs = groups(&[4, 3], &mut var_manager);
+        let mut cnf = Cnf::new();
+        let array = [2, 0, 2, 5];
+        super::element(&gs[0], &array, &gs[1], &mut cnf).unwrap();
+        check(&gs, &cnf, var_manager.n_used(), |v| array[v[0]] == v[1]);
+    }
+
+    #[test]
+    fn element_var() {
+        let mut var_manager = BasicVarManager::default();
+        let gs = groups(&[3, 2, 3, 2], &mut var_manager);
+        let mut cnf = Cnf::new();
+        let array: Vec<&[Lit]> = vec![&gs[1], &gs[2]];
+        super::element_var(&gs[0], &array, &gs[3], &mut cnf).unwrap();
+        check(&gs, &cnf, var_manager.n_used(), |v| match v[0] {
+            0 => v[1] == v[3],
+            1 => v[2] == v[3],
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn table() {
+        let tuples = [[0, 1, 1], [2, 0, 1], [1, 1, 0], [0, 3, 0]];
+        let mut var_manager = BasicVarManager::default();
+        let gs = groups(&[3, 2, 2], &mut var_manager);
+        let refs: Vec<&[Lit]> = gs.iter().map(|g| &g[..]).collect();
+        let mut cnf = Cnf::new();
+        super::table(&refs, &tuples, &mut cnf, &mut var_manager).unwrap();
+        check(&gs, &cnf, var_manager.n_used(), |v| {
+            tuples.iter().any(|t| t[..] == v[..])
+        });
+        let mut cnf = Cnf::new();
+        super::negative_table(&refs, &tuples, &mut cnf).unwrap();
+        check(&gs, &cnf, var_manager.n_used(), |v| {
+            !tuples.iter().any(|t| t[..] == v[..])
+        });
+    }
+
+    #[test]
+    fn regular() {
+        // words over {0, 1, 2} with an even number of 1s that do not contain 2
+        // directly after 0
+        let mut dfa = super::Dfa::new(4, 3, 0);
+        for (from, sym, to) in [
+            (0, 0, 1),
+            (0, 1, 2),
+            (0, 2, 0),
+            (1, 0, 1),
+            (1, 1, 2),
+            (2, 0, 3),
+            (2, 1, 0),
+            (2, 2, 2),
+            (3, 0, 3),
+            (3, 1, 0),
+        ] {
+            dfa.add_transition(from, sym, to);
+        }
+        dfa.set_accepting(0);
+        dfa.set_accepting(1);
+        for sizes in [&[3, 3, 3, 3][..], &[3, 2, 3, 1], &[1, 1]] {
+            let mut var_manager = BasicVarManager::default();
+            let gs = groups(sizes, &mut var_manager);
+            let refs: Vec<&[Lit]> = gs.iter().map(|g| &g[..]).collect();
+            let mut cnf = Cnf::new();
+            super::regular(&refs, &dfa, &mut cnf, &mut var_manager).unwrap();
+            check(&gs, &cnf, var_manager.n_used(), |v| dfa.accepts(v));
+        }
+    }
+}
@@ -0,0 +1,364 @@
+//! # Encodings for Finite-Domain Integer Variables
+//!
+//! Integer variables over a domain `[lb, ub]` represented by boolean
+//! literals. Two representations are available:
+//!
+//! - The _order encoding_ (also called ladder encoding) uses `ub - lb`
+//!   literals, where the `i`-th literal is true if and only if the value is at
+//!   least `lb + i + 1`.
+//! - The _log encoding_ (also called binary encoding) uses `ceil(log2(ub - lb
+//!   + 1))` literals representing the bits of the value minus `lb`.
+//!
+//! Linear constraints over integer variables can be compiled to
+//! [`PBConstraint`]s with [`linear_ub`], [`linear_lb`] and [`linear_eq`].
+//!
+//! ## Example Useage
+//!
+//! ```
+//! # use rustsat::{
+//! #     encodings::int::{self, IntVar},
+//! #     instances::{BasicVarManager, Cnf, ManageVars},
+//! # };
+//! #
+//! let mut var_manager = BasicVarManager::default();
+//! let mut encoding = Cnf::new();
+//!
+//! let x = IntVar::new_order(-2, 5, &mut encoding, &mut var_manager).unwrap();
+//! let y = IntVar::new_log(0, 10, &mut encoding, &mut var_manager).unwrap();
+//! // 3x - 2y <= 4
+//! let constr = int::linear_ub([(3, &x), (-2, &y)], 4);
+//! ```
+
+use crate::{
+    clause,
+    encodings::{atomics, CollectClauses},
+    instances::ManageVars,
+    types::{constraints::PBConstraint, Assignment, Lit, TernaryVal},
+};
+
+/// The representations of integer variables
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntEncoding {
+    /// Order encoding, literal `i` is true iff the value is at least `lb + i + 1`
+    Order,
+    /// Log encoding, literal `i` is bit `i` of the value minus `lb`
+    Log,
+}
+
+/// An integer variable over the domain `[lb, ub]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntVar {
+    /// The lower bound of the domain
+    lb: isize,
+    /// The upper bound of the domain
+    ub: isize,
+    /// The representation of the variable
+    encoding: IntEncoding,
+    /// The literals representing the variable
+    lits: Vec<Lit>,
+}
+
+impl IntVar {
+    /// Creates a new integer variable in the order encoding and adds the
+    /// clauses `x >= v + 1 -> x >= v` to the collector
+    ///
+    /// # Panics
+    ///
+    /// If the domain is empty, i.e., `lb > ub`.
+    ///
+    /// # Errors
+    ///
+    /// If the clause collector runs out of memory, returns [`crate::OutOfMemory`].
+    pub fn new_order<Col>(
+        lb: isize,
+        ub: isize,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<Self, crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        assert!(lb <= ub, "empty domain [{lb}, {ub}]");
+        let lits: Vec<Lit> = (lb..ub).map(|_| var_manager.new_var().pos_lit()).collect();
+        collector.extend_clauses(
+            lits.windows(2)
+                .map(|lits| atomics::lit_impl_lit(lits[1], lits[0])),
+        )?;
+        Ok(IntVar {
+            lb,
+            ub,
+            encoding: IntEncoding::Order,
+            lits,
+        })
+    }
+
+    /// Creates a new integer variable in the log encoding and adds clauses
+    /// excluding values larger than `ub` to the collector
+    ///
+    /// # Panics
+    ///
+    /// If the domain is empty, i.e., `lb > ub`.
+    ///
+    /// # Errors
+    ///
+    /// If the clause collector runs out of memory, returns [`crate::OutOfMemory`].
+    pub fn new_log<Col>(
+        lb: isize,
+        ub: isize,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<Self, crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        assert!(lb <= ub, "empty domain [{lb}, {ub}]");
+        let range = ub.abs_diff(lb);
+        let n_bits = (usize::BITS - range.leading_zeros()) as usize;
+        let lits: Vec<Lit> = (0..n_bits)
+            .map(|_| var_manager.new_var().pos_lit())
+            .collect();
+        // for each zero bit of the range, exclude values that have the same
+        // higher one bits and this bit set
+        collector.extend_clauses((0..n_bits).filter(|&bit| range >> bit & 1 == 0).map(|bit| {
+            let higher_ones: Vec<Lit> = (bit + 1..n_bits)
+                .filter(|&higher| range >> higher & 1 == 1)
+                .map(|higher| lits[higher])
+                .collect();
+            atomics::cube_impl_lit(&higher_ones, !lits[bit])
+        }))?;
+        Ok(IntVar {
+            lb,
+            ub,
+            encoding: IntEncoding::Log,
+            lits,
+        })
+    }
+
+    /// Gets the lower bound of the domain
+    pub fn lb(&self) -> isize {
+        self.lb
+    }
+
+    /// Gets the upper bound of the domain
+    pub fn ub(&self) -> isize {
+        self.ub
+    }
+
+    /// Gets the representation of the variable
+    pub fn encoding(&self) -> IntEncoding {
+        self.encoding
+    }
+
+    /// Gets the literals representing the variable
+    pub fn lits(&self) -> &[Lit] {
+        &self.lits
+    }
+
+    /// Gets the literals of the variable with the weights they contribute to
+    /// the value, i.e., `value = lb + sum of weights of true literals`
+    pub fn weighted_lits(&self) -> impl Iterator<Item = (Lit, usize)> + '_ {
+        let log = self.encoding == IntEncoding::Log;
+        self.lits
+            .iter()
+            .enumerate()
+            .map(move |(idx, &lit)| (lit, if log { 1 << idx } else { 1 }))
+    }
+
+    /// Extracts the value of the variable from an assignment. Returns `None`
+    /// if any of the literals of the variable is unassigned.
+    pub fn value(&self, assign: &Assignment) -> Option<isize> {
+        self.weighted_lits()
+            .try_fold(self.lb, |val, (lit, weight)| match assign.lit_value(lit) {
+                TernaryVal::True => Some(val + weight as isize),
+                TernaryVal::False => Some(val),
+                TernaryVal::DontCare => None,
+            })
+    }
+
+    /// Gets a cube of literals that fixes the variable to a value, e.g., to
+    /// be used as assumptions
+    ///
+    /// # Panics
+    ///
+    /// If the value is not in the domain.
+    pub fn value_cube(&self, val: isize) -> Vec<Lit> {
+        assert!(
+            self.lb <= val && val <= self.ub,
+            "{val} is not in domain [{}, {}]",
+            self.lb,
+            self.ub
+        );
+        let offset = val.abs_diff(self.lb);
+        self.lits
+            .iter()
+            .enumerate()
+            .map(|(idx, &lit)| {
+                let true_lit = match self.encoding {
+                    IntEncoding::Order => idx < offset,
+                    IntEncoding::Log => offset >> idx & 1 == 1,
+                };
+                if true_lit {
+                    lit
+                } else {
+                    !lit
+                }
+            })
+            .collect()
+    }
+
+    /// Adds unit clauses fixing the variable to a value
+    ///
+    /// # Panics
+    ///
+    /// If the value is not in the domain.
+    ///
+    /// # Errors
+    ///
+    /// If the clause collector runs out of memory, returns [`crate::OutOfMemory`].
+    pub fn fix<Col: CollectClauses>(
+        &self,
+        val: isize,
+        collector: &mut Col,
+    ) -> Result<(), crate::OutOfMemory> {
+        collector.extend_clauses(self.value_cube(val).into_iter().map(|lit| clause![lit]))
+    }
+}
+
+/// Converts a linear term to weighted literals and a constant offset
+fn linear_lits<'a, I>(terms: I) -> (Vec<(Lit, isize)>, isize)
+where
+    I: IntoIterator<Item = (isize, &'a IntVar)>,
+{
+    let mut offset = 0;
+    let mut lits = vec![];
+    for (coeff, var) in terms {
+        offset += coeff * var.lb;
+        lits.extend(
+            var.weighted_lits()
+                .map(|(lit, weight)| (lit, coeff * weight as isize)),
+        );
+    }
+    (lits, offset)
+}
+
+/// Compiles the linear constraint `sum of coeff * var <= b` to a
+/// [`PBConstraint`]
+pub fn linear_ub<'a, I>(terms: I, b: isize) -> PBConstraint
+where
+    I: IntoIterator<Item = (isize, &'a IntVar)>,
+{
+    let (lits, offset) = linear_lits(terms);
+    PBConstraint::new_ub(lits, b - offset)
+}
+
+/// Compiles the linear constraint `sum of coeff * var >= b` to a
+/// [`PBConstraint`]
+pub fn linear_lb<'a, I>(terms: I, b: isize) -> PBConstraint
+where
+    I: IntoIterator<Item = (isize, &'a IntVar)>,
+{
+    let (lits, offset) = linear_lits(terms);
+    PBConstraint::new_lb(lits, b - offset)
+}
+
+/// Compiles the linear constraint `sum of coeff * var = b` to a
+/// [`PBConstraint`]
+pub fn linear_eq<'a, I>(terms: I, b: isize) -> PBConstraint
+where
+    I: IntoIterator<Item = (isize, &'a IntVar)>,
+{
+    let (lits, offset) = linear_lits(terms);
+    PBConstraint::new_eq(lits, b - offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IntVar;
+    use crate::{
+        instances::{BasicVarManager, Cnf},
+        types::Assignment,
+    };
+
+    /// Checks that exactly the assignments encoding a value in the domain
+    /// satisfy the domain clauses, and that each value is encoded once
+    fn check_domain(var: &IntVar, cnf: &Cnf) {
+        let n = var.lits().len();
+        let mut values = vec![];
+        for vals in 0..1_usize << n {
+            let assign: Assignment = var
+                .lits()
+                .iter()
+                .enumerate()
+                .map(|(idx, &lit)| if vals >> idx & 1 == 1 { lit } else { !lit })
+                .collect();
+            if cnf.iter().all(|cl| cl.is_sat(&assign)) {
+                let val = var.value(&assign).unwrap();
+                assert!(var.lb() <= val && val <= var.ub());
+                values.push(val);
+            }
+        }
+        values.sort_unstable();
+        assert_eq!(values, (var.lb()..=var.ub()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn order_domain() {
+        for (lb, ub) in [(0, 0), (-3, 4), (2, 9)] {
+            let mut var_manager = BasicVarManager::default();
+            let mut cnf = Cnf::new();
+            let var = IntVar::new_order(lb, ub, &mut cnf, &mut var_manager).unwrap();
+            assert_eq!(var.lits().len(), ub.abs_diff(lb));
+            check_domain(&var, &cnf);
+        }
+    }
+
+    #[test]
+    fn log_domain() {
+        for (lb, ub) in [(0, 0), (-3, 4), (2, 9), (0, 10), (5, 20), (-1, 31)] {
+            let mut var_manager = BasicVarManager::default();
+            let mut cnf = Cnf::new();
+            let var = IntVar::new_log(lb, ub, &mut cnf, &mut var_manager).unwrap();
+            check_domain(&var, &cnf);
+        }
+    }
+
+    #[test]
+    fn value_cube() {
+        let mut var_manager = BasicVarManager::default();
+        let mut order_cnf = Cnf::new();
+        let x = IntVar::new_order(-2, 3, &mut order_cnf, &mut var_manager).unwrap();
+        let mut log_cnf = Cnf::new();
+        let y = IntVar::new_log(1, 6, &mut log_cnf, &mut var_manager).unwrap();
+        for (var, cnf) in [(&x, &order_cnf), (&y, &log_cnf)] {
+            for val in var.lb()..=var.ub() {
+                let assign: Assignment = var.value_cube(val).into_iter().collect();
+                assert!(cnf.iter().all(|cl| cl.is_sat(&assign)));
+                assert_eq!(var.value(&assign), Some(val));
+            }
+        }
+    }
+
+    #[test]
+    fn linear() {
+        let mut var_manager = BasicVarManager::default();
+        let mut cnf = Cnf::new();
+        let x = IntVar::new_order(-2, 3, &mut cnf, &mut var_manager).unwrap();
+        let y = IntVar::new_log(1, 6, &mut cnf, &mut var_manager).unwrap();
+        let ub = super::linear_ub([(2, &x), (-3, &y)], -4);
+        let lb = super::linear_lb([(2, &x), (-3, &y)], -4);
+        let eq = super::linear_eq([(2, &x), (-3, &y)], -4);
+        for xval in x.lb()..=x.ub() {
+            for yval in y.lb()..=y.ub() {
+                let assign: Assignment = x
+                    .value_cube(xval)
+                    .into_iter()
+                    .chain(y.value_cube(yval))
+                    .collect();
+                let sum = 2 * xval - 3 * yval;
+                assert_eq!(ub.is_sat(&assign), sum <= -4);
+                assert_eq!(lb.is_sat(&assign), sum >= -4);
+                assert_eq!(eq.is_sat(&assign), sum == -4);
+            }
+        }
+    }
+}
@@ -37,12 +37,12 @@ use std::{
     ops::{Bound, Range, RangeBounds},
 };
 
-use super::{card, CollectClauses, Error};
+use super::{atomics, card, CollectClauses, Error, Reification};
 use crate::{
     clause,
     instances::ManageVars,
     types::{
-        constraints::{PBConstraint, PBEQConstr, PBLBConstr, PBUBConstr},
+        constraints::{PBConstraint, PBEQConstr, PBLBConstr, PBToCardError, PBUBConstr},
         Clause, Lit,
     },
 };
@@ -53,9 +53,10 @@ pub use gte::GeneralizedTotalizer;
 pub mod simulators;
 /// Inverted generalized totalizer that can be used for lower bounding PB expressions
 pub type InvertedGeneralizedTotalizer = simulators::Inverted<GeneralizedTotalizer>;
-/// Double generalized totalizer that can be used for upper and lower bounding PB expressions
-pub type DoubleGeneralizedTotalizer =
-    simulators::Double<GeneralizedTotalizer, InvertedGeneralizedTotalizer>;
+/// Generalized totalizer that can be used for upper and lower bounding PB
+/// expressions. Since [`GeneralizedTotalizer`] natively supports both bounds on
+/// the same tree, this is only an alias for it.
+pub type DoubleGeneralizedTotalizer = GeneralizedTotalizer;
 
 pub mod dpw;
 pub use dpw::DynamicPolyWatchdog;
@@ -63,6 +64,27 @@ pub use dpw::DynamicPolyWatchdog;
 pub mod dbgte;
 pub use dbgte::DbGte;
 
+pub mod pw;
+pub use pw::{LocalPolyWatchdog, PolyWatchdog};
+
+pub mod bdd;
+pub use bdd::Bdd;
+
+pub mod adder;
+pub use adder::Adder;
+
+pub mod auto;
+pub use auto::Auto;
+
+pub mod swc;
+pub use swc::SeqWeightCounter;
+/// Inverted sequential weight counter that can be used for lower bounding PB
+/// expressions
+pub type InvertedSeqWeightCounter = simulators::Inverted<SeqWeightCounter>;
+/// Double sequential weight counter that can be used for upper and lower
+/// bounding PB expressions, e.g., with [`encode_pb_constraint`]
+pub type DoubleSeqWeightCounter = simulators::Double<SeqWeightCounter, InvertedSeqWeightCounter>;
+
 /// Trait for all pseudo-boolean encodings of form `weighted sum of lits <> rhs`
 pub trait Encode {
     /// Get the sum of weights in the encoding
@@ -100,6 +122,31 @@ pub trait BoundUpper: Encode {
     /// adequately and nothing has been called afterwards, otherwise
     /// [`Error::NotEncoded`] will be returned.
     fn enforce_ub(&self, ub: usize) -> Result<Vec<Lit>, Error>;
+    /// Encodes the half-reified upper bound `act -> (weighted sum of lits <=
+    /// ub)` to CNF. Full reification can be achieved by additionally encoding
+    /// `!act -> (weighted sum of lits >= ub + 1)` with
+    /// [`BoundLower::encode_lb_reified`].
+    fn encode_ub_reified<Col>(
+        &mut self,
+        ub: usize,
+        act: Lit,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        if ub >= self.weight_sum() {
+            return Ok(());
+        }
+        self.encode_ub(ub..ub + 1, collector, var_manager)?;
+        let assumps = self.enforce_ub(ub).expect("upper bound was just encoded");
+        collector.extend_clauses(
+            assumps
+                .into_iter()
+                .map(|assump| atomics::lit_impl_lit(act, assump)),
+        )
+    }
     /// Encodes an upper bound pseudo-boolean constraint to CNF
     ///
     /// # Errors
@@ -161,6 +208,34 @@ pub trait BoundLower: Encode {
     /// the weighted sum of literals in the encoding, [`Error::Unsat`]
     /// is returned.
     fn enforce_lb(&self, lb: usize) -> Result<Vec<Lit>, Error>;
+    /// Encodes the half-reified lower bound `act -> (weighted sum of lits >=
+    /// lb)` to CNF. Full reification can be achieved by additionally encoding
+    /// `!act -> (weighted sum of lits <= lb - 1)` with
+    /// [`BoundUpper::encode_ub_reified`].
+    fn encode_lb_reified<Col>(
+        &mut self,
+        lb: usize,
+        act: Lit,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        if lb == 0 {
+            return Ok(());
+        }
+        if lb > self.weight_sum() {
+            return collector.add_clause(clause![!act]);
+        }
+        self.encode_lb(lb..lb + 1, collector, var_manager)?;
+        let assumps = self.enforce_lb(lb).expect("lower bound was just encoded");
+        collector.extend_clauses(
+            assumps
+                .into_iter()
+                .map(|assump| atomics::lit_impl_lit(act, assump)),
+        )
+    }
     /// Encodes a lower bound pseudo-boolean constraint to CNF
     ///
     /// # Errors
@@ -230,6 +305,20 @@ pub trait BoundBoth: BoundUpper + BoundLower {
         assumps.extend(self.enforce_lb(b)?);
         Ok(assumps)
     }
+    /// Encodes the half-reified equality `act -> (weighted sum of lits = b)` to CNF
+    fn encode_eq_reified<Col>(
+        &mut self,
+        b: usize,
+        act: Lit,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        self.encode_ub_reified(b, act, collector, var_manager)?;
+        self.encode_lb_reified(b, act, collector, var_manager)
+    }
     /// Encodes an equality pseudo-boolean constraint to CNF
     ///
     /// # Errors
@@ -357,14 +446,14 @@ impl<PBE> BoundBothIncremental for PBE where PBE: BoundUpperIncremental + BoundL
 
 /// The default upper bound encoding. For now this is a [`GeneralizedTotalizer`].
 pub type DefUpperBounding = GeneralizedTotalizer;
-/// The default lower bound encoding. For now this is a [`InvertedGeneralizedTotalizer`].
-pub type DefLowerBounding = InvertedGeneralizedTotalizer;
+/// The default lower bound encoding. For now this is a [`GeneralizedTotalizer`].
+pub type DefLowerBounding = GeneralizedTotalizer;
 /// The default encoding for both bounds. For now this is a [`DoubleGeneralizedTotalizer`].
 pub type DefBothBounding = DoubleGeneralizedTotalizer;
 /// The default incremental upper bound encoding. For now this is a [`GeneralizedTotalizer`].
 pub type DefIncUpperBounding = GeneralizedTotalizer;
-/// The default incremental lower bound encoding. For now this is a [`InvertedGeneralizedTotalizer`].
-pub type DefIncLowerBounding = InvertedGeneralizedTotalizer;
+/// The default incremental lower bound encoding. For now this is a [`GeneralizedTotalizer`].
+pub type DefIncLowerBounding = GeneralizedTotalizer;
 /// The default incremental encoding for both bounds. For now this is a [`DoubleGeneralizedTotalizer`].
 pub type DefIncBothBounding = DoubleGeneralizedTotalizer;
 
@@ -398,15 +487,179 @@ pub fn new_default_inc_both() -> impl BoundBoth + Extend<(Lit, usize)> {
     DefIncBothBounding::default()
 }
 
-/// A default encoder for any pseudo-boolean constraint. This uses a
-/// [`DefBothBounding`] to encode true pseudo-boolean constraints and
-/// [`card::default_encode_cardinality_constraint`] for cardinality constraints.
+/// A default encoder for any pseudo-boolean constraint. This selects an
+/// encoding for each constraint with [`auto_encode_pb_constraint`].
 pub fn default_encode_pb_constraint<Col: CollectClauses>(
     constr: PBConstraint,
     collector: &mut Col,
     var_manager: &mut dyn ManageVars,
 ) -> Result<(), crate::OutOfMemory> {
-    encode_pb_constraint::<DefBothBounding, Col>(constr, collector, var_manager)
+    auto_encode_pb_constraint(constr, collector, var_manager)
+}
+
+/// An encoder for any pseudo-boolean constraint that automatically selects an
+/// encoding based on the constraint. Cardinality constraints are encoded with
+/// [`card::default_encode_cardinality_constraint`]. For other constraints,
+/// input literals that can not be true without violating the constraint are
+/// fixed, the remaining weights and the bound are divided by the greatest
+/// common divisor of the weights, and the encoding is selected with
+/// [`auto::Variant::auto`].
+pub fn auto_encode_pb_constraint<Col: CollectClauses>(
+    constr: PBConstraint,
+    collector: &mut Col,
+    var_manager: &mut dyn ManageVars,
+) -> Result<(), crate::OutOfMemory> {
+    let constr = merge_lits(constr).normalize();
+    if constr.is_tautology() {
+        return Ok(());
+    }
+    if constr.is_unsat() {
+        return collector.add_clause(Clause::new());
+    }
+    if constr.is_positive_assignment() {
+        return collector
+            .extend_clauses(constr.into_lits().into_iter().map(|(lit, _)| clause![lit]));
+    }
+    if constr.is_negative_assignment() {
+        return collector
+            .extend_clauses(constr.into_lits().into_iter().map(|(lit, _)| clause![!lit]));
+    }
+    if constr.is_clause() {
+        return collector.add_clause(constr.into_clause().unwrap());
+    }
+    if constr.is_card() {
+        return match constr.into_card_constr() {
+            Ok(card) => card::default_encode_cardinality_constraint(card, collector, var_manager),
+            // Equality constraint with bound not divisible by the unit weight
+            Err(PBToCardError::Unsat) => collector.add_clause(Clause::new()),
+            Err(_) => unreachable!("constraint is a non-trivial cardinality constraint"),
+        };
+    }
+    match constr {
+        PBConstraint::UB(constr) => {
+            let (lits, ub) = constr.decompose();
+            auto_encode_ub(lits, ub as usize, collector, var_manager)
+        }
+        PBConstraint::LB(constr) => {
+            // `sum of lits >= lb` is encoded as `sum of negated lits <= weight_sum - lb`
+            let (lits, lb) = constr.decompose();
+            let weight_sum = lits.iter().fold(0, |sum, &(_, w)| sum + w);
+            let lits = lits.into_iter().map(|(l, w)| (!l, w)).collect();
+            auto_encode_ub(lits, weight_sum - lb as usize, collector, var_manager)
+        }
+        PBConstraint::EQ(constr) => {
+            let (mut lits, b) = constr.decompose();
+            let common = divide_common_weight(&mut lits);
+            if b as usize % common != 0 {
+                return collector.add_clause(Clause::new());
+            }
+            let b = b as usize / common;
+            let weight_sum = lits.iter().fold(0, |sum, &(_, w)| sum + w);
+            let variant = auto::Variant::auto(&lits, cmp::max(b, weight_sum - b));
+            let mut enc = Auto::new(variant, lits.into_iter().collect());
+            enc.encode_both(b..b + 1, collector, var_manager)?;
+            collector.extend_clauses(
+                enc.enforce_eq(b)
+                    .expect("bounds were just encoded")
+                    .into_iter()
+                    .map(|unit| clause![unit]),
+            )
+        }
+    }
+}
+
+/// Encodes `sum of lits <= ub` with an automatically selected encoding
+fn auto_encode_ub<Col: CollectClauses>(
+    lits: Vec<(Lit, usize)>,
+    ub: usize,
+    collector: &mut Col,
+    var_manager: &mut dyn ManageVars,
+) -> Result<(), crate::OutOfMemory> {
+    let (fixed, mut lits): (Vec<_>, Vec<_>) = lits.into_iter().partition(|&(_, w)| w > ub);
+    collector.extend_clauses(fixed.into_iter().map(|(lit, _)| clause![!lit]))?;
+    let ub = ub / divide_common_weight(&mut lits);
+    match auto::Variant::auto(&lits, ub) {
+        auto::Variant::AtMostOne => {
+            use super::am1::{self, Encode as _};
+            am1::Selected::from_iter(lits.into_iter().map(|(lit, _)| lit))
+                .encode(collector, var_manager)
+        }
+        variant => {
+            let mut enc = Auto::new(variant, lits.into_iter().collect());
+            enc.encode_ub(ub..ub + 1, collector, var_manager)?;
+            collector.extend_clauses(
+                enc.enforce_ub(ub)
+                    .expect("bound was just encoded")
+                    .into_iter()
+                    .map(|unit| clause![unit]),
+            )
+        }
+    }
+}
+
+/// Divides all weights by their greatest common divisor and returns the divisor
+fn divide_common_weight(lits: &mut [(Lit, usize)]) -> usize {
+    let gcd = |mut a: usize, mut b: usize| {
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        a
+    };
+    let common = lits.iter().fold(0, |common, &(_, w)| gcd(common, w));
+    if common <= 1 {
+        return 1;
+    }
+    lits.iter_mut().for_each(|(_, w)| *w /= common);
+    common
+}
+
+/// Merges duplicate and complementary literals in a constraint and removes
+/// literals with weight zero. Encodings collect their input literals into a
+/// map, so a constraint must not contain a literal more than once.
+fn merge_lits(constr: PBConstraint) -> PBConstraint {
+    let merge = |mut lits: Vec<(Lit, usize)>, mut b: isize| {
+        // After sorting, all occurrences of a literal are followed by all
+        // occurrences of its negation
+        lits.sort_unstable_by_key(|&(l, _)| l);
+        let mut merged: Vec<(Lit, usize)> = Vec::with_capacity(lits.len());
+        for (l, w) in lits {
+            match merged.last_mut() {
+                Some((last, last_w)) if *last == l => *last_w += w,
+                Some((last, last_w)) if *last == !l => {
+                    // w * l + last_w * ~l = (w - last_w) * l + last_w
+                    b -= w.min(*last_w) as isize;
+                    if w > *last_w {
+                        *last = l;
+                        *last_w = w - *last_w;
+                    } else {
+                        *last_w -= w;
+                    }
+                }
+                _ => merged.push((l, w)),
+            }
+        }
+        let lits = merged
+            .into_iter()
+            .filter_map(|(l, w)| if w > 0 { Some((l, w as isize)) } else { None });
+        (lits, b)
+    };
+    match constr {
+        PBConstraint::UB(constr) => {
+            let (lits, ub) = constr.decompose();
+            let (lits, ub) = merge(lits, ub);
+            PBConstraint::new_ub(lits, ub)
+        }
+        PBConstraint::LB(constr) => {
+            let (lits, lb) = constr.decompose();
+            let (lits, lb) = merge(lits, lb);
+            PBConstraint::new_lb(lits, lb)
+        }
+        PBConstraint::EQ(constr) => {
+            let (lits, b) = constr.decompose();
+            let (lits, b) = merge(lits, b);
+            PBConstraint::new_eq(lits, b)
+        }
+    }
 }
 
 /// An encoder for any pseudo-boolean constraint with an encoding of choice
@@ -415,6 +668,7 @@ pub fn encode_pb_constraint<PBE: BoundBoth + FromIterator<(Lit, usize)>, Col: Co
     collector: &mut Col,
     var_manager: &mut dyn ManageVars,
 ) -> Result<(), crate::OutOfMemory> {
+    let constr = merge_lits(constr);
     if constr.is_tautology() {
         return Ok(());
     }
@@ -433,8 +687,12 @@ pub fn encode_pb_constraint<PBE: BoundBoth + FromIterator<(Lit, usize)>, Col: Co
         return collector.add_clause(constr.into_clause().unwrap());
     }
     if constr.is_card() {
-        let card = constr.into_card_constr().unwrap();
-        return card::default_encode_cardinality_constraint(card, collector, var_manager);
+        return match constr.into_card_constr() {
+            Ok(card) => card::default_encode_cardinality_constraint(card, collector, var_manager),
+            // Equality constraint with bound not divisible by the unit weight
+            Err(PBToCardError::Unsat) => collector.add_clause(Clause::new()),
+            Err(_) => unreachable!("constraint is a non-trivial cardinality constraint"),
+        };
     }
     match PBE::encode_constr(constr, collector, var_manager) {
         Ok(_) => Ok(()),
@@ -444,6 +702,129 @@ pub fn encode_pb_constraint<PBE: BoundBoth + FromIterator<(Lit, usize)>, Col: Co
     }
 }
 
+/// A default encoder for reified pseudo-boolean constraints. Encodes `act ->
+/// constr` for [`Reification::Half`] and `act <-> constr` for
+/// [`Reification::Full`]. This uses a [`DefBothBounding`] to encode true
+/// pseudo-boolean constraints and
+/// [`card::default_encode_reified_cardinality_constraint`] for cardinality
+/// constraints.
+pub fn default_encode_reified_pb_constraint<Col: CollectClauses>(
+    constr: PBConstraint,
+    act: Lit,
+    reif: Reification,
+    collector: &mut Col,
+    var_manager: &mut dyn ManageVars,
+) -> Result<(), crate::OutOfMemory> {
+    encode_reified_pb_constraint::<DefBothBounding, Col>(constr, act, reif, collector, var_manager)
+}
+
+/// An encoder for reified pseudo-boolean constraints with an encoding of
+/// choice. Encodes `act -> constr` for [`Reification::Half`] and `act <->
+/// constr` for [`Reification::Full`].
+pub fn encode_reified_pb_constraint<
+    PBE: BoundBoth + FromIterator<(Lit, usize)>,
+    Col: CollectClauses,
+>(
+    constr: PBConstraint,
+    act: Lit,
+    reif: Reification,
+    collector: &mut Col,
+    var_manager: &mut dyn ManageVars,
+) -> Result<(), crate::OutOfMemory> {
+    let constr = merge_lits(constr);
+    if constr.is_tautology() {
+        return encode_reified_tautology(act, reif, collector);
+    }
+    if constr.is_unsat() {
+        return collector.add_clause(clause![!act]);
+    }
+    if reif == Reification::Half && constr.is_clause() {
+        let mut clause = constr.into_clause().unwrap();
+        clause.add(!act);
+        return collector.add_clause(clause);
+    }
+    if constr.is_card() {
+        return match constr.into_card_constr() {
+            Ok(card) => card::default_encode_reified_cardinality_constraint(
+                card,
+                act,
+                reif,
+                collector,
+                var_manager,
+            ),
+            Err(PBToCardError::Tautology) => encode_reified_tautology(act, reif, collector),
+            // Also covers equality constraints with bound not divisible by the
+            // unit weight
+            Err(PBToCardError::Unsat) => collector.add_clause(clause![!act]),
+            Err(PBToCardError::NotACard) => {
+                unreachable!("constraint was checked to be a cardinality constraint")
+            }
+        };
+    }
+    match constr {
+        PBConstraint::UB(constr) => {
+            let (lits, ub) = constr.decompose();
+            if ub < 0 {
+                return collector.add_clause(clause![!act]);
+            }
+            let ub = ub as usize;
+            let mut enc = PBE::from_iter(lits);
+            enc.encode_ub_reified(ub, act, collector, var_manager)?;
+            if reif == Reification::Full {
+                enc.encode_lb_reified(ub + 1, !act, collector, var_manager)?;
+            }
+        }
+        PBConstraint::LB(constr) => {
+            let (lits, lb) = constr.decompose();
+            if lb <= 0 {
+                return encode_reified_tautology(act, reif, collector);
+            }
+            let lb = lb as usize;
+            let mut enc = PBE::from_iter(lits);
+            enc.encode_lb_reified(lb, act, collector, var_manager)?;
+            if reif == Reification::Full {
+                enc.encode_ub_reified(lb - 1, !act, collector, var_manager)?;
+            }
+        }
+        PBConstraint::EQ(constr) => {
+            let (lits, b) = constr.decompose();
+            if b < 0 {
+                return collector.add_clause(clause![!act]);
+            }
+            let b = b as usize;
+            let mut enc = PBE::from_iter(lits);
+            enc.encode_eq_reified(b, act, collector, var_manager)?;
+            if reif == Reification::Full {
+                // `!act -> (weighted sum <= b - 1 or weighted sum >= b + 1)`
+                let mut clause = clause![act];
+                if b > 0 {
+                    let below = var_manager.new_var().pos_lit();
+                    enc.encode_ub_reified(b - 1, below, collector, var_manager)?;
+                    clause.add(below);
+                }
+                let above = var_manager.new_var().pos_lit();
+                enc.encode_lb_reified(b + 1, above, collector, var_manager)?;
+                clause.add(above);
+                collector.add_clause(clause)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Encodes `act -> true` (nothing to do) for [`Reification::Half`] and `act <->
+/// true` (forcing `act`) for [`Reification::Full`]
+fn encode_reified_tautology<Col: CollectClauses>(
+    act: Lit,
+    reif: Reification,
+    collector: &mut Col,
+) -> Result<(), crate::OutOfMemory> {
+    if reif == Reification::Full {
+        collector.add_clause(clause![act])?;
+    }
+    Ok(())
+}
+
 fn prepare_ub_range<Enc: Encode, R: RangeBounds<usize>>(enc: &Enc, range: R) -> Range<usize> {
     (match range.start_bound() {
         Bound::Included(b) => *b,
@@ -0,0 +1,523 @@
+//! # Binary Adder Encoding
+//!
+//! Implementation of the binary adder network encoding for pseudo-boolean
+//! constraints \[1\]. The weighted sum of the input literals is computed in
+//! binary with full and half adders and bounds are enforced with a binary
+//! comparator on the output bits. The size of the encoding only depends
+//! logarithmically on the weights, which makes it applicable to constraints
+//! with very large weights. The encoding is not arc-consistent.
+//!
+//! The implementation is incremental in the bound: the adder network is only
+//! built once and tightening or relaxing a bound only encodes a new comparator.
+//! Extending the encoding with new input literals builds a new adder network.
+//!
+//! ## References
+//!
+//! - \[1\] Niklas Eén and Niklas Sörensson: _Translating Pseudo-Boolean
+//!   Constraints into SAT_, JSAT 2006.
+
+use super::{
+    BoundLower, BoundLowerIncremental, BoundUpper, BoundUpperIncremental, Encode,
+    EncodeIncremental, Error,
+};
+use crate::{
+    clause,
+    encodings::{CollectClauses, EncodeStats, IterWeightedInputs},
+    instances::ManageVars,
+    types::{Clause, Lit, RsHashMap},
+};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    ops::RangeBounds,
+};
+
+/// Implementation of the binary adder network encoding \[1\]. Each encoded
+/// bound gets its own comparator on the shared adder network. Since every
+/// value in the range passed to [`BoundUpper::encode_ub`] or
+/// [`BoundLower::encode_lb`] gets a comparator, the ranges should be kept
+/// small.
+///
+/// # References
+///
+/// - \[1\] Niklas Eén and Niklas Sörensson: _Translating Pseudo-Boolean
+///   Constraints into SAT_, JSAT 2006.
+#[derive(Default)]
+pub struct Adder {
+    /// Input literals and weights for the encoding
+    in_lits: RsHashMap<Lit, usize>,
+    /// Sum of all input weight
+    weight_sum: usize,
+    /// The output bits of the adder network, least significant first, if
+    /// constructed. `None` is a constant false bit.
+    sum_bits: Option<Vec<Option<Lit>>>,
+    /// Comparator literals for the encoded upper bounds. `None` is a bound that
+    /// is always satisfied.
+    ub_comps: BTreeMap<usize, Option<Lit>>,
+    /// Comparator literals for the encoded lower bounds. `None` is a bound that
+    /// is always satisfied.
+    lb_comps: BTreeMap<usize, Option<Lit>>,
+    /// The number of variables in the encoding
+    n_vars: u32,
+    /// The number of clauses in the encoding
+    n_clauses: usize,
+}
+
+impl Adder {
+    /// Builds the adder network if it is not already built
+    fn build_network<Col>(
+        &mut self,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        if self.sum_bits.is_some() {
+            return Ok(());
+        }
+        // Sort the input literals to make the encoding deterministic
+        let mut lits: Vec<(Lit, usize)> = self.in_lits.iter().map(|(&l, &w)| (l, w)).collect();
+        lits.sort_unstable();
+        let mut buckets: Vec<VecDeque<Lit>> = vec![];
+        for (lit, weight) in lits {
+            for bit in 0..(usize::BITS - weight.leading_zeros()) as usize {
+                if weight & (1 << bit) == 0 {
+                    continue;
+                }
+                if buckets.len() <= bit {
+                    buckets.resize_with(bit + 1, Default::default);
+                }
+                buckets[bit].push_back(lit);
+            }
+        }
+        let mut sum_bits = Vec::with_capacity(buckets.len() + 1);
+        let mut bit = 0;
+        while bit < buckets.len() {
+            while buckets[bit].len() >= 3 {
+                let a = buckets[bit].pop_front().unwrap();
+                let b = buckets[bit].pop_front().unwrap();
+                let c = buckets[bit].pop_front().unwrap();
+                let (sum, carry) = full_adder(a, b, c, collector, var_manager)?;
+                buckets[bit].push_back(sum);
+                if buckets.len() <= bit + 1 {
+                    buckets.push(Default::default());
+                }
+                buckets[bit + 1].push_back(carry);
+            }
+            if buckets[bit].len() == 2 {
+                let a = buckets[bit].pop_front().unwrap();
+                let b = buckets[bit].pop_front().unwrap();
+                let (sum, carry) = half_adder(a, b, collector, var_manager)?;
+                buckets[bit].push_back(sum);
+                if buckets.len() <= bit + 1 {
+                    buckets.push(Default::default());
+                }
+                buckets[bit + 1].push_back(carry);
+            }
+            sum_bits.push(buckets[bit].pop_front());
+            bit += 1;
+        }
+        self.sum_bits = Some(sum_bits);
+        Ok(())
+    }
+
+    /// Encodes a comparator for `sum of lits <= ub`
+    fn encode_ub_comp<Col>(
+        &mut self,
+        ub: usize,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        if self.ub_comps.contains_key(&ub) {
+            return Ok(());
+        }
+        let bits = self.sum_bits.as_ref().unwrap();
+        // For every bit that is 0 in the bound: the bit being 1 while all
+        // higher bits that are 1 in the bound are also 1 violates the bound
+        let mut clauses = vec![];
+        'bits: for (idx, bit) in bits.iter().enumerate() {
+            if ub & (1 << idx) != 0 {
+                continue;
+            }
+            let Some(bit) = bit else {
+                continue;
+            };
+            let mut cl = Clause::new();
+            cl.add(!*bit);
+            for (jdx, higher) in bits.iter().enumerate().skip(idx + 1) {
+                if ub & (1 << jdx) == 0 {
+                    continue;
+                }
+                match higher {
+                    Some(higher) => cl.add(!*higher),
+                    // The bit is constant false, hence the prefix is smaller
+                    None => continue 'bits,
+                }
+            }
+            clauses.push(cl);
+        }
+        let comp = add_comparator(clauses, collector, var_manager)?;
+        self.ub_comps.insert(ub, comp);
+        Ok(())
+    }
+
+    /// Encodes a comparator for `sum of lits >= lb`
+    fn encode_lb_comp<Col>(
+        &mut self,
+        lb: usize,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        if self.lb_comps.contains_key(&lb) {
+            return Ok(());
+        }
+        let bits = self.sum_bits.as_ref().unwrap();
+        // For every bit that is 1 in the bound: the bit being 0 while all
+        // higher bits that are 0 in the bound are also 0 violates the bound
+        let mut clauses = vec![];
+        for (idx, bit) in bits.iter().enumerate() {
+            if lb & (1 << idx) == 0 {
+                continue;
+            }
+            let mut cl = Clause::new();
+            if let Some(bit) = bit {
+                cl.add(*bit);
+            }
+            for (jdx, higher) in bits.iter().enumerate().skip(idx + 1) {
+                if lb & (1 << jdx) != 0 {
+                    continue;
+                }
+                if let Some(higher) = higher {
+                    cl.add(*higher);
+                }
+            }
+            clauses.push(cl);
+        }
+        let comp = add_comparator(clauses, collector, var_manager)?;
+        self.lb_comps.insert(lb, comp);
+        Ok(())
+    }
+}
+
+/// Adds the clauses of a comparator, each extended with the negation of a new
+/// comparator literal. Returns `None` if there are no clauses.
+fn add_comparator<Col>(
+    clauses: Vec<Clause>,
+    collector: &mut Col,
+    var_manager: &mut dyn ManageVars,
+) -> Result<Option<Lit>, crate::OutOfMemory>
+where
+    Col: CollectClauses,
+{
+    if clauses.is_empty() {
+        return Ok(None);
+    }
+    let comp = var_manager.new_var().pos_lit();
+    collector.extend_clauses(clauses.into_iter().map(|mut cl| {
+        cl.add(!comp);
+        cl
+    }))?;
+    Ok(Some(comp))
+}
+
+/// Encodes a full adder and returns the sum and carry literals
+fn full_adder<Col>(
+    a: Lit,
+    b: Lit,
+    c: Lit,
+    collector: &mut Col,
+    var_manager: &mut dyn ManageVars,
+) -> Result<(Lit, Lit), crate::OutOfMemory>
+where
+    Col: CollectClauses,
+{
+    let sum = var_manager.new_var().pos_lit();
+    let carry = var_manager.new_var().pos_lit();
+    collector.extend_clauses([
+        // sum = a xor b xor c
+        clause![!a, !b, !c, sum],
+        clause![!a, b, c, sum],
+        clause![a, !b, c, sum],
+        clause![a, b, !c, sum],
+        clause![a, b, c, !sum],
+        clause![a, !b, !c, !sum],
+        clause![!a, b, !c, !sum],
+        clause![!a, !b, c, !sum],
+        // carry = majority(a, b, c)
+        clause![!a, !b, carry],
+        clause![!a, !c, carry],
+        clause![!b, !c, carry],
+        clause![a, b, !carry],
+        clause![a, c, !carry],
+        clause![b, c, !carry],
+    ])?;
+    Ok((sum, carry))
+}
+
+/// Encodes a half adder and returns the sum and carry literals
+fn half_adder<Col>(
+    a: Lit,
+    b: Lit,
+    collector: &mut Col,
+    var_manager: &mut dyn ManageVars,
+) -> Result<(Lit, Lit), crate::OutOfMemory>
+where
+    Col: CollectClauses,
+{
+    let sum = var_manager.new_var().pos_lit();
+    let carry = var_manager.new_var().pos_lit();
+    collector.extend_clauses([
+        // sum = a xor b
+        clause![!a, b, sum],
+        clause![a, !b, sum],
+        clause![a, b, !sum],
+        clause![!a, !b, !sum],
+        // carry = a and b
+        clause![!a, !b, carry],
+        clause![a, !carry],
+        clause![b, !carry],
+    ])?;
+    Ok((sum, carry))
+}
+
+impl Encode for Adder {
+    fn weight_sum(&self) -> usize {
+        self.weight_sum
+    }
+}
+
+impl IterWeightedInputs for Adder {
+    type Iter<'a> = super::gte::GteIter<'a>;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.in_lits.iter().map(super::gte::copy_key_val)
+    }
+}
+
+impl EncodeIncremental for Adder {
+    fn reserve(&mut self, _var_manager: &mut dyn ManageVars) {}
+}
+
+impl BoundUpper for Adder {
+    fn encode_ub<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        let range = super::prepare_ub_range(self, range);
+        if range.is_empty() {
+            return Ok(());
+        }
+        let n_vars_before = var_manager.n_used();
+        let n_clauses_before = collector.n_clauses();
+        self.build_network(collector, var_manager)?;
+        for ub in range {
+            self.encode_ub_comp(ub, collector, var_manager)?;
+        }
+        self.n_clauses += collector.n_clauses() - n_clauses_before;
+        self.n_vars += var_manager.n_used() - n_vars_before;
+        Ok(())
+    }
+
+    fn enforce_ub(&self, ub: usize) -> Result<Vec<Lit>, Error> {
+        if ub >= self.weight_sum {
+            return Ok(vec![]);
+        }
+        match self.ub_comps.get(&ub) {
+            Some(comp) => Ok(comp.iter().copied().collect()),
+            None => Err(Error::NotEncoded),
+        }
+    }
+}
+
+impl BoundUpperIncremental for Adder {
+    fn encode_ub_change<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        // Only comparators that are not yet encoded are added
+        self.encode_ub(range, collector, var_manager)
+    }
+}
+
+impl BoundLower for Adder {
+    fn encode_lb<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        let range = super::prepare_lb_range(self, range);
+        if range.is_empty() {
+            return Ok(());
+        }
+        let n_vars_before = var_manager.n_used();
+        let n_clauses_before = collector.n_clauses();
+        self.build_network(collector, var_manager)?;
+        for lb in range {
+            self.encode_lb_comp(lb, collector, var_manager)?;
+        }
+        self.n_clauses += collector.n_clauses() - n_clauses_before;
+        self.n_vars += var_manager.n_used() - n_vars_before;
+        Ok(())
+    }
+
+    fn enforce_lb(&self, lb: usize) -> Result<Vec<Lit>, Error> {
+        if lb > self.weight_sum {
+            return Err(Error::Unsat);
+        }
+        if lb == 0 {
+            return Ok(vec![]);
+        }
+        match self.lb_comps.get(&lb) {
+            Some(comp) => Ok(comp.iter().copied().collect()),
+            None => Err(Error::NotEncoded),
+        }
+    }
+}
+
+impl BoundLowerIncremental for Adder {
+    fn encode_lb_change<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        // Only comparators that are not yet encoded are added
+        self.encode_lb(range, collector, var_manager)
+    }
+}
+
+impl EncodeStats for Adder {
+    fn n_clauses(&self) -> usize {
+        self.n_clauses
+    }
+
+    fn n_vars(&self) -> u32 {
+        self.n_vars
+    }
+}
+
+impl From<RsHashMap<Lit, usize>> for Adder {
+    fn from(lits: RsHashMap<Lit, usize>) -> Self {
+        let weight_sum = lits.iter().fold(0, |sum, (_, w)| sum + *w);
+        Self {
+            in_lits: lits,
+            weight_sum,
+            ..Default::default()
+        }
+    }
+}
+
+impl FromIterator<(Lit, usize)> for Adder {
+    fn from_iter<T: IntoIterator<Item = (Lit, usize)>>(iter: T) -> Self {
+        let lits: RsHashMap<Lit, usize> = RsHashMap::from_iter(iter);
+        Self::from(lits)
+    }
+}
+
+impl Extend<(Lit, usize)> for Adder {
+    fn extend<T: IntoIterator<Item = (Lit, usize)>>(&mut self, iter: T) {
+        iter.into_iter().for_each(|(l, w)| {
+            if w == 0 {
+                return;
+            }
+            self.weight_sum += w;
+            // The sum changes, so the network and comparators need to be rebuilt
+            self.sum_bits = None;
+            self.ub_comps.clear();
+            self.lb_comps.clear();
+            match self.in_lits.get_mut(&l) {
+                Some(old_w) => *old_w += w,
+                None => {
+                    self.in_lits.insert(l, w);
+                }
+            };
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Adder;
+    use crate::{
+        encodings::{
+            pb::{BoundLower, BoundUpper, BoundUpperIncremental},
+            EncodeStats, Error,
+        },
+        instances::{BasicVarManager, Cnf, ManageVars},
+        lit,
+        types::RsHashMap,
+        var,
+    };
+
+    #[test]
+    fn adder_functions() {
+        let mut lits = RsHashMap::default();
+        lits.insert(lit![0], 5);
+        lits.insert(lit![1], 5);
+        lits.insert(lit![2], 3);
+        lits.insert(lit![3], 3);
+        let mut adder = Adder::from(lits);
+        assert_eq!(adder.enforce_ub(4), Err(Error::NotEncoded));
+        assert_eq!(adder.enforce_lb(4), Err(Error::NotEncoded));
+        assert_eq!(adder.enforce_ub(16), Ok(vec![]));
+        assert_eq!(adder.enforce_lb(17), Err(Error::Unsat));
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        let mut cnf = Cnf::new();
+        adder.encode_ub(4..5, &mut cnf, &mut var_manager).unwrap();
+        assert_eq!(adder.enforce_ub(4).unwrap().len(), 1);
+        assert_eq!(adder.n_clauses(), cnf.len());
+        assert_eq!(adder.n_vars(), var_manager.n_used() - 4);
+        adder.encode_lb(4..5, &mut cnf, &mut var_manager).unwrap();
+        assert_eq!(adder.enforce_lb(4).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn adder_incremental_comparator_only() {
+        let mut lits = RsHashMap::default();
+        lits.insert(lit![0], 1_000_000_007);
+        lits.insert(lit![1], 999_999_937);
+        lits.insert(lit![2], 123_456_789);
+        let mut adder = Adder::from(lits);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![3]);
+        let mut cnf = Cnf::new();
+        adder
+            .encode_ub_change(1_500_000_000..1_500_000_001, &mut cnf, &mut var_manager)
+            .unwrap();
+        let n_vars = adder.n_vars();
+        adder
+            .encode_ub_change(1_100_000_000..1_100_000_001, &mut cnf, &mut var_manager)
+            .unwrap();
+        // Only one new comparator literal
+        assert_eq!(adder.n_vars(), n_vars + 1);
+        assert_eq!(adder.n_clauses(), cnf.len());
+    }
+}
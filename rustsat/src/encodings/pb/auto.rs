@@ -0,0 +1,357 @@
+//! # Automatic Encoding Selection
+//!
+//! Selection of an encoding for a pseudo-boolean expression based on the
+//! number of input literals, the distribution of the weights and the bound to
+//! enforce, similar to the automatic encoding selection in PBLib \[1\].
+//!
+//! The selection is a heuristic. Expressions where no two literals can be true
+//! at the same time are encoded with an at-most-1 encoding, expressions with
+//! (few) small weights with a [`Totalizer`] over literals repeated by their
+//! weight, and general expressions with a [`GeneralizedTotalizer`], unless the
+//! number of distinct sums the totalizer would have to represent is large, in
+//! which case a [`DynamicPolyWatchdog`] is used.
+//!
+//! ## References
+//!
+//! - \[1\] Tobias Philipp and Peter Steinke: _PBLib - A Library for Encoding
+//!   Pseudo-Boolean Constraints into CNF_, SAT 2015.
+
+use std::{collections::BTreeSet, ops::RangeBounds};
+
+use super::{
+    simulators::Card, BoundLower, BoundLowerIncremental, BoundUpper, BoundUpperIncremental,
+    DynamicPolyWatchdog, Encode, EncodeIncremental, GeneralizedTotalizer,
+};
+use crate::{
+    encodings::{card::Totalizer, CollectClauses, EncodeStats, Error},
+    instances::ManageVars,
+    types::{Lit, RsHashMap},
+};
+
+/// If the sum of weights is at most this factor times the number of input
+/// literals, the literals are repeated and a [`Totalizer`] is used
+const TOTALIZER_WEIGHT_FACTOR: usize = 2;
+
+/// If the number of distinct sums up to the bound is at most this factor times
+/// the number of input literals, a [`GeneralizedTotalizer`] is used
+const GTE_SUMS_FACTOR: usize = 64;
+
+/// The encodings that can be selected by [`Variant::auto`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variant {
+    /// No two input literals can be true at the same time without exceeding
+    /// the bound. The expression can be encoded with an at-most-1 encoding
+    /// from [`crate::encodings::am1`], for few literals this is the pairwise
+    /// encoding.
+    AtMostOne,
+    /// A [`Totalizer`] over the input literals repeated by their weight
+    Totalizer,
+    /// A [`GeneralizedTotalizer`]
+    Gte,
+    /// A [`DynamicPolyWatchdog`]
+    Dpw,
+}
+
+impl Variant {
+    /// Selects an encoding for upper bounding the weighted sum of `lits` by
+    /// `ub`. A [`Totalizer`] is only selected for small weights, so weights
+    /// with a common divisor should be divided by it before the selection.
+    pub fn auto(lits: &[(Lit, usize)], ub: usize) -> Self {
+        if lits.len() <= 1 {
+            return Variant::Totalizer;
+        }
+        // Weights above the bound behave like weights of `ub + 1`
+        let mut weights: Vec<usize> = lits.iter().map(|&(_, w)| w.min(ub + 1)).collect();
+        weights.sort_unstable();
+        if weights[0] + weights[1] > ub {
+            return Variant::AtMostOne;
+        }
+        let weight_sum: usize = weights.iter().sum();
+        if weight_sum <= TOTALIZER_WEIGHT_FACTOR * weights.len() {
+            return Variant::Totalizer;
+        }
+        if n_distinct_sums(&weights, ub, GTE_SUMS_FACTOR * weights.len()).is_some() {
+            return Variant::Gte;
+        }
+        Variant::Dpw
+    }
+}
+
+/// Counts the distinct sums of subsets of `weights` up to `ub`, where all
+/// larger sums count as one. Returns `None` if there are more than `limit`.
+fn n_distinct_sums(weights: &[usize], ub: usize, limit: usize) -> Option<usize> {
+    let mut sums = BTreeSet::from([0]);
+    for &weight in weights {
+        let new: Vec<usize> = sums.iter().map(|&s| (s + weight).min(ub + 1)).collect();
+        sums.extend(new);
+        if sums.len() > limit {
+            return None;
+        }
+    }
+    Some(sums.len())
+}
+
+/// A pseudo-boolean encoding that is automatically selected based on the input
+/// literals via [`Variant::auto`]. If constructed from literals only, the
+/// selection assumes that bounds up to the sum of weights will be enforced.
+///
+/// For encoding entire constraints, including the bound in the selection, see
+/// [`super::auto_encode_pb_constraint`].
+pub enum Auto {
+    /// A [`Totalizer`] over repeated literals
+    Totalizer(Card<Totalizer>),
+    /// A [`GeneralizedTotalizer`]
+    Gte(GeneralizedTotalizer),
+    /// A [`DynamicPolyWatchdog`]
+    Dpw(DynamicPolyWatchdog),
+}
+
+impl Auto {
+    /// Creates a new encoding of the given variant over some literals. Since
+    /// [`Variant::AtMostOne`] is not a bound encoding, a [`Totalizer`] is used
+    /// for it.
+    pub fn new(variant: Variant, lits: RsHashMap<Lit, usize>) -> Self {
+        match variant {
+            Variant::AtMostOne | Variant::Totalizer => Auto::Totalizer(Card::from(lits)),
+            Variant::Gte => Auto::Gte(GeneralizedTotalizer::from(lits)),
+            Variant::Dpw => Auto::Dpw(DynamicPolyWatchdog::from(lits)),
+        }
+    }
+
+    /// Gets the variant of the encoding
+    pub fn variant(&self) -> Variant {
+        match self {
+            Auto::Totalizer(_) => Variant::Totalizer,
+            Auto::Gte(_) => Variant::Gte,
+            Auto::Dpw(_) => Variant::Dpw,
+        }
+    }
+}
+
+impl Default for Auto {
+    fn default() -> Self {
+        Auto::Gte(GeneralizedTotalizer::default())
+    }
+}
+
+impl From<RsHashMap<Lit, usize>> for Auto {
+    fn from(lits: RsHashMap<Lit, usize>) -> Self {
+        let lit_vec: Vec<(Lit, usize)> = lits
+            .iter()
+            .filter(|(_, &w)| w > 0)
+            .map(|(&l, &w)| (l, w))
+            .collect();
+        let weight_sum = lit_vec.iter().fold(0, |sum, &(_, w)| sum + w);
+        Auto::new(Variant::auto(&lit_vec, weight_sum), lits)
+    }
+}
+
+impl FromIterator<(Lit, usize)> for Auto {
+    fn from_iter<T: IntoIterator<Item = (Lit, usize)>>(iter: T) -> Self {
+        let mut lits: RsHashMap<Lit, usize> = RsHashMap::default();
+        iter.into_iter()
+            .for_each(|(l, w)| *lits.entry(l).or_default() += w);
+        Self::from(lits)
+    }
+}
+
+impl Encode for Auto {
+    fn weight_sum(&self) -> usize {
+        match self {
+            Auto::Totalizer(enc) => enc.weight_sum(),
+            Auto::Gte(enc) => enc.weight_sum(),
+            Auto::Dpw(enc) => enc.weight_sum(),
+        }
+    }
+
+    fn next_higher(&self, val: usize) -> usize {
+        match self {
+            Auto::Totalizer(enc) => enc.next_higher(val),
+            Auto::Gte(enc) => enc.next_higher(val),
+            Auto::Dpw(enc) => enc.next_higher(val),
+        }
+    }
+
+    fn next_lower(&self, val: usize) -> usize {
+        match self {
+            Auto::Totalizer(enc) => enc.next_lower(val),
+            Auto::Gte(enc) => enc.next_lower(val),
+            Auto::Dpw(enc) => enc.next_lower(val),
+        }
+    }
+}
+
+impl EncodeIncremental for Auto {
+    fn reserve(&mut self, var_manager: &mut dyn ManageVars) {
+        match self {
+            Auto::Totalizer(enc) => enc.reserve(var_manager),
+            Auto::Gte(enc) => enc.reserve(var_manager),
+            Auto::Dpw(enc) => enc.reserve(var_manager),
+        }
+    }
+}
+
+impl BoundUpper for Auto {
+    fn encode_ub<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        match self {
+            Auto::Totalizer(enc) => enc.encode_ub(range, collector, var_manager),
+            Auto::Gte(enc) => enc.encode_ub(range, collector, var_manager),
+            Auto::Dpw(enc) => enc.encode_ub(range, collector, var_manager),
+        }
+    }
+
+    fn enforce_ub(&self, ub: usize) -> Result<Vec<Lit>, Error> {
+        match self {
+            Auto::Totalizer(enc) => enc.enforce_ub(ub),
+            Auto::Gte(enc) => enc.enforce_ub(ub),
+            Auto::Dpw(enc) => enc.enforce_ub(ub),
+        }
+    }
+
+    fn coarse_ub(&self, ub: usize) -> usize {
+        match self {
+            Auto::Totalizer(enc) => enc.coarse_ub(ub),
+            Auto::Gte(enc) => enc.coarse_ub(ub),
+            Auto::Dpw(enc) => enc.coarse_ub(ub),
+        }
+    }
+}
+
+impl BoundLower for Auto {
+    fn encode_lb<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        match self {
+            Auto::Totalizer(enc) => enc.encode_lb(range, collector, var_manager),
+            Auto::Gte(enc) => enc.encode_lb(range, collector, var_manager),
+            Auto::Dpw(enc) => enc.encode_lb(range, collector, var_manager),
+        }
+    }
+
+    fn enforce_lb(&self, lb: usize) -> Result<Vec<Lit>, Error> {
+        match self {
+            Auto::Totalizer(enc) => enc.enforce_lb(lb),
+            Auto::Gte(enc) => enc.enforce_lb(lb),
+            Auto::Dpw(enc) => enc.enforce_lb(lb),
+        }
+    }
+
+    fn coarse_lb(&self, lb: usize) -> usize {
+        match self {
+            Auto::Totalizer(enc) => enc.coarse_lb(lb),
+            Auto::Gte(enc) => enc.coarse_lb(lb),
+            Auto::Dpw(enc) => enc.coarse_lb(lb),
+        }
+    }
+}
+
+impl BoundUpperIncremental for Auto {
+    fn encode_ub_change<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        match self {
+            Auto::Totalizer(enc) => enc.encode_ub_change(range, collector, var_manager),
+            Auto::Gte(enc) => enc.encode_ub_change(range, collector, var_manager),
+            Auto::Dpw(enc) => enc.encode_ub_change(range, collector, var_manager),
+        }
+    }
+}
+
+impl BoundLowerIncremental for Auto {
+    fn encode_lb_change<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        match self {
+            Auto::Totalizer(enc) => enc.encode_lb_change(range, collector, var_manager),
+            Auto::Gte(enc) => enc.encode_lb_change(range, collector, var_manager),
+            Auto::Dpw(enc) => enc.encode_lb_change(range, collector, var_manager),
+        }
+    }
+}
+
+impl EncodeStats for Auto {
+    fn n_clauses(&self) -> usize {
+        match self {
+            Auto::Totalizer(enc) => enc.n_clauses(),
+            Auto::Gte(enc) => enc.n_clauses(),
+            Auto::Dpw(enc) => enc.n_clauses(),
+        }
+    }
+
+    fn n_vars(&self) -> u32 {
+        match self {
+            Auto::Totalizer(enc) => enc.n_vars(),
+            Auto::Gte(enc) => enc.n_vars(),
+            Auto::Dpw(enc) => enc.n_vars(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Auto, Variant};
+    use crate::{lit, types::RsHashMap};
+
+    #[test]
+    fn auto_selection() {
+        let lits = [(lit![0], 3), (lit![1], 4), (lit![2], 5)];
+        assert_eq!(Variant::auto(&lits, 6), Variant::AtMostOne);
+        assert_eq!(Variant::auto(&lits, 7), Variant::Gte);
+        let lits = [(lit![0], 2), (lit![1], 2), (lit![2], 2)];
+        assert_eq!(Variant::auto(&lits, 4), Variant::Totalizer);
+        let lits = [
+            (lit![0], 1_000_000),
+            (lit![1], 1_000_000),
+            (lit![2], 1_000_000),
+        ];
+        assert_eq!(Variant::auto(&lits, 2_000_000), Variant::Gte);
+        let lits = [(lit![0], 1), (lit![1], 2), (lit![2], 1), (lit![3], 3)];
+        assert_eq!(Variant::auto(&lits, 4), Variant::Totalizer);
+        let lits: Vec<_> = (0..20).map(|idx| (lit![idx], 1 << idx)).collect();
+        assert_eq!(Variant::auto(&lits, (1 << 19) + 7), Variant::Dpw);
+        assert_eq!(Variant::auto(&lits, 100), Variant::Gte);
+    }
+
+    #[test]
+    fn auto_from_lits() {
+        let mut lits = RsHashMap::default();
+        lits.insert(lit![0], 1);
+        lits.insert(lit![1], 1);
+        lits.insert(lit![2], 1);
+        assert_eq!(Auto::from(lits).variant(), Variant::Totalizer);
+        let enc: Auto = (0..4).map(|idx| (lit![idx], 1_000_000)).collect();
+        assert_eq!(enc.variant(), Variant::Gte);
+        let enc: Auto = (0..20).map(|idx| (lit![idx], 1 << idx)).collect();
+        assert_eq!(enc.variant(), Variant::Dpw);
+    }
+}
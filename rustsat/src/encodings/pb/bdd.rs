@@ -0,0 +1,497 @@
+//! # Binary Decision Diagram Encoding
+//!
+//! Implementation of the reduced ordered binary decision diagram (ROBDD)
+//! encoding for pseudo-boolean constraints \[1\] with the interval-based node
+//! merging from \[2\]. Each node of the diagram represents the constraint
+//! `weighted sum of the remaining lits <= k` for an interval of values for
+//! `k`. Upper and lower bounds are encoded on the same diagram and share the
+//! node literals. The encoding is arc-consistent and, especially for
+//! constraints with few distinct weights, much smaller than the
+//! [`super::GeneralizedTotalizer`].
+//!
+//! The implementation is not incremental. Extending the encoding with new input
+//! literals after encoding builds a new diagram.
+//!
+//! ## References
+//!
+//! - \[1\] Niklas Eén and Niklas Sörensson: _Translating Pseudo-Boolean
+//!   Constraints into SAT_, JSAT 2006.
+//! - \[2\] Ignasi Abío and Robert Nieuwenhuis and Albert Oliveras and Enric
+//!   Rodríguez-Carbonell: _BDDs for Pseudo-Boolean Constraints - Revisited_,
+//!   SAT 2011.
+
+use super::{BoundLower, BoundUpper, Encode, Error};
+use crate::{
+    clause,
+    encodings::{atomics, CollectClauses, EncodeStats, IterWeightedInputs},
+    instances::ManageVars,
+    types::{Lit, RsHashMap},
+};
+use std::{collections::BTreeMap, ops::RangeBounds};
+
+/// Implementation of the reduced ordered binary decision diagram encoding
+/// \[1\] with interval-based node merging \[2\]. Upper and lower bounds are
+/// encoded on the same diagram and share the node literals.
+///
+/// # References
+///
+/// - \[1\] Niklas Eén and Niklas Sörensson: _Translating Pseudo-Boolean
+///   Constraints into SAT_, JSAT 2006.
+/// - \[2\] Ignasi Abío and Robert Nieuwenhuis and Albert Oliveras and Enric
+///   Rodríguez-Carbonell: _BDDs for Pseudo-Boolean Constraints - Revisited_,
+///   SAT 2011.
+#[derive(Default)]
+pub struct Bdd {
+    /// Input literals and weights for the encoding
+    in_lits: RsHashMap<Lit, usize>,
+    /// Sum of all input weight
+    weight_sum: usize,
+    /// The decision diagram, if constructed
+    diagram: Option<Diagram>,
+    /// The number of variables in the BDD
+    n_vars: u32,
+    /// The number of clauses in the BDD
+    n_clauses: usize,
+}
+
+impl Bdd {
+    /// Gets the diagram, constructing it if needed
+    fn diagram(&mut self) -> &mut Diagram {
+        if self.diagram.is_none() {
+            self.diagram = Some(Diagram::new(&self.in_lits));
+        }
+        self.diagram.as_mut().unwrap()
+    }
+}
+
+impl Encode for Bdd {
+    fn weight_sum(&self) -> usize {
+        self.weight_sum
+    }
+}
+
+impl IterWeightedInputs for Bdd {
+    type Iter<'a> = super::gte::GteIter<'a>;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.in_lits.iter().map(super::gte::copy_key_val)
+    }
+}
+
+impl BoundUpper for Bdd {
+    fn encode_ub<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        let range = super::prepare_ub_range(self, range);
+        if range.is_empty() {
+            return Ok(());
+        }
+        let n_vars_before = var_manager.n_used();
+        let n_clauses_before = collector.n_clauses();
+        let diagram = self.diagram();
+        for ub in range {
+            let root = diagram.build(0, ub as isize, var_manager);
+            diagram.encode_ub(root, collector)?;
+        }
+        self.n_clauses += collector.n_clauses() - n_clauses_before;
+        self.n_vars += var_manager.n_used() - n_vars_before;
+        Ok(())
+    }
+
+    fn enforce_ub(&self, ub: usize) -> Result<Vec<Lit>, Error> {
+        if ub >= self.weight_sum {
+            return Ok(vec![]);
+        }
+        let Some(diagram) = &self.diagram else {
+            return Err(Error::NotEncoded);
+        };
+        match diagram.lookup(0, ub as isize) {
+            Some((Child::Node(idx), _)) if diagram.nodes[idx].ub_encoded => {
+                Ok(vec![diagram.nodes[idx].lit])
+            }
+            _ => Err(Error::NotEncoded),
+        }
+    }
+}
+
+impl BoundLower for Bdd {
+    fn encode_lb<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        let range = super::prepare_lb_range(self, range);
+        if range.is_empty() {
+            return Ok(());
+        }
+        let n_vars_before = var_manager.n_used();
+        let n_clauses_before = collector.n_clauses();
+        let diagram = self.diagram();
+        for lb in range {
+            // `sum >= lb` is enforced by falsifying the node for `sum <= lb - 1`
+            let root = diagram.build(0, lb as isize - 1, var_manager);
+            diagram.encode_lb(root, collector)?;
+        }
+        self.n_clauses += collector.n_clauses() - n_clauses_before;
+        self.n_vars += var_manager.n_used() - n_vars_before;
+        Ok(())
+    }
+
+    fn enforce_lb(&self, lb: usize) -> Result<Vec<Lit>, Error> {
+        if lb > self.weight_sum {
+            return Err(Error::Unsat);
+        }
+        if lb == 0 {
+            return Ok(vec![]);
+        }
+        let Some(diagram) = &self.diagram else {
+            return Err(Error::NotEncoded);
+        };
+        match diagram.lookup(0, lb as isize - 1) {
+            Some((Child::Node(idx), _)) if diagram.nodes[idx].lb_encoded => {
+                Ok(vec![!diagram.nodes[idx].lit])
+            }
+            _ => Err(Error::NotEncoded),
+        }
+    }
+}
+
+impl EncodeStats for Bdd {
+    fn n_clauses(&self) -> usize {
+        self.n_clauses
+    }
+
+    fn n_vars(&self) -> u32 {
+        self.n_vars
+    }
+}
+
+impl From<RsHashMap<Lit, usize>> for Bdd {
+    fn from(lits: RsHashMap<Lit, usize>) -> Self {
+        let weight_sum = lits.iter().fold(0, |sum, (_, w)| sum + *w);
+        Self {
+            in_lits: lits,
+            weight_sum,
+            ..Default::default()
+        }
+    }
+}
+
+impl FromIterator<(Lit, usize)> for Bdd {
+    fn from_iter<T: IntoIterator<Item = (Lit, usize)>>(iter: T) -> Self {
+        let lits: RsHashMap<Lit, usize> = RsHashMap::from_iter(iter);
+        Self::from(lits)
+    }
+}
+
+impl Extend<(Lit, usize)> for Bdd {
+    fn extend<T: IntoIterator<Item = (Lit, usize)>>(&mut self, iter: T) {
+        iter.into_iter().for_each(|(l, w)| {
+            if w == 0 {
+                return;
+            }
+            self.weight_sum += w;
+            // The variable order changes, so the diagram needs to be rebuilt
+            self.diagram = None;
+            match self.in_lits.get_mut(&l) {
+                Some(old_w) => *old_w += w,
+                None => {
+                    self.in_lits.insert(l, w);
+                }
+            };
+        });
+    }
+}
+
+/// A child of a node in the decision diagram
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Child {
+    /// The constant true terminal
+    True,
+    /// The constant false terminal
+    False,
+    /// An internal node, given by its index
+    Node(usize),
+}
+
+/// An internal node of the decision diagram. The node literal is equivalent to
+/// the weighted sum of the literals from the node's level onwards being at
+/// most `k` for all values `k` in the node's interval.
+#[derive(Debug)]
+struct Node {
+    /// The literal representing the node
+    lit: Lit,
+    /// The input literal decided on at this node
+    decision: Lit,
+    /// The child if the decision literal is false
+    lo: Child,
+    /// The child if the decision literal is true
+    hi: Child,
+    /// Whether the upper bounding clauses are encoded
+    ub_encoded: bool,
+    /// Whether the lower bounding clauses are encoded
+    lb_encoded: bool,
+}
+
+/// The reduced ordered decision diagram
+struct Diagram {
+    /// The input literals in the order of the diagram levels
+    lits: Vec<(Lit, usize)>,
+    /// For each level, the sum of the weights from the level onwards
+    suffix_sums: Vec<isize>,
+    /// For each level, the intervals of existing nodes, indexed by the lower
+    /// end of the interval and containing the upper end and the node
+    levels: Vec<BTreeMap<isize, (isize, usize)>>,
+    /// The node arena
+    nodes: Vec<Node>,
+}
+
+impl Diagram {
+    /// Initializes an empty diagram. Literals are ordered by decreasing weight.
+    fn new(in_lits: &RsHashMap<Lit, usize>) -> Self {
+        let mut lits: Vec<(Lit, usize)> = in_lits.iter().map(|(&l, &w)| (l, w)).collect();
+        lits.sort_unstable_by(|(l1, w1), (l2, w2)| w2.cmp(w1).then(l1.cmp(l2)));
+        let mut suffix_sums = vec![0; lits.len() + 1];
+        for idx in (0..lits.len()).rev() {
+            suffix_sums[idx] = suffix_sums[idx + 1] + lits[idx].1 as isize;
+        }
+        Self {
+            levels: vec![BTreeMap::new(); lits.len()],
+            lits,
+            suffix_sums,
+            nodes: vec![],
+        }
+    }
+
+    /// Looks up the node representing `sum of lits from level onwards <= k`
+    /// without constructing it. Returns the node together with the interval of
+    /// values for `k` that it represents.
+    fn lookup(&self, level: usize, k: isize) -> Option<(Child, (isize, isize))> {
+        if k < 0 {
+            return Some((Child::False, (isize::MIN, -1)));
+        }
+        if k >= self.suffix_sums[level] {
+            return Some((Child::True, (self.suffix_sums[level], isize::MAX)));
+        }
+        self.levels[level]
+            .range(..=k)
+            .next_back()
+            .and_then(|(&lower, &(upper, idx))| {
+                if k <= upper {
+                    Some((Child::Node(idx), (lower, upper)))
+                } else {
+                    None
+                }
+            })
+    }
+
+    /// Recursively constructs the node representing `sum of lits from level
+    /// onwards <= k`
+    fn build(&mut self, level: usize, k: isize, var_manager: &mut dyn ManageVars) -> Child {
+        self.build_rec(level, k, var_manager).0
+    }
+
+    /// Recursively constructs the node representing `sum of lits from level
+    /// onwards <= k` and returns it together with its interval
+    fn build_rec(
+        &mut self,
+        level: usize,
+        k: isize,
+        var_manager: &mut dyn ManageVars,
+    ) -> (Child, (isize, isize)) {
+        if let Some(found) = self.lookup(level, k) {
+            return found;
+        }
+        let (decision, weight) = self.lits[level];
+        let weight = weight as isize;
+        let (lo, (lo_lower, lo_upper)) = self.build_rec(level + 1, k, var_manager);
+        let (hi, (hi_lower, hi_upper)) = self.build_rec(level + 1, k - weight, var_manager);
+        let lower = std::cmp::max(lo_lower, hi_lower.saturating_add(weight));
+        let upper = std::cmp::min(lo_upper, hi_upper.saturating_add(weight));
+        if lo == hi {
+            // Reduction: the decision literal does not matter
+            return (lo, (lower, upper));
+        }
+        let idx = self.nodes.len();
+        self.nodes.push(Node {
+            lit: var_manager.new_var().pos_lit(),
+            decision,
+            lo,
+            hi,
+            ub_encoded: false,
+            lb_encoded: false,
+        });
+        self.levels[level].insert(lower, (upper, idx));
+        (Child::Node(idx), (lower, upper))
+    }
+
+    /// Encodes the upper bounding clauses for all nodes reachable from `root`
+    fn encode_ub<Col>(&mut self, root: Child, collector: &mut Col) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        let mut stack = vec![root];
+        while let Some(child) = stack.pop() {
+            let Child::Node(idx) = child else {
+                continue;
+            };
+            if self.nodes[idx].ub_encoded {
+                continue;
+            }
+            let node = &self.nodes[idx];
+            // node -> lo
+            match node.lo {
+                Child::True => (),
+                Child::False => collector.add_clause(clause![!node.lit])?,
+                Child::Node(lo) => {
+                    collector.add_clause(atomics::lit_impl_lit(node.lit, self.nodes[lo].lit))?
+                }
+            }
+            // node & decision -> hi
+            match node.hi {
+                Child::True => (),
+                Child::False => {
+                    collector.add_clause(atomics::lit_impl_lit(node.lit, !node.decision))?
+                }
+                Child::Node(hi) => collector.add_clause(atomics::cube_impl_lit(
+                    &[node.lit, node.decision],
+                    self.nodes[hi].lit,
+                ))?,
+            }
+            stack.push(node.lo);
+            stack.push(node.hi);
+            self.nodes[idx].ub_encoded = true;
+        }
+        Ok(())
+    }
+
+    /// Encodes the lower bounding clauses for all nodes reachable from `root`
+    fn encode_lb<Col>(&mut self, root: Child, collector: &mut Col) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        let mut stack = vec![root];
+        while let Some(child) = stack.pop() {
+            let Child::Node(idx) = child else {
+                continue;
+            };
+            if self.nodes[idx].lb_encoded {
+                continue;
+            }
+            let node = &self.nodes[idx];
+            // hi -> node
+            match node.hi {
+                Child::True => collector.add_clause(clause![node.lit])?,
+                Child::False => (),
+                Child::Node(hi) => {
+                    collector.add_clause(atomics::lit_impl_lit(self.nodes[hi].lit, node.lit))?
+                }
+            }
+            // lo & !decision -> node
+            match node.lo {
+                Child::True => {
+                    collector.add_clause(atomics::lit_impl_lit(!node.decision, node.lit))?
+                }
+                Child::False => (),
+                Child::Node(lo) => collector.add_clause(atomics::cube_impl_lit(
+                    &[self.nodes[lo].lit, !node.decision],
+                    node.lit,
+                ))?,
+            }
+            stack.push(node.lo);
+            stack.push(node.hi);
+            self.nodes[idx].lb_encoded = true;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Bdd;
+    use crate::{
+        encodings::{
+            pb::{BoundLower, BoundUpper},
+            EncodeStats, Error,
+        },
+        instances::{BasicVarManager, Cnf, ManageVars},
+        lit,
+        types::RsHashMap,
+        var,
+    };
+
+    #[test]
+    fn bdd_functions() {
+        let mut lits = RsHashMap::default();
+        lits.insert(lit![0], 5);
+        lits.insert(lit![1], 5);
+        lits.insert(lit![2], 3);
+        lits.insert(lit![3], 3);
+        let mut bdd = Bdd::from(lits);
+        assert_eq!(bdd.enforce_ub(4), Err(Error::NotEncoded));
+        assert_eq!(bdd.enforce_lb(4), Err(Error::NotEncoded));
+        assert_eq!(bdd.enforce_ub(16), Ok(vec![]));
+        assert_eq!(bdd.enforce_lb(17), Err(Error::Unsat));
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        let mut cnf = Cnf::new();
+        bdd.encode_ub(0..7, &mut cnf, &mut var_manager).unwrap();
+        assert_eq!(bdd.enforce_ub(4).unwrap().len(), 1);
+        assert_eq!(bdd.n_clauses(), cnf.len());
+        assert_eq!(bdd.n_vars(), var_manager.n_used() - 4);
+        assert_eq!(bdd.enforce_lb(4), Err(Error::NotEncoded));
+        bdd.encode_lb(4..5, &mut cnf, &mut var_manager).unwrap();
+        assert_eq!(bdd.enforce_lb(4).unwrap().len(), 1);
+        assert_eq!(
+            bdd.enforce_ub(4).unwrap(),
+            vec![!bdd.enforce_lb(5).unwrap()[0]]
+        );
+    }
+
+    #[test]
+    fn bdd_interval_merging() {
+        // All bounds between two achievable values are the same constraint
+        let mut lits = RsHashMap::default();
+        lits.insert(lit![0], 4);
+        lits.insert(lit![1], 4);
+        lits.insert(lit![2], 4);
+        let mut bdd = Bdd::from(lits);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![3]);
+        let mut cnf = Cnf::new();
+        bdd.encode_ub(4..8, &mut cnf, &mut var_manager).unwrap();
+        let n_vars = bdd.n_vars();
+        let assumps = bdd.enforce_ub(4).unwrap();
+        for ub in 5..8 {
+            assert_eq!(bdd.enforce_ub(ub).unwrap(), assumps);
+        }
+        bdd.encode_ub(5..8, &mut cnf, &mut var_manager).unwrap();
+        assert_eq!(bdd.n_vars(), n_vars);
+    }
+
+    #[test]
+    fn bdd_extend_rebuilds() {
+        let mut bdd = Bdd::from_iter([(lit![0], 2), (lit![1], 3)]);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![3]);
+        let mut cnf = Cnf::new();
+        bdd.encode_ub(2..3, &mut cnf, &mut var_manager).unwrap();
+        assert!(bdd.enforce_ub(2).is_ok());
+        bdd.extend([(lit![2], 1)]);
+        assert_eq!(bdd.enforce_ub(2), Err(Error::NotEncoded));
+        bdd.encode_ub(2..3, &mut cnf, &mut var_manager).unwrap();
+        assert!(bdd.enforce_ub(2).is_ok());
+    }
+}
@@ -3,7 +3,7 @@
 //! This is an alternative implementation of the
 //! [`crate::encodings::pb::GeneralizedTotalizer`] encoding.
 
-use std::ops::RangeBounds;
+use std::ops::{Range, RangeBounds};
 
 use crate::{
     encodings::{
@@ -15,13 +15,13 @@ use crate::{
     types::{Lit, RsHashMap},
 };
 
-use super::{BoundUpper, BoundUpperIncremental, Encode, EncodeIncremental};
+use super::{
+    BoundLower, BoundLowerIncremental, BoundUpper, BoundUpperIncremental, Encode, EncodeIncremental,
+};
 
 /// Implementation of the binary adder tree generalized totalizer encoding
 /// \[1\]. The implementation is incremental. The implementation is recursive.
-/// This encoding only support upper bounding. Lower bounding can be achieved by
-/// negating the input literals. This is implemented in
-/// [`super::simulators::Inverted`].
+/// Upper and lower bounds share the same output literals.
 /// The implementation is based on a node database.
 ///
 /// # References
@@ -37,6 +37,9 @@ pub struct DbGte {
     /// Maximum weight of a leaf, needed for computing how much more than
     /// `max_rhs` to encode
     max_leaf_weight: usize,
+    /// The range of upper bounds that has been encoded, needed to re-encode
+    /// upper bounds when the tree is extended for lower bounding
+    ub_range: Range<usize>,
     /// The number of variables in the totalizer
     n_vars: u32,
     /// The number of clauses in the totalizer
@@ -110,6 +113,55 @@ impl DbGte {
     pub fn depth(&self) -> usize {
         self.root.map_or(0, |con| self.db[con.id].depth())
     }
+
+    /// Defines the output literals of the root required for the upper bounds
+    /// in `range`
+    fn encode_ub_range<Col>(
+        &mut self,
+        range: Range<usize>,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        if let Some(con) = self.root {
+            self.db[con.id]
+                .vals(
+                    con.rev_map_round_up(range.start + 1)
+                        ..=con.rev_map(range.end + self.max_leaf_weight),
+                )
+                .try_for_each(|val| {
+                    self.db
+                        .define_pos(con.id, val, collector, var_manager)?
+                        .unwrap();
+                    Ok::<(), crate::OutOfMemory>(())
+                })?
+        }
+        Ok(())
+    }
+
+    /// Extends the tree with all remaining input literals, as required for
+    /// lower bounding. Since literals that were too heavy for the encoded
+    /// upper bounds are added to the tree, the upper bounds are re-encoded at
+    /// the new root.
+    fn extend_tree_lb<Col>(
+        &mut self,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        if self.lit_buffer.is_empty() {
+            return Ok(());
+        }
+        self.extend_tree(usize::MAX);
+        if self.ub_range.is_empty() {
+            return Ok(());
+        }
+        self.encode_ub_range(self.ub_range.clone(), collector, var_manager)
+    }
 }
 
 impl Encode for DbGte {
@@ -168,7 +220,8 @@ impl BoundUpper for DbGte {
         Col: CollectClauses,
         R: RangeBounds<usize>,
     {
-        self.db.reset_encoded();
+        self.db.reset_encoded_pos();
+        self.ub_range = 0..0;
         self.encode_ub_change(range, collector, var_manager)
     }
 
@@ -197,7 +250,7 @@ impl BoundUpper for DbGte {
                             return Ok(());
                         }
                         INode::Unit(node) => {
-                            if let LitData::Lit { lit, enc_pos } = node.lits[val - 1] {
+                            if let LitData::Lit { lit, enc_pos, .. } = node.lits[val - 1] {
                                 if enc_pos {
                                     assumps.push(!lit);
                                     return Ok(());
@@ -205,7 +258,7 @@ impl BoundUpper for DbGte {
                             }
                         }
                         INode::General(node) => {
-                            if let LitData::Lit { lit, enc_pos } = node.lits[&val] {
+                            if let LitData::Lit { lit, enc_pos, .. } = node.lits[&val] {
                                 if enc_pos {
                                     assumps.push(!lit);
                                     return Ok(());
@@ -239,18 +292,85 @@ impl BoundUpperIncremental for DbGte {
         let n_vars_before = var_manager.n_used();
         let n_clauses_before = collector.n_clauses();
         self.extend_tree(range.end - 1);
+        self.ub_range = super::gte::extend_range(&self.ub_range, &range);
+        self.encode_ub_range(range, collector, var_manager)?;
+        self.n_clauses += collector.n_clauses() - n_clauses_before;
+        self.n_vars += var_manager.n_used() - n_vars_before;
+        Ok(())
+    }
+}
+
+impl BoundLower for DbGte {
+    fn encode_lb<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        self.db.reset_encoded_neg();
+        self.encode_lb_change(range, collector, var_manager)
+    }
+
+    fn enforce_lb(&self, lb: usize) -> Result<Vec<Lit>, Error> {
+        if lb > self.weight_sum() {
+            return Err(Error::Unsat);
+        }
+        if lb == 0 {
+            return Ok(vec![]);
+        }
+        if !self.lit_buffer.is_empty() {
+            return Err(Error::NotEncoded);
+        }
         if let Some(con) = self.root {
-            self.db[con.id]
-                .vals(
-                    con.rev_map_round_up(range.start + 1)
-                        ..=con.rev_map(range.end + self.max_leaf_weight),
-                )
-                .try_for_each(|val| {
+            // The lowest value of the root that is at least `lb`
+            let val = self.db[con.id]
+                .vals(con.rev_map_round_up(lb)..)
+                .next()
+                .unwrap();
+            let node = &self.db[con.id];
+            if node.encoded_neg(val) {
+                return Ok(vec![node[val]]);
+            }
+        }
+        Err(Error::NotEncoded)
+    }
+}
+
+impl BoundLowerIncremental for DbGte {
+    fn encode_lb_change<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        let range = super::prepare_lb_range(self, range);
+        if range.is_empty() {
+            return Ok(());
+        }
+        let n_vars_before = var_manager.n_used();
+        let n_clauses_before = collector.n_clauses();
+        // All input literals are relevant for lower bounding
+        self.extend_tree_lb(collector, var_manager)?;
+        if let Some(con) = self.root {
+            let node = &self.db[con.id];
+            let first = node.vals(con.rev_map_round_up(range.start)..).next();
+            let last = node.vals(con.rev_map_round_up(range.end - 1)..).next();
+            if let (Some(first), Some(last)) = (first, last) {
+                self.db[con.id].vals(first..=last).try_for_each(|val| {
                     self.db
-                        .define_pos(con.id, val, collector, var_manager)?
+                        .define_neg(con.id, val, collector, var_manager)?
                         .unwrap();
                     Ok::<(), crate::OutOfMemory>(())
                 })?
+            }
         }
         self.n_clauses += collector.n_clauses() - n_clauses_before;
         self.n_vars += var_manager.n_used() - n_vars_before;
@@ -269,7 +389,9 @@ impl EncodeStats for DbGte {
 }
 
 impl From<RsHashMap<Lit, usize>> for DbGte {
-    fn from(lits: RsHashMap<Lit, usize>) -> Self {
+    fn from(mut lits: RsHashMap<Lit, usize>) -> Self {
+        // Zero-weight literals do not contribute to the sum
+        lits.retain(|_, w| *w > 0);
         Self {
             lit_buffer: lits,
             ..Default::default()
@@ -287,6 +409,10 @@ impl FromIterator<(Lit, usize)> for DbGte {
 impl Extend<(Lit, usize)> for DbGte {
     fn extend<T: IntoIterator<Item = (Lit, usize)>>(&mut self, iter: T) {
         iter.into_iter().for_each(|(l, w)| {
+            if w == 0 {
+                // Zero-weight literals do not contribute to the sum
+                return;
+            }
             // Insert into buffer to be added to tree
             match self.lit_buffer.get_mut(&l) {
                 Some(old_w) => *old_w += w,
@@ -307,7 +433,10 @@ pub mod referenced {
         encodings::{
             card::dbtotalizer::{INode, LitData, TotDb},
             nodedb::{NodeCon, NodeLike},
-            pb::{BoundUpper, BoundUpperIncremental, Encode, EncodeIncremental},
+            pb::{
+                BoundLower, BoundLowerIncremental, BoundUpper, BoundUpperIncremental, Encode,
+                EncodeIncremental,
+            },
             CollectClauses, Error,
         },
         instances::ManageVars,
@@ -449,7 +578,7 @@ pub mod referenced {
             Col: CollectClauses,
             R: RangeBounds<usize>,
         {
-            self.db.reset_encoded();
+            self.db.reset_encoded_pos();
             self.encode_ub_change(range, collector, var_manager)
         }
 
@@ -472,7 +601,7 @@ pub mod referenced {
                             return Ok(());
                         }
                         INode::Unit(node) => {
-                            if let LitData::Lit { lit, enc_pos } = node.lits[val - 1] {
+                            if let LitData::Lit { lit, enc_pos, .. } = node.lits[val - 1] {
                                 if enc_pos {
                                     assumps.push(!lit);
                                     return Ok(());
@@ -480,7 +609,7 @@ pub mod referenced {
                             }
                         }
                         INode::General(node) => {
-                            if let LitData::Lit { lit, enc_pos } = node.lits[&val] {
+                            if let LitData::Lit { lit, enc_pos, .. } = node.lits[&val] {
                                 if enc_pos {
                                     assumps.push(!lit);
                                     return Ok(());
@@ -506,7 +635,7 @@ pub mod referenced {
             Col: CollectClauses,
             R: RangeBounds<usize>,
         {
-            self.db.borrow_mut().reset_encoded();
+            self.db.borrow_mut().reset_encoded_pos();
             self.encode_ub_change(range, collector, var_manager)
         }
 
@@ -529,7 +658,7 @@ pub mod referenced {
                             return Ok(());
                         }
                         INode::Unit(node) => {
-                            if let LitData::Lit { lit, enc_pos } = node.lits[val - 1] {
+                            if let LitData::Lit { lit, enc_pos, .. } = node.lits[val - 1] {
                                 if enc_pos {
                                     assumps.push(!lit);
                                     return Ok(());
@@ -537,7 +666,7 @@ pub mod referenced {
                             }
                         }
                         INode::General(node) => {
-                            if let LitData::Lit { lit, enc_pos } = node.lits[&val] {
+                            if let LitData::Lit { lit, enc_pos, .. } = node.lits[&val] {
                                 if enc_pos {
                                     assumps.push(!lit);
                                     return Ok(());
@@ -611,6 +740,142 @@ pub mod referenced {
             Ok(())
         }
     }
+
+    impl BoundLower for Gte<'_> {
+        fn encode_lb<Col, R>(
+            &mut self,
+            range: R,
+            collector: &mut Col,
+            var_manager: &mut dyn ManageVars,
+        ) -> Result<(), crate::OutOfMemory>
+        where
+            Col: CollectClauses,
+            R: RangeBounds<usize>,
+        {
+            self.db.reset_encoded_neg();
+            self.encode_lb_change(range, collector, var_manager)
+        }
+
+        fn enforce_lb(&self, lb: usize) -> Result<Vec<Lit>, Error> {
+            if lb > self.weight_sum() {
+                return Err(Error::Unsat);
+            }
+            if lb == 0 {
+                return Ok(vec![]);
+            }
+            let node = &self.db[self.root.id];
+            // The lowest value of the root that is at least `lb`
+            let val = node.vals(self.root.rev_map_round_up(lb)..).next().unwrap();
+            if node.encoded_neg(val) {
+                return Ok(vec![node[val]]);
+            }
+            Err(Error::NotEncoded)
+        }
+    }
+
+    impl BoundLower for GteCell<'_> {
+        fn encode_lb<Col, R>(
+            &mut self,
+            range: R,
+            collector: &mut Col,
+            var_manager: &mut dyn ManageVars,
+        ) -> Result<(), crate::OutOfMemory>
+        where
+            Col: CollectClauses,
+            R: RangeBounds<usize>,
+        {
+            self.db.borrow_mut().reset_encoded_neg();
+            self.encode_lb_change(range, collector, var_manager)
+        }
+
+        fn enforce_lb(&self, lb: usize) -> Result<Vec<Lit>, Error> {
+            if lb > self.weight_sum() {
+                return Err(Error::Unsat);
+            }
+            if lb == 0 {
+                return Ok(vec![]);
+            }
+            let db = self.db.borrow();
+            let node = &db[self.root.id];
+            // The lowest value of the root that is at least `lb`
+            let val = node.vals(self.root.rev_map_round_up(lb)..).next().unwrap();
+            if node.encoded_neg(val) {
+                return Ok(vec![node[val]]);
+            }
+            Err(Error::NotEncoded)
+        }
+    }
+
+    impl BoundLowerIncremental for Gte<'_> {
+        fn encode_lb_change<Col, R>(
+            &mut self,
+            range: R,
+            collector: &mut Col,
+            var_manager: &mut dyn ManageVars,
+        ) -> Result<(), crate::OutOfMemory>
+        where
+            Col: CollectClauses,
+            R: RangeBounds<usize>,
+        {
+            let range = super::super::prepare_lb_range(self, range);
+            if range.is_empty() {
+                return Ok(());
+            }
+            let node = &self.db[self.root.id];
+            let first = node.vals(self.root.rev_map_round_up(range.start)..).next();
+            let last = node
+                .vals(self.root.rev_map_round_up(range.end - 1)..)
+                .next();
+            if let (Some(first), Some(last)) = (first, last) {
+                self.db[self.root.id]
+                    .vals(first..=last)
+                    .try_for_each(|val| {
+                        self.db
+                            .define_neg(self.root.id, val, collector, var_manager)?
+                            .unwrap();
+                        Ok::<(), crate::OutOfMemory>(())
+                    })?;
+            }
+            Ok(())
+        }
+    }
+
+    impl BoundLowerIncremental for GteCell<'_> {
+        fn encode_lb_change<Col, R>(
+            &mut self,
+            range: R,
+            collector: &mut Col,
+            var_manager: &mut dyn ManageVars,
+        ) -> Result<(), crate::OutOfMemory>
+        where
+            Col: CollectClauses,
+            R: RangeBounds<usize>,
+        {
+            let range = super::super::prepare_lb_range(self, range);
+            if range.is_empty() {
+                return Ok(());
+            }
+            let vals = {
+                let db = self.db.borrow();
+                let node = &db[self.root.id];
+                let first = node.vals(self.root.rev_map_round_up(range.start)..).next();
+                let last = node
+                    .vals(self.root.rev_map_round_up(range.end - 1)..)
+                    .next();
+                match (first, last) {
+                    (Some(first), Some(last)) => node.vals(first..=last),
+                    _ => return Ok(()),
+                }
+            };
+            for val in vals {
+                self.db
+                    .borrow_mut()
+                    .define_neg(self.root.id, val, collector, var_manager)?
+                    .unwrap();
+            }
+            Ok(())
+        }
+    }
 }
 
 #[cfg(test)]
@@ -619,7 +884,7 @@ mod tests {
     use crate::{
         encodings::{
             card,
-            pb::{BoundUpper, BoundUpperIncremental},
+            pb::{BoundBoth, BoundLower, BoundLowerIncremental, BoundUpper, BoundUpperIncremental},
             EncodeStats, Error,
         },
         instances::{BasicVarManager, Cnf, ManageVars},
@@ -669,6 +934,86 @@ mod tests {
         assert_eq!(cnf2.len(), gte2.n_clauses());
     }
 
+    #[test]
+    fn lb_gte_functions() {
+        let mut gte = DbGte::default();
+        let mut lits = RsHashMap::default();
+        lits.insert(lit![0], 5);
+        lits.insert(lit![1], 5);
+        lits.insert(lit![2], 3);
+        lits.insert(lit![3], 3);
+        gte.extend(lits);
+        assert_eq!(gte.enforce_lb(4), Err(Error::NotEncoded));
+        assert_eq!(gte.enforce_lb(17), Err(Error::Unsat));
+        assert_eq!(gte.enforce_lb(0), Ok(vec![]));
+        let mut var_manager = BasicVarManager::default();
+        gte.encode_lb(4..7, &mut Cnf::new(), &mut var_manager)
+            .unwrap();
+        assert_eq!(gte.depth(), 3);
+        assert_eq!(gte.enforce_lb(4).unwrap().len(), 1);
+        assert_eq!(gte.enforce_lb(6).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn lb_gte_incremental_building() {
+        let mut gte1 = DbGte::default();
+        let mut lits = RsHashMap::default();
+        lits.insert(lit![0], 5);
+        lits.insert(lit![1], 5);
+        lits.insert(lit![2], 3);
+        lits.insert(lit![3], 3);
+        gte1.extend(lits.clone());
+        let mut var_manager = BasicVarManager::default();
+        let mut cnf1 = Cnf::new();
+        gte1.encode_lb(0..9, &mut cnf1, &mut var_manager).unwrap();
+        let mut gte2 = DbGte::default();
+        gte2.extend(lits);
+        let mut var_manager = BasicVarManager::default();
+        let mut cnf2 = Cnf::new();
+        gte2.encode_lb(0..4, &mut cnf2, &mut var_manager).unwrap();
+        gte2.encode_lb_change(0..9, &mut cnf2, &mut var_manager)
+            .unwrap();
+        assert_eq!(cnf1.len(), cnf2.len());
+        assert_eq!(cnf1.len(), gte1.n_clauses());
+        assert_eq!(cnf2.len(), gte2.n_clauses());
+    }
+
+    #[test]
+    fn both_gte_shared_outputs() {
+        let mut gte = DbGte::default();
+        let mut lits = RsHashMap::default();
+        lits.insert(lit![0], 5);
+        lits.insert(lit![1], 5);
+        lits.insert(lit![2], 3);
+        lits.insert(lit![3], 3);
+        gte.extend(lits);
+        let mut var_manager = BasicVarManager::default();
+        let mut cnf = Cnf::new();
+        gte.encode_ub(0..17, &mut cnf, &mut var_manager).unwrap();
+        let n_vars = gte.n_vars();
+        gte.encode_lb(0..17, &mut cnf, &mut var_manager).unwrap();
+        assert_eq!(gte.n_vars(), n_vars);
+        assert!(gte.enforce_eq(8).is_ok());
+    }
+
+    #[test]
+    fn both_gte_heavy_lits() {
+        let mut gte = DbGte::default();
+        let mut lits = RsHashMap::default();
+        lits.insert(lit![0], 1);
+        lits.insert(lit![1], 1);
+        lits.insert(lit![2], 10);
+        gte.extend(lits);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![3]);
+        let mut cnf = Cnf::new();
+        gte.encode_ub(1..2, &mut cnf, &mut var_manager).unwrap();
+        assert!(gte.enforce_ub(1).is_ok());
+        gte.encode_lb(1..2, &mut cnf, &mut var_manager).unwrap();
+        assert!(gte.enforce_ub(1).is_ok());
+        assert!(gte.enforce_eq(1).is_ok());
+    }
+
     #[test]
     fn ub_gte_multiplication() {
         let mut gte1 = DbGte::default();
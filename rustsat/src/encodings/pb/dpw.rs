@@ -8,6 +8,10 @@
 //! encoding resets the entire encoding and with the next encode and entirely
 //! new encoding will be returned.
 //!
+//! Lower bounds are encoded as upper bounds on the negated input literals in a
+//! separate structure, since the tares of one structure can not be used to
+//! enforce an upper and a lower bound at the same time.
+//!
 //! ## References
 //!
 //! - \[1\] Tobias Paxian and Sven Reimer and Bernd Becker: _Dynamic Polynomial
@@ -17,7 +21,7 @@ use std::{
     cmp,
     collections::BTreeMap,
     num::{NonZeroU8, NonZeroUsize},
-    ops::RangeBounds,
+    ops::{Range, RangeBounds},
 };
 
 use crate::{
@@ -26,15 +30,17 @@ use crate::{
         atomics,
         card::dbtotalizer::{GeneralNode, INode, LitData, Node, TotDb, UnitNode},
         nodedb::{NodeById, NodeCon, NodeId, NodeLike},
-        CollectClauses, EncodeStats, Error, IterWeightedInputs,
+        CollectClauses, EncodeStats, EncodingStats, Error, IterWeightedInputs, PredictStats,
     },
-    instances::ManageVars,
+    instances::{BasicVarManager, ManageVars},
     lit,
-    types::{Lit, RsHashMap},
+    types::{Lit, RsHashMap, RsHashSet},
     utils,
 };
 
-use super::{BoundUpper, BoundUpperIncremental, Encode, EncodeIncremental};
+use super::{
+    BoundLower, BoundLowerIncremental, BoundUpper, BoundUpperIncremental, Encode, EncodeIncremental,
+};
 
 type WeightQ = BTreeMap<usize, Vec<NodeCon>>;
 
@@ -57,6 +63,13 @@ pub enum PrecisionError {
 /// encoding resets the entire encoding and with the next encode and entirely
 /// new encoding will be returned.
 ///
+/// **Note**: lower bounds are encoded as upper bounds on the negated input
+/// literals (`sum of lits >= lb` iff `sum of negated lits <= weight_sum - lb`)
+/// in a second, internal DPW that is built when lower bounding is first
+/// requested. Using both upper and lower bounds therefore roughly doubles the
+/// size of the encoding. Both structures are always extended to the same
+/// precision, so the precision API refers to both of them.
+///
 /// ## References
 ///
 /// - \[1\] Tobias Paxian and Sven Reimer and Bernd Becker: _Dynamic Polynomial
@@ -73,6 +86,8 @@ pub struct DynamicPolyWatchdog {
     prec_div: usize,
     /// Sum of all input weight
     weight_sum: usize,
+    /// The DPW over the negated input literals used for lower bounding, if built
+    lb_dpw: Option<Box<DynamicPolyWatchdog>>,
     /// The number of variables
     n_vars: u32,
     /// The number of clauses
@@ -82,19 +97,35 @@ pub struct DynamicPolyWatchdog {
 }
 
 impl DynamicPolyWatchdog {
-    /// Gets the maximum depth of the tree
+    /// Gets the maximum depth of the tree, considering the structures for
+    /// both upper and lower bounding
     pub fn depth(&self) -> usize {
-        match &self.structure {
+        let depth = match &self.structure {
             Some(structure) => self.db[structure.root()].depth(),
             None => 0,
+        };
+        match &self.lb_dpw {
+            Some(lb_dpw) => cmp::max(depth, lb_dpw.depth()),
+            None => depth,
+        }
+    }
+
+    /// Gets the sum of all input weights at the current precision, i.e., the
+    /// sum of all input weights divided by the precision divisor
+    fn prec_weight_sum(&self) -> usize {
+        if self.prec_div <= 1 {
+            return self.weight_sum;
         }
+        self.in_lits
+            .values()
+            .fold(0, |sum, w| sum + w / self.prec_div)
     }
 
     /// Helper for the C-API to add input literals to an already existing object. Errors if the
     /// object is already encoded.
     #[cfg(feature = "internals")]
     pub fn add_input(&mut self, lit: Lit, weight: usize) -> Result<(), crate::NotAllowed> {
-        if self.structure.is_some() {
+        if self.structure.is_some() || self.lb_dpw.is_some() {
             return Err(crate::NotAllowed(
                 "cannot add inputs after building the encoding",
             ));
@@ -131,6 +162,9 @@ impl DynamicPolyWatchdog {
         if self.structure.is_some() && divisor > self.prec_div {
             return Err(PrecisionError::PrecisionDecreased);
         }
+        if let Some(lb_dpw) = &mut self.lb_dpw {
+            lb_dpw.set_precision(divisor)?;
+        }
         self.prec_div = divisor;
         Ok(())
     }
@@ -142,16 +176,72 @@ impl DynamicPolyWatchdog {
     /// calling `set_precision` and then encoding will produce the smalles non-empty next segment
     /// of the encoding.
     pub fn next_precision(&self) -> usize {
-        if self.weight_queue.is_empty() {
-            return 1;
+        let weight_queue = &self.encoded().weight_queue;
+        if weight_queue.is_empty() {
+            1
+        } else {
+            let digits = utils::digits(*weight_queue.iter().next_back().unwrap().0, 2) as usize;
+            1 << (digits - 1)
         }
-        let digits = utils::digits(*self.weight_queue.iter().next_back().unwrap().0, 2) as usize;
-        1 << (digits - 1)
     }
 
     /// Checks whether the encoding is already at the maximum precision
     pub fn is_max_precision(&self) -> bool {
-        self.weight_queue.is_empty()
+        self.encoded().weight_queue.is_empty()
+    }
+
+    /// Gets the DPW that determines the encoded precision. Since the
+    /// structures for upper and lower bounding are always extended to the
+    /// same precision, this is the one for lower bounding only if upper
+    /// bounding has not been encoded.
+    fn encoded(&self) -> &DynamicPolyWatchdog {
+        match (&self.structure, &self.lb_dpw) {
+            (None, Some(lb_dpw)) => lb_dpw,
+            _ => self,
+        }
+    }
+
+    /// Builds the structure at the current precision or extends it if the
+    /// precision has been increased, without encoding any outputs
+    fn extend_structure<Col>(
+        &mut self,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        if self.in_lits.len() <= 1 {
+            return Ok(());
+        }
+        let n_vars_before = var_manager.n_used();
+        let n_clauses_before = collector.n_clauses();
+        if let Some(structure) = &mut self.structure {
+            if !self.weight_queue.is_empty()
+                && self.weight_queue.iter().next_back().unwrap().0 >= &self.prec_div
+            {
+                // precision has been increased, need to extend encoding
+                let new_struct = build_structure(
+                    &mut self.weight_queue,
+                    self.prec_div,
+                    false,
+                    &mut self.db,
+                    var_manager,
+                );
+                merge_structures(structure, new_struct, &mut self.db, collector, var_manager)?;
+            }
+        } else {
+            self.structure = Some(build_structure(
+                &mut self.weight_queue,
+                self.prec_div,
+                true,
+                &mut self.db,
+                var_manager,
+            ));
+        }
+        self.n_clauses += collector.n_clauses() - n_clauses_before;
+        self.n_vars += var_manager.n_used() - n_vars_before;
+        Ok(())
     }
 
     /// Given a range of output values to limit the encoding to, returns additional clauses that
@@ -209,8 +299,70 @@ impl DynamicPolyWatchdog {
                 }
             }
         };
+        if let Some(lb_dpw) = &self.lb_dpw {
+            // values of the negated inputs are mirrored
+            if !range.is_empty() {
+                let mirror = self.weight_sum + 1;
+                lb_dpw.limit_range(mirror - range.end..mirror - range.start, collector)?;
+            }
+        }
         Ok(())
     }
+
+    /// Predicts the size of a fresh encoding of an already prepared upper
+    /// bound range. The structure is built in a scratch database, since the
+    /// tares and shared substructures depend on the entire structure.
+    fn predict_ub_range(&self, range: Range<usize>) -> EncodingStats {
+        if range.is_empty() || self.in_lits.len() <= 1 {
+            return EncodingStats::default();
+        }
+        let mut db = TotDb::default();
+        let mut weight_queue = lit_weight_queue(self.in_lits.iter().map(copy_key_val), &mut db);
+        let mut var_manager = BasicVarManager::default();
+        let structure = build_structure(
+            &mut weight_queue,
+            self.prec_div,
+            true,
+            &mut db,
+            &mut var_manager,
+        );
+        let mut stats = EncodingStats {
+            n_clauses: 0,
+            n_vars: var_manager.n_used(),
+        };
+        let output_weight = 1 << (structure.output_power());
+        let max_oidx = db[structure.root()].max_val();
+        let mut defined = RsHashSet::default();
+        for oidx in range.start / output_weight..(range.end - 1) / output_weight + 1 {
+            if oidx < max_oidx {
+                stats += db.predict_pos_tot(structure.root(), oidx, &mut defined);
+            }
+        }
+        stats
+    }
+}
+
+impl PredictStats for DynamicPolyWatchdog {
+    fn predict_ub<R>(&self, range: R) -> EncodingStats
+    where
+        R: RangeBounds<usize>,
+    {
+        self.predict_ub_range(super::prepare_ub_range(self, range))
+    }
+
+    fn predict_lb<R>(&self, range: R) -> EncodingStats
+    where
+        R: RangeBounds<usize>,
+    {
+        let range = super::prepare_lb_range(self, range);
+        if range.is_empty() {
+            return EncodingStats::default();
+        }
+        // The DPW over the negated input literals has the same structure
+        let mirror = self.prec_weight_sum() + 1;
+        let ub_range = mirror.saturating_sub(range.end)..mirror.saturating_sub(range.start);
+        self.predict_ub_range(super::prepare_ub_range(self, ub_range))
+    }
 }
 
 /// Type containing information about the DPW encoding structure
@@ -259,6 +411,9 @@ impl EncodeIncremental for DynamicPolyWatchdog {
         if let Some(structure) = &self.structure {
             self.db.reserve_vars(structure.root(), var_manager);
         }
+        if let Some(lb_dpw) = &mut self.lb_dpw {
+            lb_dpw.reserve(var_manager);
+        }
     }
 }
 
@@ -342,56 +497,109 @@ impl BoundUpperIncremental for DynamicPolyWatchdog {
         if range.is_empty() || self.in_lits.len() <= 1 {
             return Ok(());
         }
-        let n_vars_before = var_manager.n_used();
-        let mut fresh = false;
-        if self.structure.is_none() && !self.in_lits.is_empty() {
-            fresh = true;
-            self.structure = Some(build_structure(
-                &mut self.weight_queue,
-                self.prec_div,
-                true,
-                &mut self.db,
-                var_manager,
-            ))
+        self.extend_structure(collector, var_manager)?;
+        if let Some(lb_dpw) = &mut self.lb_dpw {
+            // keep the lower bounding structure at the same precision
+            lb_dpw.extend_structure(collector, var_manager)?;
         }
-        match &mut self.structure {
-            Some(structure) => {
-                let n_clauses_before = collector.n_clauses();
-                if !fresh
-                    && !self.weight_queue.is_empty()
-                    && self.weight_queue.iter().next_back().unwrap().0 >= &self.prec_div
-                {
-                    // precision has been increased, need to extend encoding
-                    let new_struct = build_structure(
-                        &mut self.weight_queue,
-                        self.prec_div,
-                        false,
-                        &mut self.db,
-                        var_manager,
-                    );
-                    merge_structures(structure, new_struct, &mut self.db, collector, var_manager)?;
-                }
-                let output_weight = 1 << (structure.output_power());
-                let output_range = range.start / output_weight..(range.end - 1) / output_weight + 1;
-                for oidx in output_range {
-                    encode_output(structure, oidx, &mut self.db, collector, var_manager)?;
-                }
-                self.n_clauses += collector.n_clauses() - n_clauses_before;
-                self.n_vars += var_manager.n_used() - n_vars_before;
+        if let Some(structure) = &self.structure {
+            let n_vars_before = var_manager.n_used();
+            let n_clauses_before = collector.n_clauses();
+            let output_weight = 1 << (structure.output_power());
+            let output_range = range.start / output_weight..(range.end - 1) / output_weight + 1;
+            for oidx in output_range {
+                encode_output(structure, oidx, &mut self.db, collector, var_manager)?;
             }
-            None => (),
-        };
+            self.n_clauses += collector.n_clauses() - n_clauses_before;
+            self.n_vars += var_manager.n_used() - n_vars_before;
+        }
         Ok(())
     }
 }
 
+impl BoundLower for DynamicPolyWatchdog {
+    fn encode_lb<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        if let Some(lb_dpw) = &mut self.lb_dpw {
+            lb_dpw.db.reset_encoded();
+        }
+        self.encode_lb_change(range, collector, var_manager)
+    }
+
+    fn enforce_lb(&self, lb: usize) -> Result<Vec<Lit>, Error> {
+        if lb == 0 {
+            return Ok(vec![]);
+        }
+        let weight_sum = self.prec_weight_sum();
+        if lb > weight_sum {
+            return Err(Error::Unsat);
+        }
+        match &self.lb_dpw {
+            Some(lb_dpw) => lb_dpw.enforce_ub(weight_sum - lb),
+            None => Err(Error::NotEncoded),
+        }
+    }
+
+    fn coarse_lb(&self, lb: usize) -> usize {
+        let weight_sum = self.prec_weight_sum();
+        match &self.lb_dpw {
+            Some(lb_dpw) if lb <= weight_sum => weight_sum - lb_dpw.coarse_ub(weight_sum - lb),
+            _ => lb,
+        }
+    }
+}
+
+impl BoundLowerIncremental for DynamicPolyWatchdog {
+    fn encode_lb_change<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        let range = super::prepare_lb_range(self, range);
+        if range.is_empty() {
+            return Ok(());
+        }
+        // `sum of lits >= lb` is encoded as `sum of negated lits <= weight_sum - lb`
+        let mirror = self.prec_weight_sum() + 1;
+        let ub_range = mirror.saturating_sub(range.end)..mirror.saturating_sub(range.start);
+        if ub_range.is_empty() {
+            return Ok(());
+        }
+        if self.structure.is_some() {
+            // keep the upper bounding structure at the same precision
+            self.extend_structure(collector, var_manager)?;
+        }
+        let prec_div = self.prec_div;
+        let lb_dpw = self.lb_dpw.get_or_insert_with(|| {
+            let mut lb_dpw: Box<DynamicPolyWatchdog> =
+                Box::new(self.in_lits.iter().map(|(&l, &w)| (!l, w)).collect());
+            lb_dpw.prec_div = prec_div;
+            lb_dpw
+        });
+        lb_dpw.encode_ub_change(ub_range, collector, var_manager)
+    }
+}
+
 impl EncodeStats for DynamicPolyWatchdog {
     fn n_clauses(&self) -> usize {
-        self.n_clauses
+        self.n_clauses + self.lb_dpw.as_ref().map_or(0, |lb_dpw| lb_dpw.n_clauses())
     }
 
     fn n_vars(&self) -> u32 {
-        self.n_vars
+        self.n_vars + self.lb_dpw.as_ref().map_or(0, |lb_dpw| lb_dpw.n_vars())
     }
 }
 
@@ -406,6 +614,7 @@ impl From<RsHashMap<Lit, usize>> for DynamicPolyWatchdog {
             weight_queue,
             prec_div: 1,
             weight_sum,
+            lb_dpw: None,
             n_vars: Default::default(),
             n_clauses: Default::default(),
             db,
@@ -631,7 +840,10 @@ type DpwIter<'a> = std::iter::Map<
 
 /// Builds a DPW [`Structure`] over weighted input literals
 #[cfg_attr(feature = "internals", visibility::make(pub))]
-fn lit_weight_queue<LI: Iterator<Item = (Lit, usize)>>(lits: LI, tot_db: &mut TotDb) -> WeightQ {
+pub(super) fn lit_weight_queue<LI: Iterator<Item = (Lit, usize)>>(
+    lits: LI,
+    tot_db: &mut TotDb,
+) -> WeightQ {
     let lit_to_con = |(lit, weight)| {
         let node = tot_db.insert(Node::leaf(lit));
         NodeCon::weighted(node, weight)
@@ -664,7 +876,7 @@ fn con_weight_queue<CI: Iterator<Item = NodeCon>>(cons: CI) -> WeightQ {
 
 /// Builds a DPW [`Structure`] from up to a given `precision` from a `weight_queue`
 #[cfg_attr(feature = "internals", visibility::make(pub))]
-fn build_structure(
+pub(super) fn build_structure(
     weight_queue: &mut WeightQ,
     prec_div: usize,
     topmost: bool,
@@ -891,7 +1103,7 @@ where
             .iter()
             .enumerate()
             .filter_map(|(idx, litdat)| {
-                if let &LitData::Lit { lit, enc_pos } = litdat {
+                if let &LitData::Lit { lit, enc_pos, .. } = litdat {
                     if enc_pos && idx + 1 >= old_right_max {
                         return Some((lit, idx + 1));
                     }
@@ -931,7 +1143,7 @@ where
 
 /// Encodes an output of the DPW [`Structure`]
 #[cfg_attr(feature = "internals", visibility::make(pub))]
-fn encode_output<Col>(
+pub(super) fn encode_output<Col>(
     dpw: &Structure,
     oidx: usize,
     tot_db: &mut TotDb,
@@ -950,7 +1162,7 @@ where
 
 /// Enforces an upper bound value on a DPW [`Structure`]
 #[cfg_attr(feature = "internals", visibility::make(pub))]
-fn enforce_ub(dpw: &Structure, ub: usize, tot_db: &TotDb) -> Result<Vec<Lit>, Error> {
+pub(super) fn enforce_ub(dpw: &Structure, ub: usize, tot_db: &TotDb) -> Result<Vec<Lit>, Error> {
     let output_weight = 1 << (dpw.output_power());
     let oidx = ub / output_weight;
     if oidx >= tot_db[dpw.root()].max_val() {
@@ -984,8 +1196,8 @@ fn enforce_ub(dpw: &Structure, ub: usize, tot_db: &TotDb) -> Result<Vec<Lit>, Er
 mod tests {
     use crate::{
         encodings::{
-            pb::{BoundUpper, BoundUpperIncremental},
-            EncodeStats,
+            pb::{BoundLower, BoundLowerIncremental, BoundUpper, BoundUpperIncremental},
+            EncodeStats, EncodingStats, Error, PredictStats,
         },
         instances::{BasicVarManager, Cnf},
         lit,
@@ -1080,6 +1292,132 @@ mod tests {
         }
     }
 
+    #[test]
+    fn basic_lb() {
+        let mut lits = RsHashMap::default();
+        lits.insert(lit![0], 1);
+        lits.insert(lit![1], 1);
+        lits.insert(lit![2], 2);
+        lits.insert(lit![3], 2);
+        let mut dpw = DynamicPolyWatchdog::from(lits);
+        assert_eq!(dpw.enforce_lb(2), Err(Error::NotEncoded));
+        assert_eq!(dpw.enforce_lb(7), Err(Error::Unsat));
+        let mut var_manager = BasicVarManager::from_next_free(Var::new(4));
+        let mut cnf = Cnf::new();
+        dpw.encode_lb(0..=6, &mut cnf, &mut var_manager).unwrap();
+        assert_eq!(dpw.n_vars(), 9);
+        assert_eq!(cnf.len(), 13);
+        assert!(dpw.enforce_lb(0).unwrap().is_empty());
+        for lb in 1..=6 {
+            assert!(!dpw.enforce_lb(lb).unwrap().is_empty());
+        }
+    }
+
+    #[test]
+    fn single_lit_lb() {
+        let mut lits = RsHashMap::default();
+        lits.insert(lit![0], 4);
+        let mut dpw = DynamicPolyWatchdog::from(lits);
+        let mut var_manager = BasicVarManager::from_next_free(Var::new(1));
+        let mut cnf = Cnf::new();
+        dpw.encode_lb(0..=6, &mut cnf, &mut var_manager).unwrap();
+        assert_eq!(dpw.n_vars(), 0);
+        assert_eq!(cnf.len(), 0);
+        debug_assert!(dpw.enforce_lb(0).unwrap().is_empty());
+        let assumps = dpw.enforce_lb(2).unwrap();
+        debug_assert_eq!(assumps, vec![lit![0]]);
+        let assumps = dpw.enforce_lb(4).unwrap();
+        debug_assert_eq!(assumps, vec![lit![0]]);
+    }
+
+    #[test]
+    fn coarse_convergence_lb() {
+        let mut lits = RsHashMap::default();
+        lits.insert(lit![0], 5);
+        lits.insert(lit![1], 3);
+        lits.insert(lit![2], 8);
+        lits.insert(lit![3], 7);
+        let mut dpw = DynamicPolyWatchdog::from(lits);
+        let mut var_manager = BasicVarManager::default();
+        let mut cnf = Cnf::new();
+        dpw.encode_lb(1..=23, &mut cnf, &mut var_manager).unwrap();
+        for lb in 1..17 {
+            let coarse_lb = dpw.coarse_lb(lb);
+            debug_assert!(coarse_lb >= lb);
+            if lb % 8 == 0 {
+                debug_assert_eq!(coarse_lb, lb);
+            }
+            let assumps = dpw.enforce_lb(coarse_lb).unwrap();
+            debug_assert_eq!(assumps.len(), 1);
+        }
+    }
+
+    #[test]
+    fn incremental_precision_lb() {
+        let mut lits = RsHashMap::default();
+        lits.insert(lit![0], 5);
+        lits.insert(lit![1], 3);
+        lits.insert(lit![2], 8);
+        lits.insert(lit![3], 7);
+        let mut dpw = DynamicPolyWatchdog::from(lits);
+        let mut var_manager = BasicVarManager::default();
+        for prec in [8, 4, 2, 1] {
+            debug_assert_eq!(dpw.next_precision(), prec);
+            dpw.set_precision(prec).unwrap();
+            let mut cnf = Cnf::new();
+            dpw.encode_lb_change(1..=4, &mut cnf, &mut var_manager)
+                .unwrap();
+            debug_assert!(!cnf.is_empty());
+        }
+        debug_assert!(dpw.is_max_precision());
+        debug_assert_eq!(dpw.next_precision(), 1);
+        debug_assert_eq!(
+            dpw.set_precision(2),
+            Err(super::PrecisionError::PrecisionDecreased)
+        );
+    }
+
+    #[test]
+    fn incremental_precision_mixed() {
+        let mut lits = RsHashMap::default();
+        lits.insert(lit![0], 5);
+        lits.insert(lit![1], 3);
+        lits.insert(lit![2], 8);
+        lits.insert(lit![3], 7);
+        let mut dpw = DynamicPolyWatchdog::from(lits);
+        let mut var_manager = BasicVarManager::default();
+        let mut cnf = Cnf::new();
+        debug_assert_eq!(dpw.next_precision(), 8);
+        dpw.set_precision(8).unwrap();
+        dpw.encode_ub_change(0..=2, &mut cnf, &mut var_manager)
+            .unwrap();
+        debug_assert_eq!(dpw.next_precision(), 4);
+        dpw.set_precision(4).unwrap();
+        // lower bounding also extends the upper bounding structure
+        dpw.encode_lb_change(1..=4, &mut cnf, &mut var_manager)
+            .unwrap();
+        debug_assert_eq!(dpw.next_precision(), 2);
+        debug_assert!(!dpw.is_max_precision());
+        dpw.set_precision(2).unwrap();
+        // upper bounding also extends the lower bounding structure
+        dpw.encode_ub_change(0..=4, &mut cnf, &mut var_manager)
+            .unwrap();
+        debug_assert_eq!(dpw.next_precision(), 1);
+        let n_vars = dpw.n_vars();
+        dpw.set_precision(1).unwrap();
+        dpw.encode_lb_change(1..=4, &mut cnf, &mut var_manager)
+            .unwrap();
+        debug_assert!(dpw.n_vars() > n_vars);
+        debug_assert!(dpw.is_max_precision());
+        debug_assert_eq!(dpw.next_precision(), 1);
+        debug_assert!(dpw.enforce_ub(4).is_ok());
+        debug_assert!(dpw.enforce_lb(4).is_ok());
+        debug_assert_eq!(
+            dpw.set_precision(2),
+            Err(super::PrecisionError::PrecisionDecreased)
+        );
+    }
+
     #[test]
     fn incremental_precision() {
         let mut lits = RsHashMap::default();
@@ -1326,4 +1664,56 @@ mod tests {
         dpw.limit_range(1..4, &mut hardened).unwrap();
         assert_eq!(hardened.len(), 2);
     }
+
+    #[test]
+    fn predict_stats() {
+        let weight_sets: [&[usize]; 5] = [
+            &[1, 1, 2, 2],
+            &[1, 2, 3, 4, 5],
+            &[5, 3, 8, 2],
+            &[7, 7, 3, 3, 1],
+            &[10, 1, 20, 5, 5, 2],
+        ];
+        for weights in weight_sets {
+            let lits: RsHashMap<_, _> = weights
+                .iter()
+                .enumerate()
+                .map(|(idx, &w)| (lit![idx as u32], w))
+                .collect();
+            let weight_sum: usize = weights.iter().sum();
+            for start in 0..=weight_sum + 1 {
+                for end in start..=weight_sum + 2 {
+                    let mut dpw = DynamicPolyWatchdog::from(lits.clone());
+                    let pred = dpw.predict_ub(start..end);
+                    let mut var_manager =
+                        BasicVarManager::from_next_free(Var::new(weights.len() as u32));
+                    let mut cnf = Cnf::new();
+                    dpw.encode_ub(start..end, &mut cnf, &mut var_manager)
+                        .unwrap();
+                    assert_eq!(
+                        pred,
+                        EncodingStats {
+                            n_clauses: cnf.len(),
+                            n_vars: dpw.n_vars()
+                        }
+                    );
+
+                    let mut dpw = DynamicPolyWatchdog::from(lits.clone());
+                    let pred = dpw.predict_lb(start..end);
+                    let mut var_manager =
+                        BasicVarManager::from_next_free(Var::new(weights.len() as u32));
+                    let mut cnf = Cnf::new();
+                    dpw.encode_lb(start..end, &mut cnf, &mut var_manager)
+                        .unwrap();
+                    assert_eq!(
+                        pred,
+                        EncodingStats {
+                            n_clauses: cnf.len(),
+                            n_vars: dpw.n_vars()
+                        }
+                    );
+                }
+            }
+        }
+    }
 }
@@ -8,23 +8,28 @@
 //!
 //! - \[1\] Saurabh Joshi and Ruben Martins and Vasco Manquinho: _Generalized Totalizer Encoding for Pseudo-Boolean Constraints_, CP 2015.
 
-use super::{BoundUpper, BoundUpperIncremental, Encode, EncodeIncremental, Error};
+use super::{
+    BoundLower, BoundLowerIncremental, BoundUpper, BoundUpperIncremental, Encode,
+    EncodeIncremental, Error,
+};
 use crate::{
-    encodings::{atomics, CollectClauses, EncodeStats, IterWeightedInputs},
+    clause,
+    encodings::{
+        atomics, CollectClauses, EncodeStats, EncodingStats, IterWeightedInputs, PredictStats,
+    },
     instances::ManageVars,
-    types::{Lit, RsHashMap},
+    types::{constraints::PBLBConstr, Lit, RsHashMap},
 };
 use std::{
     cmp,
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     ops::{Range, RangeBounds},
 };
 
 /// Implementation of the binary adder tree generalized totalizer encoding
 /// \[1\]. The implementation is incremental. The implementation is recursive.
-/// This encoding only support upper bounding. Lower bounding can be achieved by
-/// negating the input literals. This is implemented in
-/// [`super::simulators::Inverted`].
+/// Upper and lower bounds are encoded on the same tree and share the output
+/// literals.
 ///
 /// # References
 ///
@@ -42,6 +47,9 @@ pub struct GeneralizedTotalizer {
     max_leaf_weight: usize,
     /// Sum of all input weight
     weight_sum: usize,
+    /// The range of upper bounds that has been encoded, needed to re-encode
+    /// upper bounds when the tree is extended for lower bounding
+    ub_range: Range<usize>,
     /// The number of variables in the GTE
     n_vars: u32,
     /// The number of clauses in the GTE
@@ -99,6 +107,58 @@ impl GeneralizedTotalizer {
         }
     }
 
+    /// Extends the tree with all remaining input literals, as required for
+    /// lower bounding. Since literals that were too heavy for the encoded
+    /// upper bounds are added to the tree, the upper bounds are re-encoded at
+    /// the new root.
+    fn extend_tree_lb<Col>(
+        &mut self,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        if self.lit_buffer.is_empty() {
+            return Ok(());
+        }
+        self.extend_tree(usize::MAX);
+        if self.ub_range.is_empty() {
+            return Ok(());
+        }
+        if let Some(root) = self.root.as_mut() {
+            root.rec_encode_change(
+                self.ub_range.start + 1..self.ub_range.end + self.max_leaf_weight,
+                collector,
+                var_manager,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Gets the weights of the buffered literals that [`Self::extend_tree`]
+    /// adds to the tree for a given maximum weight, in the order they are added
+    fn extension_weights(&self, max_weight: usize) -> Vec<usize> {
+        let mut weights: Vec<usize> = self
+            .lit_buffer
+            .values()
+            .copied()
+            .filter(|&w| w <= max_weight)
+            .collect();
+        weights.sort_unstable();
+        weights
+    }
+
+    /// Gets the shape of the tree after extending it with literals of the given weights
+    fn shape<'a>(&'a self, weights: &'a [usize]) -> Option<Shape<'a>> {
+        match &self.root {
+            None if weights.is_empty() => None,
+            None => Some(Shape::Weights(weights)),
+            Some(root) if weights.is_empty() => Some(Shape::Built(root)),
+            Some(root) => Some(Shape::Extended(root, weights)),
+        }
+    }
+
     /// Gets the maximum depth of the tree
     pub fn depth(&mut self) -> usize {
         self.root.as_ref().map_or(0, |node| node.depth())
@@ -171,6 +231,7 @@ impl BoundUpper for GeneralizedTotalizer {
         let n_vars_before = var_manager.n_used();
         let n_clauses_before = collector.n_clauses();
         self.extend_tree(range.end - 1);
+        self.ub_range = extend_range(&self.ub_range, &range);
         match &mut self.root {
             None => (),
             Some(root) => root.rec_encode(
@@ -254,6 +315,7 @@ impl BoundUpperIncremental for GeneralizedTotalizer {
         let n_vars_before = var_manager.n_used();
         let n_clauses_before = collector.n_clauses();
         self.extend_tree(range.end - 1);
+        self.ub_range = extend_range(&self.ub_range, &range);
         if let Some(root) = self.root.as_mut() {
             root.rec_encode_change(
                 range.start + 1..range.end + self.max_leaf_weight,
@@ -267,6 +329,140 @@ impl BoundUpperIncremental for GeneralizedTotalizer {
     }
 }
 
+impl BoundLower for GeneralizedTotalizer {
+    fn encode_lb<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        let range = super::prepare_lb_range(self, range);
+        if range.is_empty() {
+            return Ok(());
+        };
+        let n_vars_before = var_manager.n_used();
+        let n_clauses_before = collector.n_clauses();
+        // All input literals are relevant for lower bounding
+        self.extend_tree_lb(collector, var_manager)?;
+        match &mut self.root {
+            None => (),
+            Some(root) => root.rec_encode_lb(
+                range.start..range.end + self.max_leaf_weight - 1,
+                self.max_leaf_weight,
+                collector,
+                var_manager,
+            )?,
+        };
+        self.n_clauses += collector.n_clauses() - n_clauses_before;
+        self.n_vars += var_manager.n_used() - n_vars_before;
+        Ok(())
+    }
+
+    fn enforce_lb(&self, lb: usize) -> Result<Vec<Lit>, Error> {
+        if lb > self.weight_sum {
+            return Err(Error::Unsat);
+        }
+        if lb == 0 {
+            return Ok(vec![]);
+        }
+        if !self.lit_buffer.is_empty() {
+            return Err(Error::NotEncoded);
+        }
+        match &self.root {
+            None => Err(Error::NotEncoded),
+            Some(root_node) => match &root_node {
+                Node::Leaf { lit, .. } => Ok(vec![*lit]),
+                Node::Internal {
+                    out_lits,
+                    lb_enc_range,
+                    ..
+                } => {
+                    // The lowest output value that is at least `lb`
+                    match out_lits.range(lb..).next() {
+                        Some((val, &olit))
+                            if lb_enc_range.contains(&lb) && lb_enc_range.contains(val) =>
+                        {
+                            Ok(vec![olit])
+                        }
+                        _ => Err(Error::NotEncoded),
+                    }
+                }
+            },
+        }
+    }
+
+    /// Encodes a single lower bound constraint as an upper bound on the
+    /// negated input literals, since for a fixed bound this requires fewer
+    /// output literals than the native lower bounding encoding
+    fn encode_lb_constr<Col>(
+        constr: PBLBConstr,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> anyhow::Result<()>
+    where
+        Col: CollectClauses,
+        Self: FromIterator<(Lit, usize)> + Sized,
+    {
+        let (lits, lb) = constr.decompose();
+        let lb = if lb < 0 {
+            return Ok(()); // tautology
+        } else {
+            lb as usize
+        };
+        let weight_sum = lits.iter().fold(0, |sum, (_, w)| sum + w);
+        if lb > weight_sum {
+            anyhow::bail!(Error::Unsat);
+        }
+        let ub = weight_sum - lb;
+        let mut enc: GeneralizedTotalizer = lits.into_iter().map(|(l, w)| (!l, w)).collect();
+        enc.encode_ub(ub..ub + 1, collector, var_manager)?;
+        collector.extend_clauses(
+            enc.enforce_ub(ub)
+                .unwrap()
+                .into_iter()
+                .map(|unit| clause![unit]),
+        )?;
+        Ok(())
+    }
+}
+
+impl BoundLowerIncremental for GeneralizedTotalizer {
+    fn encode_lb_change<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        let range = super::prepare_lb_range(self, range);
+        if range.is_empty() {
+            return Ok(());
+        };
+        let n_vars_before = var_manager.n_used();
+        let n_clauses_before = collector.n_clauses();
+        // All input literals are relevant for lower bounding
+        self.extend_tree_lb(collector, var_manager)?;
+        if let Some(root) = self.root.as_mut() {
+            root.rec_encode_lb_change(
+                range.start..range.end + self.max_leaf_weight - 1,
+                self.max_leaf_weight,
+                collector,
+                var_manager,
+            )?;
+        }
+        self.n_clauses += collector.n_clauses() - n_clauses_before;
+        self.n_vars += var_manager.n_used() - n_vars_before;
+        Ok(())
+    }
+}
+
 impl EncodeStats for GeneralizedTotalizer {
     fn n_clauses(&self) -> usize {
         self.n_clauses
@@ -277,6 +473,59 @@ impl EncodeStats for GeneralizedTotalizer {
     }
 }
 
+impl PredictStats for GeneralizedTotalizer {
+    fn predict_ub<R>(&self, range: R) -> EncodingStats
+    where
+        R: RangeBounds<usize>,
+    {
+        let range = super::prepare_ub_range(self, range);
+        if range.is_empty() {
+            return EncodingStats::default();
+        }
+        let weights = self.extension_weights(range.end - 1);
+        let max_leaf_weight = weights
+            .last()
+            .map_or(self.max_leaf_weight, |&w| cmp::max(w, self.max_leaf_weight));
+        self.shape(&weights)
+            .map_or_else(EncodingStats::default, |shape| {
+                shape
+                    .predict(range.start + 1..range.end + max_leaf_weight + 1)
+                    .0
+            })
+    }
+
+    fn predict_lb<R>(&self, range: R) -> EncodingStats
+    where
+        R: RangeBounds<usize>,
+    {
+        let range = super::prepare_lb_range(self, range);
+        if range.is_empty() {
+            return EncodingStats::default();
+        }
+        let weights = self.extension_weights(usize::MAX);
+        let max_leaf_weight = weights
+            .last()
+            .map_or(self.max_leaf_weight, |&w| cmp::max(w, self.max_leaf_weight));
+        self.shape(&weights)
+            .map_or_else(EncodingStats::default, |shape| {
+                shape
+                    .predict_lb(
+                        range.start..range.end + max_leaf_weight - 1,
+                        max_leaf_weight,
+                    )
+                    .0
+            })
+    }
+}
+
+/// Gets the smallest range containing both given ranges
+pub(super) fn extend_range(a: &Range<usize>, b: &Range<usize>) -> Range<usize> {
+    if a.is_empty() {
+        return b.clone();
+    }
+    cmp::min(a.start, b.start)..cmp::max(a.end, b.end)
+}
+
 pub(super) fn copy_key_val(key_val_refs: (&Lit, &usize)) -> (Lit, usize) {
     (*key_val_refs.0, *key_val_refs.1)
 }
@@ -286,7 +535,9 @@ pub(super) type GteIter<'a> = std::iter::Map<
 >;
 
 impl From<RsHashMap<Lit, usize>> for GeneralizedTotalizer {
-    fn from(lits: RsHashMap<Lit, usize>) -> Self {
+    fn from(mut lits: RsHashMap<Lit, usize>) -> Self {
+        // Zero-weight literals do not contribute to the sum
+        lits.retain(|_, w| *w > 0);
         let weight_sum = lits.iter().fold(0, |sum, (_, w)| sum + *w);
         Self {
             in_lits: lits.clone(),
@@ -307,6 +558,10 @@ impl FromIterator<(Lit, usize)> for GeneralizedTotalizer {
 impl Extend<(Lit, usize)> for GeneralizedTotalizer {
     fn extend<T: IntoIterator<Item = (Lit, usize)>>(&mut self, iter: T) {
         iter.into_iter().for_each(|(l, w)| {
+            if w == 0 {
+                // Zero-weight literals do not contribute to the sum
+                return;
+            }
             self.weight_sum += w;
             // Insert into buffer to be added to tree
             match self.lit_buffer.get_mut(&l) {
@@ -330,13 +585,12 @@ impl Extend<(Lit, usize)> for GeneralizedTotalizer {
 /// be reused in more complex encodings, for using the GTE, this should
 /// not be directly accessed but only through [`GeneralizedTotalizer`].
 ///
-/// The Totalizer nodes are _only_ for upper bounding. Lower bounding in the GTE
-/// is possible by negating input literals. This conversion entirely happens in
-/// the [`super::InvertedGeneralizedTotalizer`] struct. Equally, bounds given on the
-/// encode methods for this type strictly refer to the output literals that
+/// An output literal with value `val` is implied by the weighted sum of the
+/// inputs being at least `val` (upper bounding encoding) and implies that the
+/// weighted sum is at least `val` (lower bounding encoding). Bounds given on
+/// the encode methods for this type strictly refer to the output literals that
 /// should be encoded. Converting right hand sides to required encoded output
-/// literals happens in the [`GeneralizedTotalizer`] or
-/// [`super::InvertedGeneralizedTotalizer`] structs.
+/// literals happens in the [`GeneralizedTotalizer`] struct.
 #[cfg_attr(feature = "internals", visibility::make(pub))]
 enum Node {
     /// A weighted input literal, i.e., a leaf node of the tree
@@ -358,6 +612,8 @@ enum Node {
         max_val: usize,
         /// The encoded range of this node
         enc_range: Range<usize>,
+        /// The encoded range of this node for lower bounding
+        lb_enc_range: Range<usize>,
         /// The left child
         left: Box<Node>,
         /// The right child
@@ -378,6 +634,7 @@ impl Node {
             depth: cmp::max(left.depth() + 1, right.depth() + 1),
             n_clauses: 0,
             enc_range: 0..0,
+            lb_enc_range: 0..0,
             max_val: left.max_val() + right.max_val(),
             left: Box::new(left),
             right: Box::new(right),
@@ -585,6 +842,178 @@ impl Node {
         Ok(())
     }
 
+    /// Encodes the lower bounding direction of the output literals for this
+    /// node in a given range. This requires the output literals of the children
+    /// to be encoded for lower bounding in the ranges computed in
+    /// [`Node::rec_encode_lb`]. This method only produces the encoding and does
+    /// _not_ change any of the stats of the node.
+    fn encode_lb_range<Col>(
+        &mut self,
+        range: Range<usize>,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        let range = self.limit_range(range);
+        if range.is_empty() {
+            return Ok(());
+        }
+
+        // Reserve vars if needed
+        self.reserve_vars_range(range.clone(), var_manager);
+        match &*self {
+            Node::Leaf { .. } => (),
+            Node::Internal {
+                out_lits,
+                left,
+                right,
+                ..
+            } => {
+                let mut left_tmp_map = BTreeMap::new();
+                let mut right_tmp_map = BTreeMap::new();
+                let left_lits = left.lit_map(&mut left_tmp_map);
+                let right_lits = right.lit_map(&mut right_tmp_map);
+                let right_max = right.max_val();
+                for (&val, &olit) in out_lits.range(range) {
+                    // If the left value is too small, the right value can not
+                    // make up for the difference
+                    if val > right_max {
+                        let (_, &left_lit) = left_lits.range(val - right_max..).next().unwrap();
+                        collector.add_clause(atomics::lit_impl_lit(olit, left_lit))?;
+                    }
+                    // If the left value is at most `left_val`, the right value
+                    // must be at least `val - left_val`
+                    let left_min = val.saturating_sub(right_max);
+                    let left_vals = std::iter::once(0)
+                        .filter(|&lval| lval >= left_min)
+                        .chain(left_lits.range(cmp::max(left_min, 1)..val).map(|(&v, _)| v));
+                    for left_val in left_vals {
+                        let (_, &right_lit) = right_lits.range(val - left_val..).next().unwrap();
+                        collector.add_clause(
+                            if let Some((_, &left_lit)) = left_lits.range(left_val + 1..).next() {
+                                atomics::lit_impl_clause(olit, &[left_lit, right_lit])
+                            } else {
+                                atomics::lit_impl_lit(olit, right_lit)
+                            },
+                        )?;
+                    }
+                }
+            }
+        };
+        Ok(())
+    }
+
+    /// Encodes the lower bounding direction of the output literals from the
+    /// children to this node in a given range. Recurses depth first. Always
+    /// encodes the full requested CNF encoding. `max_leaf_weight` needs to be
+    /// an upper bound on the weight of any leaf in the subtree.
+    pub fn rec_encode_lb<Col>(
+        &mut self,
+        range: Range<usize>,
+        max_leaf_weight: usize,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        let range = self.limit_range(range);
+        if range.is_empty() {
+            return Ok(());
+        }
+
+        // Ignore all previous encoding and encode from scratch
+        match self {
+            Node::Leaf { .. } => (),
+            Node::Internal { left, right, .. } => {
+                let (left_range, right_range) = Node::compute_required_lb_enc(
+                    range.clone(),
+                    left.max_val(),
+                    right.max_val(),
+                    max_leaf_weight,
+                );
+                // Recurse
+                left.rec_encode_lb(left_range, max_leaf_weight, collector, var_manager)?;
+                right.rec_encode_lb(right_range, max_leaf_weight, collector, var_manager)?;
+
+                // Encode current node
+                let n_clauses_before = collector.n_clauses();
+                self.encode_lb_range(range.clone(), collector, var_manager)?;
+
+                self.update_lb_stats(range, collector.n_clauses() - n_clauses_before);
+            }
+        };
+
+        Ok(())
+    }
+
+    /// Encodes the lower bounding direction of the output literals from the
+    /// children to this node in a given range. Recurses depth first.
+    /// Incrementally only encodes new clauses. `max_leaf_weight` needs to be
+    /// an upper bound on the weight of any leaf in the subtree.
+    pub fn rec_encode_lb_change<Col>(
+        &mut self,
+        range: Range<usize>,
+        max_leaf_weight: usize,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        let range = self.limit_range(range);
+        if range.is_empty() {
+            return Ok(());
+        }
+
+        match self {
+            Node::Leaf { .. } => (),
+            Node::Internal {
+                left,
+                right,
+                lb_enc_range,
+                ..
+            } => {
+                // Copy to avoid borrow checker
+                let lb_enc_range = lb_enc_range.clone();
+
+                let (left_range, right_range) = Node::compute_required_lb_enc(
+                    range.clone(),
+                    left.max_val(),
+                    right.max_val(),
+                    max_leaf_weight,
+                );
+                // Recurse
+                left.rec_encode_lb_change(left_range, max_leaf_weight, collector, var_manager)?;
+                right.rec_encode_lb_change(right_range, max_leaf_weight, collector, var_manager)?;
+
+                // Encode changes for current node
+                let n_clauses_before = collector.n_clauses();
+                if lb_enc_range.is_empty() {
+                    // First time encoding this node
+                    self.encode_lb_range(range.clone(), collector, var_manager)?;
+                } else {
+                    // Partially encoded
+                    if range.start < lb_enc_range.start {
+                        self.encode_lb_range(
+                            range.start..lb_enc_range.start,
+                            collector,
+                            var_manager,
+                        )?;
+                    };
+                    if range.end > lb_enc_range.end {
+                        self.encode_lb_range(lb_enc_range.end..range.end, collector, var_manager)?;
+                    };
+                };
+
+                self.update_lb_stats(range, collector.n_clauses() - n_clauses_before);
+            }
+        };
+        Ok(())
+    }
+
     /// Reserves variables this node might need in a given range
     fn reserve_vars_range(&mut self, range: Range<usize>, var_manager: &mut dyn ManageVars) {
         let range = self.limit_range(range);
@@ -672,6 +1101,33 @@ impl Node {
         }
     }
 
+    /// Computes the required lower bounding encoding ranges for the children
+    /// of a node given a requested range for the node and the maximum values
+    /// of the children. Since the encoding of a value refers to the next
+    /// higher values of the children, the ranges are extended by the maximum
+    /// leaf weight.
+    fn compute_required_lb_enc(
+        requested_range: Range<usize>,
+        left_max: usize,
+        right_max: usize,
+        max_leaf_weight: usize,
+    ) -> (Range<usize>, Range<usize>) {
+        let extend = |range: Range<usize>| {
+            if range.is_empty() {
+                range
+            } else {
+                range.start..range.end + max_leaf_weight
+            }
+        };
+        (
+            extend(Node::compute_required_min_enc(
+                requested_range.clone(),
+                right_max,
+            )),
+            extend(Node::compute_required_min_enc(requested_range, left_max)),
+        )
+    }
+
     /// Limits a range by the maximum of the node
     fn limit_range(&self, range: Range<usize>) -> Range<usize> {
         match self {
@@ -701,6 +1157,170 @@ impl Node {
             }
         }
     }
+
+    /// Updates the statistics of the node by increasing the number of clauses
+    /// and updating the encoded range for lower bounding
+    fn update_lb_stats(&mut self, new_enc_range: Range<usize>, new_n_clauses: usize) {
+        match self {
+            Node::Leaf { .. } => debug_assert_eq!(new_n_clauses, 0),
+            Node::Internal {
+                n_clauses,
+                lb_enc_range,
+                max_val,
+                ..
+            } => {
+                *n_clauses += new_n_clauses;
+                *lb_enc_range = if (*lb_enc_range).is_empty() {
+                    new_enc_range.start..cmp::min(*max_val + 1, new_enc_range.end)
+                } else {
+                    cmp::min(new_enc_range.start, lb_enc_range.start)
+                        ..cmp::min(*max_val + 1, cmp::max(new_enc_range.end, lb_enc_range.end))
+                };
+            }
+        }
+    }
+}
+
+/// The shape of a (partially) unbuilt subtree, used for predicting the size of
+/// the encoding without building the tree
+#[derive(Clone, Copy)]
+enum Shape<'a> {
+    /// A subtree that is already built
+    Built(&'a Node),
+    /// A subtree that [`GeneralizedTotalizer::build_tree`] will build over
+    /// literals of the given weights
+    Weights(&'a [usize]),
+    /// A built subtree that [`GeneralizedTotalizer::extend_tree`] will extend
+    /// with literals of the given weights
+    Extended(&'a Node, &'a [usize]),
+}
+
+impl<'a> Shape<'a> {
+    fn max_val(self) -> usize {
+        match self {
+            Shape::Built(node) => node.max_val(),
+            Shape::Weights(weights) => weights.iter().sum(),
+            Shape::Extended(node, weights) => node.max_val() + weights.iter().sum::<usize>(),
+        }
+    }
+
+    /// Gets the children of the subtree root, or the weight if it is a leaf
+    fn children(self) -> Result<(Shape<'a>, Shape<'a>), usize> {
+        match self {
+            Shape::Built(Node::Leaf { weight, .. }) => Err(*weight),
+            Shape::Weights(&[weight]) => Err(weight),
+            Shape::Built(Node::Internal { left, right, .. }) => {
+                Ok((Shape::Built(left), Shape::Built(right)))
+            }
+            Shape::Weights(weights) => {
+                let split = weights.len() / 2;
+                Ok((
+                    Shape::Weights(&weights[..split]),
+                    Shape::Weights(&weights[split..]),
+                ))
+            }
+            Shape::Extended(node, weights) => Ok((Shape::Built(node), Shape::Weights(weights))),
+        }
+    }
+
+    /// Predicts the output values and size of [`Node::rec_encode`] for a fresh
+    /// subtree
+    fn predict(self, range: Range<usize>) -> (EncodingStats, BTreeSet<usize>) {
+        let (left, right) = match self.children() {
+            Ok(children) => children,
+            Err(weight) => return (EncodingStats::default(), BTreeSet::from([weight])),
+        };
+        let range = range.start..cmp::min(self.max_val() + 1, range.end);
+        if range.is_empty() {
+            return (EncodingStats::default(), BTreeSet::new());
+        }
+        let (mut stats, left_vals) = left.predict(Node::compute_required_min_enc(
+            range.clone(),
+            right.max_val(),
+        ));
+        let (right_stats, right_vals) = right.predict(Node::compute_required_min_enc(
+            range.clone(),
+            left.max_val(),
+        ));
+        stats += right_stats;
+        // Same as in `Node::encode_range`
+        let (vals, n_sums) = reserved_vals(&left_vals, &right_vals, range.clone());
+        stats += EncodingStats {
+            n_clauses: left_vals.range(range.clone()).count()
+                + right_vals.range(range).count()
+                + n_sums,
+            n_vars: vals.len() as u32,
+        };
+        (stats, vals)
+    }
+
+    /// Predicts the output values and size of [`Node::rec_encode_lb`] for a
+    /// fresh subtree
+    fn predict_lb(
+        self,
+        range: Range<usize>,
+        max_leaf_weight: usize,
+    ) -> (EncodingStats, BTreeSet<usize>) {
+        let (left, right) = match self.children() {
+            Ok(children) => children,
+            Err(weight) => return (EncodingStats::default(), BTreeSet::from([weight])),
+        };
+        let range = range.start..cmp::min(self.max_val() + 1, range.end);
+        if range.is_empty() {
+            return (EncodingStats::default(), BTreeSet::new());
+        }
+        let right_max = right.max_val();
+        let (left_range, right_range) = Node::compute_required_lb_enc(
+            range.clone(),
+            left.max_val(),
+            right_max,
+            max_leaf_weight,
+        );
+        let (mut stats, left_vals) = left.predict_lb(left_range, max_leaf_weight);
+        let (right_stats, right_vals) = right.predict_lb(right_range, max_leaf_weight);
+        stats += right_stats;
+        // Same as in `Node::encode_lb_range`
+        let (vals, _) = reserved_vals(&left_vals, &right_vals, range);
+        let n_clauses = vals
+            .iter()
+            .map(|&val| {
+                let left_min = val.saturating_sub(right_max);
+                usize::from(val > right_max)
+                    + usize::from(left_min == 0)
+                    + left_vals.range(cmp::max(left_min, 1)..val).count()
+            })
+            .sum();
+        stats += EncodingStats {
+            n_clauses,
+            n_vars: vals.len() as u32,
+        };
+        (stats, vals)
+    }
+}
+
+/// Gets the output values that [`Node::reserve_vars_range`] reserves in a
+/// given range for given child values, and the number of child value
+/// combinations summing up to a value in the range
+fn reserved_vals(
+    left_vals: &BTreeSet<usize>,
+    right_vals: &BTreeSet<usize>,
+    range: Range<usize>,
+) -> (BTreeSet<usize>, usize) {
+    let mut vals: BTreeSet<usize> = left_vals.range(range.clone()).copied().collect();
+    vals.extend(right_vals.range(range.clone()));
+    let mut n_sums = 0;
+    if range.end > 1 {
+        for &left_val in left_vals.range(1..range.end - 1) {
+            let right_min = range.start.saturating_sub(left_val);
+            for &right_val in right_vals.range(right_min..range.end - left_val) {
+                if range.contains(&(left_val + right_val)) {
+                    vals.insert(left_val + right_val);
+                    n_sums += 1;
+                }
+            }
+        }
+    }
+    (vals, n_sums)
 }
 
 #[cfg(test)]
@@ -711,8 +1331,11 @@ mod tests {
     use crate::{
         encodings::{
             card,
-            pb::{BoundUpper, BoundUpperIncremental},
-            EncodeStats, Error,
+            pb::{
+                BoundBoth, BoundLower, BoundLowerIncremental, BoundUpper, BoundUpperIncremental,
+                Encode,
+            },
+            EncodeStats, EncodingStats, Error, PredictStats,
         },
         instances::{BasicVarManager, Cnf, ManageVars},
         lit,
@@ -749,6 +1372,7 @@ mod tests {
             n_clauses: 0,
             max_val: 8,
             enc_range: 0..9,
+            lb_enc_range: 0..0,
             // Dummy nodes for children
             left: Box::new(Node::new_leaf(lit![0], 5)),
             right: Box::new(Node::new_leaf(lit![0], 3)),
@@ -763,6 +1387,7 @@ mod tests {
             n_clauses: 0,
             max_val: 8,
             enc_range: 0..9,
+            lb_enc_range: 0..0,
             // Dummy nodes for children
             left: Box::new(Node::new_leaf(lit![0], 5)),
             right: Box::new(Node::new_leaf(lit![0], 3)),
@@ -791,6 +1416,7 @@ mod tests {
             n_clauses: 0,
             max_val: 8,
             enc_range: 0..9,
+            lb_enc_range: 0..0,
             // Dummy nodes for children
             left: Box::new(Node::new_leaf(lit![0], 5)),
             right: Box::new(Node::new_leaf(lit![0], 3)),
@@ -805,6 +1431,7 @@ mod tests {
             n_clauses: 0,
             max_val: 8,
             enc_range: 0..9,
+            lb_enc_range: 0..0,
             // Dummy nodes for children
             left: Box::new(Node::new_leaf(lit![0], 5)),
             right: Box::new(Node::new_leaf(lit![0], 3)),
@@ -833,6 +1460,7 @@ mod tests {
             n_clauses: 0,
             max_val: 8,
             enc_range: 0..9,
+            lb_enc_range: 0..0,
             // Dummy nodes for children
             left: Box::new(Node::new_leaf(lit![0], 5)),
             right: Box::new(Node::new_leaf(lit![0], 3)),
@@ -847,6 +1475,7 @@ mod tests {
             n_clauses: 0,
             max_val: 8,
             enc_range: 0..9,
+            lb_enc_range: 0..0,
             // Dummy nodes for children
             left: Box::new(Node::new_leaf(lit![0], 5)),
             right: Box::new(Node::new_leaf(lit![0], 3)),
@@ -899,6 +1528,104 @@ mod tests {
         assert_eq!(cnf2.len(), gte2.n_clauses());
     }
 
+    #[test]
+    fn lb_gte_functions() {
+        let mut gte = GeneralizedTotalizer::default();
+        let mut lits = RsHashMap::default();
+        lits.insert(lit![0], 5);
+        lits.insert(lit![1], 5);
+        lits.insert(lit![2], 3);
+        lits.insert(lit![3], 3);
+        gte.extend(lits);
+        assert_eq!(gte.enforce_lb(4), Err(Error::NotEncoded));
+        assert_eq!(gte.enforce_lb(17), Err(Error::Unsat));
+        assert_eq!(gte.enforce_lb(0), Ok(vec![]));
+        let mut var_manager = BasicVarManager::default();
+        gte.encode_lb(4..7, &mut Cnf::new(), &mut var_manager)
+            .unwrap();
+        assert_eq!(gte.depth(), 3);
+        assert_eq!(gte.enforce_lb(4).unwrap().len(), 1);
+        assert_eq!(gte.enforce_lb(6).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn lb_gte_incremental_building() {
+        let mut gte1 = GeneralizedTotalizer::default();
+        let mut lits = RsHashMap::default();
+        lits.insert(lit![0], 5);
+        lits.insert(lit![1], 5);
+        lits.insert(lit![2], 3);
+        lits.insert(lit![3], 3);
+        gte1.extend(lits.clone());
+        let mut var_manager = BasicVarManager::default();
+        let mut cnf1 = Cnf::new();
+        gte1.encode_lb(0..9, &mut cnf1, &mut var_manager).unwrap();
+        let mut gte2 = GeneralizedTotalizer::default();
+        gte2.extend(lits);
+        let mut var_manager = BasicVarManager::default();
+        let mut cnf2 = Cnf::new();
+        gte2.encode_lb(0..4, &mut cnf2, &mut var_manager).unwrap();
+        gte2.encode_lb_change(0..9, &mut cnf2, &mut var_manager)
+            .unwrap();
+        assert_eq!(cnf1.len(), cnf2.len());
+        assert_eq!(cnf1.len(), gte1.n_clauses());
+        assert_eq!(cnf2.len(), gte2.n_clauses());
+    }
+
+    #[test]
+    fn lb_gte_zero_weight() {
+        let mut gte = GeneralizedTotalizer::default();
+        let mut lits = RsHashMap::default();
+        lits.insert(lit![0], 2);
+        lits.insert(lit![1], 5);
+        lits.insert(lit![2], 0);
+        lits.insert(lit![3], 1);
+        gte.extend(lits);
+        assert_eq!(gte.weight_sum(), 8);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        gte.encode_lb(0..9, &mut Cnf::new(), &mut var_manager)
+            .unwrap();
+        assert_eq!(gte.enforce_lb(3).unwrap().len(), 1);
+        assert_eq!(gte.enforce_lb(9), Err(Error::Unsat));
+    }
+
+    #[test]
+    fn both_gte_shared_outputs() {
+        let mut gte = GeneralizedTotalizer::default();
+        let mut lits = RsHashMap::default();
+        lits.insert(lit![0], 5);
+        lits.insert(lit![1], 5);
+        lits.insert(lit![2], 3);
+        lits.insert(lit![3], 3);
+        gte.extend(lits);
+        let mut var_manager = BasicVarManager::default();
+        let mut cnf = Cnf::new();
+        gte.encode_ub(0..17, &mut cnf, &mut var_manager).unwrap();
+        let n_vars = gte.n_vars();
+        gte.encode_lb(0..17, &mut cnf, &mut var_manager).unwrap();
+        assert_eq!(gte.n_vars(), n_vars);
+        assert!(gte.enforce_eq(8).is_ok());
+    }
+
+    #[test]
+    fn both_gte_heavy_lits() {
+        let mut gte = GeneralizedTotalizer::default();
+        let mut lits = RsHashMap::default();
+        lits.insert(lit![0], 1);
+        lits.insert(lit![1], 1);
+        lits.insert(lit![2], 10);
+        gte.extend(lits);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![3]);
+        let mut cnf = Cnf::new();
+        gte.encode_ub(1..2, &mut cnf, &mut var_manager).unwrap();
+        assert!(gte.enforce_ub(1).is_ok());
+        gte.encode_lb(1..2, &mut cnf, &mut var_manager).unwrap();
+        assert!(gte.enforce_ub(1).is_ok());
+        assert!(gte.enforce_eq(1).is_ok());
+    }
+
     #[test]
     fn ub_gte_multiplication() {
         let mut gte1 = GeneralizedTotalizer::default();
@@ -965,4 +1692,76 @@ mod tests {
         assert_eq!(gte_cnf.len(), gte.n_clauses());
         assert_eq!(tot_cnf.len(), tot.n_clauses());
     }
+
+    #[test]
+    fn predict_stats() {
+        let weight_sets: [&[usize]; 5] = [
+            &[1, 1, 1, 1, 1],
+            &[1, 2, 3, 4, 5],
+            &[5, 3, 8, 2],
+            &[7, 7, 3, 3, 1],
+            &[10, 1, 20, 5, 5, 2],
+        ];
+        for weights in weight_sets {
+            let lits: RsHashMap<_, _> = weights
+                .iter()
+                .enumerate()
+                .map(|(idx, &w)| (lit![idx as u32], w))
+                .collect();
+            let weight_sum: usize = weights.iter().sum();
+            for start in 0..=weight_sum + 1 {
+                for end in start..=weight_sum + 2 {
+                    let mut gte = GeneralizedTotalizer::from(lits.clone());
+                    let pred = gte.predict_ub(start..end);
+                    let mut var_manager = BasicVarManager::default();
+                    var_manager.increase_next_free(var![weights.len() as u32]);
+                    let mut cnf = Cnf::new();
+                    gte.encode_ub(start..end, &mut cnf, &mut var_manager)
+                        .unwrap();
+                    assert_eq!(
+                        pred,
+                        EncodingStats {
+                            n_clauses: cnf.len(),
+                            n_vars: gte.n_vars()
+                        }
+                    );
+
+                    let mut gte = GeneralizedTotalizer::from(lits.clone());
+                    let pred = gte.predict_lb(start..end);
+                    let mut var_manager = BasicVarManager::default();
+                    var_manager.increase_next_free(var![weights.len() as u32]);
+                    let mut cnf = Cnf::new();
+                    gte.encode_lb(start..end, &mut cnf, &mut var_manager)
+                        .unwrap();
+                    assert_eq!(
+                        pred,
+                        EncodingStats {
+                            n_clauses: cnf.len(),
+                            n_vars: gte.n_vars()
+                        }
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn predict_stats_extended() {
+        let mut gte = GeneralizedTotalizer::default();
+        let mut lits = RsHashMap::default();
+        lits.insert(lit![0], 1);
+        lits.insert(lit![1], 2);
+        lits.insert(lit![2], 3);
+        lits.insert(lit![3], 6);
+        lits.insert(lit![4], 8);
+        gte.extend(lits);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![5]);
+        let mut cnf = Cnf::new();
+        gte.encode_ub(0..4, &mut cnf, &mut var_manager).unwrap();
+        let pred = gte.predict_ub(0..10);
+        let mut cnf = Cnf::new();
+        gte.encode_ub(0..10, &mut cnf, &mut var_manager).unwrap();
+        assert_eq!(pred.n_clauses, cnf.len());
+    }
 }
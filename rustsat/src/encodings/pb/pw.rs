@@ -0,0 +1,347 @@
+//! # Polynomial Watchdog Encodings
+//!
+//! Implementation of the static polynomial watchdog (PW) and the local
+//! polynomial watchdog (LPW) encodings \[1\]. Both encode the weighted sum in
+//! buckets of totalizers, one per bit of the weights, where each bucket also
+//! receives every second output of the next lower bucket. The bound is
+//! compiled into the encoding via a constant _tare_ that is added to the sum,
+//! such that the bound is exceeded exactly if an output of the top bucket is
+//! set. The structure is the same as for the [`super::DynamicPolyWatchdog`],
+//! but with constant instead of assumed tares.
+//!
+//! The PW encoding detects violations of the bound by unit propagation, the
+//! LPW encoding is arc-consistent. The LPW encoding builds one watchdog per
+//! input literal over all other inputs and is therefore considerably larger.
+//!
+//! Each bound gets its own watchdog, hence the ranges passed to
+//! [`BoundUpper::encode_ub`] and [`BoundLower::encode_lb`] should be kept
+//! small. Lower bounds are encoded as upper bounds on the negated input
+//! literals.
+//!
+//! ## References
+//!
+//! - \[1\] Olivier Bailleux and Yacine Boufkhad and Olivier Roussel: _New
+//!   Encodings of Pseudo-Boolean Constraints into CNF_, SAT 2009.
+
+use super::{dpw, BoundLower, BoundUpper, Encode, Error};
+use crate::{
+    clause,
+    encodings::{
+        atomics, card::dbtotalizer::TotDb, CollectClauses, EncodeStats, IterWeightedInputs,
+    },
+    instances::ManageVars,
+    types::{Lit, RsHashMap},
+};
+use std::{collections::BTreeMap, ops::RangeBounds};
+
+/// Implementation of the polynomial watchdog encodings \[1\]. If `LOCAL` is
+/// `false`, this is the static polynomial watchdog encoding, otherwise it is
+/// the arc-consistent local polynomial watchdog encoding.
+///
+/// # References
+///
+/// - \[1\] Olivier Bailleux and Yacine Boufkhad and Olivier Roussel: _New
+///   Encodings of Pseudo-Boolean Constraints into CNF_, SAT 2009.
+#[derive(Default)]
+pub struct PolyWatchdog<const LOCAL: bool = false> {
+    /// Input literals and weights for the encoding
+    in_lits: RsHashMap<Lit, usize>,
+    /// Sum of all input weight
+    weight_sum: usize,
+    /// The literals enforcing the encoded upper bounds. `None` is a bound that
+    /// is always satisfied.
+    ub_enforcers: BTreeMap<usize, Option<Lit>>,
+    /// The literals enforcing the encoded lower bounds. `None` is a bound that
+    /// is always satisfied.
+    lb_enforcers: BTreeMap<usize, Option<Lit>>,
+    /// The number of variables in the encoding
+    n_vars: u32,
+    /// The number of clauses in the encoding
+    n_clauses: usize,
+    /// The node database of the totalizers
+    db: TotDb,
+}
+
+/// The arc-consistent local polynomial watchdog encoding
+pub type LocalPolyWatchdog = PolyWatchdog<true>;
+
+impl<const LOCAL: bool> PolyWatchdog<LOCAL> {
+    /// Gets the input literals in a deterministic order, possibly negated
+    fn sorted_lits(&self, negated: bool) -> Vec<(Lit, usize)> {
+        let mut lits: Vec<(Lit, usize)> = self
+            .in_lits
+            .iter()
+            .map(|(&l, &w)| if negated { (!l, w) } else { (l, w) })
+            .collect();
+        lits.sort_unstable();
+        lits
+    }
+
+    /// Encodes `sum of lits <= ub` and returns the literal enforcing it
+    fn encode_bound<Col>(
+        &mut self,
+        lits: &[(Lit, usize)],
+        ub: usize,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<Option<Lit>, crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        if !LOCAL {
+            return Ok(
+                encode_watchdog(lits, ub, &mut self.db, collector, var_manager)?.map(|viol| !viol),
+            );
+        }
+        let enforcer = var_manager.new_var().pos_lit();
+        let mut others = Vec::with_capacity(lits.len());
+        for (idx, &(lit, weight)) in lits.iter().enumerate() {
+            if weight > ub {
+                collector.add_clause(atomics::lit_impl_lit(enforcer, !lit))?;
+                continue;
+            }
+            others.clear();
+            others.extend_from_slice(&lits[..idx]);
+            others.extend_from_slice(&lits[idx + 1..]);
+            // If `lit` is true, the other literals can only sum up to `ub - weight`
+            if let Some(viol) =
+                encode_watchdog(&others, ub - weight, &mut self.db, collector, var_manager)?
+            {
+                collector.add_clause(atomics::cube_impl_lit(&[enforcer, lit], !viol))?;
+            }
+        }
+        Ok(Some(enforcer))
+    }
+}
+
+/// Builds a static polynomial watchdog for `sum of lits <= ub` and returns a
+/// literal that is true if the bound is violated. Returns `None` if the bound
+/// can not be violated.
+fn encode_watchdog<Col>(
+    lits: &[(Lit, usize)],
+    ub: usize,
+    db: &mut TotDb,
+    collector: &mut Col,
+    var_manager: &mut dyn ManageVars,
+) -> Result<Option<Lit>, crate::OutOfMemory>
+where
+    Col: CollectClauses,
+{
+    if lits.iter().fold(0, |sum, (_, w)| sum + w) <= ub {
+        return Ok(None);
+    }
+    if lits.len() == 1 {
+        return Ok(Some(lits[0].0));
+    }
+    let mut weight_queue = dpw::lit_weight_queue(lits.iter().copied(), db);
+    let structure = dpw::build_structure(&mut weight_queue, 1, true, db, var_manager);
+    let output_weight = 1 << structure.output_power();
+    dpw::encode_output(&structure, ub / output_weight, db, collector, var_manager)?;
+    let assumps = dpw::enforce_ub(&structure, ub, db).expect("output was just encoded");
+    let Some((&output, tares)) = assumps.split_first() else {
+        return Ok(None);
+    };
+    // Make the tare constant
+    for &tare in &structure.tares {
+        if tares.contains(&tare) {
+            collector.add_clause(clause![tare])?;
+        } else {
+            collector.add_clause(clause![!tare])?;
+        }
+    }
+    Ok(Some(!output))
+}
+
+impl<const LOCAL: bool> Encode for PolyWatchdog<LOCAL> {
+    fn weight_sum(&self) -> usize {
+        self.weight_sum
+    }
+}
+
+impl<const LOCAL: bool> IterWeightedInputs for PolyWatchdog<LOCAL> {
+    type Iter<'a> = super::gte::GteIter<'a>;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.in_lits.iter().map(super::gte::copy_key_val)
+    }
+}
+
+impl<const LOCAL: bool> BoundUpper for PolyWatchdog<LOCAL> {
+    fn encode_ub<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        let range = super::prepare_ub_range(self, range);
+        if range.is_empty() {
+            return Ok(());
+        }
+        let n_vars_before = var_manager.n_used();
+        let n_clauses_before = collector.n_clauses();
+        let lits = self.sorted_lits(false);
+        for ub in range {
+            if self.ub_enforcers.contains_key(&ub) {
+                continue;
+            }
+            let enforcer = self.encode_bound(&lits, ub, collector, var_manager)?;
+            self.ub_enforcers.insert(ub, enforcer);
+        }
+        self.n_clauses += collector.n_clauses() - n_clauses_before;
+        self.n_vars += var_manager.n_used() - n_vars_before;
+        Ok(())
+    }
+
+    fn enforce_ub(&self, ub: usize) -> Result<Vec<Lit>, Error> {
+        if ub >= self.weight_sum {
+            return Ok(vec![]);
+        }
+        match self.ub_enforcers.get(&ub) {
+            Some(enforcer) => Ok(enforcer.iter().copied().collect()),
+            None => Err(Error::NotEncoded),
+        }
+    }
+}
+
+impl<const LOCAL: bool> BoundLower for PolyWatchdog<LOCAL> {
+    fn encode_lb<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        let range = super::prepare_lb_range(self, range);
+        if range.is_empty() {
+            return Ok(());
+        }
+        let n_vars_before = var_manager.n_used();
+        let n_clauses_before = collector.n_clauses();
+        let lits = self.sorted_lits(true);
+        for lb in range {
+            if self.lb_enforcers.contains_key(&lb) {
+                continue;
+            }
+            // `sum of lits >= lb` is encoded as `sum of negated lits <= weight_sum - lb`
+            let enforcer =
+                self.encode_bound(&lits, self.weight_sum - lb, collector, var_manager)?;
+            self.lb_enforcers.insert(lb, enforcer);
+        }
+        self.n_clauses += collector.n_clauses() - n_clauses_before;
+        self.n_vars += var_manager.n_used() - n_vars_before;
+        Ok(())
+    }
+
+    fn enforce_lb(&self, lb: usize) -> Result<Vec<Lit>, Error> {
+        if lb > self.weight_sum {
+            return Err(Error::Unsat);
+        }
+        if lb == 0 {
+            return Ok(vec![]);
+        }
+        match self.lb_enforcers.get(&lb) {
+            Some(enforcer) => Ok(enforcer.iter().copied().collect()),
+            None => Err(Error::NotEncoded),
+        }
+    }
+}
+
+impl<const LOCAL: bool> EncodeStats for PolyWatchdog<LOCAL> {
+    fn n_clauses(&self) -> usize {
+        self.n_clauses
+    }
+
+    fn n_vars(&self) -> u32 {
+        self.n_vars
+    }
+}
+
+impl<const LOCAL: bool> From<RsHashMap<Lit, usize>> for PolyWatchdog<LOCAL> {
+    fn from(lits: RsHashMap<Lit, usize>) -> Self {
+        Self::from_iter(lits)
+    }
+}
+
+impl<const LOCAL: bool> FromIterator<(Lit, usize)> for PolyWatchdog<LOCAL> {
+    fn from_iter<T: IntoIterator<Item = (Lit, usize)>>(iter: T) -> Self {
+        let mut pw = Self::default();
+        pw.extend(iter);
+        pw
+    }
+}
+
+impl<const LOCAL: bool> Extend<(Lit, usize)> for PolyWatchdog<LOCAL> {
+    fn extend<T: IntoIterator<Item = (Lit, usize)>>(&mut self, iter: T) {
+        iter.into_iter().for_each(|(l, w)| {
+            if w == 0 {
+                return;
+            }
+            self.weight_sum += w;
+            // Encoded bounds are not valid anymore
+            self.ub_enforcers.clear();
+            self.lb_enforcers.clear();
+            match self.in_lits.get_mut(&l) {
+                Some(old_w) => *old_w += w,
+                None => {
+                    self.in_lits.insert(l, w);
+                }
+            };
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LocalPolyWatchdog, PolyWatchdog};
+    use crate::{
+        encodings::{
+            pb::{BoundLower, BoundUpper},
+            EncodeStats, Error,
+        },
+        instances::{BasicVarManager, Cnf, ManageVars},
+        lit, var,
+    };
+
+    #[test]
+    fn pw_functions() {
+        let mut pw: PolyWatchdog =
+            PolyWatchdog::from_iter([(lit![0], 5), (lit![1], 5), (lit![2], 3), (lit![3], 3)]);
+        assert_eq!(pw.enforce_ub(4), Err(Error::NotEncoded));
+        assert_eq!(pw.enforce_lb(4), Err(Error::NotEncoded));
+        assert_eq!(pw.enforce_ub(16), Ok(vec![]));
+        assert_eq!(pw.enforce_lb(17), Err(Error::Unsat));
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        let mut cnf = Cnf::new();
+        pw.encode_ub(4..5, &mut cnf, &mut var_manager).unwrap();
+        assert_eq!(pw.enforce_ub(4).unwrap().len(), 1);
+        assert_eq!(pw.n_clauses(), cnf.len());
+        assert_eq!(pw.n_vars(), var_manager.n_used() - 4);
+        pw.encode_lb(4..5, &mut cnf, &mut var_manager).unwrap();
+        assert_eq!(pw.enforce_lb(4).unwrap().len(), 1);
+        pw.extend([(lit![4], 1)]);
+        assert_eq!(pw.enforce_ub(4), Err(Error::NotEncoded));
+    }
+
+    #[test]
+    fn lpw_larger_than_pw() {
+        let lits = [(lit![0], 5), (lit![1], 5), (lit![2], 3), (lit![3], 3)];
+        let mut pw: PolyWatchdog = PolyWatchdog::from_iter(lits);
+        let mut lpw = LocalPolyWatchdog::from_iter(lits);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        let mut cnf = Cnf::new();
+        pw.encode_ub(8..9, &mut cnf, &mut var_manager).unwrap();
+        lpw.encode_ub(8..9, &mut cnf, &mut var_manager).unwrap();
+        assert_eq!(pw.n_clauses() + lpw.n_clauses(), cnf.len());
+        assert!(lpw.n_clauses() > pw.n_clauses());
+        assert_eq!(lpw.enforce_ub(8).unwrap().len(), 1);
+    }
+}
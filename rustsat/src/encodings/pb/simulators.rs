@@ -631,6 +631,19 @@ where
     }
 }
 
+impl<CE> EncodeStats for Card<CE>
+where
+    CE: card::Encode + EncodeStats,
+{
+    fn n_clauses(&self) -> usize {
+        self.card_enc.n_clauses()
+    }
+
+    fn n_vars(&self) -> u32 {
+        self.card_enc.n_vars()
+    }
+}
+
 fn add_unit_weight(lit: Lit) -> (Lit, usize) {
     (lit, 1)
 }
@@ -0,0 +1,282 @@
+//! # Sequential Weight Counter Encoding
+//!
+//! Implementation of the sequential weight counter encoding \[1\]. The encoding
+//! requires `O(n*k)` clauses and variables, where `n` is the number of input
+//! literals and `k` is the largest bound to enforce. This makes it a good
+//! choice for small bounds on constraints with many input literals. The
+//! implementation is incremental both in the bound and in the input literals.
+//!
+//! ## References
+//!
+//! - \[1\] Steffen Hölldobler and Norbert Manthey and Peter Steinke: _A
+//!   Compact Encoding of Pseudo-Boolean Constraints into SAT_, KI 2012.
+
+use super::{BoundUpper, BoundUpperIncremental, Encode, EncodeIncremental, Error};
+use crate::{
+    encodings::{atomics, CollectClauses, EncodeStats, IterWeightedInputs},
+    instances::ManageVars,
+    types::{Lit, RsHashMap},
+};
+use std::{cmp, ops::RangeBounds};
+
+/// Implementation of the sequential weight counter encoding \[1\].
+///
+/// The register `regs[i][j]` is true if the weighted sum of the first `i + 1`
+/// input literals is at least `j + 1`. Only upper bounding is supported
+/// natively, for lower bounding use [`super::InvertedSeqWeightCounter`].
+///
+/// # References
+///
+/// - \[1\] Steffen Hölldobler and Norbert Manthey and Peter Steinke: _A
+///   Compact Encoding of Pseudo-Boolean Constraints into SAT_, KI 2012.
+#[derive(Default)]
+pub struct SeqWeightCounter {
+    /// Input literals and weights to the counter
+    in_lits: Vec<(Lit, usize)>,
+    /// Sum of all input weight
+    weight_sum: usize,
+    /// The counter registers, one row per input literal
+    regs: Vec<Vec<Lit>>,
+    /// The number of rows encoded for upper bounding
+    ub_rows: usize,
+    /// The number of counter columns encoded for upper bounding
+    ub_cols: usize,
+    /// The number of variables in the counter
+    n_vars: u32,
+    /// The number of clauses in the counter
+    n_clauses: usize,
+}
+
+impl SeqWeightCounter {
+    /// Makes sure that registers exist for all input literals up to counter
+    /// value `cols`
+    fn extend_regs(&mut self, cols: usize, var_manager: &mut dyn ManageVars) {
+        let mut prefix_sum = 0;
+        for (idx, &(lit, weight)) in self.in_lits.iter().enumerate() {
+            prefix_sum += weight;
+            let width = cmp::min(prefix_sum, cols);
+            if idx >= self.regs.len() {
+                self.regs.push(vec![]);
+            }
+            let row = &mut self.regs[idx];
+            while row.len() < width {
+                if idx == 0 {
+                    // The first registers are the first input literal itself
+                    row.push(lit);
+                    continue;
+                }
+                row.push(var_manager.new_var().pos_lit());
+                self.n_vars += 1;
+            }
+        }
+    }
+
+    /// Encodes the upward propagating clauses for all registers up to counter
+    /// value `cols` that are not encoded yet
+    fn encode_ub_cols<Col>(
+        &mut self,
+        cols: usize,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        let cols = cmp::max(cols, self.ub_cols);
+        if cols == 0 || (self.ub_rows == self.in_lits.len() && cols == self.ub_cols) {
+            return Ok(());
+        }
+        self.extend_regs(cols, var_manager);
+        let n_clauses_before = collector.n_clauses();
+        for idx in 1..self.in_lits.len() {
+            let first_col = if idx < self.ub_rows { self.ub_cols } else { 0 };
+            let (lit, weight) = self.in_lits[idx];
+            let (prev, row) = (&self.regs[idx - 1], &self.regs[idx]);
+            // Registers might be reserved beyond `cols`
+            for col in first_col..cmp::min(row.len(), cols) {
+                if col < prev.len() {
+                    // The counter does not decrease
+                    collector.add_clause(atomics::lit_impl_lit(prev[col], row[col]))?;
+                }
+                if col < weight {
+                    collector.add_clause(atomics::lit_impl_lit(lit, row[col]))?;
+                } else if col - weight < prev.len() {
+                    collector
+                        .add_clause(atomics::cube_impl_lit(&[lit, prev[col - weight]], row[col]))?;
+                }
+            }
+        }
+        self.n_clauses += collector.n_clauses() - n_clauses_before;
+        self.ub_rows = self.in_lits.len();
+        self.ub_cols = cols;
+        Ok(())
+    }
+}
+
+impl Encode for SeqWeightCounter {
+    fn weight_sum(&self) -> usize {
+        self.weight_sum
+    }
+}
+
+impl IterWeightedInputs for SeqWeightCounter {
+    type Iter<'a> = std::iter::Copied<std::slice::Iter<'a, (Lit, usize)>>;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.in_lits.iter().copied()
+    }
+}
+
+impl EncodeIncremental for SeqWeightCounter {
+    fn reserve(&mut self, var_manager: &mut dyn ManageVars) {
+        self.extend_regs(self.weight_sum, var_manager);
+    }
+}
+
+impl BoundUpper for SeqWeightCounter {
+    fn encode_ub<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        self.ub_rows = 0;
+        self.ub_cols = 0;
+        self.encode_ub_change(range, collector, var_manager)
+    }
+
+    fn enforce_ub(&self, ub: usize) -> Result<Vec<Lit>, Error> {
+        if ub >= self.weight_sum {
+            return Ok(vec![]);
+        };
+        if self.ub_rows != self.in_lits.len() || ub >= self.ub_cols {
+            return Err(Error::NotEncoded);
+        };
+        Ok(vec![!self.regs[self.in_lits.len() - 1][ub]])
+    }
+}
+
+impl BoundUpperIncremental for SeqWeightCounter {
+    fn encode_ub_change<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        let range = super::prepare_ub_range(self, range);
+        if range.is_empty() {
+            return Ok(());
+        };
+        // Enforcing `ub` requires the counter to count up to `ub + 1`
+        self.encode_ub_cols(range.end, collector, var_manager)
+    }
+}
+
+impl EncodeStats for SeqWeightCounter {
+    fn n_clauses(&self) -> usize {
+        self.n_clauses
+    }
+
+    fn n_vars(&self) -> u32 {
+        self.n_vars
+    }
+}
+
+impl From<RsHashMap<Lit, usize>> for SeqWeightCounter {
+    fn from(lits: RsHashMap<Lit, usize>) -> Self {
+        Self::from_iter(lits)
+    }
+}
+
+impl FromIterator<(Lit, usize)> for SeqWeightCounter {
+    fn from_iter<T: IntoIterator<Item = (Lit, usize)>>(iter: T) -> Self {
+        let mut swc = Self::default();
+        swc.extend(iter);
+        swc
+    }
+}
+
+impl Extend<(Lit, usize)> for SeqWeightCounter {
+    fn extend<T: IntoIterator<Item = (Lit, usize)>>(&mut self, iter: T) {
+        iter.into_iter().for_each(|(l, w)| {
+            if w == 0 {
+                return;
+            }
+            self.weight_sum += w;
+            self.in_lits.push((l, w));
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SeqWeightCounter;
+    use crate::{
+        encodings::{
+            pb::{BoundUpper, BoundUpperIncremental, EncodeIncremental},
+            EncodeStats, Error,
+        },
+        instances::{BasicVarManager, Cnf, ManageVars},
+        lit, var,
+    };
+
+    #[test]
+    fn swc_functions() {
+        let mut swc = SeqWeightCounter::from_iter([(lit![0], 2), (lit![1], 3), (lit![2], 1)]);
+        assert_eq!(swc.enforce_ub(2), Err(Error::NotEncoded));
+        assert_eq!(swc.enforce_ub(6), Ok(vec![]));
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![3]);
+        let mut cnf = Cnf::new();
+        swc.encode_ub(0..3, &mut cnf, &mut var_manager).unwrap();
+        assert_eq!(swc.enforce_ub(2).unwrap().len(), 1);
+        assert_eq!(swc.enforce_ub(3), Err(Error::NotEncoded));
+        assert_eq!(swc.n_clauses(), cnf.len());
+        assert_eq!(swc.n_vars(), var_manager.n_used() - 3);
+    }
+
+    #[test]
+    fn swc_incremental_building() {
+        let mut swc1 =
+            SeqWeightCounter::from_iter([(lit![0], 2), (lit![1], 3), (lit![2], 1), (lit![3], 2)]);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        let mut cnf1 = Cnf::new();
+        swc1.encode_ub(0..5, &mut cnf1, &mut var_manager).unwrap();
+
+        let mut swc2 = SeqWeightCounter::from_iter([(lit![0], 2), (lit![1], 3), (lit![2], 1)]);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        let mut cnf2 = Cnf::new();
+        swc2.encode_ub(0..2, &mut cnf2, &mut var_manager).unwrap();
+        swc2.encode_ub_change(0..5, &mut cnf2, &mut var_manager)
+            .unwrap();
+        swc2.extend([(lit![3], 2)]);
+        assert_eq!(swc2.enforce_ub(3), Err(Error::NotEncoded));
+        swc2.encode_ub_change(0..5, &mut cnf2, &mut var_manager)
+            .unwrap();
+
+        assert_eq!(cnf1.len(), cnf2.len());
+        assert_eq!(swc1.n_vars(), swc2.n_vars());
+    }
+
+    #[test]
+    fn swc_reserve() {
+        let mut swc = SeqWeightCounter::from_iter([(lit![0], 2), (lit![1], 3), (lit![2], 1)]);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![3]);
+        swc.reserve(&mut var_manager);
+        let n_vars = var_manager.n_used();
+        let mut cnf = Cnf::new();
+        swc.encode_ub(0..6, &mut cnf, &mut var_manager).unwrap();
+        assert_eq!(var_manager.n_used(), n_vars);
+    }
+}
@@ -0,0 +1,282 @@
+//! # CNF Encodings for XOR Constraints
+//!
+//! The module contains implementations of CNF encodings for XOR (parity)
+//! constraints. An XOR over `n` literals can be encoded directly with
+//! `2^(n-1)` clauses and no auxiliary variables. For longer constraints, the
+//! [`TseitinChain`] encoding cuts the XOR into a chain of shorter XORs that are
+//! connected by auxiliary variables and encodes each of them directly.
+//!
+//! ## Example Useage
+//!
+//! ```
+//! # use rustsat::{
+//! #     encodings::xor::TseitinChain,
+//! #     instances::{BasicVarManager, Cnf, ManageVars},
+//! #     lit, types::constraints::XorConstraint, var,
+//! # };
+//! #
+//! let mut var_manager = BasicVarManager::default();
+//! var_manager.increase_next_free(var![6]);
+//!
+//! let xor = XorConstraint::new(
+//!     vec![lit![0], lit![1], lit![2], lit![3], lit![4], lit![5]],
+//!     true,
+//! );
+//! let mut enc = TseitinChain::new(3);
+//! let mut encoding = Cnf::new();
+//! enc.encode(xor, &mut encoding, &mut var_manager).unwrap();
+//! ```
+
+use super::{CollectClauses, EncodeStats};
+use crate::{
+    instances::ManageVars,
+    types::{constraints::XorConstraint, Clause, Lit},
+};
+
+/// The default cut length of [`TseitinChain`]
+pub const DEFAULT_CUT_LEN: usize = 4;
+
+/// Tseitin chain encoding for XOR constraints. The constraint is cut into
+/// XORs with at most `cut_len` literals each, including the auxiliary
+/// variables connecting the pieces of the chain. Each piece is then encoded
+/// directly with [`encode_direct`].
+///
+/// A longer cut length results in fewer auxiliary variables but exponentially
+/// more clauses per piece.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TseitinChain {
+    /// The maximum number of literals in a piece of the chain
+    cut_len: usize,
+    /// The number of clauses in the encoding
+    n_clauses: usize,
+    /// The number of variables in the encoding
+    n_vars: u32,
+}
+
+impl TseitinChain {
+    /// Creates a new encoder with a given cut length
+    ///
+    /// # Panics
+    ///
+    /// If `cut_len` is less than 3, since pieces in the middle of the chain
+    /// could otherwise not contain any input literals.
+    pub fn new(cut_len: usize) -> Self {
+        assert!(cut_len >= 3, "cut length must be at least 3");
+        TseitinChain {
+            cut_len,
+            n_clauses: 0,
+            n_vars: 0,
+        }
+    }
+
+    /// Gets the cut length of the encoder
+    pub fn cut_len(&self) -> usize {
+        self.cut_len
+    }
+
+    /// Encodes an XOR constraint to CNF. The constraint is normalized
+    /// first, duplicate literals therefore cancel out and constraints without
+    /// literals are encoded as the empty clause or nothing.
+    ///
+    /// # Errors
+    ///
+    /// If the clause collector runs out of memory, returns [`crate::OutOfMemory`].
+    pub fn encode<Col>(
+        &mut self,
+        constr: XorConstraint,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        let n_vars_before = var_manager.n_used();
+        let n_clauses_before = collector.n_clauses();
+        let (lits, rhs) = constr.normalize().decompose();
+        let mut rest = &lits[..];
+        let mut piece = Vec::with_capacity(self.cut_len);
+        loop {
+            // number of input literals that fit in this piece without a new
+            // auxiliary variable
+            let n_inputs = self.cut_len - piece.len();
+            if rest.len() <= n_inputs {
+                piece.extend_from_slice(rest);
+                encode_direct(&piece, rhs, collector)?;
+                break;
+            }
+            let (take, remain) = rest.split_at(n_inputs - 1);
+            let aux = var_manager.new_var().pos_lit();
+            piece.extend_from_slice(take);
+            piece.push(aux);
+            encode_direct(&piece, false, collector)?;
+            piece.clear();
+            piece.push(aux);
+            rest = remain;
+        }
+        self.n_clauses += collector.n_clauses() - n_clauses_before;
+        self.n_vars += var_manager.n_used() - n_vars_before;
+        Ok(())
+    }
+}
+
+impl Default for TseitinChain {
+    fn default() -> Self {
+        Self::new(DEFAULT_CUT_LEN)
+    }
+}
+
+impl EncodeStats for TseitinChain {
+    fn n_clauses(&self) -> usize {
+        self.n_clauses
+    }
+
+    fn n_vars(&self) -> u32 {
+        self.n_vars
+    }
+}
+
+/// Directly encodes the XOR constraint `lits[0] ^ ... ^ lits[n-1] = rhs` with
+/// one clause excluding each assignment of the wrong parity. This produces
+/// `2^(n-1)` clauses, or the empty clause if `lits` is empty and `rhs` is true.
+///
+/// # Errors
+///
+/// If the clause collector runs out of memory, returns [`crate::OutOfMemory`].
+pub fn encode_direct<Col>(
+    lits: &[Lit],
+    rhs: bool,
+    collector: &mut Col,
+) -> Result<(), crate::OutOfMemory>
+where
+    Col: CollectClauses,
+{
+    debug_assert!(lits.len() < usize::BITS as usize);
+    let clauses = (0..1_usize << lits.len())
+        // an assignment setting the literals in `mask` to true
+        .filter(|mask| (mask.count_ones() % 2 == 1) != rhs)
+        .map(|mask| {
+            lits.iter()
+                .enumerate()
+                .map(|(idx, &lit)| if mask >> idx & 1 == 1 { !lit } else { lit })
+                .collect::<Clause>()
+        });
+    collector.extend_clauses(clauses)
+}
+
+/// Default encoder for XOR constraints, using a [`TseitinChain`] with
+/// [`DEFAULT_CUT_LEN`]
+///
+/// # Errors
+///
+/// If the clause collector runs out of memory, returns [`crate::OutOfMemory`].
+pub fn default_encode_xor_constraint<Col: CollectClauses>(
+    constr: XorConstraint,
+    collector: &mut Col,
+    var_manager: &mut dyn ManageVars,
+) -> Result<(), crate::OutOfMemory> {
+    TseitinChain::default().encode(constr, collector, var_manager)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode_direct, TseitinChain};
+    use crate::{
+        encodings::EncodeStats,
+        instances::{BasicVarManager, Cnf, ManageVars},
+        lit,
+        types::{constraints::XorConstraint, Assignment, Lit, TernaryVal, Var},
+        var,
+    };
+
+    /// Checks whether the encoding can be extended to a model for exactly the
+    /// assignments of the `n_inputs` input variables that satisfy the
+    /// constraint
+    fn check_equisat(constr: &XorConstraint, n_inputs: u32, cnf: &Cnf, n_vars: u32) {
+        for in_vals in 0..1_u32 << n_inputs {
+            let assign_vars = |vals: u32, offset: u32, n: u32, assign: &mut Assignment| {
+                for idx in 0..n {
+                    assign.assign_var(
+                        Var::new(offset + idx),
+                        TernaryVal::from((vals >> idx) & 1 == 1),
+                    );
+                }
+            };
+            let mut assign = Assignment::default();
+            assign_vars(in_vals, 0, n_inputs, &mut assign);
+            let expected = constr.is_sat(&assign);
+            let n_aux = n_vars - n_inputs;
+            let extendable = (0..1_u32 << n_aux).any(|aux_vals| {
+                assign_vars(aux_vals, n_inputs, n_aux, &mut assign);
+                cnf.iter().all(|cl| cl.is_sat(&assign))
+            });
+            assert_eq!(expected, extendable, "{constr:?} with inputs {in_vals:b}");
+        }
+    }
+
+    #[test]
+    fn direct() {
+        for rhs in [false, true] {
+            let lits = [lit![0], !lit![1], lit![2]];
+            let mut cnf = Cnf::new();
+            encode_direct(&lits, rhs, &mut cnf).unwrap();
+            assert_eq!(cnf.len(), 4);
+            check_equisat(&XorConstraint::new(lits, rhs), 3, &cnf, 3);
+        }
+    }
+
+    #[test]
+    fn empty() {
+        let mut var_manager = BasicVarManager::default();
+        let mut cnf = Cnf::new();
+        let mut enc = TseitinChain::default();
+        enc.encode(XorConstraint::new([], false), &mut cnf, &mut var_manager)
+            .unwrap();
+        assert_eq!(cnf.len(), 0);
+        enc.encode(
+            XorConstraint::new([lit![0], lit![0]], true),
+            &mut cnf,
+            &mut var_manager,
+        )
+        .unwrap();
+        assert_eq!(cnf.len(), 1);
+        assert!(cnf.iter().next().unwrap().is_empty());
+    }
+
+    #[test]
+    fn chain() {
+        for cut_len in 3..6 {
+            for n_lits in 1..9_u32 {
+                for rhs in [false, true] {
+                    let lits: Vec<Lit> =
+                        (0..n_lits).map(|idx| Lit::new(idx, idx % 3 == 0)).collect();
+                    let constr = XorConstraint::new(lits, rhs);
+                    let mut var_manager = BasicVarManager::default();
+                    var_manager.increase_next_free(var![n_lits]);
+                    let mut cnf = Cnf::new();
+                    let mut enc = TseitinChain::new(cut_len);
+                    enc.encode(constr.clone(), &mut cnf, &mut var_manager)
+                        .unwrap();
+                    assert_eq!(enc.n_clauses(), cnf.len());
+                    assert_eq!(enc.n_vars() + n_lits, var_manager.n_used());
+                    check_equisat(&constr, n_lits, &cnf, var_manager.n_used());
+                    for cl in cnf.iter() {
+                        assert!(cl.len() <= cut_len);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn duplicates() {
+        let constr = XorConstraint::new([lit![0], lit![1], !lit![0], lit![2], lit![1]], false);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![3]);
+        let mut cnf = Cnf::new();
+        TseitinChain::default()
+            .encode(constr.clone(), &mut cnf, &mut var_manager)
+            .unwrap();
+        assert_eq!(var_manager.n_used(), 3);
+        check_equisat(&constr, 3, &cnf, 3);
+    }
+}
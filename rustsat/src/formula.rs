@@ -0,0 +1,758 @@
+//! # Propositional Formulas
+//!
+//! Arbitrary propositional formulas over literals and their transformation to
+//! CNF. The [`Encoder`] converts formulas into clauses either with the full
+//! Tseitin transformation or with the polarity-aware Plaisted-Greenbaum
+//! transformation. Gates are structurally hashed, i.e., subformulas that
+//! appear multiple times (also across multiple calls to the encoder) share the
+//! same auxiliary variable.
+//!
+//! Internally, formulas are normalized to conjunctions, binary XORs and
+//! if-then-else gates, disjunctions and implications are expressed via De
+//! Morgan's laws. Structural hashing therefore also detects, e.g., that `!a |
+//! !b` is the negation of `a & b`.
+//!
+//! ## Example Useage
+//!
+//! ```
+//! # use rustsat::{
+//! #     formula::{Encoder, Formula},
+//! #     instances::{BasicVarManager, Cnf, ManageVars},
+//! #     lit, var,
+//! # };
+//! #
+//! let mut var_manager = BasicVarManager::default();
+//! var_manager.increase_next_free(var![3]);
+//!
+//! // (x0 & x1) -> (x1 ^ x2)
+//! let formula = Formula::implies(
+//!     Formula::and([lit![0].into(), lit![1].into()]),
+//!     Formula::xor(lit![1].into(), lit![2].into()),
+//! );
+//! let mut enc = Encoder::plaisted_greenbaum();
+//! let mut encoding = Cnf::new();
+//! enc.encode(&formula, &mut encoding, &mut var_manager).unwrap();
+//! ```
+
+use std::ops;
+
+use crate::{
+    clause,
+    encodings::{atomics, CollectClauses, EncodeStats},
+    instances::ManageVars,
+    types::{Assignment, Lit, RsHashMap, TernaryVal},
+};
+
+/// A propositional formula over literals
+#[derive(Hash, Eq, PartialEq, Clone, Debug)]
+pub enum Formula {
+    /// A literal
+    Lit(Lit),
+    /// Negation of a formula
+    Not(Box<Formula>),
+    /// Conjunction of formulas, the empty conjunction is true
+    And(Vec<Formula>),
+    /// Disjunction of formulas, the empty disjunction is false
+    Or(Vec<Formula>),
+    /// Exclusive or of two formulas
+    Xor(Box<Formula>, Box<Formula>),
+    /// Implication `a -> b`
+    Implies(Box<Formula>, Box<Formula>),
+    /// Equivalence `a <-> b`
+    Iff(Box<Formula>, Box<Formula>),
+    /// If-then-else, i.e., `(c & t) | (!c & e)`
+    Ite(Box<Formula>, Box<Formula>, Box<Formula>),
+}
+
+impl Formula {
+    /// Creates a conjunction of formulas
+    pub fn and<I: IntoIterator<Item = Formula>>(fs: I) -> Self {
+        Formula::And(fs.into_iter().collect())
+    }
+
+    /// Creates a disjunction of formulas
+    pub fn or<I: IntoIterator<Item = Formula>>(fs: I) -> Self {
+        Formula::Or(fs.into_iter().collect())
+    }
+
+    /// Creates the exclusive or `a ^ b`
+    pub fn xor(a: Formula, b: Formula) -> Self {
+        Formula::Xor(Box::new(a), Box::new(b))
+    }
+
+    /// Creates the implication `a -> b`
+    pub fn implies(a: Formula, b: Formula) -> Self {
+        Formula::Implies(Box::new(a), Box::new(b))
+    }
+
+    /// Creates the equivalence `a <-> b`
+    pub fn iff(a: Formula, b: Formula) -> Self {
+        Formula::Iff(Box::new(a), Box::new(b))
+    }
+
+    /// Creates the if-then-else `c ? t : e`
+    pub fn ite(c: Formula, t: Formula, e: Formula) -> Self {
+        Formula::Ite(Box::new(c), Box::new(t), Box::new(e))
+    }
+
+    /// Evaluates the formula under a given assignment. Subformulas that depend
+    /// on unassigned variables evaluate to [`TernaryVal::DontCare`].
+    pub fn evaluate(&self, assign: &Assignment) -> TernaryVal {
+        let and = |vals: &mut dyn Iterator<Item = TernaryVal>| {
+            vals.fold(TernaryVal::True, |acc, val| match (acc, val) {
+                (TernaryVal::False, _) | (_, TernaryVal::False) => TernaryVal::False,
+                (TernaryVal::True, TernaryVal::True) => TernaryVal::True,
+                _ => TernaryVal::DontCare,
+            })
+        };
+        let xor = |a: TernaryVal, b: TernaryVal| match (a, b) {
+            (TernaryVal::DontCare, _) | (_, TernaryVal::DontCare) => TernaryVal::DontCare,
+            (a, b) => TernaryVal::from(a != b),
+        };
+        match self {
+            Formula::Lit(lit) => assign.lit_value(*lit),
+            Formula::Not(f) => !f.evaluate(assign),
+            Formula::And(fs) => and(&mut fs.iter().map(|f| f.evaluate(assign))),
+            Formula::Or(fs) => !and(&mut fs.iter().map(|f| !f.evaluate(assign))),
+            Formula::Xor(a, b) => xor(a.evaluate(assign), b.evaluate(assign)),
+            Formula::Implies(a, b) => {
+                !and(&mut [a.evaluate(assign), !b.evaluate(assign)].into_iter())
+            }
+            Formula::Iff(a, b) => !xor(a.evaluate(assign), b.evaluate(assign)),
+            Formula::Ite(c, t, e) => match c.evaluate(assign) {
+                TernaryVal::True => t.evaluate(assign),
+                TernaryVal::False => e.evaluate(assign),
+                TernaryVal::DontCare => {
+                    let t = t.evaluate(assign);
+                    if t == e.evaluate(assign) {
+                        t
+                    } else {
+                        TernaryVal::DontCare
+                    }
+                }
+            },
+        }
+    }
+
+    /// Checks whether the formula is satisfied by the given assignment
+    pub fn is_sat(&self, assign: &Assignment) -> bool {
+        self.evaluate(assign) == TernaryVal::True
+    }
+}
+
+impl From<Lit> for Formula {
+    fn from(lit: Lit) -> Self {
+        Formula::Lit(lit)
+    }
+}
+
+impl ops::Not for Formula {
+    type Output = Formula;
+
+    fn not(self) -> Self::Output {
+        match self {
+            Formula::Lit(lit) => Formula::Lit(!lit),
+            Formula::Not(f) => *f,
+            f => Formula::Not(Box::new(f)),
+        }
+    }
+}
+
+/// The CNF transformation used by an [`Encoder`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Transformation {
+    /// The Tseitin transformation, defining each auxiliary variable to be
+    /// equivalent to its subformula
+    #[default]
+    Tseitin,
+    /// The Plaisted-Greenbaum transformation, only encoding the directions of
+    /// the definitions that are required by the polarity of the subformulas
+    PlaistedGreenbaum,
+}
+
+/// The polarities that a gate is used in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Polarity {
+    /// The gate output implies the gate
+    pos: bool,
+    /// The gate implies the gate output
+    neg: bool,
+}
+
+impl Polarity {
+    const POS: Polarity = Polarity {
+        pos: true,
+        neg: false,
+    };
+    const BOTH: Polarity = Polarity {
+        pos: true,
+        neg: true,
+    };
+
+    fn flip(self) -> Self {
+        Polarity {
+            pos: self.neg,
+            neg: self.pos,
+        }
+    }
+
+    /// The polarities in `self` that are not in `other`
+    fn missing(self, other: Polarity) -> Self {
+        Polarity {
+            pos: self.pos && !other.pos,
+            neg: self.neg && !other.neg,
+        }
+    }
+
+    fn union(self, other: Polarity) -> Self {
+        Polarity {
+            pos: self.pos || other.pos,
+            neg: self.neg || other.neg,
+        }
+    }
+}
+
+/// A normalized gate used as the key for structural hashing
+#[derive(Hash, Eq, PartialEq, Clone, Debug)]
+enum Gate {
+    /// Conjunction of sorted, distinct literals
+    And(Vec<Lit>),
+    /// Exclusive or of two sorted, distinct, positive literals
+    Xor(Lit, Lit),
+    /// If-then-else with positive condition and positive then-literal
+    Ite(Lit, Lit, Lit),
+}
+
+/// Encoder transforming [`Formula`]s to CNF. The encoder keeps track of all
+/// gates it has defined, reusing them for subsequent formulas. This requires
+/// that all clauses produced by one encoder are added to the same collector.
+#[derive(Debug, Clone, Default)]
+pub struct Encoder {
+    /// The transformation to use
+    transformation: Transformation,
+    /// Mapping from gates to their output literal and the defined polarities
+    gates: RsHashMap<Gate, (Lit, Polarity)>,
+    /// A literal that is fixed to true, if one has been created
+    true_lit: Option<Lit>,
+    /// The number of clauses in the encoding
+    n_clauses: usize,
+    /// The number of variables in the encoding
+    n_vars: u32,
+}
+
+impl Encoder {
+    /// Creates a new encoder using a given transformation
+    pub fn new(transformation: Transformation) -> Self {
+        Encoder {
+            transformation,
+            ..Default::default()
+        }
+    }
+
+    /// Creates a new encoder using the Tseitin transformation
+    pub fn tseitin() -> Self {
+        Self::new(Transformation::Tseitin)
+    }
+
+    /// Creates a new encoder using the Plaisted-Greenbaum transformation
+    pub fn plaisted_greenbaum() -> Self {
+        Self::new(Transformation::PlaistedGreenbaum)
+    }
+
+    /// Gets the transformation that the encoder uses
+    pub fn transformation(&self) -> Transformation {
+        self.transformation
+    }
+
+    /// Gets the number of distinct gates that the encoder has defined
+    pub fn n_gates(&self) -> usize {
+        self.gates.len()
+    }
+
+    /// Encodes clauses enforcing that the formula holds
+    ///
+    /// # Errors
+    ///
+    /// If the clause collector runs out of memory, returns [`crate::OutOfMemory`].
+    pub fn encode<Col>(
+        &mut self,
+        formula: &Formula,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        let n_vars_before = var_manager.n_used();
+        let n_clauses_before = collector.n_clauses();
+        self.assert(formula, collector, var_manager)?;
+        self.n_clauses += collector.n_clauses() - n_clauses_before;
+        self.n_vars += var_manager.n_used() - n_vars_before;
+        Ok(())
+    }
+
+    /// Encodes the formula and returns a literal representing it. With the
+    /// Tseitin transformation, the literal is equivalent to the formula. With
+    /// the Plaisted-Greenbaum transformation, the literal only implies the
+    /// formula, i.e., it may only be used positively, e.g., as an assumption.
+    ///
+    /// # Errors
+    ///
+    /// If the clause collector runs out of memory, returns [`crate::OutOfMemory`].
+    pub fn encode_lit<Col>(
+        &mut self,
+        formula: &Formula,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<Lit, crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        let n_vars_before = var_manager.n_used();
+        let n_clauses_before = collector.n_clauses();
+        let lit = self.define(formula, self.root_polarity(), collector, var_manager)?;
+        self.n_clauses += collector.n_clauses() - n_clauses_before;
+        self.n_vars += var_manager.n_used() - n_vars_before;
+        Ok(lit)
+    }
+
+    fn root_polarity(&self) -> Polarity {
+        match self.transformation {
+            Transformation::Tseitin => Polarity::BOTH,
+            Transformation::PlaistedGreenbaum => Polarity::POS,
+        }
+    }
+
+    /// Adds clauses enforcing the formula, encoding top-level conjunctions
+    /// and disjunctions without auxiliary variables
+    fn assert<Col>(
+        &mut self,
+        formula: &Formula,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        match formula {
+            Formula::And(fs) => fs
+                .iter()
+                .try_for_each(|f| self.assert(f, collector, var_manager)),
+            Formula::Or(fs) => {
+                let pol = self.root_polarity();
+                let cl = fs
+                    .iter()
+                    .map(|f| self.define(f, pol, collector, var_manager))
+                    .collect::<Result<_, _>>()?;
+                collector.add_clause(cl)
+            }
+            Formula::Implies(a, b) => {
+                let pol = self.root_polarity();
+                let a = self.define(a, pol.flip(), collector, var_manager)?;
+                let b = self.define(b, pol, collector, var_manager)?;
+                collector.add_clause(atomics::lit_impl_lit(a, b))
+            }
+            _ => {
+                let lit = self.define(formula, self.root_polarity(), collector, var_manager)?;
+                collector.add_clause(clause![lit])
+            }
+        }
+    }
+
+    /// Recursively defines a literal representing the formula in the given
+    /// polarity
+    fn define<Col>(
+        &mut self,
+        formula: &Formula,
+        pol: Polarity,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<Lit, crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        match formula {
+            Formula::Lit(lit) => Ok(*lit),
+            Formula::Not(f) => Ok(!self.define(f, pol.flip(), collector, var_manager)?),
+            Formula::And(fs) => {
+                let lits = fs
+                    .iter()
+                    .map(|f| self.define(f, pol, collector, var_manager))
+                    .collect::<Result<_, _>>()?;
+                self.and_gate(lits, pol, collector, var_manager)
+            }
+            Formula::Or(fs) => {
+                let lits = fs
+                    .iter()
+                    .map(|f| self.define(f, pol, collector, var_manager).map(|l| !l))
+                    .collect::<Result<_, _>>()?;
+                Ok(!self.and_gate(lits, pol.flip(), collector, var_manager)?)
+            }
+            Formula::Implies(a, b) => {
+                let a = self.define(a, pol.flip(), collector, var_manager)?;
+                let b = self.define(b, pol, collector, var_manager)?;
+                Ok(!self.and_gate(vec![a, !b], pol.flip(), collector, var_manager)?)
+            }
+            Formula::Xor(a, b) => {
+                let a = self.define(a, Polarity::BOTH, collector, var_manager)?;
+                let b = self.define(b, Polarity::BOTH, collector, var_manager)?;
+                self.xor_gate(a, b, pol, collector, var_manager)
+            }
+            Formula::Iff(a, b) => {
+                let a = self.define(a, Polarity::BOTH, collector, var_manager)?;
+                let b = self.define(b, Polarity::BOTH, collector, var_manager)?;
+                Ok(!self.xor_gate(a, b, pol.flip(), collector, var_manager)?)
+            }
+            Formula::Ite(c, t, e) => {
+                let c = self.define(c, Polarity::BOTH, collector, var_manager)?;
+                let t = self.define(t, pol, collector, var_manager)?;
+                let e = self.define(e, pol, collector, var_manager)?;
+                self.ite_gate(c, t, e, pol, collector, var_manager)
+            }
+        }
+    }
+
+    /// Gets a literal that is fixed to true
+    fn true_lit<Col>(
+        &mut self,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<Lit, crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        if let Some(lit) = self.true_lit {
+            return Ok(lit);
+        }
+        let lit = var_manager.new_var().pos_lit();
+        collector.add_clause(clause![lit])?;
+        self.true_lit = Some(lit);
+        Ok(lit)
+    }
+
+    fn and_gate<Col>(
+        &mut self,
+        mut lits: Vec<Lit>,
+        pol: Polarity,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<Lit, crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        lits.sort_unstable();
+        lits.dedup();
+        // complementary literals are adjacent after sorting
+        if lits.windows(2).any(|w| w[0] == !w[1]) {
+            return Ok(!self.true_lit(collector, var_manager)?);
+        }
+        match lits.len() {
+            0 => self.true_lit(collector, var_manager),
+            1 => Ok(lits[0]),
+            _ => self.gate(Gate::And(lits), pol, collector, var_manager),
+        }
+    }
+
+    fn xor_gate<Col>(
+        &mut self,
+        a: Lit,
+        b: Lit,
+        pol: Polarity,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<Lit, crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        let negated = a.is_neg() != b.is_neg();
+        let pol = if negated { pol.flip() } else { pol };
+        let (a, b) = (a.var().pos_lit(), b.var().pos_lit());
+        let out = match a.cmp(&b) {
+            std::cmp::Ordering::Equal => !self.true_lit(collector, var_manager)?,
+            std::cmp::Ordering::Less => self.gate(Gate::Xor(a, b), pol, collector, var_manager)?,
+            std::cmp::Ordering::Greater => {
+                self.gate(Gate::Xor(b, a), pol, collector, var_manager)?
+            }
+        };
+        Ok(if negated { !out } else { out })
+    }
+
+    fn ite_gate<Col>(
+        &mut self,
+        c: Lit,
+        t: Lit,
+        e: Lit,
+        pol: Polarity,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<Lit, crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        let (c, t, e) = if c.is_neg() { (!c, e, t) } else { (c, t, e) };
+        if t == e {
+            return Ok(t);
+        }
+        if c == t || c == !e {
+            // c ? c : e = c | e, and c ? t : !c = !c | t
+            return Ok(!self.and_gate(vec![!t, !e], pol.flip(), collector, var_manager)?);
+        }
+        if c == !t || c == e {
+            // c ? !c : e = !c & e, and c ? t : c = c & t
+            return self.and_gate(vec![t, e], pol, collector, var_manager);
+        }
+        if t.is_neg() {
+            let out = self.gate(Gate::Ite(c, !t, !e), pol.flip(), collector, var_manager)?;
+            return Ok(!out);
+        }
+        self.gate(Gate::Ite(c, t, e), pol, collector, var_manager)
+    }
+
+    /// Gets the output literal of a gate, adding the clauses for all required
+    /// polarities that are not yet defined
+    fn gate<Col>(
+        &mut self,
+        gate: Gate,
+        pol: Polarity,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<Lit, crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        let (out, missing) = match self.gates.get_mut(&gate) {
+            Some((out, defined)) => {
+                let missing = pol.missing(*defined);
+                *defined = defined.union(pol);
+                (*out, missing)
+            }
+            None => {
+                let out = var_manager.new_var().pos_lit();
+                self.gates.insert(gate.clone(), (out, pol));
+                (out, pol)
+            }
+        };
+        if missing.pos {
+            match &gate {
+                Gate::And(lits) => {
+                    collector.extend_clauses(lits.iter().map(|&l| atomics::lit_impl_lit(out, l)))?
+                }
+                Gate::Xor(a, b) => collector.extend_clauses([
+                    atomics::cube_impl_clause(&[out], &[*a, *b]),
+                    atomics::cube_impl_clause(&[out, *a], &[!*b]),
+                ])?,
+                Gate::Ite(c, t, e) => collector.extend_clauses([
+                    atomics::cube_impl_lit(&[out, *c], *t),
+                    atomics::cube_impl_clause(&[out], &[*c, *e]),
+                ])?,
+            }
+        }
+        if missing.neg {
+            match &gate {
+                Gate::And(lits) => collector.add_clause(atomics::cube_impl_lit(lits, out))?,
+                Gate::Xor(a, b) => collector.extend_clauses([
+                    atomics::cube_impl_lit(&[*a, !*b], out),
+                    atomics::cube_impl_lit(&[!*a, *b], out),
+                ])?,
+                Gate::Ite(c, t, e) => collector.extend_clauses([
+                    atomics::cube_impl_lit(&[*c, *t], out),
+                    atomics::cube_impl_lit(&[!*c, *e], out),
+                ])?,
+            }
+        }
+        Ok(out)
+    }
+}
+
+impl EncodeStats for Encoder {
+    fn n_clauses(&self) -> usize {
+        self.n_clauses
+    }
+
+    fn n_vars(&self) -> u32 {
+        self.n_vars
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Encoder, Formula, Transformation};
+    use crate::{
+        clause,
+        encodings::EncodeStats,
+        instances::{BasicVarManager, Cnf, ManageVars},
+        lit,
+        types::{Assignment, Clause, TernaryVal, Var},
+        var,
+    };
+
+    fn f(idx: u32) -> Formula {
+        lit![idx].into()
+    }
+
+    fn formulas() -> Vec<Formula> {
+        vec![
+            Formula::and([f(0), !f(1), f(2)]),
+            Formula::or([f(0), Formula::and([f(1), f(2)])]),
+            !Formula::or([!f(0), f(1)]),
+            Formula::xor(f(0), Formula::or([f(1), f(2)])),
+            Formula::implies(Formula::and([f(0), f(1)]), Formula::xor(f(1), f(2))),
+            Formula::iff(Formula::xor(!f(0), f(1)), !f(2)),
+            Formula::ite(!f(0), Formula::and([f(1), f(2)]), !f(2)),
+            Formula::ite(f(0), f(0), f(1)),
+            Formula::and([
+                Formula::or([f(0), f(1)]),
+                !Formula::and([!f(0), !f(1)]),
+                Formula::iff(f(2), f(2)),
+            ]),
+            Formula::or([Formula::and([f(0), !f(0)]), Formula::xor(f(1), !f(1))]),
+            Formula::and([]),
+            Formula::or([]),
+        ]
+    }
+
+    /// Checks that exactly the assignments of the three input variables that
+    /// satisfy the formula can be extended to satisfy `cnf` and `extra`
+    fn check_models(formula: &Formula, cnf: &Cnf, n_vars: u32, extra: &[Clause]) {
+        let n_inputs = 3;
+        for in_vals in 0..1_u32 << n_inputs {
+            let assign_vars = |vals: u32, offset: u32, n: u32, assign: &mut Assignment| {
+                for idx in 0..n {
+                    assign.assign_var(
+                        Var::new(offset + idx),
+                        TernaryVal::from((vals >> idx) & 1 == 1),
+                    );
+                }
+            };
+            let mut assign = Assignment::default();
+            assign_vars(in_vals, 0, n_inputs, &mut assign);
+            let expected = formula.is_sat(&assign);
+            let n_aux = n_vars - n_inputs;
+            let extendable = (0..1_u32 << n_aux).any(|aux_vals| {
+                assign_vars(aux_vals, n_inputs, n_aux, &mut assign);
+                cnf.iter().chain(extra).all(|cl| cl.is_sat(&assign))
+            });
+            assert_eq!(expected, extendable, "{formula:?} with inputs {in_vals:b}");
+        }
+    }
+
+    #[test]
+    fn encode() {
+        for trans in [Transformation::Tseitin, Transformation::PlaistedGreenbaum] {
+            for formula in formulas() {
+                let mut var_manager = BasicVarManager::default();
+                var_manager.increase_next_free(var![3]);
+                let mut cnf = Cnf::new();
+                let mut enc = Encoder::new(trans);
+                enc.encode(&formula, &mut cnf, &mut var_manager).unwrap();
+                assert_eq!(enc.n_clauses(), cnf.len());
+                assert_eq!(enc.n_vars() + 3, var_manager.n_used());
+                check_models(&formula, &cnf, var_manager.n_used(), &[]);
+            }
+        }
+    }
+
+    #[test]
+    fn encode_lit() {
+        for formula in formulas() {
+            // Tseitin: the literal is equivalent to the formula
+            let mut var_manager = BasicVarManager::default();
+            var_manager.increase_next_free(var![3]);
+            let mut cnf = Cnf::new();
+            let mut enc = Encoder::tseitin();
+            let lit = enc
+                .encode_lit(&formula, &mut cnf, &mut var_manager)
+                .unwrap();
+            let n_vars = var_manager.n_used();
+            check_models(&formula, &cnf, n_vars, &[clause![lit]]);
+            check_models(&!formula.clone(), &cnf, n_vars, &[clause![!lit]]);
+            // Plaisted-Greenbaum: the literal implies the formula
+            let mut var_manager = BasicVarManager::default();
+            var_manager.increase_next_free(var![3]);
+            let mut cnf = Cnf::new();
+            let mut enc = Encoder::plaisted_greenbaum();
+            let lit = enc
+                .encode_lit(&formula, &mut cnf, &mut var_manager)
+                .unwrap();
+            check_models(&formula, &cnf, var_manager.n_used(), &[clause![lit]]);
+        }
+    }
+
+    #[test]
+    fn structural_hashing() {
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![3]);
+        let mut cnf = Cnf::new();
+        let mut enc = Encoder::tseitin();
+        let and = Formula::and([f(0), f(1), f(2)]);
+        let a = enc.encode_lit(&and, &mut cnf, &mut var_manager).unwrap();
+        let b = enc
+            .encode_lit(
+                &Formula::and([f(2), f(0), f(1), f(0)]),
+                &mut cnf,
+                &mut var_manager,
+            )
+            .unwrap();
+        assert_eq!(a, b);
+        let c = enc
+            .encode_lit(
+                &Formula::or([!f(0), !f(1), !f(2)]),
+                &mut cnf,
+                &mut var_manager,
+            )
+            .unwrap();
+        assert_eq!(a, !c);
+        let d = enc
+            .encode_lit(&Formula::xor(f(0), and.clone()), &mut cnf, &mut var_manager)
+            .unwrap();
+        let e = enc
+            .encode_lit(
+                &Formula::iff(!f(0), and.clone()),
+                &mut cnf,
+                &mut var_manager,
+            )
+            .unwrap();
+        assert_eq!(d, e);
+        assert_eq!(enc.n_gates(), 2);
+        assert_eq!(var_manager.n_used(), 5);
+    }
+
+    #[test]
+    fn polarity_completion() {
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![3]);
+        let mut cnf = Cnf::new();
+        let mut enc = Encoder::plaisted_greenbaum();
+        let and = Formula::and([f(0), f(1)]);
+        enc.encode(
+            &Formula::or([and.clone(), f(2)]),
+            &mut cnf,
+            &mut var_manager,
+        )
+        .unwrap();
+        // only the positive direction is needed
+        assert_eq!(cnf.len(), 3);
+        enc.encode(
+            &Formula::or([!and.clone(), f(2)]),
+            &mut cnf,
+            &mut var_manager,
+        )
+        .unwrap();
+        // the negative direction is added, the gate is reused
+        assert_eq!(cnf.len(), 5);
+        assert_eq!(enc.n_gates(), 1);
+    }
+
+    #[test]
+    fn evaluate() {
+        let mut assign = Assignment::default();
+        assign.assign_var(var![0], TernaryVal::True);
+        assign.assign_var(var![1], TernaryVal::DontCare);
+        let formula = Formula::or([f(0), f(1)]);
+        assert_eq!(formula.evaluate(&assign), TernaryVal::True);
+        let formula = Formula::and([f(0), f(1)]);
+        assert_eq!(formula.evaluate(&assign), TernaryVal::DontCare);
+        let formula = Formula::ite(f(1), f(0), f(0));
+        assert_eq!(formula.evaluate(&assign), TernaryVal::True);
+    }
+}
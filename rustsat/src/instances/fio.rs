@@ -13,6 +13,7 @@ use thiserror::Error;
 
 use crate::types::{self, Assignment};
 
+pub mod aiger;
 pub mod dimacs;
 pub mod opb;
 
@@ -0,0 +1,966 @@
+//! # Parsing and Writing AIGER Files
+//!
+//! Module for And-Inverter Graphs (AIGs) in the AIGER format, both in the
+//! ASCII (`aag`) and the binary (`aig`) variant. Parsed files are represented
+//! as an [`Aig`], which structurally hashes its and gates. An [`Aig`] can be
+//! converted to clauses with the Tseitin transformation, either for a set of
+//! literals with [`Aig::encode`] or for its properties with
+//! [`Aig::to_sat_instance`].
+//!
+//! Symbol tables and comments are ignored when parsing. Justice and fairness
+//! properties are not supported. As required by the format, the maximum
+//! variable index `M` of the header must be the number of inputs, latches and
+//! and gates in the binary variant, while the ASCII variant may have unused
+//! variable indices.
+//!
+//! ## References
+//!
+//! - [The AIGER And-Inverter Graph (AIG) Format Version 20071012](https://fmv.jku.at/aiger/FORMAT)
+//! - [AIGER 1.9 And Beyond](https://fmv.jku.at/papers/BiereHeljankoWieringa-FMV-TR-11-2.pdf)
+
+use std::{
+    fmt,
+    io::{self, BufRead, Write},
+    ops,
+    path::Path,
+};
+
+use anyhow::Context;
+use thiserror::Error;
+
+use crate::{
+    clause,
+    encodings::{atomics, CollectClauses},
+    instances::{Cnf, ManageVars, SatInstance},
+    types::{Lit, RsHashMap, RsHashSet, Var},
+};
+
+/// Errors in AIGER files
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum Error {
+    /// The header line is invalid
+    #[error("invalid aiger header '{0}'")]
+    InvalidHeader(String),
+    /// The file contains justice or fairness properties
+    #[error("justice and fairness properties are not supported")]
+    JusticeFairness,
+    /// A literal is out of range or defined in an invalid place
+    #[error("invalid literal {0}")]
+    InvalidLit(u32),
+    /// A literal is defined multiple times
+    #[error("literal {0} is defined multiple times")]
+    Redefined(u32),
+    /// A literal is used but never defined
+    #[error("literal {0} is used but never defined")]
+    Undefined(u32),
+    /// The and gates are cyclic
+    #[error("and gates contain a cycle through literal {0}")]
+    Cycle(u32),
+}
+
+/// A literal in an [`Aig`]. Variable `0` is the constant false.
+#[derive(Hash, Eq, PartialEq, PartialOrd, Ord, Clone, Copy)]
+pub struct AigLit(u32);
+
+impl AigLit {
+    /// The constant false literal
+    pub const FALSE: AigLit = AigLit(0);
+    /// The constant true literal
+    pub const TRUE: AigLit = AigLit(1);
+
+    fn new(var: u32, negated: bool) -> Self {
+        AigLit((var << 1) | u32::from(negated))
+    }
+
+    /// Gets the index of the variable of the literal
+    pub fn var(self) -> u32 {
+        self.0 >> 1
+    }
+
+    /// Checks whether the literal is negated
+    pub fn is_neg(self) -> bool {
+        self.0 & 1 == 1
+    }
+
+    /// Checks whether the literal is one of the two constants
+    pub fn is_const(self) -> bool {
+        self.var() == 0
+    }
+}
+
+impl ops::Not for AigLit {
+    type Output = AigLit;
+
+    fn not(self) -> Self::Output {
+        AigLit(self.0 ^ 1)
+    }
+}
+
+impl fmt::Display for AigLit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl fmt::Debug for AigLit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}a{}", if self.is_neg() { "~" } else { "" }, self.var())
+    }
+}
+
+/// A node in an [`Aig`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Node {
+    Const,
+    Input,
+    Latch { next: AigLit, init: Option<bool> },
+    And(AigLit, AigLit),
+}
+
+/// An And-Inverter Graph. Nodes can only refer to nodes that have been created
+/// before them, except for the next state functions of latches. And gates are
+/// structurally hashed and trivial gates are simplified away.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Aig {
+    nodes: Vec<Node>,
+    inputs: Vec<u32>,
+    latches: Vec<u32>,
+    outputs: Vec<AigLit>,
+    bad: Vec<AigLit>,
+    constraints: Vec<AigLit>,
+    strash: RsHashMap<(AigLit, AigLit), u32>,
+}
+
+impl Default for Aig {
+    fn default() -> Self {
+        Aig {
+            nodes: vec![Node::Const],
+            inputs: vec![],
+            latches: vec![],
+            outputs: vec![],
+            bad: vec![],
+            constraints: vec![],
+            strash: RsHashMap::default(),
+        }
+    }
+}
+
+impl Aig {
+    /// Creates a new empty AIG
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn new_node(&mut self, node: Node) -> AigLit {
+        let var = u32::try_from(self.nodes.len()).expect("too many nodes in aig");
+        self.nodes.push(node);
+        AigLit::new(var, false)
+    }
+
+    /// Adds a new input and returns its literal
+    pub fn add_input(&mut self) -> AigLit {
+        let lit = self.new_node(Node::Input);
+        self.inputs.push(lit.var());
+        lit
+    }
+
+    /// Adds a new latch with an initial value and returns its literal. `None`
+    /// leaves the latch uninitialized. The next state function is initially
+    /// constant false and can be set with [`Aig::set_latch_next`].
+    pub fn add_latch(&mut self, init: Option<bool>) -> AigLit {
+        let lit = self.new_node(Node::Latch {
+            next: AigLit::FALSE,
+            init,
+        });
+        self.latches.push(lit.var());
+        lit
+    }
+
+    /// Sets the next state function of a latch
+    ///
+    /// # Panics
+    ///
+    /// If `latch` is not a positive latch literal or `next` is not in the AIG.
+    pub fn set_latch_next(&mut self, latch: AigLit, next: AigLit) {
+        assert!(!latch.is_neg(), "latch literal must be positive");
+        assert!((next.var() as usize) < self.nodes.len());
+        match &mut self.nodes[latch.var() as usize] {
+            Node::Latch { next: n, .. } => *n = next,
+            _ => panic!("{latch:?} is not a latch"),
+        }
+    }
+
+    /// Gets the literal of the conjunction `a & b`
+    pub fn and(&mut self, a: AigLit, b: AigLit) -> AigLit {
+        debug_assert!((a.var() as usize) < self.nodes.len());
+        debug_assert!((b.var() as usize) < self.nodes.len());
+        if a == AigLit::FALSE || b == AigLit::FALSE || a == !b {
+            return AigLit::FALSE;
+        }
+        if a == AigLit::TRUE || a == b {
+            return b;
+        }
+        if b == AigLit::TRUE {
+            return a;
+        }
+        let key = if a < b { (a, b) } else { (b, a) };
+        if let Some(&var) = self.strash.get(&key) {
+            return AigLit::new(var, false);
+        }
+        let lit = self.new_node(Node::And(key.0, key.1));
+        self.strash.insert(key, lit.var());
+        lit
+    }
+
+    /// Gets the literal of the disjunction `a | b`
+    pub fn or(&mut self, a: AigLit, b: AigLit) -> AigLit {
+        !self.and(!a, !b)
+    }
+
+    /// Adds an output
+    pub fn add_output(&mut self, lit: AigLit) {
+        self.outputs.push(lit)
+    }
+
+    /// Adds a bad state property
+    pub fn add_bad(&mut self, lit: AigLit) {
+        self.bad.push(lit)
+    }
+
+    /// Adds an invariant constraint
+    pub fn add_constraint(&mut self, lit: AigLit) {
+        self.constraints.push(lit)
+    }
+
+    /// Gets the number of inputs
+    pub fn n_inputs(&self) -> usize {
+        self.inputs.len()
+    }
+
+    /// Gets the number of latches
+    pub fn n_latches(&self) -> usize {
+        self.latches.len()
+    }
+
+    /// Gets the number of and gates
+    pub fn n_ands(&self) -> usize {
+        self.nodes.len() - 1 - self.inputs.len() - self.latches.len()
+    }
+
+    /// Gets an iterator over the input literals
+    pub fn inputs(&self) -> impl Iterator<Item = AigLit> + '_ {
+        self.inputs.iter().map(|&var| AigLit::new(var, false))
+    }
+
+    /// Gets an iterator over the latch literals
+    pub fn latches(&self) -> impl Iterator<Item = AigLit> + '_ {
+        self.latches.iter().map(|&var| AigLit::new(var, false))
+    }
+
+    /// Gets the next state function and the initial value of a latch
+    ///
+    /// # Panics
+    ///
+    /// If `latch` is not a latch literal.
+    pub fn latch(&self, latch: AigLit) -> (AigLit, Option<bool>) {
+        match self.nodes[latch.var() as usize] {
+            Node::Latch { next, init } => (next, init),
+            _ => panic!("{latch:?} is not a latch"),
+        }
+    }
+
+    /// Gets the outputs
+    pub fn outputs(&self) -> &[AigLit] {
+        &self.outputs
+    }
+
+    /// Gets the bad state properties
+    pub fn bad(&self) -> &[AigLit] {
+        &self.bad
+    }
+
+    /// Gets the invariant constraints
+    pub fn constraints(&self) -> &[AigLit] {
+        &self.constraints
+    }
+
+    /// Parses an AIG in ASCII or binary AIGER format from a reader object.
+    /// The format is detected from the header.
+    pub fn from_aiger<R: BufRead>(reader: R) -> anyhow::Result<Self> {
+        parse_aiger(reader)
+    }
+
+    /// Parses an AIG from a file path. For more details see
+    /// [`Aig::from_aiger`]. With feature `compression` supports bzip2 and
+    /// gzip compression, detected by the file extension.
+    pub fn from_aiger_path<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let reader =
+            super::open_compressed_uncompressed_read(path).context("failed to open reader")?;
+        Aig::from_aiger(reader)
+    }
+
+    /// Writes the AIG in ASCII AIGER format
+    ///
+    /// # Performance
+    ///
+    /// For performance, consider using a [`std::io::BufWriter`] instance.
+    pub fn write_aag<W: Write>(&self, writer: &mut W) -> Result<(), io::Error> {
+        write_aiger(writer, self, false)
+    }
+
+    /// Writes the AIG in binary AIGER format
+    ///
+    /// # Performance
+    ///
+    /// For performance, consider using a [`std::io::BufWriter`] instance.
+    pub fn write_aig<W: Write>(&self, writer: &mut W) -> Result<(), io::Error> {
+        write_aiger(writer, self, true)
+    }
+
+    /// Encodes the cones of influence of the given literals to CNF with the
+    /// Tseitin transformation and returns SAT literals equivalent to them.
+    /// Inputs and latches that are not yet in `map` get fresh variables,
+    /// latches are treated as free variables representing the current state.
+    /// Nodes that are already in `map` are not encoded again, `map` should
+    /// therefore only be reused with the same collector.
+    ///
+    /// # Errors
+    ///
+    /// If the clause collector runs out of memory, returns [`crate::OutOfMemory`].
+    pub fn encode<Col>(
+        &self,
+        lits: &[AigLit],
+        map: &mut VarMap,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<Vec<Lit>, crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        map.lits.resize(self.nodes.len(), None);
+        // collect the unencoded cone, children have smaller indices than
+        // their parents, so encoding in ascending order is topological
+        let mut cone = vec![];
+        let mut stack: Vec<u32> = lits.iter().map(|l| l.var()).collect();
+        let mut seen = vec![false; self.nodes.len()];
+        while let Some(var) = stack.pop() {
+            if seen[var as usize] || map.lits[var as usize].is_some() {
+                continue;
+            }
+            seen[var as usize] = true;
+            cone.push(var);
+            if let Node::And(a, b) = self.nodes[var as usize] {
+                stack.push(a.var());
+                stack.push(b.var());
+            }
+        }
+        cone.sort_unstable();
+        for var in cone {
+            let out = var_manager.new_var().pos_lit();
+            match self.nodes[var as usize] {
+                Node::Const => collector.add_clause(clause![!out])?,
+                Node::Input | Node::Latch { .. } => (),
+                Node::And(a, b) => {
+                    let a = map.lit(a).unwrap();
+                    let b = map.lit(b).unwrap();
+                    collector.extend_clauses([
+                        atomics::lit_impl_lit(out, a),
+                        atomics::lit_impl_lit(out, b),
+                        atomics::cube_impl_lit(&[a, b], out),
+                    ])?
+                }
+            }
+            map.lits[var as usize] = Some(out);
+        }
+        Ok(lits.iter().map(|&l| map.lit(l).unwrap()).collect())
+    }
+
+    /// Converts the AIG into a satisfiability instance that is satisfiable
+    /// if and only if one of the properties can be violated in a single step,
+    /// i.e., with latches treated as free variables. If the AIG has bad state
+    /// properties, these are the properties, otherwise the outputs are.
+    /// Invariant constraints are enforced. Inputs and latches are mapped to
+    /// the first variables of the instance, in this order.
+    ///
+    /// # Panics
+    ///
+    /// If the encoding runs out of memory.
+    pub fn to_sat_instance<VM: ManageVars + Default>(&self) -> (SatInstance<VM>, VarMap) {
+        let mut var_manager = VM::default();
+        let mut map = VarMap::default();
+        map.lits.resize(self.nodes.len(), None);
+        for &var in self.inputs.iter().chain(&self.latches) {
+            map.lits[var as usize] = Some(var_manager.new_var().pos_lit());
+        }
+        let props = if self.bad.is_empty() {
+            &self.outputs
+        } else {
+            &self.bad
+        };
+        let mut cnf = Cnf::new();
+        let props = self
+            .encode(props, &mut map, &mut cnf, &mut var_manager)
+            .expect("aiger encoding ran out of memory");
+        let constrs = self
+            .encode(&self.constraints, &mut map, &mut cnf, &mut var_manager)
+            .expect("aiger encoding ran out of memory");
+        let mut inst = SatInstance::new_with_manager(var_manager);
+        cnf.into_iter().for_each(|cl| inst.add_clause(cl));
+        inst.add_nary(&props);
+        constrs.into_iter().for_each(|l| inst.add_unit(l));
+        (inst, map)
+    }
+}
+
+/// Mapping from [`Aig`] nodes to SAT literals, as produced by [`Aig::encode`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VarMap {
+    lits: Vec<Option<Lit>>,
+}
+
+impl VarMap {
+    /// Gets the SAT literal of an AIG literal, if it has been encoded
+    pub fn lit(&self, lit: AigLit) -> Option<Lit> {
+        let sat_lit = (*self.lits.get(lit.var() as usize)?)?;
+        Some(if lit.is_neg() { !sat_lit } else { sat_lit })
+    }
+
+    /// Gets the SAT variable of an AIG node, e.g., an input or a latch, if it
+    /// has been encoded
+    pub fn var(&self, lit: AigLit) -> Option<Var> {
+        self.lit(lit).map(|l| l.var())
+    }
+}
+
+/// The counts in an AIGER header
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Header {
+    binary: bool,
+    max_var: u32,
+    inputs: u32,
+    latches: u32,
+    outputs: u32,
+    ands: u32,
+    bad: u32,
+    constraints: u32,
+}
+
+/// Definitions of the variables in a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Def {
+    Input,
+    Latch,
+    And(u32, u32),
+}
+
+/// Parses an AIG in ASCII or binary AIGER format
+pub fn parse_aiger<R: BufRead>(mut reader: R) -> anyhow::Result<Aig> {
+    let mut buf = String::new();
+    read_line(&mut reader, &mut buf)?;
+    let header = parse_header(&buf)?;
+    let max_lit = header
+        .max_var
+        .checked_mul(2)
+        .and_then(|lit| lit.checked_add(1))
+        .ok_or_else(|| Error::InvalidHeader(buf.trim_end().to_string()))?;
+    let check_lit = |lit: u32| {
+        if lit > max_lit {
+            return Err(Error::InvalidLit(lit));
+        }
+        Ok(lit)
+    };
+    // the header counts are not trusted, so memory is only allocated for what
+    // is actually read
+    let mut defs: RsHashMap<u32, Def> = RsHashMap::default();
+    let mut define = |lit: u32, def: Def| {
+        let lit = check_lit(lit)?;
+        if lit & 1 == 1 || lit < 2 {
+            return Err(Error::InvalidLit(lit));
+        }
+        if defs.insert(lit >> 1, def).is_some() {
+            return Err(Error::Redefined(lit));
+        }
+        Ok(())
+    };
+    let mut inputs = Vec::new();
+    if !header.binary {
+        for _ in 0..header.inputs {
+            let [lit] = parse_line(&mut reader, &mut buf)?;
+            define(lit, Def::Input)?;
+            inputs.push(lit);
+        }
+    }
+    let mut latches = Vec::new();
+    for idx in 0..header.latches {
+        read_line(&mut reader, &mut buf)?;
+        let nums = parse_nums(&buf)?;
+        let (lit, rest) = if header.binary {
+            (2 * (header.inputs + idx + 1), &nums[..])
+        } else {
+            anyhow::ensure!(!nums.is_empty(), "empty latch line");
+            (nums[0], &nums[1..])
+        };
+        let (next, init) = match rest {
+            [next] => (*next, Some(false)),
+            [next, 0] => (*next, Some(false)),
+            [next, 1] => (*next, Some(true)),
+            [next, reset] if *reset == lit => (*next, None),
+            _ => anyhow::bail!("invalid latch line '{}'", buf.trim_end()),
+        };
+        define(lit, Def::Latch)?;
+        latches.push((lit, check_lit(next)?, init));
+    }
+    let mut read_lits = |n: u32| -> anyhow::Result<Vec<u32>> {
+        (0..n)
+            .map(|_| {
+                let [lit] = parse_line(&mut reader, &mut buf)?;
+                Ok(check_lit(lit)?)
+            })
+            .collect()
+    };
+    let outputs = read_lits(header.outputs)?;
+    let bad = read_lits(header.bad)?;
+    let constraints = read_lits(header.constraints)?;
+    for idx in 0..header.ands {
+        let (lhs, rhs0, rhs1) = if header.binary {
+            let lhs = 2 * (header.inputs + header.latches + idx + 1);
+            let rhs0 = lhs
+                .checked_sub(read_varint(&mut reader)?)
+                .ok_or(Error::InvalidLit(lhs))?;
+            let rhs1 = rhs0
+                .checked_sub(read_varint(&mut reader)?)
+                .ok_or(Error::InvalidLit(lhs))?;
+            anyhow::ensure!(rhs0 < lhs, Error::InvalidLit(lhs));
+            (lhs, rhs0, rhs1)
+        } else {
+            let [lhs, rhs0, rhs1] = parse_line(&mut reader, &mut buf)?;
+            (lhs, check_lit(rhs0)?, check_lit(rhs1)?)
+        };
+        define(lhs, Def::And(rhs0, rhs1))?;
+    }
+    if header.binary {
+        // the inputs of the binary format are implicitly the first variables
+        // and are only added once the rest of the file is read
+        inputs.extend((1..=header.inputs).map(|var| 2 * var));
+    }
+
+    // build the aig, structurally hashing the and gates
+    let mut aig = Aig::new();
+    let mut map: RsHashMap<u32, AigLit> = RsHashMap::default();
+    map.insert(0, AigLit::FALSE);
+    for lit in inputs {
+        map.insert(lit >> 1, aig.add_input());
+    }
+    for &(lit, _, init) in &latches {
+        map.insert(lit >> 1, aig.add_latch(init));
+    }
+    let mut on_stack = RsHashSet::default();
+    let mut resolve = |lit: u32| resolve_lit(lit, &defs, &mut map, &mut on_stack, &mut aig);
+    let latches: Vec<_> = latches
+        .into_iter()
+        .map(|(lit, next, _)| Ok((lit, resolve(next)?)))
+        .collect::<Result<_, Error>>()?;
+    let outputs: Vec<_> = outputs
+        .into_iter()
+        .map(&mut resolve)
+        .collect::<Result<_, _>>()?;
+    let bad: Vec<_> = bad
+        .into_iter()
+        .map(&mut resolve)
+        .collect::<Result<_, _>>()?;
+    let constraints: Vec<_> = constraints
+        .into_iter()
+        .map(&mut resolve)
+        .collect::<Result<_, _>>()?;
+    for (lit, next) in latches {
+        let latch = map[&(lit >> 1)];
+        aig.set_latch_next(latch, next);
+    }
+    outputs.into_iter().for_each(|l| aig.add_output(l));
+    bad.into_iter().for_each(|l| aig.add_bad(l));
+    constraints.into_iter().for_each(|l| aig.add_constraint(l));
+    Ok(aig)
+}
+
+/// Maps a literal from the file to the AIG, adding the and gates in its cone
+/// in depth-first order. `on_stack` needs to be empty and is empty again when
+/// the literal is resolved successfully.
+fn resolve_lit(
+    lit: u32,
+    defs: &RsHashMap<u32, Def>,
+    map: &mut RsHashMap<u32, AigLit>,
+    on_stack: &mut RsHashSet<u32>,
+    aig: &mut Aig,
+) -> Result<AigLit, Error> {
+    let map_lit = |map: &RsHashMap<u32, AigLit>, lit: u32| {
+        map.get(&(lit >> 1))
+            .map(|&l| if lit & 1 == 1 { !l } else { l })
+    };
+    // the stack is always a path in the graph, so a node that is on the
+    // stack is reached again only through a cycle
+    let mut stack = vec![lit >> 1];
+    while let Some(&var) = stack.last() {
+        if map.contains_key(&var) {
+            stack.pop();
+            continue;
+        }
+        let Some(&Def::And(rhs0, rhs1)) = defs.get(&var) else {
+            return Err(Error::Undefined(2 * var));
+        };
+        on_stack.insert(var);
+        match (map_lit(map, rhs0), map_lit(map, rhs1)) {
+            (Some(a), Some(b)) => {
+                map.insert(var, aig.and(a, b));
+                on_stack.remove(&var);
+                stack.pop();
+            }
+            (None, _) | (_, None) => {
+                let child = if map_lit(map, rhs0).is_none() {
+                    rhs0 >> 1
+                } else {
+                    rhs1 >> 1
+                };
+                if on_stack.contains(&child) {
+                    return Err(Error::Cycle(2 * child));
+                }
+                stack.push(child);
+            }
+        }
+    }
+    Ok(map_lit(map, lit).unwrap())
+}
+
+fn parse_header(line: &str) -> anyhow::Result<Header> {
+    let invalid = || Error::InvalidHeader(line.trim_end().to_string());
+    let mut tokens = line.split_whitespace();
+    let binary = match tokens.next() {
+        Some("aag") => false,
+        Some("aig") => true,
+        _ => return Err(invalid().into()),
+    };
+    let nums = tokens
+        .map(|tok| tok.parse::<u32>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| invalid())?;
+    anyhow::ensure!((5..=9).contains(&nums.len()), invalid());
+    let get = |idx: usize| nums.get(idx).copied().unwrap_or(0);
+    anyhow::ensure!(get(7) == 0 && get(8) == 0, Error::JusticeFairness);
+    let header = Header {
+        binary,
+        max_var: nums[0],
+        inputs: nums[1],
+        latches: nums[2],
+        outputs: nums[3],
+        ands: nums[4],
+        bad: get(5),
+        constraints: get(6),
+    };
+    // the binary format requires `M = I + L + A`, the ASCII format only that
+    // `M` is large enough for all variables. All literals need to fit into 32
+    // bits.
+    let n_defined = u64::from(header.inputs) + u64::from(header.latches) + u64::from(header.ands);
+    if binary {
+        anyhow::ensure!(u64::from(header.max_var) == n_defined, invalid());
+    } else {
+        anyhow::ensure!(u64::from(header.max_var) >= n_defined, invalid());
+    }
+    anyhow::ensure!(header.max_var < 1 << 31, invalid());
+    Ok(header)
+}
+
+/// Reads a line into the buffer, failing at the end of the input
+fn read_line<R: BufRead>(reader: &mut R, buf: &mut String) -> anyhow::Result<()> {
+    buf.clear();
+    anyhow::ensure!(reader.read_line(buf)? > 0, "unexpected end of aiger file");
+    Ok(())
+}
+
+fn parse_nums(line: &str) -> anyhow::Result<Vec<u32>> {
+    line.split_whitespace()
+        .map(|tok| {
+            tok.parse::<u32>()
+                .with_context(|| format!("invalid number in aiger line '{}'", line.trim_end()))
+        })
+        .collect()
+}
+
+/// Reads a line with exactly `N` numbers
+fn parse_line<R: BufRead, const N: usize>(
+    reader: &mut R,
+    buf: &mut String,
+) -> anyhow::Result<[u32; N]> {
+    read_line(reader, buf)?;
+    let nums = parse_nums(buf)?;
+    nums.try_into()
+        .map_err(|_| anyhow::anyhow!("expected {N} numbers in aiger line '{}'", buf.trim_end()))
+}
+
+/// Reads a variable-length encoded delta of the binary format
+fn read_varint<R: BufRead>(reader: &mut R) -> anyhow::Result<u32> {
+    let mut val: u64 = 0;
+    let mut shift = 0;
+    loop {
+        let mut byte = [0; 1];
+        reader
+            .read_exact(&mut byte)
+            .context("unexpected end of binary and gates")?;
+        val |= u64::from(byte[0] & 0x7f) << shift;
+        anyhow::ensure!(
+            val <= u64::from(u32::MAX),
+            "delta in binary aiger out of range"
+        );
+        if byte[0] & 0x80 == 0 {
+            return Ok(val as u32);
+        }
+        shift += 7;
+        // a `u32` takes at most five bytes
+        anyhow::ensure!(shift <= 28, "delta in binary aiger out of range");
+    }
+}
+
+/// Writes a variable-length encoded delta of the binary format
+fn write_varint<W: Write>(writer: &mut W, mut val: u32) -> Result<(), io::Error> {
+    while val & !0x7f != 0 {
+        writer.write_all(&[(val & 0x7f) as u8 | 0x80])?;
+        val >>= 7;
+    }
+    writer.write_all(&[val as u8])
+}
+
+/// Writes an AIG in ASCII or binary AIGER format. Variables are renumbered so
+/// that inputs come first, followed by latches and and gates.
+pub fn write_aiger<W: Write>(writer: &mut W, aig: &Aig, binary: bool) -> Result<(), io::Error> {
+    let mut file_vars = vec![0; aig.nodes.len()];
+    let mut next = 1;
+    for &var in aig.inputs.iter().chain(&aig.latches) {
+        file_vars[var as usize] = next;
+        next += 1;
+    }
+    for (var, node) in aig.nodes.iter().enumerate() {
+        if matches!(node, Node::And(..)) {
+            file_vars[var] = next;
+            next += 1;
+        }
+    }
+    let file_lit = |lit: AigLit| 2 * file_vars[lit.var() as usize] + u32::from(lit.is_neg());
+    write!(
+        writer,
+        "{} {} {} {} {} {}",
+        if binary { "aig" } else { "aag" },
+        next - 1,
+        aig.n_inputs(),
+        aig.n_latches(),
+        aig.outputs.len(),
+        aig.n_ands()
+    )?;
+    if !aig.bad.is_empty() || !aig.constraints.is_empty() {
+        write!(writer, " {} {}", aig.bad.len(), aig.constraints.len())?;
+    }
+    writeln!(writer)?;
+    if !binary {
+        aig.inputs()
+            .try_for_each(|lit| writeln!(writer, "{}", file_lit(lit)))?;
+    }
+    for latch in aig.latches() {
+        if !binary {
+            write!(writer, "{} ", file_lit(latch))?;
+        }
+        let (next, init) = aig.latch(latch);
+        match init {
+            Some(false) => writeln!(writer, "{}", file_lit(next))?,
+            Some(true) => writeln!(writer, "{} 1", file_lit(next))?,
+            None => writeln!(writer, "{} {}", file_lit(next), file_lit(latch))?,
+        }
+    }
+    aig.outputs
+        .iter()
+        .chain(&aig.bad)
+        .chain(&aig.constraints)
+        .try_for_each(|&lit| writeln!(writer, "{}", file_lit(lit)))?;
+    for (var, node) in aig.nodes.iter().enumerate() {
+        if let Node::And(a, b) = *node {
+            let lhs = 2 * file_vars[var];
+            let (rhs0, rhs1) = (file_lit(a), file_lit(b));
+            let (rhs0, rhs1) = if rhs0 >= rhs1 {
+                (rhs0, rhs1)
+            } else {
+                (rhs1, rhs0)
+            };
+            if binary {
+                write_varint(writer, lhs - rhs0)?;
+                write_varint(writer, rhs0 - rhs1)?;
+            } else {
+                writeln!(writer, "{} {} {}", lhs, rhs0, rhs1)?;
+            }
+        }
+    }
+    writeln!(writer, "c")?;
+    writeln!(writer, "AIGER file written by RustSAT")?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::{parse_aiger, Aig, AigLit, Error};
+    use crate::{
+        instances::{BasicVarManager, SatInstance},
+        types::{Assignment, TernaryVal},
+    };
+
+    /// A half adder with a latch storing the carry
+    const HALF_ADDER: &str =
+        "aag 7 2 1 2 4 1\n2\n4\n6 12 1\n6\n10\n10\n8 2 4\n10 13 15\n12 2 4\n14 3 5\n";
+
+    fn eval(aig: &Aig, lit: AigLit, vals: &[bool]) -> bool {
+        let val = match aig.nodes[lit.var() as usize] {
+            super::Node::Const => false,
+            super::Node::Input | super::Node::Latch { .. } => {
+                let idx = aig
+                    .inputs
+                    .iter()
+                    .chain(&aig.latches)
+                    .position(|&v| v == lit.var())
+                    .unwrap();
+                vals[idx]
+            }
+            super::Node::And(a, b) => eval(aig, a, vals) && eval(aig, b, vals),
+        };
+        val != lit.is_neg()
+    }
+
+    #[test]
+    fn parse_ascii() {
+        let aig = parse_aiger(Cursor::new(HALF_ADDER)).unwrap();
+        assert_eq!(aig.n_inputs(), 2);
+        assert_eq!(aig.n_latches(), 1);
+        // gates 8 and 12 are identical
+        assert_eq!(aig.n_ands(), 3);
+        assert_eq!(aig.outputs().len(), 2);
+        assert_eq!(aig.bad(), &[aig.outputs()[1]]);
+        let latch = aig.latches().next().unwrap();
+        let (next, init) = aig.latch(latch);
+        assert_eq!(init, Some(true));
+        for vals in 0..8_u32 {
+            let vals: Vec<bool> = (0..3).map(|idx| (vals >> idx) & 1 == 1).collect();
+            assert_eq!(eval(&aig, next, &vals), vals[0] && vals[1]);
+            assert_eq!(eval(&aig, aig.outputs()[0], &vals), vals[2]);
+            assert_eq!(eval(&aig, aig.outputs()[1], &vals), vals[0] ^ vals[1]);
+        }
+    }
+
+    #[test]
+    fn write_parse_roundtrip() {
+        let aig = parse_aiger(Cursor::new(HALF_ADDER)).unwrap();
+        for binary in [false, true] {
+            let mut buf = Vec::new();
+            super::write_aiger(&mut buf, &aig, binary).unwrap();
+            let parsed = parse_aiger(Cursor::new(buf)).unwrap();
+            assert_eq!(parsed, aig);
+        }
+    }
+
+    #[test]
+    fn parse_binary() {
+        // and gate 6 = 2 & 4 with deltas 2 and 2
+        let mut data = b"aig 3 2 0 1 1\n6\n".to_vec();
+        data.extend([2, 2]);
+        data.extend(b"i0 a\nc\ncomment\n");
+        let aig = parse_aiger(Cursor::new(data)).unwrap();
+        assert_eq!(aig.n_ands(), 1);
+        let mut expected = Aig::new();
+        let a = expected.add_input();
+        let b = expected.add_input();
+        let and = expected.and(a, b);
+        expected.add_output(and);
+        assert_eq!(aig, expected);
+    }
+
+    #[test]
+    fn parse_errors() {
+        let err = |data: &str| {
+            parse_aiger(Cursor::new(data))
+                .unwrap_err()
+                .downcast::<Error>()
+                .unwrap()
+        };
+        assert_eq!(err("aag 1 1 0 0 0 0 0 1\n2\n"), Error::JusticeFairness);
+        assert_eq!(err("aag 1 1 0 1 0\n2\n4\n"), Error::InvalidLit(4));
+        assert_eq!(err("aag 2 1 0 1 0\n2\n4\n"), Error::Undefined(4));
+        assert_eq!(
+            err("aig 2 1 0 1 0\n4\n"),
+            Error::InvalidHeader("aig 2 1 0 1 0".to_string())
+        );
+        assert_eq!(
+            err("aag 4294967295 1 0 0 0\n2\n"),
+            Error::InvalidHeader("aag 4294967295 1 0 0 0".to_string())
+        );
+        assert_eq!(
+            err("aig 2147483648 2147483648 0 0 0\n"),
+            Error::InvalidHeader("aig 2147483648 2147483648 0 0 0".to_string())
+        );
+        assert_eq!(err("aag 2 2 0 0 0\n2\n2\n"), Error::Redefined(2));
+        assert_eq!(err("aag 3 1 0 1 2\n2\n6\n4 2 6\n6 2 5\n"), Error::Cycle(6));
+    }
+
+    #[test]
+    fn parse_unused_vars() {
+        // variable 2 is not used in the ascii format
+        let aig = parse_aiger(Cursor::new("aag 3 1 0 1 1\n2\n6\n6 2 3\n")).unwrap();
+        assert_eq!(aig.n_inputs(), 1);
+        assert_eq!(aig.outputs(), &[AigLit::FALSE]);
+    }
+
+    #[test]
+    fn parse_malformed() {
+        // overlong delta
+        let mut data = b"aig 3 2 0 1 1\n6\n".to_vec();
+        data.extend([0x80; 12]);
+        assert!(parse_aiger(Cursor::new(data)).is_err());
+        // counts in the header do not allocate memory before the lines are read
+        assert!(parse_aiger(Cursor::new("aag 1500000000 1500000000 0 0 0\n")).is_err());
+        assert!(parse_aiger(Cursor::new("aig 2000000000 1000000000 0 0 1000000000\n")).is_err());
+    }
+
+    #[test]
+    fn strash() {
+        let mut aig = Aig::new();
+        let a = aig.add_input();
+        let b = aig.add_input();
+        let and = aig.and(a, b);
+        assert_eq!(aig.and(b, a), and);
+        assert_eq!(aig.or(!a, !b), !and);
+        assert_eq!(aig.and(a, !a), AigLit::FALSE);
+        assert_eq!(aig.and(a, AigLit::TRUE), a);
+        assert_eq!(aig.and(a, a), a);
+        assert_eq!(aig.n_ands(), 1);
+    }
+
+    #[test]
+    fn to_sat_instance() {
+        let aig = parse_aiger(Cursor::new(HALF_ADDER)).unwrap();
+        let (inst, map): (SatInstance<BasicVarManager>, _) = aig.to_sat_instance();
+        // inputs and latches are mapped first
+        let vars: Vec<_> = aig
+            .inputs()
+            .chain(aig.latches())
+            .map(|l| map.var(l).unwrap().idx32())
+            .collect();
+        assert_eq!(vars, vec![0, 1, 2]);
+        // the bad state property (sum) holds iff the inputs differ
+        let n_vars = inst.n_vars();
+        for vals in 0..8_u32 {
+            let extendable = (0..1_u32 << (n_vars - 3)).any(|aux| {
+                let all = vals | (aux << 3);
+                let assign = Assignment::from(
+                    (0..n_vars)
+                        .map(|idx| TernaryVal::from((all >> idx) & 1 == 1))
+                        .collect::<Vec<_>>(),
+                );
+                inst.is_sat(&assign)
+            });
+            assert_eq!(extendable, (vals & 1 == 1) != (vals & 2 == 2));
+        }
+    }
+}
@@ -356,6 +356,7 @@ where
     W: Write,
     VM: ManageVars,
 {
+    check_no_xors(inst)?;
     writeln!(
         writer,
         "* #variable = {} #constraint= {}",
@@ -394,6 +395,7 @@ where
     LI: WLitIter,
     VM: ManageVars,
 {
+    check_no_xors(constrs)?;
     let cnf = &constrs.cnf;
     let cards = &constrs.cards;
     let pbs = &constrs.pbs;
@@ -434,6 +436,7 @@ where
     Iter: Iterator<Item = (LI, isize)>,
     LI: WLitIter,
 {
+    check_no_xors(constrs)?;
     let cnf = &constrs.cnf;
     let cards = &constrs.cards;
     let pbs = &constrs.pbs;
@@ -462,6 +465,18 @@ where
     writer.flush()
 }
 
+/// Checks that an instance does not contain XOR constraints, which can not be
+/// represented in OPB
+fn check_no_xors<VM: ManageVars>(constrs: &SatInstance<VM>) -> Result<(), io::Error> {
+    if constrs.n_xors() > 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "XOR constraints can not be written to OPB and need to be converted first",
+        ));
+    }
+    Ok(())
+}
+
 /// Writes a clause to an OPB file
 fn write_clause<W: Write>(writer: &mut W, clause: &Clause, opts: Options) -> Result<(), io::Error> {
     if opts.no_negated_lits {
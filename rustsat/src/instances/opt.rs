@@ -1217,7 +1217,7 @@ impl<VM: ManageVars> OptInstance<VM> {
     /// Writes the instance to a DIMACS WCNF file at a path
     ///
     /// This requires that the instance is clausal, i.e., does not contain any non-converted
-    /// cardinality of pseudo-boolean constraints. If necessary, the instance can be converted by
+    /// cardinality, pseudo-boolean or XOR constraints. If necessary, the instance can be converted by
     /// [`SatInstance::convert_to_cnf`] or [`SatInstance::convert_to_cnf_with_encoders`] first.
     ///
     /// # Errors
@@ -1232,7 +1232,7 @@ impl<VM: ManageVars> OptInstance<VM> {
     /// Write to DIMACS WCNF (post 22)
     ///
     /// This requires that the instance is clausal, i.e., does not contain any non-converted
-    /// cardinality of pseudo-boolean constraints. If necessary, the instance can be converted by
+    /// cardinality, pseudo-boolean or XOR constraints. If necessary, the instance can be converted by
     /// [`SatInstance::convert_to_cnf`] or [`SatInstance::convert_to_cnf_with_encoders`] first.
     ///
     /// # Performance
@@ -1244,7 +1244,7 @@ impl<VM: ManageVars> OptInstance<VM> {
     /// - If the instance is not clausal, returns [`RequiresClausal`]
     /// - Returns [`io::Error`] on errors during writing
     pub fn write_dimacs<W: io::Write>(&self, writer: &mut W) -> anyhow::Result<()> {
-        if self.constrs.n_cards() > 0 || self.constrs.n_pbs() > 0 {
+        if self.constrs.n_cards() > 0 || self.constrs.n_pbs() > 0 || self.constrs.n_xors() > 0 {
             return Err(RequiresClausal.into());
         }
         let n_vars = self.constrs.n_vars();
@@ -4,10 +4,10 @@ use std::{cmp, collections::TryReserveError, io, ops::Index, path::Path};
 
 use crate::{
     clause,
-    encodings::{atomics, card, pb, CollectClauses},
+    encodings::{atomics, card, pb, xor, CollectClauses, Reification},
     lit,
     types::{
-        constraints::{CardConstraint, PBConstraint},
+        constraints::{CardConstraint, PBConstraint, XorConstraint},
         Assignment, Clause, Lit, Var,
     },
     utils::LimitedIter,
@@ -284,12 +284,14 @@ impl Index<usize> for Cnf {
 }
 
 /// Type representing a satisfiability instance. Supported constraints are
-/// clauses, cardinality constraints and pseudo-boolean constraints.
+/// clauses, cardinality constraints, pseudo-boolean constraints and XOR
+/// constraints.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct SatInstance<VM: ManageVars = BasicVarManager> {
     pub(super) cnf: Cnf,
     pub(super) cards: Vec<CardConstraint>,
     pub(super) pbs: Vec<PBConstraint>,
+    pub(super) xors: Vec<XorConstraint>,
     pub(super) var_manager: VM,
 }
 
@@ -300,6 +302,7 @@ impl<VM: ManageVars> SatInstance<VM> {
             cnf: Cnf::new(),
             cards: vec![],
             pbs: vec![],
+            xors: vec![],
             var_manager,
         }
     }
@@ -319,6 +322,11 @@ impl<VM: ManageVars> SatInstance<VM> {
         self.pbs.len()
     }
 
+    /// Returns the number of XOR constraints in the instance
+    pub fn n_xors(&self) -> usize {
+        self.xors.len()
+    }
+
     /// Adds a clause to the instance
     pub fn add_clause(&mut self, cl: Clause) {
         cl.iter().for_each(|l| {
@@ -450,6 +458,61 @@ impl<VM: ManageVars> SatInstance<VM> {
         self.pbs.push(pb)
     }
 
+    /// Adds an XOR constraint
+    pub fn add_xor_constr(&mut self, xor: XorConstraint) {
+        xor.iter().for_each(|l| {
+            self.var_manager.mark_used(l.var());
+        });
+        self.xors.push(xor)
+    }
+
+    /// Adds a reified cardinality constraint, i.e., `act -> card` for
+    /// [`Reification::Half`] and `act <-> card` for [`Reification::Full`].
+    /// Other than with [`Self::add_card_constr`], the constraint is directly
+    /// encoded to clauses with
+    /// [`card::default_encode_reified_cardinality_constraint`].
+    ///
+    /// # Panic
+    ///
+    /// This might panic if the encoding runs out of memory.
+    pub fn add_reified_card_constr(&mut self, card: CardConstraint, act: Lit, reif: Reification) {
+        self.var_manager.mark_used(act.var());
+        card.iter().for_each(|l| {
+            self.var_manager.mark_used(l.var());
+        });
+        card::default_encode_reified_cardinality_constraint(
+            card,
+            act,
+            reif,
+            &mut self.cnf,
+            &mut self.var_manager,
+        )
+        .expect("cardinality encoding ran out of memory")
+    }
+
+    /// Adds a reified pseudo-boolean constraint, i.e., `act -> pb` for
+    /// [`Reification::Half`] and `act <-> pb` for [`Reification::Full`].
+    /// Other than with [`Self::add_pb_constr`], the constraint is directly
+    /// encoded to clauses with [`pb::default_encode_reified_pb_constraint`].
+    ///
+    /// # Panic
+    ///
+    /// This might panic if the encoding runs out of memory.
+    pub fn add_reified_pb_constr(&mut self, pb: PBConstraint, act: Lit, reif: Reification) {
+        self.var_manager.mark_used(act.var());
+        pb.iter().for_each(|(l, _)| {
+            self.var_manager.mark_used(l.var());
+        });
+        pb::default_encode_reified_pb_constraint(
+            pb,
+            act,
+            reif,
+            &mut self.cnf,
+            &mut self.var_manager,
+        )
+        .expect("pb encoding ran out of memory")
+    }
+
     /// Gets a reference to the internal CNF
     pub fn cnf(&self) -> &Cnf {
         &self.cnf
@@ -508,6 +571,7 @@ impl<VM: ManageVars> SatInstance<VM> {
                 cnf: self.cnf,
                 cards: self.cards,
                 pbs: self.pbs,
+                xors: self.xors,
                 var_manager: vm_converter(&self.var_manager),
             },
             self.var_manager,
@@ -606,7 +670,8 @@ impl<VM: ManageVars> SatInstance<VM> {
     }
 
     /// Converts the instance inplace to a set of clauses with explicitly specified
-    /// converters for non-clausal constraints.
+    /// converters for non-clausal constraints. XOR constraints are encoded with
+    /// [`xor::default_encode_xor_constraint`].
     ///
     /// See [`Self::into_cnf_with_encoders`] if you don't need to convert in place
     ///
@@ -627,6 +692,10 @@ impl<VM: ManageVars> SatInstance<VM> {
         self.pbs
             .drain(..)
             .for_each(|constr| pb_encoder(constr, &mut self.cnf, &mut self.var_manager));
+        self.xors.drain(..).for_each(|constr| {
+            xor::default_encode_xor_constraint(constr, &mut self.cnf, &mut self.var_manager)
+                .expect("xor encoding ran out of memory")
+        });
     }
 
     /// Extends the instance by another instance
@@ -647,10 +716,14 @@ impl<VM: ManageVars> SatInstance<VM> {
             pb.iter_mut()
                 .for_each(|(l, _)| *l = reindexer.reindex_lit(*l))
         });
+        self.xors
+            .iter_mut()
+            .for_each(|xor| xor.iter_mut().for_each(|l| *l = reindexer.reindex_lit(*l)));
         SatInstance {
             cnf: self.cnf,
             cards: self.cards,
             pbs: self.pbs,
+            xors: self.xors,
             var_manager: reindexer,
         }
     }
@@ -663,6 +736,7 @@ impl<VM: ManageVars> SatInstance<VM> {
         let mut rng = rand::thread_rng();
         self.cards[..].shuffle(&mut rng);
         self.pbs[..].shuffle(&mut rng);
+        self.xors[..].shuffle(&mut rng);
         self
     }
 
@@ -719,7 +793,7 @@ impl<VM: ManageVars> SatInstance<VM> {
     /// Writes the instance to a DIMACS CNF file at a path
     ///
     /// This requires that the instance is clausal, i.e., does not contain any non-converted
-    /// cardinality of pseudo-boolean constraints. If necessary, the instance can be converted by
+    /// cardinality, pseudo-boolean or XOR constraints. If necessary, the instance can be converted by
     /// [`Self::convert_to_cnf`] or [`Self::convert_to_cnf_with_encoders`] first.
     ///
     /// # Errors
@@ -734,7 +808,7 @@ impl<VM: ManageVars> SatInstance<VM> {
     /// Writes the instance to DIMACS CNF
     ///
     /// This requires that the instance is clausal, i.e., does not contain any non-converted
-    /// cardinality of pseudo-boolean constraints. If necessary, the instance can be converted by
+    /// cardinality, pseudo-boolean or XOR constraints. If necessary, the instance can be converted by
     /// [`Self::convert_to_cnf`] or [`Self::convert_to_cnf_with_encoders`] first.
     ///
     /// # Performance
@@ -746,7 +820,7 @@ impl<VM: ManageVars> SatInstance<VM> {
     /// - If the instance is not clausal, returns [`RequiresClausal`]
     /// - Returns [`io::Error`] on errors during writing
     pub fn write_dimacs<W: io::Write>(&self, writer: &mut W) -> anyhow::Result<()> {
-        if self.n_cards() > 0 || self.n_pbs() > 0 {
+        if self.n_cards() > 0 || self.n_pbs() > 0 || self.n_xors() > 0 {
             return Err(RequiresClausal.into());
         }
         let n_vars = self.n_vars();
@@ -794,6 +868,12 @@ impl<VM: ManageVars> SatInstance<VM> {
     /// # Performance
     ///
     /// For performance, consider using a [`std::io::BufWriter`] instance.
+    ///
+    /// # Errors
+    ///
+    /// - If the instance contains XOR constraints, returns an [`io::Error`] of
+    ///   kind [`io::ErrorKind::InvalidInput`]
+    /// - Returns [`io::Error`] on errors during writing
     pub fn write_opb<W: io::Write>(
         &self,
         writer: &mut W,
@@ -872,11 +952,31 @@ impl<VM: ManageVars> SatInstance<VM> {
                 Some(card)
             })
             .collect();
+        let xors = self
+            .xors
+            .into_iter()
+            .filter_map(|xor| {
+                let xor = xor.normalize();
+                if xor.is_tautology() {
+                    return None;
+                }
+                if xor.is_unsat() {
+                    unsat = true;
+                    return None;
+                }
+                if xor.is_clause() {
+                    cnf.add_clause(xor.into_clause().unwrap());
+                    return None;
+                }
+                Some(xor)
+            })
+            .collect();
         if unsat {
             return Self {
                 cnf: Cnf::from_iter(vec![clause![lit![0]], clause![!lit![0]]]),
                 cards: vec![],
                 pbs: vec![],
+                xors: vec![],
                 var_manager: self.var_manager,
             };
         }
@@ -884,6 +984,7 @@ impl<VM: ManageVars> SatInstance<VM> {
             cnf: Cnf::from_iter(cnf.into_iter().filter_map(|cl| cl.sanitize())),
             cards,
             pbs,
+            xors,
             var_manager: self.var_manager,
         }
     }
@@ -905,6 +1006,11 @@ impl<VM: ManageVars> SatInstance<VM> {
                 return false;
             }
         }
+        for xor in &self.xors {
+            if !xor.is_sat(assign) {
+                return false;
+            }
+        }
         true
     }
 }
@@ -965,6 +1071,7 @@ impl<VM: ManageVars + Default> Default for SatInstance<VM> {
             cnf: Default::default(),
             cards: Default::default(),
             pbs: Default::default(),
+            xors: Default::default(),
             var_manager: VM::default(),
         }
     }
@@ -73,6 +73,7 @@ use std::collections::TryReserveError;
 use thiserror::Error;
 
 pub mod encodings;
+pub mod formula;
 pub mod instances;
 pub mod solvers;
 pub mod types;
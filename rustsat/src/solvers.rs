@@ -81,6 +81,16 @@
 //! [IPASIR](https://github.com/biotomas/ipasir) is a C API for incremental SAT
 //! solvers. IPASIR bindings for rustsat are provided in the
 //! [`rustsat-ipasir`](https://crates.io/crates/rustsat-ipasir) crate.
+//!
+//! ### External Solvers
+//!
+//! Any SAT solver binary following the output format of the SAT competition
+//! can be run as a separate process with [`external::ExternalSolver`].
+//!
+//! ### Portfolios
+//!
+//! Multiple solvers can be raced against each other on separate threads with
+//! a [`Portfolio`].
 
 use crate::{
     clause,
@@ -93,6 +103,10 @@ use core::time::Duration;
 use std::fmt;
 use thiserror::Error;
 
+pub mod external;
+pub mod portfolio;
+pub use portfolio::Portfolio;
+
 /// Trait for all SAT solvers in this library.
 /// Solvers outside of this library can also implement this trait to be able to
 /// use them with this library.
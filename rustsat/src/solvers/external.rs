@@ -0,0 +1,513 @@
+//! # External Solver Processes
+//!
+//! Interface to SAT solvers that are run as separate processes. The
+//! [`ExternalSolver`] writes the clauses added to it to a DIMACS file, calls a
+//! solver binary on it and parses the solver output with
+//! [`crate::instances::fio::parse_sat_solver_output`]. Any solver binary
+//! following the output format of the SAT competition can therefore be used
+//! without bindings.
+//!
+//! ## Example Useage
+//!
+//! ```no_run
+//! # use rustsat::{
+//! #     lit,
+//! #     solvers::{external::ExternalSolver, Solve, SolverResult},
+//! # };
+//! # use std::time::Duration;
+//! #
+//! let mut solver = ExternalSolver::new("kissat");
+//! solver.arg("--quiet");
+//! solver.set_time_limit(Some(Duration::from_secs(60)));
+//! solver.add_binary(lit![0], lit![1]).unwrap();
+//! solver.add_unit(!lit![0]).unwrap();
+//! assert_eq!(solver.solve().unwrap(), SolverResult::Sat);
+//! ```
+
+use std::{
+    ffi::OsString,
+    fs::{self, File},
+    io::{self, BufReader},
+    path::{Path, PathBuf},
+    process::{Child, Command, ExitStatus, Stdio},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use thiserror::Error;
+
+use super::{
+    Interrupt, InterruptSolver, Solve, SolveStats, SolverResult, SolverState, SolverStats,
+    StateError,
+};
+use crate::{
+    instances::{
+        fio::{self, SolverOutput},
+        Cnf,
+    },
+    types::{Assignment, Clause, Lit, TernaryVal, Var},
+};
+
+/// The interval in which a running solver process is checked for termination
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Counter for unique file names of solver instances within the process
+static INSTANCE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Errors from running an external solver process
+#[derive(Error, Debug)]
+pub enum Error {
+    /// The solver process could not be started
+    #[error("failed to start solver process `{0}`: {1}")]
+    Spawn(String, io::Error),
+    /// The solver process terminated without a valid result
+    #[error("solver process exited with {status} without a valid result: {reason}")]
+    NoResult {
+        /// The exit status of the solver process
+        status: ExitStatus,
+        /// The error from parsing the output
+        reason: anyhow::Error,
+    },
+}
+
+/// Internal state of the solver wrapper
+#[derive(Debug, PartialEq, Eq)]
+enum InternalSolverState {
+    Input,
+    Sat(Assignment),
+    Unsat,
+}
+
+impl InternalSolverState {
+    fn to_external(&self) -> SolverState {
+        match self {
+            InternalSolverState::Input => SolverState::Input,
+            InternalSolverState::Sat(_) => SolverState::Sat,
+            InternalSolverState::Unsat => SolverState::Unsat,
+        }
+    }
+}
+
+/// A SAT solver that runs a solver binary as a separate process. The
+/// instance is passed to the binary as the last command line argument, the
+/// binary is expected to follow the output format of the SAT competition.
+///
+/// The instance and the logs of the solver process are written to a working
+/// directory. If no working directory is set, a temporary directory is used
+/// and the files are removed after each call. The output of the last solver
+/// call is available via [`ExternalSolver::stdout`] and
+/// [`ExternalSolver::stderr`].
+///
+/// Note that [`SolveStats::cpu_solve_time`] for this solver is the wall-clock
+/// time of the solver processes.
+///
+/// When a call is interrupted or exceeds the time limit, only the direct child
+/// process is killed. If the binary is a wrapper, e.g., a shell script, that
+/// starts the actual solver as another process without `exec`, that process
+/// keeps running.
+pub struct ExternalSolver {
+    /// The solver binary
+    cmd: PathBuf,
+    /// Additional arguments to the solver binary
+    args: Vec<OsString>,
+    /// The time limit for a solver call
+    time_limit: Option<Duration>,
+    /// The directory to keep the instance and log files in
+    work_dir: Option<PathBuf>,
+    /// Unique identifier of this solver for file names
+    id: usize,
+    /// The clauses in the solver
+    cnf: Cnf,
+    state: InternalSolverState,
+    stats: SolverStats,
+    /// The output of the last solver call
+    stdout: String,
+    /// The error output of the last solver call
+    stderr: String,
+    /// The currently running solver process
+    child: Arc<Mutex<Option<Child>>>,
+    /// Whether the current solver call was interrupted
+    interrupted: Arc<AtomicBool>,
+}
+
+impl ExternalSolver {
+    /// Creates a new solver calling the given binary
+    pub fn new<P: Into<PathBuf>>(cmd: P) -> Self {
+        ExternalSolver {
+            cmd: cmd.into(),
+            args: vec![],
+            time_limit: None,
+            work_dir: None,
+            id: INSTANCE_COUNTER.fetch_add(1, Ordering::Relaxed),
+            cnf: Cnf::new(),
+            state: InternalSolverState::Input,
+            stats: SolverStats::default(),
+            stdout: String::new(),
+            stderr: String::new(),
+            child: Arc::new(Mutex::new(None)),
+            interrupted: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Adds an argument that is passed to the solver binary before the
+    /// instance
+    pub fn arg<S: Into<OsString>>(&mut self, arg: S) -> &mut Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Adds multiple arguments that are passed to the solver binary before
+    /// the instance
+    pub fn args<I, S>(&mut self, args: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<OsString>,
+    {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    /// Sets the wall-clock time limit for solver calls. Calls exceeding the
+    /// time limit are terminated and return [`SolverResult::Interrupted`].
+    pub fn set_time_limit(&mut self, limit: Option<Duration>) {
+        self.time_limit = limit;
+    }
+
+    /// Sets the directory to write the instance and log files to. The files
+    /// in this directory are kept after solving.
+    pub fn set_work_dir<P: Into<PathBuf>>(&mut self, dir: P) {
+        self.work_dir = Some(dir.into());
+    }
+
+    /// Gets the standard output of the last solver call
+    pub fn stdout(&self) -> &str {
+        &self.stdout
+    }
+
+    /// Gets the standard error output of the last solver call
+    pub fn stderr(&self) -> &str {
+        &self.stderr
+    }
+
+    /// Gets the path of a file for this solver with a given extension
+    fn file_path(&self, ext: &str) -> PathBuf {
+        let dir = match &self.work_dir {
+            Some(dir) => dir.clone(),
+            None => std::env::temp_dir(),
+        };
+        dir.join(format!(
+            "rustsat-external-{}-{}.{ext}",
+            std::process::id(),
+            self.id
+        ))
+    }
+
+    /// Runs the solver binary on an instance file and waits for it to
+    /// terminate. Returns the exit status and whether the process was killed.
+    fn run(
+        &self,
+        instance: &Path,
+        stdout: &Path,
+        stderr: &Path,
+    ) -> anyhow::Result<(ExitStatus, bool)> {
+        let spawn_err = |err| Error::Spawn(self.cmd.display().to_string(), err);
+        let child = Command::new(&self.cmd)
+            .args(&self.args)
+            .arg(instance)
+            .stdin(Stdio::null())
+            .stdout(File::create(stdout)?)
+            .stderr(File::create(stderr)?)
+            .spawn()
+            .map_err(spawn_err)?;
+        *self.child.lock().unwrap() = Some(child);
+        let deadline = self.time_limit.map(|limit| Instant::now() + limit);
+        let mut killed = false;
+        loop {
+            {
+                let mut guard = self.child.lock().unwrap();
+                let child = guard.as_mut().expect("solver process vanished");
+                if let Some(status) = child.try_wait()? {
+                    *guard = None;
+                    // the interrupt is reset while holding the lock, so that
+                    // an interrupt arriving after this applies to the next call
+                    let interrupted = self.interrupted.swap(false, Ordering::Relaxed);
+                    return Ok((status, killed || interrupted));
+                }
+                if !killed
+                    && (self.interrupted.load(Ordering::Relaxed)
+                        || deadline.map_or(false, |dl| Instant::now() >= dl))
+                {
+                    // the process might have terminated in the meantime
+                    let _ = child.kill();
+                    killed = true;
+                }
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+impl Extend<Clause> for ExternalSolver {
+    fn extend<T: IntoIterator<Item = Clause>>(&mut self, iter: T) {
+        iter.into_iter()
+            .for_each(|cl| self.add_clause(cl).expect("Error adding clause in extend"))
+    }
+}
+
+impl<'a> Extend<&'a Clause> for ExternalSolver {
+    fn extend<T: IntoIterator<Item = &'a Clause>>(&mut self, iter: T) {
+        iter.into_iter().for_each(|cl| {
+            self.add_clause_ref(cl)
+                .expect("Error adding clause in extend")
+        })
+    }
+}
+
+impl Solve for ExternalSolver {
+    fn signature(&self) -> &'static str {
+        "external solver process"
+    }
+
+    fn reserve(&mut self, max_var: Var) -> anyhow::Result<()> {
+        self.stats.max_var = std::cmp::max(self.stats.max_var, Some(max_var));
+        Ok(())
+    }
+
+    fn solve(&mut self) -> anyhow::Result<SolverResult> {
+        // If already solved, return state
+        match self.state {
+            InternalSolverState::Sat(_) => return Ok(SolverResult::Sat),
+            InternalSolverState::Unsat => return Ok(SolverResult::Unsat),
+            InternalSolverState::Input => (),
+        }
+        // an interrupt applies to the running call or, if none is running, the
+        // next one
+        if self.interrupted.swap(false, Ordering::Relaxed) {
+            self.stats.n_terminated += 1;
+            return Ok(SolverResult::Interrupted);
+        }
+        let instance = self.file_path("cnf");
+        let stdout = self.file_path("out");
+        let stderr = self.file_path("err");
+        let n_vars = self.stats.max_var.map_or(0, |var| var.idx32() + 1);
+        self.cnf.write_dimacs_path(&instance, n_vars)?;
+        let start = Instant::now();
+        let run = self.run(&instance, &stdout, &stderr);
+        self.stats.cpu_solve_time += start.elapsed();
+        let output = run.and_then(|(status, killed)| {
+            self.stdout = fs::read_to_string(&stdout)?;
+            self.stderr = fs::read_to_string(&stderr)?;
+            let output = fio::parse_sat_solver_output(BufReader::new(self.stdout.as_bytes()));
+            match output {
+                Ok(output) => Ok(output),
+                Err(_) if killed => Ok(SolverOutput::Unknown),
+                Err(reason) => Err(Error::NoResult { status, reason }.into()),
+            }
+        });
+        if self.work_dir.is_none() {
+            for path in [&instance, &stdout, &stderr] {
+                let _ = fs::remove_file(path);
+            }
+        }
+        match output? {
+            SolverOutput::Sat(assignment) => {
+                self.stats.n_sat += 1;
+                self.state = InternalSolverState::Sat(assignment);
+                Ok(SolverResult::Sat)
+            }
+            SolverOutput::Unsat => {
+                self.stats.n_unsat += 1;
+                self.state = InternalSolverState::Unsat;
+                Ok(SolverResult::Unsat)
+            }
+            SolverOutput::Unknown => {
+                self.stats.n_terminated += 1;
+                Ok(SolverResult::Interrupted)
+            }
+        }
+    }
+
+    fn lit_val(&self, lit: Lit) -> anyhow::Result<TernaryVal> {
+        match &self.state {
+            InternalSolverState::Sat(assignment) => Ok(assignment.lit_value(lit)),
+            other => Err(StateError {
+                required_state: SolverState::Sat,
+                actual_state: other.to_external(),
+            }
+            .into()),
+        }
+    }
+
+    fn add_clause_ref(&mut self, clause: &Clause) -> anyhow::Result<()> {
+        self.add_clause(clause.clone())
+    }
+
+    fn add_clause(&mut self, clause: Clause) -> anyhow::Result<()> {
+        self.stats.avg_clause_len = (self.stats.avg_clause_len * (self.cnf.len() as f32)
+            + clause.len() as f32)
+            / (self.cnf.len() + 1) as f32;
+        self.stats.max_var =
+            std::cmp::max(self.stats.max_var, clause.iter().map(|lit| lit.var()).max());
+        self.state = InternalSolverState::Input;
+        self.cnf.add_clause(clause);
+        Ok(())
+    }
+}
+
+impl SolveStats for ExternalSolver {
+    fn stats(&self) -> SolverStats {
+        let mut stats = self.stats.clone();
+        stats.n_clauses = self.cnf.len();
+        stats
+    }
+
+    fn n_clauses(&self) -> usize {
+        self.cnf.len()
+    }
+
+    fn max_var(&self) -> Option<Var> {
+        self.stats.max_var
+    }
+}
+
+impl Interrupt for ExternalSolver {
+    type Interrupter = Interrupter;
+    fn interrupter(&mut self) -> Self::Interrupter {
+        Interrupter {
+            child: self.child.clone(),
+            interrupted: self.interrupted.clone(),
+        }
+    }
+}
+
+/// An Interrupter for the external solver, killing the running solver
+/// process. If no solver process is running, the next solver call is
+/// interrupted.
+pub struct Interrupter {
+    /// The currently running solver process
+    child: Arc<Mutex<Option<Child>>>,
+    /// Whether the current solver call was interrupted
+    interrupted: Arc<AtomicBool>,
+}
+
+impl InterruptSolver for Interrupter {
+    fn interrupt(&self) {
+        self.interrupted.store(true, Ordering::Relaxed);
+        if let Some(child) = self.child.lock().unwrap().as_mut() {
+            // the process might have terminated in the meantime
+            let _ = child.kill();
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::ExternalSolver;
+    use crate::{
+        lit,
+        solvers::{Interrupt, InterruptSolver, Solve, SolveStats, SolverResult},
+        types::TernaryVal,
+        var,
+    };
+
+    /// Creates a solver running a shell script, with the instance as `$1`
+    fn script_solver(script: &str) -> ExternalSolver {
+        let mut solver = ExternalSolver::new("sh");
+        solver.args(["-c", script, "solver"]);
+        solver
+    }
+
+    #[test]
+    fn sat() {
+        let mut solver = script_solver("cat \"$1\" >&2; printf 's SATISFIABLE\\nv 1 -2 0\\n'");
+        solver.add_binary(lit![0], lit![1]).unwrap();
+        solver.add_unit(!lit![1]).unwrap();
+        assert_eq!(solver.max_var(), Some(var![1]));
+        assert_eq!(solver.solve().unwrap(), SolverResult::Sat);
+        assert_eq!(solver.lit_val(lit![0]).unwrap(), TernaryVal::True);
+        assert_eq!(solver.var_val(var![1]).unwrap(), TernaryVal::False);
+        assert!(solver.stderr().contains("p cnf 2 2"));
+        assert!(solver.stdout().starts_with("s SATISFIABLE"));
+        assert_eq!(solver.n_sat_solves(), 1);
+    }
+
+    #[test]
+    fn unsat() {
+        let mut solver = script_solver("echo 's UNSATISFIABLE'; exit 20");
+        solver.add_unit(lit![0]).unwrap();
+        assert_eq!(solver.solve().unwrap(), SolverResult::Unsat);
+        assert!(solver.lit_val(lit![0]).is_err());
+        assert_eq!(solver.n_unsat_solves(), 1);
+    }
+
+    #[test]
+    fn no_result() {
+        let mut solver = script_solver("exit 1");
+        solver.add_unit(lit![0]).unwrap();
+        assert!(solver.solve().is_err());
+        assert!(ExternalSolver::new("/nonexistent/solver").solve().is_err());
+    }
+
+    #[test]
+    fn time_limit() {
+        let mut solver = script_solver("sleep 10");
+        solver.set_time_limit(Some(Duration::from_millis(100)));
+        let start = Instant::now();
+        assert_eq!(solver.solve().unwrap(), SolverResult::Interrupted);
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(solver.n_terminated(), 1);
+    }
+
+    #[test]
+    fn interrupt() {
+        let mut solver = script_solver("sleep 10");
+        let interrupter = solver.interrupter();
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(100));
+            interrupter.interrupt();
+        });
+        let start = Instant::now();
+        assert_eq!(solver.solve().unwrap(), SolverResult::Interrupted);
+        assert!(start.elapsed() < Duration::from_secs(5));
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn interrupt_before_solve() {
+        let mut solver = script_solver("[ -e \"$1\" ] && echo 's UNSATISFIABLE'");
+        solver.interrupter().interrupt();
+        assert_eq!(solver.solve().unwrap(), SolverResult::Interrupted);
+        // the interrupt only applies to one call
+        assert_eq!(solver.solve().unwrap(), SolverResult::Unsat);
+    }
+
+    #[test]
+    fn interrupt_after_solve() {
+        let mut solver = script_solver("echo 's UNSATISFIABLE'");
+        let interrupter = solver.interrupter();
+        assert_eq!(solver.solve().unwrap(), SolverResult::Unsat);
+        interrupter.interrupt();
+        // reset the state so that the solver is called again
+        solver.add_unit(lit![0]).unwrap();
+        assert_eq!(solver.solve().unwrap(), SolverResult::Interrupted);
+        assert_eq!(solver.solve().unwrap(), SolverResult::Unsat);
+    }
+
+    #[test]
+    fn work_dir() {
+        let dir = std::env::temp_dir().join(format!("rustsat-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut solver = script_solver("echo c log line; echo 's UNSATISFIABLE'");
+        solver.set_work_dir(&dir);
+        assert_eq!(solver.solve().unwrap(), SolverResult::Unsat);
+        let n_files = std::fs::read_dir(&dir).unwrap().count();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(n_files, 3);
+    }
+}
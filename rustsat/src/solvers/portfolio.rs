@@ -0,0 +1,666 @@
+//! # Portfolio Solver
+//!
+//! A [`Portfolio`] owns multiple solver backends, passes the same clauses to
+//! all of them and races them against each other on separate threads. As
+//! soon as one backend finishes, the others are stopped via their
+//! [`InterruptSolver`] handles.
+//!
+//! Backends of different types can be mixed in the same portfolio. A
+//! [`Portfolio`] of [`dyn Backend`](Backend) only implements [`Solve`], a
+//! [`Portfolio`] of [`dyn IncrementalBackend`](IncrementalBackend) also
+//! implements [`SolveIncremental`] but requires all backends to implement it.
+//!
+//! ## Example Useage
+//!
+//! ```no_run
+//! # use rustsat::{
+//! #     lit,
+//! #     solvers::{external::ExternalSolver, Portfolio, Solve, SolverResult},
+//! # };
+//! #
+//! let mut slow = ExternalSolver::new("sh");
+//! slow.args(["-c", "sleep 10", "slow"]);
+//! let mut fast = ExternalSolver::new("sh");
+//! fast.args(["-c", "echo 's UNSATISFIABLE'", "fast"]);
+//!
+//! let mut portfolio = Portfolio::new();
+//! portfolio.push(slow);
+//! portfolio.push(fast);
+//! portfolio.add_unit(lit![0]).unwrap();
+//! assert_eq!(portfolio.solve().unwrap(), SolverResult::Unsat);
+//! assert_eq!(portfolio.winner(), Some(1));
+//! ```
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use super::{
+    Interrupt, InterruptSolver, Solve, SolveIncremental, SolveStats, SolverResult, SolverState,
+    SolverStats, StateError,
+};
+use crate::types::{Clause, Lit, TernaryVal, Var};
+
+/// The interval in which the racing members are interrupted again, in case a
+/// member had not started solving when it was first interrupted
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Object-safe interface of solvers that can be members of a [`Portfolio`].
+/// This is implemented for all solvers implementing [`Solve`] and
+/// [`Interrupt`] that can be sent across threads.
+pub trait Backend: Send {
+    /// Gets the signature of the solver
+    fn signature(&self) -> &'static str;
+    /// Solves the instance
+    ///
+    /// # Errors
+    ///
+    /// If the backend returns an error.
+    fn solve(&mut self) -> anyhow::Result<SolverResult>;
+    /// Gets the value of a literal in the solution
+    ///
+    /// # Errors
+    ///
+    /// If the backend returns an error.
+    fn lit_val(&self, lit: Lit) -> anyhow::Result<TernaryVal>;
+    /// Adds a clause to the solver
+    ///
+    /// # Errors
+    ///
+    /// If the backend returns an error.
+    fn add_clause_ref(&mut self, clause: &Clause) -> anyhow::Result<()>;
+    /// Reserves variables in the solver
+    ///
+    /// # Errors
+    ///
+    /// If the backend returns an error.
+    fn reserve(&mut self, max_var: Var) -> anyhow::Result<()>;
+    /// Gets an interrupter for the solver
+    fn interrupter(&mut self) -> Box<dyn InterruptSolver + Send>;
+}
+
+impl<S> Backend for S
+where
+    S: Solve + Interrupt + Send,
+    S::Interrupter: Send + 'static,
+{
+    fn signature(&self) -> &'static str {
+        Solve::signature(self)
+    }
+
+    fn solve(&mut self) -> anyhow::Result<SolverResult> {
+        Solve::solve(self)
+    }
+
+    fn lit_val(&self, lit: Lit) -> anyhow::Result<TernaryVal> {
+        Solve::lit_val(self, lit)
+    }
+
+    fn add_clause_ref(&mut self, clause: &Clause) -> anyhow::Result<()> {
+        Solve::add_clause_ref(self, clause)
+    }
+
+    fn reserve(&mut self, max_var: Var) -> anyhow::Result<()> {
+        Solve::reserve(self, max_var)
+    }
+
+    fn interrupter(&mut self) -> Box<dyn InterruptSolver + Send> {
+        Box::new(Interrupt::interrupter(self))
+    }
+}
+
+/// Object-safe interface of incremental solvers that can be members of a
+/// [`Portfolio`]. This is implemented for all solvers implementing
+/// [`SolveIncremental`] and [`Interrupt`] that can be sent across threads.
+pub trait IncrementalBackend: Backend {
+    /// Solves the instance under assumptions
+    ///
+    /// # Errors
+    ///
+    /// If the backend returns an error.
+    fn solve_assumps(&mut self, assumps: &[Lit]) -> anyhow::Result<SolverResult>;
+    /// Gets the core of the last unsatisfiable query
+    ///
+    /// # Errors
+    ///
+    /// If the backend returns an error.
+    fn core(&mut self) -> anyhow::Result<Vec<Lit>>;
+}
+
+impl<S> IncrementalBackend for S
+where
+    S: SolveIncremental + Interrupt + Send,
+    S::Interrupter: Send + 'static,
+{
+    fn solve_assumps(&mut self, assumps: &[Lit]) -> anyhow::Result<SolverResult> {
+        SolveIncremental::solve_assumps(self, assumps)
+    }
+
+    fn core(&mut self) -> anyhow::Result<Vec<Lit>> {
+        SolveIncremental::core(self)
+    }
+}
+
+/// Interrupters of the members of a portfolio
+type Interrupters = Arc<Mutex<Vec<Box<dyn InterruptSolver + Send>>>>;
+
+/// A portfolio of solvers racing against each other on separate threads
+///
+/// Note that [`SolveStats::cpu_solve_time`] for the portfolio is the
+/// wall-clock time of the races.
+pub struct Portfolio<B: ?Sized + Backend = dyn Backend> {
+    /// The member solvers
+    members: Vec<Box<B>>,
+    /// Interrupters for the member solvers
+    interrupters: Interrupters,
+    /// Whether the current query was interrupted
+    interrupted: Arc<AtomicBool>,
+    /// The index of the member that finished the last query
+    winner: Option<usize>,
+    stats: SolverStats,
+}
+
+impl<B: ?Sized + Backend> Portfolio<B> {
+    /// Gets the number of member solvers
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Checks whether the portfolio has no member solvers
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// Gets the index of the member solver that finished the last query, if
+    /// the query was not interrupted
+    pub fn winner(&self) -> Option<usize> {
+        self.winner
+    }
+
+    /// Gets the signatures of the member solvers
+    pub fn signatures(&self) -> Vec<&'static str> {
+        self.members.iter().map(|m| m.signature()).collect()
+    }
+
+    /// Adds a boxed member solver
+    ///
+    /// # Panics
+    ///
+    /// If clauses were already added to the portfolio, since the new member
+    /// would not know them.
+    pub fn push_boxed(&mut self, mut solver: Box<B>) {
+        assert!(
+            self.stats.n_clauses == 0,
+            "members must be added before clauses"
+        );
+        self.interrupters.lock().unwrap().push(solver.interrupter());
+        self.members.push(solver);
+    }
+
+    /// Races the members on the query and interrupts the losers
+    ///
+    /// # Errors
+    ///
+    /// If the portfolio has no members or all members return an error.
+    fn race<F>(&mut self, solve: F) -> anyhow::Result<SolverResult>
+    where
+        F: Fn(&mut B) -> anyhow::Result<SolverResult> + Sync,
+    {
+        anyhow::ensure!(!self.members.is_empty(), "portfolio has no member solvers");
+        let start = Instant::now();
+        self.winner = None;
+        let winner: Mutex<Option<(usize, SolverResult)>> = Mutex::new(None);
+        let interrupters = &self.interrupters;
+        let interrupted = &self.interrupted;
+        // Whether the members returned from solving. This is set before a
+        // member can become the winner, so that no finished member is
+        // interrupted.
+        let finished: Vec<AtomicBool> = (0..self.members.len())
+            .map(|_| AtomicBool::new(false))
+            .collect();
+        let results: Vec<anyhow::Result<SolverResult>> = thread::scope(|scope| {
+            let handles: Vec<_> = self
+                .members
+                .iter_mut()
+                .enumerate()
+                .map(|(idx, member)| {
+                    let winner = &winner;
+                    let solve = &solve;
+                    let finished = &finished[idx];
+                    scope.spawn(move || {
+                        let res = solve(member);
+                        finished.store(true, Ordering::Relaxed);
+                        if let Ok(res @ (SolverResult::Sat | SolverResult::Unsat)) = res {
+                            let mut winner = winner.lock().unwrap();
+                            if winner.is_none() {
+                                *winner = Some((idx, res));
+                            }
+                        }
+                        res
+                    })
+                })
+                .collect();
+            // A member that has not started solving yet might reset its
+            // interrupt flag when it starts, so the unfinished members are
+            // interrupted until they all returned. Finished members are not
+            // interrupted, since some solvers (e.g., external solvers) keep an
+            // interrupt arriving between calls for their next call.
+            while !finished.iter().all(|fin| fin.load(Ordering::Relaxed)) {
+                if interrupted.load(Ordering::Relaxed) || winner.lock().unwrap().is_some() {
+                    interrupters
+                        .lock()
+                        .unwrap()
+                        .iter()
+                        .zip(&finished)
+                        .filter(|(_, fin)| !fin.load(Ordering::Relaxed))
+                        .for_each(|(int, _)| int.interrupt());
+                }
+                thread::sleep(POLL_INTERVAL);
+            }
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("portfolio member panicked"))
+                .collect()
+        });
+        self.interrupted.store(false, Ordering::Relaxed);
+        self.stats.cpu_solve_time += start.elapsed();
+        if let Some((idx, res)) = winner.into_inner().unwrap() {
+            self.winner = Some(idx);
+            match res {
+                SolverResult::Sat => self.stats.n_sat += 1,
+                SolverResult::Unsat => self.stats.n_unsat += 1,
+                SolverResult::Interrupted => unreachable!(),
+            }
+            return Ok(res);
+        }
+        // only fail if no member was regularly interrupted
+        if results
+            .iter()
+            .any(|res| matches!(res, Ok(SolverResult::Interrupted)))
+        {
+            self.stats.n_terminated += 1;
+            return Ok(SolverResult::Interrupted);
+        }
+        results
+            .into_iter()
+            .find(Result::is_err)
+            .expect("one result must be an error")
+    }
+
+    /// Gets the member solver that finished the last query
+    fn winner_member(&self) -> anyhow::Result<&B> {
+        match self.winner {
+            Some(idx) => Ok(&self.members[idx]),
+            None => Err(StateError {
+                required_state: SolverState::Sat,
+                actual_state: SolverState::Input,
+            }
+            .into()),
+        }
+    }
+}
+
+impl Portfolio<dyn Backend> {
+    /// Creates a new empty portfolio
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a member solver. The solver should not contain any clauses yet.
+    ///
+    /// # Panics
+    ///
+    /// If clauses were already added to the portfolio.
+    pub fn push<S>(&mut self, solver: S)
+    where
+        S: Solve + Interrupt + Send + 'static,
+        S::Interrupter: Send + 'static,
+    {
+        self.push_boxed(Box::new(solver));
+    }
+}
+
+impl Portfolio<dyn IncrementalBackend> {
+    /// Creates a new empty portfolio of incremental solvers
+    pub fn new_incremental() -> Self {
+        Self::default()
+    }
+
+    /// Adds an incremental member solver. The solver should not contain any
+    /// clauses yet.
+    ///
+    /// # Panics
+    ///
+    /// If clauses were already added to the portfolio.
+    pub fn push<S>(&mut self, solver: S)
+    where
+        S: SolveIncremental + Interrupt + Send + 'static,
+        S::Interrupter: Send + 'static,
+    {
+        self.push_boxed(Box::new(solver));
+    }
+}
+
+impl<B: ?Sized + Backend> Default for Portfolio<B> {
+    fn default() -> Self {
+        Portfolio {
+            members: vec![],
+            interrupters: Arc::new(Mutex::new(vec![])),
+            interrupted: Arc::new(AtomicBool::new(false)),
+            winner: None,
+            stats: SolverStats::default(),
+        }
+    }
+}
+
+impl<B: ?Sized + Backend> Extend<Clause> for Portfolio<B> {
+    fn extend<T: IntoIterator<Item = Clause>>(&mut self, iter: T) {
+        iter.into_iter()
+            .for_each(|cl| Solve::add_clause(self, cl).expect("Error adding clause in extend"))
+    }
+}
+
+impl<'a, B: ?Sized + Backend> Extend<&'a Clause> for Portfolio<B> {
+    fn extend<T: IntoIterator<Item = &'a Clause>>(&mut self, iter: T) {
+        iter.into_iter()
+            .for_each(|cl| Solve::add_clause_ref(self, cl).expect("Error adding clause in extend"))
+    }
+}
+
+impl<B: ?Sized + Backend> Solve for Portfolio<B> {
+    fn signature(&self) -> &'static str {
+        "portfolio"
+    }
+
+    fn reserve(&mut self, max_var: Var) -> anyhow::Result<()> {
+        self.stats.max_var = std::cmp::max(self.stats.max_var, Some(max_var));
+        self.members
+            .iter_mut()
+            .try_for_each(|member| member.reserve(max_var))
+    }
+
+    fn solve(&mut self) -> anyhow::Result<SolverResult> {
+        self.race(|member| member.solve())
+    }
+
+    fn lit_val(&self, lit: Lit) -> anyhow::Result<TernaryVal> {
+        self.winner_member()?.lit_val(lit)
+    }
+
+    fn add_clause_ref(&mut self, clause: &Clause) -> anyhow::Result<()> {
+        self.stats.n_clauses += 1;
+        self.stats.avg_clause_len =
+            (self.stats.avg_clause_len * ((self.stats.n_clauses - 1) as f32) + clause.len() as f32)
+                / self.stats.n_clauses as f32;
+        self.stats.max_var =
+            std::cmp::max(self.stats.max_var, clause.iter().map(|lit| lit.var()).max());
+        self.winner = None;
+        self.members
+            .iter_mut()
+            .try_for_each(|member| member.add_clause_ref(clause))
+    }
+}
+
+impl SolveIncremental for Portfolio<dyn IncrementalBackend> {
+    fn solve_assumps(&mut self, assumps: &[Lit]) -> anyhow::Result<SolverResult> {
+        self.race(|member| member.solve_assumps(assumps))
+    }
+
+    fn core(&mut self) -> anyhow::Result<Vec<Lit>> {
+        match self.winner {
+            Some(idx) => self.members[idx].core(),
+            None => Err(StateError {
+                required_state: SolverState::Unsat,
+                actual_state: SolverState::Input,
+            }
+            .into()),
+        }
+    }
+}
+
+impl<B: ?Sized + Backend> SolveStats for Portfolio<B> {
+    fn stats(&self) -> SolverStats {
+        self.stats.clone()
+    }
+}
+
+impl<B: ?Sized + Backend> Interrupt for Portfolio<B> {
+    type Interrupter = Interrupter;
+    fn interrupter(&mut self) -> Self::Interrupter {
+        Interrupter {
+            interrupters: self.interrupters.clone(),
+            interrupted: self.interrupted.clone(),
+        }
+    }
+}
+
+/// An Interrupter for the portfolio, interrupting all member solvers
+pub struct Interrupter {
+    /// Interrupters for the member solvers
+    interrupters: Interrupters,
+    /// Whether the current query was interrupted
+    interrupted: Arc<AtomicBool>,
+}
+
+impl InterruptSolver for Interrupter {
+    fn interrupt(&self) {
+        self.interrupted.store(true, Ordering::Relaxed);
+        self.interrupters
+            .lock()
+            .unwrap()
+            .iter()
+            .for_each(|int| int.interrupt());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use super::Portfolio;
+    use crate::{
+        lit,
+        solvers::{
+            external::ExternalSolver, Interrupt, InterruptSolver, Solve, SolveIncremental,
+            SolveStats, SolverResult,
+        },
+        types::{Clause, Lit, TernaryVal},
+    };
+
+    /// A mock solver returning a fixed result after a delay, unless it is
+    /// interrupted
+    struct Mock {
+        result: SolverResult,
+        /// The time before the solver starts solving and resets its
+        /// interrupt flag
+        startup: Duration,
+        delay: Duration,
+        interrupted: Arc<AtomicBool>,
+        n_clauses: usize,
+        core: Vec<Lit>,
+    }
+
+    impl Mock {
+        fn new(result: SolverResult, delay_ms: u64) -> Self {
+            Mock {
+                result,
+                startup: Duration::ZERO,
+                delay: Duration::from_millis(delay_ms),
+                interrupted: Arc::new(AtomicBool::new(false)),
+                n_clauses: 0,
+                core: vec![],
+            }
+        }
+    }
+
+    impl Extend<Clause> for Mock {
+        fn extend<T: IntoIterator<Item = Clause>>(&mut self, iter: T) {
+            iter.into_iter().for_each(|cl| self.add_clause(cl).unwrap())
+        }
+    }
+
+    impl<'a> Extend<&'a Clause> for Mock {
+        fn extend<T: IntoIterator<Item = &'a Clause>>(&mut self, iter: T) {
+            iter.into_iter()
+                .for_each(|cl| self.add_clause_ref(cl).unwrap())
+        }
+    }
+
+    impl Solve for Mock {
+        fn signature(&self) -> &'static str {
+            "mock"
+        }
+
+        fn solve(&mut self) -> anyhow::Result<SolverResult> {
+            self.solve_assumps(&[])
+        }
+
+        fn lit_val(&self, _lit: Lit) -> anyhow::Result<TernaryVal> {
+            Ok(TernaryVal::True)
+        }
+
+        fn add_clause_ref(&mut self, _clause: &Clause) -> anyhow::Result<()> {
+            self.n_clauses += 1;
+            Ok(())
+        }
+    }
+
+    impl SolveIncremental for Mock {
+        fn solve_assumps(&mut self, assumps: &[Lit]) -> anyhow::Result<SolverResult> {
+            thread::sleep(self.startup);
+            self.interrupted.store(false, Ordering::Relaxed);
+            let start = Instant::now();
+            while start.elapsed() < self.delay {
+                if self.interrupted.load(Ordering::Relaxed) {
+                    return Ok(SolverResult::Interrupted);
+                }
+                thread::sleep(Duration::from_millis(1));
+            }
+            self.core = assumps.iter().map(|&a| !a).collect();
+            Ok(self.result)
+        }
+
+        fn core(&mut self) -> anyhow::Result<Vec<Lit>> {
+            Ok(self.core.clone())
+        }
+    }
+
+    impl Interrupt for Mock {
+        type Interrupter = Arc<AtomicBool>;
+        fn interrupter(&mut self) -> Self::Interrupter {
+            self.interrupted.clone()
+        }
+    }
+
+    impl InterruptSolver for Arc<AtomicBool> {
+        fn interrupt(&self) {
+            self.store(true, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn race() {
+        let mut portfolio = Portfolio::new();
+        portfolio.push(Mock::new(SolverResult::Sat, 10_000));
+        portfolio.push(Mock::new(SolverResult::Sat, 10));
+        portfolio.push(Mock::new(SolverResult::Sat, 10_000));
+        portfolio.add_binary(lit![0], lit![1]).unwrap();
+        let start = Instant::now();
+        assert_eq!(portfolio.solve().unwrap(), SolverResult::Sat);
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(portfolio.winner(), Some(1));
+        assert_eq!(portfolio.lit_val(lit![0]).unwrap(), TernaryVal::True);
+        assert_eq!(portfolio.n_sat_solves(), 1);
+        assert_eq!(portfolio.n_clauses(), 1);
+        portfolio.add_unit(lit![0]).unwrap();
+        assert!(portfolio.lit_val(lit![0]).is_err());
+    }
+
+    #[test]
+    fn race_twice_external() {
+        let mut fast = ExternalSolver::new("sh");
+        fast.args(["-c", "echo 's UNSATISFIABLE'", "fast"]);
+        let mut portfolio = Portfolio::new();
+        portfolio.push(Mock::new(SolverResult::Unsat, 10_000));
+        portfolio.push(fast);
+        for idx in 0..5 {
+            // adding a clause resets the state of the external solver
+            portfolio.add_unit(lit![idx]).unwrap();
+            assert_eq!(portfolio.solve().unwrap(), SolverResult::Unsat);
+            assert_eq!(portfolio.winner(), Some(1));
+        }
+        assert_eq!(portfolio.n_terminated(), 0);
+    }
+
+    #[test]
+    fn race_slow_startup() {
+        let mut slow = Mock::new(SolverResult::Sat, 10_000);
+        slow.startup = Duration::from_millis(100);
+        let mut portfolio = Portfolio::new();
+        portfolio.push(slow);
+        portfolio.push(Mock::new(SolverResult::Unsat, 0));
+        let start = Instant::now();
+        assert_eq!(portfolio.solve().unwrap(), SolverResult::Unsat);
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(portfolio.winner(), Some(1));
+    }
+
+    #[test]
+    fn empty() {
+        let mut portfolio = Portfolio::new();
+        assert!(portfolio.solve().is_err());
+        assert_eq!(portfolio.n_terminated(), 0);
+    }
+
+    #[test]
+    fn incremental() {
+        let mut portfolio = Portfolio::new_incremental();
+        portfolio.push(Mock::new(SolverResult::Unsat, 10_000));
+        portfolio.push(Mock::new(SolverResult::Unsat, 10));
+        let res = portfolio.solve_assumps(&[lit![0], !lit![1]]).unwrap();
+        assert_eq!(res, SolverResult::Unsat);
+        assert_eq!(portfolio.core().unwrap(), vec![!lit![0], lit![1]]);
+    }
+
+    #[test]
+    fn interrupt() {
+        let mut portfolio = Portfolio::new();
+        portfolio.push(Mock::new(SolverResult::Sat, 10_000));
+        portfolio.push(Mock::new(SolverResult::Sat, 10_000));
+        let interrupter = portfolio.interrupter();
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            interrupter.interrupt();
+        });
+        assert_eq!(portfolio.solve().unwrap(), SolverResult::Interrupted);
+        assert_eq!(portfolio.winner(), None);
+        assert_eq!(portfolio.n_terminated(), 1);
+        handle.join().unwrap();
+        // interrupting before the members start solving is not lost
+        let mut slow = Mock::new(SolverResult::Sat, 10_000);
+        slow.startup = Duration::from_millis(100);
+        let mut portfolio = Portfolio::new();
+        portfolio.push(slow);
+        let interrupter = portfolio.interrupter();
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            interrupter.interrupt();
+        });
+        let start = Instant::now();
+        assert_eq!(portfolio.solve().unwrap(), SolverResult::Interrupted);
+        assert!(start.elapsed() < Duration::from_secs(5));
+        handle.join().unwrap();
+    }
+}
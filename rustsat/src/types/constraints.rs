@@ -1,8 +1,8 @@
 //! # Constraint Types
 //!
 //! Different types of constraints. The most important one is [`Clause`] but
-//! Rust SAT supports more complex constraints like [`PBConstraint`] or
-//! [`CardConstraint`].
+//! Rust SAT supports more complex constraints like [`PBConstraint`],
+//! [`CardConstraint`] or [`XorConstraint`].
 
 use std::{
     fmt,
@@ -410,6 +410,24 @@ impl CardConstraint {
         }
     }
 
+    /// Checks if the constraint is an at-most-1 constraint (`sum of lits <= 1`)
+    pub fn is_at_most_one(&self) -> bool {
+        match self {
+            CardConstraint::UB(constr) => constr.b == 1,
+            CardConstraint::LB(_) => false,
+            CardConstraint::EQ(_) => false,
+        }
+    }
+
+    /// Checks if the constraint is an exactly-1 constraint (`sum of lits = 1`)
+    pub fn is_exactly_one(&self) -> bool {
+        match self {
+            CardConstraint::UB(_) => false,
+            CardConstraint::LB(_) => false,
+            CardConstraint::EQ(constr) => constr.b == 1,
+        }
+    }
+
     /// Normalizes the constraint. This only consists of sorting the literals.
     /// Comparing two normalized constraints checks their logical equivalence.
     pub fn normalize(mut self) -> Self {
@@ -1151,9 +1169,130 @@ impl PBEQConstr {
     }
 }
 
+/// An XOR (parity) constraint over literals (`lit1 ^ lit2 ^ ... ^ litn = rhs`)
+#[derive(Hash, Eq, PartialEq, Clone, Debug)]
+pub struct XorConstraint {
+    lits: Vec<Lit>,
+    rhs: bool,
+}
+
+impl XorConstraint {
+    /// Constructs a new XOR constraint that requires the parity of the
+    /// literals to be `rhs`
+    pub fn new<LI: LitIter>(lits: LI, rhs: bool) -> Self {
+        XorConstraint {
+            lits: lits.into_iter().collect(),
+            rhs,
+        }
+    }
+
+    /// Adds literals to the constraint
+    pub fn add<LI: LitIter>(&mut self, lits: LI) {
+        self.lits.extend(lits)
+    }
+
+    /// Gets the right hand side of the constraint
+    pub fn rhs(&self) -> bool {
+        self.rhs
+    }
+
+    /// Gets the number of literals in the constraint
+    pub fn len(&self) -> usize {
+        self.lits.len()
+    }
+
+    /// Checks if the constraint has no literals
+    pub fn is_empty(&self) -> bool {
+        self.lits.is_empty()
+    }
+
+    /// Checks if the constraint is always satisfied. Duplicate literals are
+    /// only detected after [`XorConstraint::normalize`].
+    pub fn is_tautology(&self) -> bool {
+        self.lits.is_empty() && !self.rhs
+    }
+
+    /// Checks if the constraint is unsatisfiable. Duplicate literals are only
+    /// detected after [`XorConstraint::normalize`].
+    pub fn is_unsat(&self) -> bool {
+        self.lits.is_empty() && self.rhs
+    }
+
+    /// Checks if the constraint is a clause, i.e., a single literal
+    pub fn is_clause(&self) -> bool {
+        self.lits.len() == 1
+    }
+
+    /// Normalizes the constraint. Negations are moved into the right hand
+    /// side, the literals are sorted and pairs of duplicate literals cancel
+    /// out. Comparing two normalized constraints checks their logical
+    /// equivalence.
+    pub fn normalize(mut self) -> Self {
+        for lit in self.lits.iter_mut() {
+            if lit.is_neg() {
+                *lit = !*lit;
+                self.rhs = !self.rhs;
+            }
+        }
+        self.lits.sort_unstable();
+        let mut lits = Vec::with_capacity(self.lits.len());
+        for lit in self.lits {
+            if lits.last() == Some(&lit) {
+                lits.pop();
+            } else {
+                lits.push(lit);
+            }
+        }
+        self.lits = lits;
+        self
+    }
+
+    /// Decomposes the constraint to a set of input literals and a right hand side
+    pub fn decompose(self) -> (Vec<Lit>, bool) {
+        (self.lits, self.rhs)
+    }
+
+    /// Gets the literals that are in the constraint
+    pub fn into_lits(self) -> Vec<Lit> {
+        self.lits
+    }
+
+    /// Converts the constraint into a clause, if possible
+    pub fn into_clause(self) -> Result<Clause, RequiresClausal> {
+        if !self.is_clause() {
+            return Err(RequiresClausal);
+        }
+        if self.rhs {
+            Ok(clause![self.lits[0]])
+        } else {
+            Ok(clause![!self.lits[0]])
+        }
+    }
+
+    /// Gets an iterator over the literals in the constraint
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &Lit> {
+        self.lits.iter()
+    }
+
+    /// Gets a mutable iterator over the literals in the constraint
+    #[inline]
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Lit> {
+        self.lits.iter_mut()
+    }
+
+    /// Checks whether the XOR constraint is satisfied by the given assignment
+    pub fn is_sat(&self, assign: &Assignment) -> bool {
+        let parity = self.iter().fold(false, |par, lit| {
+            par ^ (assign.lit_value(*lit) == TernaryVal::True)
+        });
+        parity == self.rhs
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{CardConstraint, PBConstraint};
+    use super::{CardConstraint, PBConstraint, XorConstraint};
     use crate::{lit, types::Assignment, var};
 
     #[test]
@@ -1332,4 +1471,42 @@ mod tests {
         assert!(!PBConstraint::new_lb(lits.clone(), 3).is_clause());
         assert!(!PBConstraint::new_eq(lits.clone(), 2).is_card());
     }
+
+    #[test]
+    fn xor_is_sat() {
+        let xor = XorConstraint::new(vec![lit![0], lit![1], !lit![2]], true);
+        assert!(xor.is_sat(&assign!(0b000)));
+        assert!(!xor.is_sat(&assign!(0b001)));
+        assert!(!xor.is_sat(&assign!(0b010)));
+        assert!(xor.is_sat(&assign!(0b011)));
+        assert!(!xor.is_sat(&assign!(0b100)));
+        assert!(xor.is_sat(&assign!(0b101)));
+        assert!(xor.is_sat(&assign!(0b110)));
+        assert!(!xor.is_sat(&assign!(0b111)));
+    }
+
+    #[test]
+    fn xor_normalize() {
+        let xor = XorConstraint::new(vec![lit![2], !lit![0], lit![1], lit![2], !lit![3]], true);
+        assert_eq!(
+            xor.normalize(),
+            XorConstraint::new(vec![lit![0], lit![1], lit![3]], true)
+        );
+        let xor = XorConstraint::new(vec![lit![1], !lit![1], lit![0], lit![0]], false);
+        let xor = xor.normalize();
+        assert!(xor.is_unsat());
+        let xor = XorConstraint::new(vec![!lit![1], !lit![1], lit![2], lit![2]], false);
+        let xor = xor.normalize();
+        assert!(xor.is_tautology());
+    }
+
+    #[test]
+    fn xor_into_clause() {
+        let xor = XorConstraint::new(vec![lit![0]], true);
+        assert_eq!(xor.into_clause().unwrap(), clause![lit![0]]);
+        let xor = XorConstraint::new(vec![lit![0]], false);
+        assert_eq!(xor.into_clause().unwrap(), clause![!lit![0]]);
+        let xor = XorConstraint::new(vec![lit![0], lit![1]], false);
+        assert!(xor.into_clause().is_err());
+    }
 }
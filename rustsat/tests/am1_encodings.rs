@@ -1,5 +1,8 @@
 use rustsat::{
-    encodings::am1::{Encode, Pairwise},
+    encodings::am1::{
+        Bimander, Bitwise, Commander, Encode, ExactlyOne, Ladder, Pairwise, Product, Selected,
+        Variant,
+    },
     instances::{BasicVarManager, ManageVars},
     lit,
     solvers::{
@@ -38,3 +41,155 @@ fn test_am1<AM1: Encode + From<Vec<Lit>>>() {
 fn pairwise() {
     test_am1::<Pairwise>()
 }
+
+fn test_am1_exhaustive<AM1: Encode + From<Vec<Lit>>>(n_lits: u32) {
+    let mut solver = rustsat_minisat::core::Minisat::default();
+    let mut var_manager = BasicVarManager::default();
+    var_manager.increase_next_free(var![n_lits]);
+
+    let mut enc = AM1::from((0..n_lits).map(|idx| lit![idx]).collect());
+    enc.encode(&mut solver, &mut var_manager).unwrap();
+
+    for assign in 0..(1_u32 << n_lits) {
+        let assumps: Vec<Lit> = (0..n_lits)
+            .map(|idx| {
+                if assign & (1 << idx) != 0 {
+                    lit![idx]
+                } else {
+                    !lit![idx]
+                }
+            })
+            .collect();
+        let expected = if assign.count_ones() <= 1 { Sat } else { Unsat };
+        assert_eq!(solver.solve_assumps(&assumps).unwrap(), expected);
+    }
+}
+
+#[test]
+fn pairwise_exhaustive() {
+    test_am1_exhaustive::<Pairwise>(7)
+}
+
+#[test]
+fn ladder() {
+    test_am1::<Ladder>()
+}
+
+#[test]
+fn ladder_exhaustive() {
+    test_am1_exhaustive::<Ladder>(7)
+}
+
+#[test]
+fn commander() {
+    test_am1::<Commander>()
+}
+
+#[test]
+fn commander_exhaustive() {
+    test_am1_exhaustive::<Commander>(11)
+}
+
+#[test]
+fn bimander() {
+    test_am1::<Bimander>()
+}
+
+#[test]
+fn bimander_exhaustive() {
+    test_am1_exhaustive::<Bimander>(11)
+}
+
+#[test]
+fn product() {
+    test_am1::<Product>()
+}
+
+#[test]
+fn product_exhaustive() {
+    test_am1_exhaustive::<Product>(11)
+}
+
+#[test]
+fn bitwise() {
+    test_am1::<Bitwise>()
+}
+
+#[test]
+fn bitwise_exhaustive() {
+    test_am1_exhaustive::<Bitwise>(7)
+}
+
+#[test]
+fn selected() {
+    for variant in [
+        Variant::Pairwise,
+        Variant::Ladder,
+        Variant::Commander,
+        Variant::Bimander,
+        Variant::Product,
+        Variant::Bitwise,
+    ] {
+        let mut solver = rustsat_minisat::core::Minisat::default();
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![5]);
+        let mut enc = Selected::new(variant, (0..5).map(|idx| lit![idx]).collect());
+        assert_eq!(enc.variant(), variant);
+        enc.encode(&mut solver, &mut var_manager).unwrap();
+        let res = solver.solve_assumps(&[lit![1], lit![3]]).unwrap();
+        assert_eq!(res, Unsat);
+        let res = solver.solve_assumps(&[lit![4]]).unwrap();
+        assert_eq!(res, Sat);
+    }
+}
+
+#[test]
+fn selected_auto() {
+    assert_eq!(
+        Selected::from(vec![lit![0], lit![1]]).variant(),
+        Variant::Pairwise
+    );
+    assert_eq!(
+        Selected::from_iter((0..100).map(|idx| lit![idx])).variant(),
+        Variant::Product
+    );
+    test_am1_exhaustive::<Selected>(10)
+}
+
+fn test_eo_exhaustive<EO: Encode + From<Vec<Lit>>>(n_lits: u32) {
+    let mut solver = rustsat_minisat::core::Minisat::default();
+    let mut var_manager = BasicVarManager::default();
+    var_manager.increase_next_free(var![n_lits]);
+
+    let mut enc = EO::from((0..n_lits).map(|idx| lit![idx]).collect());
+    enc.encode(&mut solver, &mut var_manager).unwrap();
+
+    for assign in 0..(1_u32 << n_lits) {
+        let assumps: Vec<Lit> = (0..n_lits)
+            .map(|idx| {
+                if assign & (1 << idx) != 0 {
+                    lit![idx]
+                } else {
+                    !lit![idx]
+                }
+            })
+            .collect();
+        let expected = if assign.count_ones() == 1 { Sat } else { Unsat };
+        assert_eq!(solver.solve_assumps(&assumps).unwrap(), expected);
+    }
+}
+
+#[test]
+fn exactly_one_pairwise() {
+    test_eo_exhaustive::<ExactlyOne<Pairwise>>(5)
+}
+
+#[test]
+fn exactly_one_product() {
+    test_eo_exhaustive::<ExactlyOne<Product>>(10)
+}
+
+#[test]
+fn exactly_one_auto() {
+    test_eo_exhaustive::<ExactlyOne>(8)
+}
@@ -2,7 +2,8 @@ use rustsat::{
     clause,
     encodings::card::{
         simulators::{Double, Inverted},
-        BoundBoth, BoundBothIncremental, BoundUpperIncremental, DbTotalizer, Totalizer,
+        BoundBoth, BoundBothIncremental, BoundUpperIncremental, CardinalityNetwork, DbTotalizer,
+        KModuloTotalizer, ModuloTotalizer, SeqCounter, Totalizer,
     },
     instances::{BasicVarManager, ManageVars},
     lit,
@@ -279,6 +280,76 @@ fn dbtot_inc_ub() {
     test_inc_ub_card::<DbTotalizer>()
 }
 
+#[test]
+fn dbtot_inc_both() {
+    test_inc_both_card::<DbTotalizer>()
+}
+
+#[test]
+fn dbtot_both() {
+    test_both_card::<DbTotalizer>()
+}
+
+#[test]
+fn dbtot_min_enc() {
+    test_both_card_min_enc::<DbTotalizer>()
+}
+
+#[test]
+fn seqc_inc_ub() {
+    test_inc_ub_card::<SeqCounter>()
+}
+
+#[test]
+fn seqc_inc_both() {
+    test_inc_both_card::<SeqCounter>()
+}
+
+#[test]
+fn seqc_both() {
+    test_both_card::<SeqCounter>()
+}
+
+#[test]
+fn seqc_min_enc() {
+    test_both_card_min_enc::<SeqCounter>()
+}
+
+#[test]
+fn cnet_inc_ub() {
+    test_inc_ub_card::<CardinalityNetwork>()
+}
+
+#[test]
+fn cnet_inc_both() {
+    test_inc_both_card::<CardinalityNetwork>()
+}
+
+#[test]
+fn cnet_both() {
+    test_both_card::<CardinalityNetwork>()
+}
+
+#[test]
+fn cnet_min_enc() {
+    test_both_card_min_enc::<CardinalityNetwork>()
+}
+
+#[test]
+fn mtot_inc_ub() {
+    test_inc_ub_card::<ModuloTotalizer>()
+}
+
+#[test]
+fn kmtot_inc_ub() {
+    test_inc_ub_card::<KModuloTotalizer<3>>()
+}
+
+#[test]
+fn double_mtot() {
+    test_inc_both_card::<Double<ModuloTotalizer, Inverted<ModuloTotalizer>>>()
+}
+
 use rustsat_tools::{test_all, test_assignment};
 
 fn test_ub_exhaustive<CE: BoundUpperIncremental + From<Vec<Lit>>>() {
@@ -553,3 +624,43 @@ fn invtot_both_exhaustive() {
 fn dbtot_ub_exhaustive() {
     test_ub_exhaustive::<DbTotalizer>()
 }
+
+#[test]
+fn dbtot_both_exhaustive() {
+    test_both_exhaustive::<DbTotalizer>()
+}
+
+#[test]
+fn seqc_ub_exhaustive() {
+    test_ub_exhaustive::<SeqCounter>()
+}
+
+#[test]
+fn seqc_both_exhaustive() {
+    test_both_exhaustive::<SeqCounter>()
+}
+
+#[test]
+fn cnet_ub_exhaustive() {
+    test_ub_exhaustive::<CardinalityNetwork>()
+}
+
+#[test]
+fn cnet_both_exhaustive() {
+    test_both_exhaustive::<CardinalityNetwork>()
+}
+
+#[test]
+fn mtot_ub_exhaustive() {
+    test_ub_exhaustive::<ModuloTotalizer>()
+}
+
+#[test]
+fn kmtot_ub_exhaustive() {
+    test_ub_exhaustive::<KModuloTotalizer<3>>()
+}
+
+#[test]
+fn double_mtot_both_exhaustive() {
+    test_both_exhaustive::<Double<ModuloTotalizer, Inverted<ModuloTotalizer>>>()
+}
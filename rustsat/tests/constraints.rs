@@ -1,11 +1,13 @@
 use rustsat::{
-    instances::SatInstance,
+    encodings::{card, pb, Reification},
+    instances::{BasicVarManager, Cnf, ManageVars, SatInstance},
     lit,
     solvers::{Solve, SolveIncremental, SolverResult},
     types::{
-        constraints::{CardConstraint, PBConstraint},
-        RsHashMap,
+        constraints::{CardConstraint, PBConstraint, XorConstraint},
+        Lit, RsHashMap,
     },
+    var,
 };
 
 macro_rules! test_card {
@@ -46,6 +48,25 @@ macro_rules! test_pb {
     }};
 }
 
+macro_rules! test_xor {
+    ( $constr:expr, $sat_assump:expr, $unsat_assump:expr ) => {{
+        let mut inst: SatInstance = SatInstance::new();
+        inst.add_xor_constr($constr);
+        let (cnf, _) = inst.into_cnf();
+        println!("{:?}", cnf);
+        let mut solver = rustsat_tools::Solver::default();
+        solver.add_cnf(cnf).unwrap();
+        assert_eq!(
+            solver.solve_assumps($sat_assump).unwrap(),
+            SolverResult::Sat
+        );
+        assert_eq!(
+            solver.solve_assumps($unsat_assump).unwrap(),
+            SolverResult::Unsat
+        );
+    }};
+}
+
 #[test]
 fn card_ub() {
     let lits = vec![lit![0], lit![1], lit![2]];
@@ -61,6 +82,36 @@ fn card_ub() {
     );
 }
 
+#[test]
+fn card_am1() {
+    let lits: Vec<_> = (0..30).map(|idx| lit![idx]).collect();
+    test_card!(
+        CardConstraint::new_ub(lits.clone(), 1),
+        &[!lit![0], lit![17], !lit![29]],
+        &[lit![3], lit![25]]
+    );
+    // at-most-1 constraints are not encoded with a totalizer
+    let mut inst: SatInstance = SatInstance::new();
+    inst.add_card_constr(CardConstraint::new_ub(lits[..4].to_vec(), 1));
+    inst.convert_to_cnf();
+    assert_eq!(inst.n_clauses(), 6);
+}
+
+#[test]
+fn card_eo() {
+    let lits: Vec<_> = (0..30).map(|idx| lit![idx]).collect();
+    test_card!(
+        CardConstraint::new_eq(lits.clone(), 1),
+        &[!lit![0], lit![17], !lit![29]],
+        &[lit![3], lit![25]]
+    );
+    test_card!(
+        CardConstraint::new_eq(lits.clone(), 1),
+        &[lit![12]],
+        &lits.iter().map(|l| !*l).collect::<Vec<_>>()
+    );
+}
+
 #[test]
 fn card_lb() {
     let lits = vec![lit![0], lit![1], lit![2]];
@@ -170,6 +221,175 @@ fn pb_eq() {
     );
 }
 
+#[test]
+fn pb_eq_swc() {
+    let mut lits = RsHashMap::default();
+    lits.insert(lit![0], 1);
+    lits.insert(lit![1], 2);
+    lits.insert(lit![2], 3);
+    let mut inst: SatInstance = SatInstance::new();
+    inst.add_pb_constr(PBConstraint::new_eq(lits, 3));
+    let (cnf, _) = inst.into_cnf_with_encoders(
+        |constr, cnf, vm| card::default_encode_cardinality_constraint(constr, cnf, vm).unwrap(),
+        |constr, cnf, vm| {
+            pb::encode_pb_constraint::<pb::DoubleSeqWeightCounter, _>(constr, cnf, vm).unwrap()
+        },
+    );
+    let mut solver = rustsat_tools::Solver::default();
+    solver.add_cnf(cnf).unwrap();
+    assert_eq!(
+        solver.solve_assumps(&[lit![0], lit![1], !lit![2]]).unwrap(),
+        SolverResult::Sat
+    );
+    assert_eq!(
+        solver
+            .solve_assumps(&[!lit![0], !lit![1], lit![2]])
+            .unwrap(),
+        SolverResult::Sat
+    );
+    assert_eq!(
+        solver
+            .solve_assumps(&[!lit![0], lit![1], !lit![2]])
+            .unwrap(),
+        SolverResult::Unsat
+    );
+    assert_eq!(
+        solver.solve_assumps(&[lit![0], !lit![1], lit![2]]).unwrap(),
+        SolverResult::Unsat
+    );
+}
+
+#[test]
+fn pb_zero_weight() {
+    let weights: [isize; 4] = [2, 5, 0, 1];
+    let lits: Vec<(Lit, isize)> = weights
+        .iter()
+        .enumerate()
+        .map(|(idx, &w)| (lit![idx as u32], w))
+        .collect();
+    for b in 0..=8 {
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        let mut cnf = Cnf::new();
+        pb::encode_pb_constraint::<pb::DefBothBounding, _>(
+            PBConstraint::new_eq(lits.clone(), b),
+            &mut cnf,
+            &mut var_manager,
+        )
+        .unwrap();
+        let mut solver = rustsat_tools::Solver::default();
+        solver.add_cnf(cnf).unwrap();
+        for assign in 0..1_u32 << 4 {
+            let assumps: Vec<Lit> = (0..4)
+                .map(|idx| {
+                    if assign >> idx & 1 == 1 {
+                        lit![idx]
+                    } else {
+                        !lit![idx]
+                    }
+                })
+                .collect();
+            let sum: isize = (0..4)
+                .map(|idx| (assign >> idx & 1) as isize * weights[idx])
+                .sum();
+            let expected = if sum == b {
+                SolverResult::Sat
+            } else {
+                SolverResult::Unsat
+            };
+            assert_eq!(solver.solve_assumps(&assumps).unwrap(), expected);
+        }
+    }
+}
+
+#[test]
+fn pb_auto_amo() {
+    let mut lits = RsHashMap::default();
+    lits.insert(lit![0], 3);
+    lits.insert(lit![1], 4);
+    lits.insert(lit![2], 5);
+    lits.insert(lit![3], 7);
+    test_pb!(
+        PBConstraint::new_ub(lits.clone(), 6),
+        &[!lit![0], !lit![1], lit![2]],
+        &[lit![0], !lit![1], lit![2]]
+    );
+    test_pb!(
+        PBConstraint::new_ub(lits.clone(), 6),
+        &[lit![1]],
+        &[lit![3]]
+    );
+    // `lit![3]` is fixed, the others are encoded pairwise
+    let mut inst: SatInstance = SatInstance::new();
+    inst.add_pb_constr(PBConstraint::new_ub(lits, 6));
+    inst.convert_to_cnf();
+    assert_eq!(inst.n_clauses(), 4);
+}
+
+#[test]
+fn pb_auto_large_equal_weights() {
+    let constr = PBConstraint::new_ub(
+        [
+            (lit![0], 1_000_000),
+            (lit![1], 1_000_000),
+            (lit![2], 1_000_000),
+            (lit![3], 3_000_000),
+        ],
+        2_000_000,
+    );
+    let mut var_manager = BasicVarManager::default();
+    var_manager.increase_next_free(var![4]);
+    let mut cnf = Cnf::new();
+    pb::default_encode_pb_constraint(constr, &mut cnf, &mut var_manager).unwrap();
+    // The common weight is divided out rather than repeating the literals
+    assert!(cnf.len() < 100);
+    let mut solver = rustsat_tools::Solver::default();
+    solver.add_cnf(cnf).unwrap();
+    assert_eq!(
+        solver
+            .solve_assumps(&[lit![0], lit![1], !lit![2], !lit![3]])
+            .unwrap(),
+        SolverResult::Sat
+    );
+    assert_eq!(
+        solver.solve_assumps(&[lit![0], lit![1], lit![2]]).unwrap(),
+        SolverResult::Unsat
+    );
+    assert_eq!(
+        solver.solve_assumps(&[lit![3]]).unwrap(),
+        SolverResult::Unsat
+    );
+}
+
+#[test]
+fn pb_auto_zero_weight() {
+    // Zero-weight literals are not fixed together with the other literals
+    test_pb!(
+        PBConstraint::new_eq([(lit![0], 2), (lit![1], 3), (lit![2], 0)], 0),
+        &[!lit![0], !lit![1], lit![2]],
+        &[lit![0]]
+    );
+    test_pb!(
+        PBConstraint::new_eq([(lit![0], 2), (lit![1], 3), (lit![2], 0)], 5),
+        &[lit![0], lit![1], !lit![2]],
+        &[!lit![1]]
+    );
+}
+
+#[test]
+fn pb_eq_not_divisible() {
+    let mut lits = RsHashMap::default();
+    lits.insert(lit![0], 2);
+    lits.insert(lit![1], 2);
+    lits.insert(lit![2], 2);
+    let mut inst: SatInstance = SatInstance::new();
+    inst.add_pb_constr(PBConstraint::new_eq(lits, 3));
+    let (cnf, _) = inst.into_cnf();
+    let mut solver = rustsat_tools::Solver::default();
+    solver.add_cnf(cnf).unwrap();
+    assert_eq!(solver.solve().unwrap(), SolverResult::Unsat);
+}
+
 #[test]
 fn card_clause() {
     let lits = vec![lit![0], lit![1], lit![2]];
@@ -202,3 +422,407 @@ fn pb_clause() {
         &[!lit![0], !lit![1], !lit![2]]
     );
 }
+
+#[test]
+fn xor() {
+    let lits: Vec<_> = (0..9).map(|idx| lit![idx]).collect();
+    test_xor!(
+        XorConstraint::new(lits.clone(), true),
+        &[lit![0], lit![4], lit![8], !lit![1], !lit![2], !lit![3]],
+        &[
+            lit![0],
+            lit![4],
+            !lit![1],
+            !lit![2],
+            !lit![3],
+            !lit![5],
+            !lit![6],
+            !lit![7],
+            !lit![8]
+        ]
+    );
+    test_xor!(
+        XorConstraint::new(lits.clone(), false),
+        &[lit![0], lit![4], !lit![1], !lit![2], !lit![3]],
+        &[
+            lit![0],
+            lit![4],
+            lit![8],
+            !lit![1],
+            !lit![2],
+            !lit![3],
+            !lit![5],
+            !lit![6],
+            !lit![7]
+        ]
+    );
+}
+
+#[test]
+fn xor_convert() {
+    let mut inst: SatInstance = SatInstance::new();
+    inst.add_xor_constr(XorConstraint::new(vec![lit![0], lit![1], lit![2]], true));
+    inst.add_xor_constr(XorConstraint::new(vec![lit![0], !lit![1]], true));
+    assert_eq!(inst.n_xors(), 2);
+    inst.convert_to_cnf();
+    assert_eq!(inst.n_xors(), 0);
+    assert_eq!(inst.n_clauses(), 6);
+    assert_eq!(inst.n_vars(), 3);
+    assert!(inst.write_dimacs(&mut std::io::sink()).is_ok());
+}
+
+#[test]
+fn xor_unsat() {
+    let mut inst: SatInstance = SatInstance::new();
+    inst.add_xor_constr(XorConstraint::new(vec![lit![0], lit![1], lit![2]], true));
+    inst.add_xor_constr(XorConstraint::new(vec![lit![1], lit![0]], false));
+    inst.add_xor_constr(XorConstraint::new(vec![lit![2]], false));
+    let (cnf, _) = inst.into_cnf();
+    let mut solver = rustsat_tools::Solver::default();
+    solver.add_cnf(cnf).unwrap();
+    assert_eq!(solver.solve().unwrap(), SolverResult::Unsat);
+}
+
+/// Checks a reified constraint over the variables `0..n` with activation
+/// literal `lit![n]` for all assignments
+fn test_reified<Add, Holds>(n: u32, reif: Reification, add: Add, holds: Holds)
+where
+    Add: FnOnce(&mut SatInstance),
+    Holds: Fn(&[usize]) -> bool,
+{
+    let mut inst: SatInstance = SatInstance::new();
+    add(&mut inst);
+    let (cnf, _) = inst.into_cnf();
+    let mut solver = rustsat_tools::Solver::default();
+    solver.add_cnf(cnf).unwrap();
+    for assign in 0..1_u32 << (n + 1) {
+        let assumps: Vec<Lit> = (0..=n)
+            .map(|idx| {
+                if assign >> idx & 1 == 1 {
+                    lit![idx]
+                } else {
+                    !lit![idx]
+                }
+            })
+            .collect();
+        let vals: Vec<usize> = (0..n).map(|idx| (assign >> idx & 1) as usize).collect();
+        let act = assign >> n & 1 == 1;
+        let expected = match reif {
+            Reification::Half => !act || holds(&vals),
+            Reification::Full => act == holds(&vals),
+        };
+        let res = solver.solve_assumps(&assumps).unwrap();
+        assert_eq!(
+            res == SolverResult::Sat,
+            expected,
+            "assumps: {:?}, reification: {:?}",
+            assumps,
+            reif
+        );
+    }
+}
+
+#[test]
+fn card_reified() {
+    let lits = vec![lit![0], lit![1], lit![2]];
+    for reif in [Reification::Half, Reification::Full] {
+        for b in 0..=4 {
+            test_reified(
+                3,
+                reif,
+                |inst| {
+                    inst.add_reified_card_constr(
+                        CardConstraint::new_ub(lits.clone(), b),
+                        lit![3],
+                        reif,
+                    )
+                },
+                |vals| vals.iter().sum::<usize>() <= b,
+            );
+            test_reified(
+                3,
+                reif,
+                |inst| {
+                    inst.add_reified_card_constr(
+                        CardConstraint::new_lb(lits.clone(), b),
+                        lit![3],
+                        reif,
+                    )
+                },
+                |vals| vals.iter().sum::<usize>() >= b,
+            );
+            test_reified(
+                3,
+                reif,
+                |inst| {
+                    inst.add_reified_card_constr(
+                        CardConstraint::new_eq(lits.clone(), b),
+                        lit![3],
+                        reif,
+                    )
+                },
+                |vals| vals.iter().sum::<usize>() == b,
+            );
+        }
+    }
+}
+
+#[test]
+fn pb_reified() {
+    let weights: [isize; 3] = [1, 2, 3];
+    let lits: RsHashMap<Lit, isize> = weights
+        .iter()
+        .enumerate()
+        .map(|(idx, &w)| (lit![idx as u32], w))
+        .collect();
+    let sum =
+        |vals: &[usize]| -> isize { vals.iter().zip(weights).map(|(&v, w)| v as isize * w).sum() };
+    for reif in [Reification::Half, Reification::Full] {
+        for b in -1..=7 {
+            test_reified(
+                3,
+                reif,
+                |inst| {
+                    inst.add_reified_pb_constr(PBConstraint::new_ub(lits.clone(), b), lit![3], reif)
+                },
+                |vals| sum(vals) <= b,
+            );
+            test_reified(
+                3,
+                reif,
+                |inst| {
+                    inst.add_reified_pb_constr(PBConstraint::new_lb(lits.clone(), b), lit![3], reif)
+                },
+                |vals| sum(vals) >= b,
+            );
+            test_reified(
+                3,
+                reif,
+                |inst| {
+                    inst.add_reified_pb_constr(PBConstraint::new_eq(lits.clone(), b), lit![3], reif)
+                },
+                |vals| sum(vals) == b,
+            );
+        }
+    }
+}
+
+#[test]
+fn pb_reified_zero_weight() {
+    let weights: [isize; 4] = [2, 5, 0, 1];
+    let lits: RsHashMap<Lit, isize> = weights
+        .iter()
+        .enumerate()
+        .map(|(idx, &w)| (lit![idx as u32], w))
+        .collect();
+    let sum =
+        |vals: &[usize]| -> isize { vals.iter().zip(weights).map(|(&v, w)| v as isize * w).sum() };
+    for reif in [Reification::Half, Reification::Full] {
+        for b in 0..=9 {
+            test_reified(
+                4,
+                reif,
+                |inst| {
+                    inst.add_reified_pb_constr(PBConstraint::new_lb(lits.clone(), b), lit![4], reif)
+                },
+                |vals| sum(vals) >= b,
+            );
+            test_reified(
+                4,
+                reif,
+                |inst| {
+                    inst.add_reified_pb_constr(PBConstraint::new_eq(lits.clone(), b), lit![4], reif)
+                },
+                |vals| sum(vals) == b,
+            );
+            test_reified(
+                4,
+                reif,
+                |inst| {
+                    let mut var_manager = BasicVarManager::default();
+                    var_manager.increase_next_free(var![5]);
+                    pb::encode_reified_pb_constraint::<pb::DynamicPolyWatchdog, _>(
+                        PBConstraint::new_eq(lits.clone(), b),
+                        lit![4],
+                        reif,
+                        inst,
+                        &mut var_manager,
+                    )
+                    .unwrap()
+                },
+                |vals| sum(vals) == b,
+            );
+        }
+    }
+}
+
+#[test]
+fn pb_reified_duplicate_lits() {
+    // Contains a repeated literal and complementary literals
+    let lits: Vec<(Lit, isize)> = vec![
+        (!lit![0], -1),
+        (lit![2], 2),
+        (!lit![2], -1),
+        (lit![1], 3),
+        (lit![1], -1),
+        (lit![0], 2),
+    ];
+    let sum = |vals: &[usize]| -> isize {
+        lits.iter()
+            .map(|&(l, w)| {
+                if (vals[l.vidx()] == 1) == l.is_pos() {
+                    w
+                } else {
+                    0
+                }
+            })
+            .sum()
+    };
+    for reif in [Reification::Half, Reification::Full] {
+        for b in -3..=7 {
+            test_reified(
+                3,
+                reif,
+                |inst| {
+                    inst.add_reified_pb_constr(PBConstraint::new_ub(lits.clone(), b), lit![3], reif)
+                },
+                |vals| sum(vals) <= b,
+            );
+            test_reified(
+                3,
+                reif,
+                |inst| {
+                    inst.add_reified_pb_constr(PBConstraint::new_lb(lits.clone(), b), lit![3], reif)
+                },
+                |vals| sum(vals) >= b,
+            );
+            test_reified(
+                3,
+                reif,
+                |inst| {
+                    let mut var_manager = BasicVarManager::default();
+                    var_manager.increase_next_free(var![4]);
+                    pb::encode_reified_pb_constraint::<pb::Adder, _>(
+                        PBConstraint::new_eq(lits.clone(), b),
+                        lit![3],
+                        reif,
+                        inst,
+                        &mut var_manager,
+                    )
+                    .unwrap()
+                },
+                |vals| sum(vals) == b,
+            );
+        }
+    }
+}
+
+#[test]
+fn pb_reified_card() {
+    // PB constraints with equal weights are encoded as cardinality constraints
+    for weight in [1, 2] {
+        let lits: RsHashMap<Lit, isize> = (0..3).map(|idx| (lit![idx], weight)).collect();
+        let sum = |vals: &[usize]| -> isize { vals.iter().sum::<usize>() as isize * weight };
+        for reif in [Reification::Half, Reification::Full] {
+            for b in -1..=3 * weight + 1 {
+                test_reified(
+                    3,
+                    reif,
+                    |inst| {
+                        inst.add_reified_pb_constr(
+                            PBConstraint::new_ub(lits.clone(), b),
+                            lit![3],
+                            reif,
+                        )
+                    },
+                    |vals| sum(vals) <= b,
+                );
+                test_reified(
+                    3,
+                    reif,
+                    |inst| {
+                        inst.add_reified_pb_constr(
+                            PBConstraint::new_lb(lits.clone(), b),
+                            lit![3],
+                            reif,
+                        )
+                    },
+                    |vals| sum(vals) >= b,
+                );
+                test_reified(
+                    3,
+                    reif,
+                    |inst| {
+                        inst.add_reified_pb_constr(
+                            PBConstraint::new_eq(lits.clone(), b),
+                            lit![3],
+                            reif,
+                        )
+                    },
+                    |vals| sum(vals) == b,
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn pb_reified_full_tautology() {
+    let mut inst: SatInstance = SatInstance::new();
+    inst.add_reified_pb_constr(
+        PBConstraint::new_ub([(lit![0], 1), (lit![1], 1)], 5),
+        lit![2],
+        Reification::Full,
+    );
+    let (cnf, _) = inst.into_cnf();
+    let mut solver = rustsat_tools::Solver::default();
+    solver.add_cnf(cnf).unwrap();
+    assert_eq!(
+        solver.solve_assumps(&[lit![0], lit![1], lit![2]]).unwrap(),
+        SolverResult::Sat
+    );
+    assert_eq!(
+        solver.solve_assumps(&[!lit![2]]).unwrap(),
+        SolverResult::Unsat
+    );
+}
+
+#[test]
+fn pb_reified_encoder() {
+    let mut lits = RsHashMap::default();
+    lits.insert(lit![0], 1);
+    lits.insert(lit![1], 2);
+    lits.insert(lit![2], 3);
+    let mut var_manager = BasicVarManager::default();
+    var_manager.increase_next_free(var![3]);
+    let act = var_manager.new_var().pos_lit();
+    let mut cnf = Cnf::new();
+    pb::encode_reified_pb_constraint::<pb::DoubleSeqWeightCounter, _>(
+        PBConstraint::new_eq(lits, 3),
+        act,
+        Reification::Full,
+        &mut cnf,
+        &mut var_manager,
+    )
+    .unwrap();
+    let mut solver = rustsat_tools::Solver::default();
+    solver.add_cnf(cnf).unwrap();
+    assert_eq!(
+        solver
+            .solve_assumps(&[lit![0], lit![1], !lit![2], !act])
+            .unwrap(),
+        SolverResult::Unsat
+    );
+    assert_eq!(
+        solver
+            .solve_assumps(&[lit![0], !lit![1], lit![2], !act])
+            .unwrap(),
+        SolverResult::Sat
+    );
+    assert_eq!(
+        solver
+            .solve_assumps(&[!lit![0], !lit![1], lit![2], act])
+            .unwrap(),
+        SolverResult::Sat
+    );
+}
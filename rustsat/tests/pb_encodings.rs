@@ -3,9 +3,11 @@ use rustsat::{
     encodings::{
         card::Totalizer,
         pb::{
-            simulators::Card, BoundBoth, BoundBothIncremental, BoundLower, BoundUpper,
-            BoundUpperIncremental, DbGte, DoubleGeneralizedTotalizer, DynamicPolyWatchdog,
-            GeneralizedTotalizer, InvertedGeneralizedTotalizer,
+            simulators::Card, Adder, Auto, Bdd, BoundBoth, BoundLower, BoundLowerIncremental,
+            BoundUpper, BoundUpperIncremental, DbGte, DoubleGeneralizedTotalizer,
+            DoubleSeqWeightCounter, DynamicPolyWatchdog, GeneralizedTotalizer,
+            InvertedGeneralizedTotalizer, InvertedSeqWeightCounter, LocalPolyWatchdog,
+            PolyWatchdog, SeqWeightCounter,
         },
     },
     instances::{BasicVarManager, ManageVars},
@@ -103,7 +105,7 @@ fn test_inc_pb_ub<PBE: BoundUpperIncremental + Extend<(Lit, usize)> + Default>()
     assert_eq!(res, SolverResult::Sat);
 }
 
-fn test_pb_eq<PBE: BoundBothIncremental + From<RsHashMap<Lit, usize>>>() {
+fn test_pb_eq<PBE: BoundBoth + From<RsHashMap<Lit, usize>>>() {
     // Set up instance
     let mut solver = rustsat_minisat::core::Minisat::default();
     let mut var_manager = BasicVarManager::default();
@@ -257,6 +259,11 @@ fn gte_min_enc() {
     test_pb_ub_min_enc::<GeneralizedTotalizer>()
 }
 
+#[test]
+fn gte_native_lb() {
+    test_pb_lb::<GeneralizedTotalizer>()
+}
+
 #[test]
 fn gte_eq() {
     test_pb_eq::<DoubleGeneralizedTotalizer>()
@@ -272,6 +279,16 @@ fn dpw_min_enc() {
     test_pb_ub_min_enc::<DynamicPolyWatchdog>()
 }
 
+#[test]
+fn dpw_lb() {
+    test_pb_lb::<DynamicPolyWatchdog>()
+}
+
+#[test]
+fn dpw_eq() {
+    test_pb_eq::<DynamicPolyWatchdog>()
+}
+
 #[test]
 fn dbgte_ub() {
     test_inc_pb_ub::<DbGte>()
@@ -282,6 +299,155 @@ fn dbgte_min_enc() {
     test_pb_ub_min_enc::<DbGte>()
 }
 
+#[test]
+fn dbgte_lb() {
+    test_pb_lb::<DbGte>()
+}
+
+#[test]
+fn dbgte_eq() {
+    test_pb_eq::<DbGte>()
+}
+
+#[test]
+fn bdd_min_enc() {
+    test_pb_ub_min_enc::<Bdd>()
+}
+
+#[test]
+fn bdd_lb() {
+    test_pb_lb::<Bdd>()
+}
+
+#[test]
+fn bdd_eq() {
+    test_pb_eq::<Bdd>()
+}
+
+#[test]
+fn pw_min_enc() {
+    test_pb_ub_min_enc::<PolyWatchdog>()
+}
+
+#[test]
+fn pw_lb() {
+    test_pb_lb::<PolyWatchdog>()
+}
+
+#[test]
+fn pw_eq() {
+    test_pb_eq::<PolyWatchdog>()
+}
+
+#[test]
+fn lpw_min_enc() {
+    test_pb_ub_min_enc::<LocalPolyWatchdog>()
+}
+
+#[test]
+fn lpw_lb() {
+    test_pb_lb::<LocalPolyWatchdog>()
+}
+
+#[test]
+fn lpw_eq() {
+    test_pb_eq::<LocalPolyWatchdog>()
+}
+
+#[test]
+fn auto_min_enc() {
+    test_pb_ub_min_enc::<Auto>()
+}
+
+#[test]
+fn auto_lb() {
+    test_pb_lb::<Auto>()
+}
+
+#[test]
+fn auto_eq() {
+    test_pb_eq::<Auto>()
+}
+
+#[test]
+fn swc_ub() {
+    test_inc_pb_ub::<SeqWeightCounter>()
+}
+
+#[test]
+fn swc_min_enc() {
+    test_pb_ub_min_enc::<SeqWeightCounter>()
+}
+
+#[test]
+fn swc_lb() {
+    test_pb_lb::<InvertedSeqWeightCounter>()
+}
+
+#[test]
+fn swc_eq() {
+    test_pb_eq::<DoubleSeqWeightCounter>()
+}
+
+#[test]
+fn adder_ub() {
+    test_inc_pb_ub::<Adder>()
+}
+
+#[test]
+fn adder_min_enc() {
+    test_pb_ub_min_enc::<Adder>()
+}
+
+#[test]
+fn adder_lb() {
+    test_pb_lb::<Adder>()
+}
+
+#[test]
+fn adder_eq() {
+    test_pb_eq::<Adder>()
+}
+
+#[test]
+fn adder_huge_weights() {
+    let mut solver = rustsat_minisat::core::Minisat::default();
+    let mut var_manager = BasicVarManager::default();
+    var_manager.increase_next_free(var![4]);
+
+    let mut lits = RsHashMap::default();
+    lits.insert(lit![0], 3_000_000_019);
+    lits.insert(lit![1], 2_000_000_011);
+    lits.insert(lit![2], 1_000_000_007);
+    lits.insert(lit![3], 1_000_000_009);
+    let mut enc = Adder::from(lits);
+
+    enc.encode_ub_change(4_000_000_027..4_000_000_028, &mut solver, &mut var_manager)
+        .unwrap();
+    let mut assumps = enc.enforce_ub(4_000_000_027).unwrap();
+    assumps.extend([lit![0], lit![2], !lit![1], !lit![3]]);
+    assert_eq!(solver.solve_assumps(&assumps).unwrap(), SolverResult::Sat);
+    let mut assumps = enc.enforce_ub(4_000_000_027).unwrap();
+    assumps.extend([lit![0], lit![3]]);
+    assert_eq!(solver.solve_assumps(&assumps).unwrap(), SolverResult::Unsat);
+
+    enc.encode_ub_change(4_000_000_025..4_000_000_026, &mut solver, &mut var_manager)
+        .unwrap();
+    let mut assumps = enc.enforce_ub(4_000_000_025).unwrap();
+    assumps.push(lit![0]);
+    assumps.push(lit![2]);
+    assert_eq!(solver.solve_assumps(&assumps).unwrap(), SolverResult::Unsat);
+
+    enc.encode_lb_change(6_000_000_030..6_000_000_031, &mut solver, &mut var_manager)
+        .unwrap();
+    let mut assumps = enc.enforce_lb(6_000_000_030).unwrap();
+    assumps.push(!lit![0]);
+    assert_eq!(solver.solve_assumps(&assumps).unwrap(), SolverResult::Unsat);
+    let mut assumps = enc.enforce_lb(6_000_000_030).unwrap();
+    assumps.push(!lit![3]);
+    assert_eq!(solver.solve_assumps(&assumps).unwrap(), SolverResult::Sat);
+}
+
 use rustsat_tools::{test_all, test_assignment};
 
 fn test_ub_exhaustive<PBE: BoundUpperIncremental + From<RsHashMap<Lit, usize>>>(
@@ -341,6 +507,212 @@ fn test_ub_exhaustive<PBE: BoundUpperIncremental + From<RsHashMap<Lit, usize>>>(
     }
 }
 
+fn test_lb_exhaustive<PBE: BoundLowerIncremental + From<RsHashMap<Lit, usize>>>(
+    weights: [usize; 4],
+    decreasing: bool,
+) {
+    let mut solver = rustsat_minisat::core::Minisat::default();
+    let mut lits = RsHashMap::default();
+    lits.insert(lit![0], weights[0]);
+    lits.insert(lit![1], weights[1]);
+    lits.insert(lit![2], weights[2]);
+    lits.insert(lit![3], weights[3]);
+    let mut enc = PBE::from(lits);
+    let mut var_manager = BasicVarManager::default();
+    var_manager.increase_next_free(var![4]);
+
+    let max_val = weights.iter().sum::<usize>();
+    let expected = |assign: usize, bound: usize| {
+        let sum = (0..4).fold(0, |sum, idx| sum + ((assign >> idx) & 1) * weights[3 - idx]);
+        if sum >= bound {
+            Sat
+        } else {
+            Unsat
+        }
+    };
+
+    for mut bound in 0..=max_val {
+        if decreasing {
+            bound = max_val - bound;
+        }
+        println!("bound: {}", bound);
+
+        enc.encode_lb_change(bound..bound + 1, &mut solver, &mut var_manager)
+            .unwrap();
+        let assumps = enc.enforce_lb(bound).unwrap();
+
+        test_all!(
+            solver,
+            assumps, //
+            expected(0b1111, bound),
+            expected(0b1110, bound),
+            expected(0b1101, bound),
+            expected(0b1100, bound),
+            expected(0b1011, bound),
+            expected(0b1010, bound),
+            expected(0b1001, bound),
+            expected(0b1000, bound),
+            expected(0b0111, bound),
+            expected(0b0110, bound),
+            expected(0b0101, bound),
+            expected(0b0100, bound),
+            expected(0b0011, bound),
+            expected(0b0010, bound),
+            expected(0b0001, bound),
+            expected(0b0000, bound)
+        );
+    }
+}
+
+fn test_both_exhaustive<PBE: BoundBoth + From<RsHashMap<Lit, usize>>>(weights: [usize; 4]) {
+    let max_val = weights.iter().sum::<usize>();
+    let expected = |assign: usize, lb: usize, ub: usize| {
+        let sum = (0..4).fold(0, |sum, idx| sum + ((assign >> idx) & 1) * weights[3 - idx]);
+        if sum >= lb && sum <= ub {
+            Sat
+        } else {
+            Unsat
+        }
+    };
+
+    for lb in 0..=max_val {
+        for ub in lb..=max_val {
+            println!("bounds: {}..={}", lb, ub);
+            let mut solver = rustsat_minisat::core::Minisat::default();
+            let mut lits = RsHashMap::default();
+            lits.insert(lit![0], weights[0]);
+            lits.insert(lit![1], weights[1]);
+            lits.insert(lit![2], weights[2]);
+            lits.insert(lit![3], weights[3]);
+            let mut enc = PBE::from(lits);
+            let mut var_manager = BasicVarManager::default();
+            var_manager.increase_next_free(var![4]);
+
+            enc.encode_ub(ub..ub + 1, &mut solver, &mut var_manager)
+                .unwrap();
+            enc.encode_lb(lb..lb + 1, &mut solver, &mut var_manager)
+                .unwrap();
+            let mut assumps = enc.enforce_ub(ub).unwrap();
+            assumps.extend(enc.enforce_lb(lb).unwrap());
+
+            test_all!(
+                solver,
+                assumps, //
+                expected(0b1111, lb, ub),
+                expected(0b1110, lb, ub),
+                expected(0b1101, lb, ub),
+                expected(0b1100, lb, ub),
+                expected(0b1011, lb, ub),
+                expected(0b1010, lb, ub),
+                expected(0b1001, lb, ub),
+                expected(0b1000, lb, ub),
+                expected(0b0111, lb, ub),
+                expected(0b0110, lb, ub),
+                expected(0b0101, lb, ub),
+                expected(0b0100, lb, ub),
+                expected(0b0011, lb, ub),
+                expected(0b0010, lb, ub),
+                expected(0b0001, lb, ub),
+                expected(0b0000, lb, ub)
+            );
+        }
+    }
+}
+
+macro_rules! generate_exhaustive_both {
+    ($mod:ident, $enc:ty) => {
+        mod $mod {
+            use rustsat::encodings::pb::*;
+
+            #[test]
+            fn both_1111() {
+                super::test_both_exhaustive::<$enc>([1, 1, 1, 1]);
+            }
+
+            #[test]
+            fn both_5533() {
+                super::test_both_exhaustive::<$enc>([5, 5, 3, 3]);
+            }
+
+            #[test]
+            fn both_2173() {
+                super::test_both_exhaustive::<$enc>([2, 1, 7, 3]);
+            }
+
+            #[test]
+            fn both_8918() {
+                super::test_both_exhaustive::<$enc>([8, 9, 1, 8]);
+            }
+        }
+    };
+}
+
+generate_exhaustive_both!(bdd_exhaustive, Bdd);
+
+generate_exhaustive_both!(pw_exhaustive, PolyWatchdog);
+
+generate_exhaustive_both!(lpw_exhaustive, LocalPolyWatchdog);
+
+#[test]
+fn gte_zero_weight() {
+    test_lb_exhaustive::<GeneralizedTotalizer>([2, 5, 0, 1], false);
+    test_both_exhaustive::<GeneralizedTotalizer>([2, 5, 0, 1]);
+}
+
+#[test]
+fn dbgte_zero_weight() {
+    test_lb_exhaustive::<DbGte>([2, 5, 0, 1], false);
+    test_both_exhaustive::<DbGte>([2, 5, 0, 1]);
+}
+
+macro_rules! generate_exhaustive_lb {
+    ($mod:ident, $enc:ty) => {
+        mod $mod {
+            use rustsat::encodings::pb::*;
+
+            #[test]
+            fn increasing_1111() {
+                super::test_lb_exhaustive::<$enc>([1, 1, 1, 1], false);
+            }
+
+            #[test]
+            fn decreasing_1111() {
+                super::test_lb_exhaustive::<$enc>([1, 1, 1, 1], true);
+            }
+
+            #[test]
+            fn increasing_5533() {
+                super::test_lb_exhaustive::<$enc>([5, 5, 3, 3], false);
+            }
+
+            #[test]
+            fn decreasing_5533() {
+                super::test_lb_exhaustive::<$enc>([5, 5, 3, 3], true);
+            }
+
+            #[test]
+            fn increasing_2173() {
+                super::test_lb_exhaustive::<$enc>([2, 1, 7, 3], false);
+            }
+
+            #[test]
+            fn decreasing_2173() {
+                super::test_lb_exhaustive::<$enc>([2, 1, 7, 3], true);
+            }
+
+            #[test]
+            fn increasing_8918() {
+                super::test_lb_exhaustive::<$enc>([8, 9, 1, 8], false);
+            }
+
+            #[test]
+            fn decreasing_8918() {
+                super::test_lb_exhaustive::<$enc>([8, 9, 1, 8], true);
+            }
+        }
+    };
+}
+
 macro_rules! generate_exhaustive {
     ($mod:ident, $enc:ty) => {
         mod $mod {
@@ -413,8 +785,20 @@ generate_exhaustive!(gte, GeneralizedTotalizer);
 
 generate_exhaustive!(dbgte, DbGte);
 
+generate_exhaustive_lb!(gte_lb_exhaustive, GeneralizedTotalizer);
+
+generate_exhaustive_lb!(dbgte_lb_exhaustive, DbGte);
+
+generate_exhaustive_lb!(dpw_lb_exhaustive, DynamicPolyWatchdog);
+
 generate_exhaustive!(dpw, DynamicPolyWatchdog);
 
+generate_exhaustive!(adder, Adder);
+
+generate_exhaustive!(swc, SeqWeightCounter);
+
+generate_exhaustive_lb!(adder_lb_exhaustive, Adder);
+
 generate_exhaustive!(
     gte_inv_inv,
     simulators::Inverted<simulators::Inverted<GeneralizedTotalizer>>
@@ -427,7 +811,10 @@ generate_exhaustive!(
 
 mod dpw_inc_prec {
     use rustsat::{
-        encodings::pb::{dpw::DynamicPolyWatchdog, BoundUpper, BoundUpperIncremental},
+        encodings::pb::{
+            dpw::DynamicPolyWatchdog, BoundLower, BoundLowerIncremental, BoundUpper,
+            BoundUpperIncremental,
+        },
         instances::{BasicVarManager, Cnf, ManageVars, OptInstance},
         lit,
         solvers::{
@@ -508,6 +895,138 @@ mod dpw_inc_prec {
         }
     }
 
+    #[test]
+    fn incremental_precision_lb() {
+        let weights = [5, 8, 7, 3];
+        let mut lits = RsHashMap::default();
+        lits.insert(lit![0], weights[0]);
+        lits.insert(lit![1], weights[1]);
+        lits.insert(lit![2], weights[2]);
+        lits.insert(lit![3], weights[3]);
+        for decreasing in [false, true] {
+            println!("decreasing: {}", decreasing);
+            let mut solver = rustsat_minisat::core::Minisat::default();
+            let mut enc = DynamicPolyWatchdog::from(lits.clone());
+            let mut var_manager = BasicVarManager::default();
+            var_manager.increase_next_free(var![4]);
+
+            for prec in 0..3 {
+                let prec_div = 1 << (3 - prec);
+                println!("precision: {}", prec_div);
+                enc.set_precision(prec_div).unwrap();
+
+                let max_val = weights.iter().fold(0, |sum, &w| sum + (w / prec_div));
+                let expected = |assign: usize, bound: usize| {
+                    let sum = (0..4).fold(0, |sum, idx| {
+                        sum + ((assign >> idx) & 1) * (weights[3 - idx] / prec_div)
+                    });
+                    if sum >= bound {
+                        Sat
+                    } else {
+                        Unsat
+                    }
+                };
+
+                for mut bound in 0..=max_val {
+                    if decreasing {
+                        bound = max_val - bound;
+                    }
+                    println!("bound: {}", bound);
+                    let mut cnf = Cnf::default();
+                    enc.encode_lb_change(bound..bound + 1, &mut cnf, &mut var_manager)
+                        .unwrap();
+                    println!("extending encoding: {:?}", cnf);
+                    solver.add_cnf(cnf).unwrap();
+                    let assumps = enc.enforce_lb(bound).unwrap();
+
+                    test_all!(
+                        solver,
+                        assumps, //
+                        expected(0b1111, bound),
+                        expected(0b1110, bound),
+                        expected(0b1101, bound),
+                        expected(0b1100, bound),
+                        expected(0b1011, bound),
+                        expected(0b1010, bound),
+                        expected(0b1001, bound),
+                        expected(0b1000, bound),
+                        expected(0b0111, bound),
+                        expected(0b0110, bound),
+                        expected(0b0101, bound),
+                        expected(0b0100, bound),
+                        expected(0b0011, bound),
+                        expected(0b0010, bound),
+                        expected(0b0001, bound),
+                        expected(0b0000, bound)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn incremental_precision_both() {
+        let weights = [5, 8, 7, 3];
+        let mut lits = RsHashMap::default();
+        lits.insert(lit![0], weights[0]);
+        lits.insert(lit![1], weights[1]);
+        lits.insert(lit![2], weights[2]);
+        lits.insert(lit![3], weights[3]);
+        let mut solver = rustsat_minisat::core::Minisat::default();
+        let mut enc = DynamicPolyWatchdog::from(lits);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+
+        for prec in 0..3 {
+            let prec_div = 1 << (3 - prec);
+            println!("precision: {}", prec_div);
+            enc.set_precision(prec_div).unwrap();
+
+            let max_val = weights.iter().fold(0, |sum, &w| sum + (w / prec_div));
+            let expected = |assign: usize, bound: usize| {
+                let sum = (0..4).fold(0, |sum, idx| {
+                    sum + ((assign >> idx) & 1) * (weights[3 - idx] / prec_div)
+                });
+                if sum == bound {
+                    Sat
+                } else {
+                    Unsat
+                }
+            };
+
+            for bound in 0..=max_val {
+                println!("bound: {}", bound);
+                enc.encode_ub_change(bound..bound + 1, &mut solver, &mut var_manager)
+                    .unwrap();
+                enc.encode_lb_change(bound..bound + 1, &mut solver, &mut var_manager)
+                    .unwrap();
+                let mut assumps = enc.enforce_ub(bound).unwrap();
+                assumps.extend(enc.enforce_lb(bound).unwrap());
+
+                test_all!(
+                    solver,
+                    assumps, //
+                    expected(0b1111, bound),
+                    expected(0b1110, bound),
+                    expected(0b1101, bound),
+                    expected(0b1100, bound),
+                    expected(0b1011, bound),
+                    expected(0b1010, bound),
+                    expected(0b1001, bound),
+                    expected(0b1000, bound),
+                    expected(0b0111, bound),
+                    expected(0b0110, bound),
+                    expected(0b0101, bound),
+                    expected(0b0100, bound),
+                    expected(0b0011, bound),
+                    expected(0b0010, bound),
+                    expected(0b0001, bound),
+                    expected(0b0000, bound)
+                );
+            }
+        }
+    }
+
     #[test]
     fn incremental_precision_2() {
         let inst: OptInstance = OptInstance::from_dimacs_path("./data/inc-sis-fails.wcnf").unwrap();
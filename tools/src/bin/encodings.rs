@@ -4,6 +4,7 @@
 //!
 //! `clustering`: Constrained correlation clustering encodings following \[1\].
 //! `knapsack`: Multi-criteria 0-1 knapsack.
+//! `card`: A single cardinality constraint, for comparing cardinality encodings.
 //!
 //! ## References
 //!
@@ -11,13 +12,22 @@
 //! correlation clustering via weighted partial Maximum Satisfiability_, AIJ
 //! 2017.
 
-use clap::{Args, Parser, Subcommand};
-use rustsat::{encodings::pb, instances::fio::dimacs};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use rustsat::{
+    encodings::{card, pb, EncodeStats},
+    instances::{fio::dimacs, BasicVarManager, Cnf, ManageVars},
+    types::Lit,
+};
 use rustsat_tools::encodings::{
     clustering::{self, saturating_map, scaling_map, Encoding, Variant},
     knapsack,
 };
-use std::{fs::File, io, path::PathBuf};
+use std::{
+    fmt,
+    fs::File,
+    io::{self, Write},
+    path::PathBuf,
+};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -30,6 +40,7 @@ struct CliArgs {
 enum Command {
     Clustering(ClusteringArgs),
     Knapsack(KnapsackArgs),
+    Card(CardArgs),
 }
 
 #[derive(Args)]
@@ -93,6 +104,46 @@ struct KnapsackArgs {
     seed: u64,
 }
 
+#[derive(Args)]
+struct CardArgs {
+    /// The DIMACS CNF output path. Writes to `stdout` if not given.
+    out_path: Option<PathBuf>,
+    /// The cardinality encoding to use
+    #[arg(long, default_value_t = CardEncoding::default())]
+    encoding: CardEncoding,
+    /// The number of input literals
+    #[arg(long, default_value_t = 100)]
+    n_lits: u32,
+    /// The bound to enforce on the input literals
+    #[arg(long, default_value_t = 10)]
+    bound: usize,
+    /// Enforce the bound as a lower bound instead of an upper bound
+    #[arg(long)]
+    lower: bool,
+}
+
+#[derive(Default, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum CardEncoding {
+    #[default]
+    Totalizer,
+    DbTotalizer,
+    SeqCounter,
+    ModuloTotalizer,
+    CardinalityNetwork,
+}
+
+impl fmt::Display for CardEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CardEncoding::Totalizer => write!(f, "totalizer"),
+            CardEncoding::DbTotalizer => write!(f, "db-totalizer"),
+            CardEncoding::SeqCounter => write!(f, "seq-counter"),
+            CardEncoding::ModuloTotalizer => write!(f, "modulo-totalizer"),
+            CardEncoding::CardinalityNetwork => write!(f, "cardinality-network"),
+        }
+    }
+}
+
 fn clustering(args: ClusteringArgs) -> anyhow::Result<()> {
     let mcnf_to_wcnf = |line: dimacs::McnfLine| match line {
         dimacs::McnfLine::Comment(c) => dimacs::WcnfLine::Comment(c),
@@ -167,11 +218,118 @@ fn knapsack(args: KnapsackArgs) -> anyhow::Result<()> {
     Ok(())
 }
 
+fn card(args: CardArgs) -> anyhow::Result<()> {
+    let mut var_manager = BasicVarManager::default();
+    let lits: Vec<Lit> = (0..args.n_lits)
+        .map(|_| var_manager.new_var().pos_lit())
+        .collect();
+    let mut cnf = Cnf::new();
+    let (n_vars, n_clauses) = match (args.encoding, args.lower) {
+        (CardEncoding::Totalizer, false) => {
+            encode_card_ub::<card::Totalizer>(lits, args.bound, &mut cnf, &mut var_manager)?
+        }
+        (CardEncoding::Totalizer, true) => {
+            encode_card_lb::<card::Totalizer>(lits, args.bound, &mut cnf, &mut var_manager)?
+        }
+        (CardEncoding::DbTotalizer, false) => {
+            encode_card_ub::<card::DbTotalizer>(lits, args.bound, &mut cnf, &mut var_manager)?
+        }
+        (CardEncoding::DbTotalizer, true) => {
+            encode_card_lb::<card::DbTotalizer>(lits, args.bound, &mut cnf, &mut var_manager)?
+        }
+        (CardEncoding::SeqCounter, false) => {
+            encode_card_ub::<card::SeqCounter>(lits, args.bound, &mut cnf, &mut var_manager)?
+        }
+        (CardEncoding::SeqCounter, true) => {
+            encode_card_lb::<card::SeqCounter>(lits, args.bound, &mut cnf, &mut var_manager)?
+        }
+        (CardEncoding::ModuloTotalizer, false) => {
+            encode_card_ub::<card::ModuloTotalizer>(lits, args.bound, &mut cnf, &mut var_manager)?
+        }
+        (CardEncoding::ModuloTotalizer, true) => {
+            anyhow::bail!("the modulo totalizer does not support lower bounds")
+        }
+        (CardEncoding::CardinalityNetwork, false) => encode_card_ub::<card::CardinalityNetwork>(
+            lits,
+            args.bound,
+            &mut cnf,
+            &mut var_manager,
+        )?,
+        (CardEncoding::CardinalityNetwork, true) => encode_card_lb::<card::CardinalityNetwork>(
+            lits,
+            args.bound,
+            &mut cnf,
+            &mut var_manager,
+        )?,
+    };
+    let write = |mut writer: &mut dyn Write| -> anyhow::Result<()> {
+        writeln!(
+            writer,
+            "c {} encoding of sum of {} literals {} {}",
+            args.encoding,
+            args.n_lits,
+            if args.lower { ">=" } else { "<=" },
+            args.bound
+        )?;
+        writeln!(
+            writer,
+            "c encoding variables: {}, encoding clauses: {}",
+            n_vars, n_clauses
+        )?;
+        dimacs::write_cnf_annotated(&mut writer, &cnf, var_manager.n_used())?;
+        Ok(())
+    };
+    if let Some(out_path) = args.out_path {
+        write(&mut File::create(out_path)?)
+    } else {
+        write(&mut io::stdout())
+    }
+}
+
+/// Encodes an upper bound on the literals, returns the number of variables
+/// and clauses of the encoding
+fn encode_card_ub<Enc>(
+    lits: Vec<Lit>,
+    ub: usize,
+    cnf: &mut Cnf,
+    var_manager: &mut dyn ManageVars,
+) -> anyhow::Result<(u32, usize)>
+where
+    Enc: card::BoundUpper + EncodeStats + FromIterator<Lit>,
+{
+    let mut enc: Enc = lits.into_iter().collect();
+    enc.encode_ub(ub..=ub, cnf, var_manager)?;
+    enc.enforce_ub(ub)?
+        .into_iter()
+        .for_each(|unit| cnf.add_unit(unit));
+    Ok((enc.n_vars(), enc.n_clauses()))
+}
+
+/// Encodes a lower bound on the literals, returns the number of variables
+/// and clauses of the encoding
+fn encode_card_lb<Enc>(
+    lits: Vec<Lit>,
+    lb: usize,
+    cnf: &mut Cnf,
+    var_manager: &mut dyn ManageVars,
+) -> anyhow::Result<(u32, usize)>
+where
+    Enc: card::BoundLower + EncodeStats + FromIterator<Lit>,
+{
+    let mut enc: Enc = lits.into_iter().collect();
+    enc.encode_lb(lb..=lb, cnf, var_manager)?;
+    enc.enforce_lb(lb)?
+        .into_iter()
+        .for_each(|unit| cnf.add_unit(unit));
+    Ok((enc.n_vars(), enc.n_clauses()))
+}
+
 fn main() -> anyhow::Result<()> {
     let args = CliArgs::parse();
 
     match args.cmd {
         Command::Clustering(args) => clustering(args),
         Command::Knapsack(args) => knapsack(args),
+        Command::Card(args) => card(args),
     }
 }
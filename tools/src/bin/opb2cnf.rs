@@ -3,9 +3,16 @@
 //! A small tool for converting OPB files to DIMACS CNF.
 
 use anyhow::Context;
-use clap::Parser;
-use rustsat::instances::{fio::opb::Options as OpbOptions, SatInstance};
-use std::{io, path::PathBuf};
+use clap::{Parser, ValueEnum};
+use rustsat::{
+    encodings::{
+        card,
+        pb::{self, BoundBoth},
+    },
+    instances::{fio::opb::Options as OpbOptions, SatInstance},
+    types::Lit,
+};
+use std::{fmt, io, path::PathBuf};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -17,6 +24,48 @@ struct Args {
     /// The index in the OPB file to treat as the lowest variable
     #[arg(long, default_value_t = 1)]
     first_var_idx: usize,
+    /// The encoding to use for pseudo-boolean constraints
+    #[arg(long, default_value_t = PbEncoding::default())]
+    pb_encoding: PbEncoding,
+}
+
+#[derive(ValueEnum, Default, Clone, Copy, PartialEq, Eq)]
+enum PbEncoding {
+    /// Automatically select an encoding for each constraint
+    #[default]
+    Auto,
+    /// The generalized totalizer encoding
+    Gte,
+    /// The dynamic polynomial watchdog encoding
+    Dpw,
+    /// The binary decision diagram encoding. (This is typically smaller for
+    /// constraints with few distinct weights.)
+    Bdd,
+}
+
+impl fmt::Display for PbEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PbEncoding::Auto => write!(f, "auto"),
+            PbEncoding::Gte => write!(f, "gte"),
+            PbEncoding::Dpw => write!(f, "dpw"),
+            PbEncoding::Bdd => write!(f, "bdd"),
+        }
+    }
+}
+
+/// Converts the instance to CNF, encoding pseudo-boolean constraints with `PBE`
+fn convert<PBE: BoundBoth + FromIterator<(Lit, usize)>>(inst: &mut SatInstance) {
+    inst.convert_to_cnf_with_encoders(
+        |constr, cnf, vm| {
+            card::default_encode_cardinality_constraint(constr, cnf, vm)
+                .expect("cardinality encoding ran out of memory")
+        },
+        |constr, cnf, vm| {
+            pb::encode_pb_constraint::<PBE, _>(constr, cnf, vm)
+                .expect("pb encoding ran out of memory")
+        },
+    )
 }
 
 fn main() -> anyhow::Result<()> {
@@ -37,7 +86,12 @@ fn main() -> anyhow::Result<()> {
     println!("{} cards", inst.n_cards());
     println!("{} pbs", inst.n_pbs());
 
-    inst.convert_to_cnf();
+    match args.pb_encoding {
+        PbEncoding::Auto => inst.convert_to_cnf(),
+        PbEncoding::Gte => convert::<pb::GeneralizedTotalizer>(&mut inst),
+        PbEncoding::Dpw => convert::<pb::DynamicPolyWatchdog>(&mut inst),
+        PbEncoding::Bdd => convert::<pb::Bdd>(&mut inst),
+    }
 
     if let Some(out_path) = args.out_path {
         inst.write_dimacs_path(out_path)
@@ -48,3 +102,61 @@ fn main() -> anyhow::Result<()> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use rustsat::{
+        encodings::pb::{self, BoundBoth},
+        instances::{fio::opb::Options as OpbOptions, SatInstance},
+        lit,
+        solvers::{Solve, SolveIncremental, SolverResult},
+        types::Lit,
+    };
+
+    /// Checks the encoding of `2 x1 + 5 x2 + 0 x3 + 1 x4 = 3` for all assignments
+    fn zero_coefficient<PBE: BoundBoth + FromIterator<(Lit, usize)>>() {
+        let opb = "+2 x1 +5 x2 +0 x3 +1 x4 = 3 ;\n";
+        let mut inst: SatInstance =
+            SatInstance::from_opb(opb.as_bytes(), OpbOptions::default()).unwrap();
+        super::convert::<PBE>(&mut inst);
+        let (cnf, _) = inst.into_cnf();
+        let mut solver = rustsat_tools::Solver::default();
+        solver.add_cnf(cnf).unwrap();
+        let weights = [2, 5, 0, 1];
+        for assign in 0..1_u32 << 4 {
+            let assumps: Vec<Lit> = (0..4)
+                .map(|idx| {
+                    if assign >> idx & 1 == 1 {
+                        lit![idx]
+                    } else {
+                        !lit![idx]
+                    }
+                })
+                .collect();
+            let sum: usize = (0..4)
+                .map(|idx| (assign >> idx & 1) as usize * weights[idx])
+                .sum();
+            let expected = if sum == 3 {
+                SolverResult::Sat
+            } else {
+                SolverResult::Unsat
+            };
+            assert_eq!(solver.solve_assumps(&assumps).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn gte_zero_coefficient() {
+        zero_coefficient::<pb::GeneralizedTotalizer>();
+    }
+
+    #[test]
+    fn dpw_zero_coefficient() {
+        zero_coefficient::<pb::DynamicPolyWatchdog>();
+    }
+
+    #[test]
+    fn bdd_zero_coefficient() {
+        zero_coefficient::<pb::Bdd>();
+    }
+}